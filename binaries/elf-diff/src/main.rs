@@ -0,0 +1,455 @@
+//! Reports the differences between two ELF files, such as two builds of the
+//! same target.
+//!
+//! Sections are matched by name and symbols by name, where a name appearing
+//! several times is matched by its order of appearance. Segments are matched
+//! by type and order, so the second `PT_LOAD` of one file is compared with
+//! the second `PT_LOAD` of the other. The static and dynamic symbol tables
+//! are compared separately, so stripping a file only removes the former.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    error::Error,
+    fmt::{LowerHex, Write},
+    fs::File,
+    io::Read,
+    process::ExitCode,
+};
+
+use reindeer::{elf_aux_structures::*, ElfFile, ElfProgramHeader, ElfSectionHeader};
+use sha2::{Digest, Sha256};
+
+const USAGE: &str = "\
+Usage: elf-diff <old-file> <new-file>
+ Report the differences between two ELF files: header fields, added, removed
+ and resized sections, section contents (by SHA-256), segments, and static
+ and dynamic symbols.
+
+ Exits with 0 if the files are the same, 1 if they differ and 2 on error.";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Collects the differences of one part of the files, printed under a heading.
+#[derive(Default)]
+struct Report {
+    lines: Vec<String>,
+}
+
+impl Report {
+    fn added(&mut self, what: impl AsRef<str>) {
+        self.lines.push(format!("  + {}", what.as_ref()));
+    }
+
+    fn removed(&mut self, what: impl AsRef<str>) {
+        self.lines.push(format!("  - {}", what.as_ref()));
+    }
+
+    fn changed<T: PartialEq + LowerHex>(&mut self, what: &str, old: T, new: T) {
+        if old != new {
+            self.lines.push(format!("  ~ {what}: {old:#x} -> {new:#x}"));
+        }
+    }
+
+    fn changed_str(&mut self, what: &str, old: &str, new: &str) {
+        if old != new {
+            self.lines.push(format!("  ~ {what}: {old} -> {new}"));
+        }
+    }
+
+    /// Prints the report under `heading`, returning whether there was anything
+    /// to print.
+    fn print(&self, heading: &str) -> bool {
+        if self.lines.is_empty() {
+            return false;
+        }
+
+        println!("{heading}:");
+        for line in &self.lines {
+            println!("{line}");
+        }
+        true
+    }
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut buffer))
+        .map_err(|error| format!("{path}: {error}"))?;
+    Ok(buffer)
+}
+
+fn constant_name(name: Option<&'static str>, value: impl LowerHex) -> String {
+    name.map_or_else(|| format!("{value:#x}"), Into::into)
+}
+
+fn diff_headers(old: &ElfFile, new: &ElfFile) -> Report {
+    let (old, new) = (old.header(), new.header());
+    let mut report = Report::default();
+
+    let (old_class, new_class) = (old.e_ident().ei_class, new.e_ident().ei_class);
+    report.changed_str(
+        "class",
+        &constant_name(old_class.name(), old_class.0),
+        &constant_name(new_class.name(), new_class.0),
+    );
+    let (old_abi, new_abi) = (old.e_ident().ei_osabi, new.e_ident().ei_osabi);
+    report.changed_str(
+        "os_abi",
+        &constant_name(old_abi.name(), old_abi.0),
+        &constant_name(new_abi.name(), new_abi.0),
+    );
+    report.changed_str(
+        "e_type",
+        &constant_name(old.e_type().name(), old.e_type().0),
+        &constant_name(new.e_type().name(), new.e_type().0),
+    );
+    report.changed_str(
+        "e_machine",
+        &constant_name(old.e_machine().name(), old.e_machine().0),
+        &constant_name(new.e_machine().name(), new.e_machine().0),
+    );
+    report.changed(
+        "e_entry",
+        old.e_entry().map_or(0, |entry| entry.get()),
+        new.e_entry().map_or(0, |entry| entry.get()),
+    );
+    report.changed("e_flags", old.e_flags(), new.e_flags());
+    report.changed(
+        "e_phnum",
+        old.e_phnum().map_or(0, |num| num.get()),
+        new.e_phnum().map_or(0, |num| num.get()),
+    );
+    report.changed(
+        "e_shnum",
+        old.e_shnum().map_or(0, |num| num.get()),
+        new.e_shnum().map_or(0, |num| num.get()),
+    );
+
+    report
+}
+
+fn sha256(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Keys items by name and occurrence, so that repeated names are matched in
+/// order. The returned vector keeps the original order of the items.
+fn keyed<T>(items: impl Iterator<Item = (String, T)>) -> Vec<((String, usize), T)> {
+    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+
+    items
+        .map(|(name, item)| {
+            let count = seen.entry(name.clone()).or_default();
+            let key = (name, *count);
+            *count = count.saturating_add(1);
+            (key, item)
+        })
+        .collect()
+}
+
+fn display_key((name, occurrence): &(String, usize)) -> String {
+    match occurrence {
+        0 => name.clone(),
+        n => format!("{name} (#{})", n.saturating_add(1)),
+    }
+}
+
+fn named_sections<'buf>(file: &ElfFile<'buf>) -> Vec<((String, usize), ElfSectionHeader<'buf>)> {
+    keyed(
+        file.sections()
+            .skip(1)
+            .map(|section| (file.section_name(section).unwrap_or("").to_owned(), section)),
+    )
+}
+
+fn diff_sections(old: &ElfFile, new: &ElfFile) -> Result<Report> {
+    let mut report = Report::default();
+    let old_sections = named_sections(old);
+    let new_sections: BTreeMap<_, _> = named_sections(new).into_iter().collect();
+
+    for (key, old_section) in &old_sections {
+        let name = display_key(key);
+        let Some(new_section) = new_sections.get(key) else {
+            report.removed(format!("{name} (size {:#x})", old_section.sh_size()));
+            continue;
+        };
+
+        let (old_type, new_type) = (old_section.sh_type(), new_section.sh_type());
+        report.changed_str(
+            &format!("{name}: type"),
+            &constant_name(old_type.name(), old_type.0),
+            &constant_name(new_type.name(), new_type.0),
+        );
+        report.changed(
+            &format!("{name}: flags"),
+            old_section.sh_flags(),
+            new_section.sh_flags(),
+        );
+        report.changed(
+            &format!("{name}: address"),
+            old_section.sh_addr().map_or(0, |addr| addr.get()),
+            new_section.sh_addr().map_or(0, |addr| addr.get()),
+        );
+        report.changed(
+            &format!("{name}: size"),
+            old_section.sh_size(),
+            new_section.sh_size(),
+        );
+        report.changed_str(
+            &format!("{name}: contents"),
+            &sha256(old.section_data(*old_section)?),
+            &sha256(new.section_data(*new_section)?),
+        );
+    }
+
+    let old_keys: BTreeMap<_, _> = old_sections.into_iter().collect();
+    for (key, new_section) in named_sections(new) {
+        if !old_keys.contains_key(&key) {
+            report.added(format!(
+                "{} (size {:#x})",
+                display_key(&key),
+                new_section.sh_size()
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+fn typed_segments<'buf>(file: &ElfFile<'buf>) -> Vec<((String, usize), ElfProgramHeader<'buf>)> {
+    keyed(file.segments().map(|segment| {
+        let p_type = segment.p_type();
+        (constant_name(p_type.name(), p_type.0), segment)
+    }))
+}
+
+fn diff_segments(old: &ElfFile, new: &ElfFile) -> Report {
+    let mut report = Report::default();
+    let old_segments = typed_segments(old);
+    let new_segments: BTreeMap<_, _> = typed_segments(new).into_iter().collect();
+
+    for (key, old_segment) in &old_segments {
+        let name = display_key(key);
+        let Some(new_segment) = new_segments.get(key) else {
+            report.removed(&name);
+            continue;
+        };
+
+        report.changed(
+            &format!("{name}: offset"),
+            old_segment.p_offset(),
+            new_segment.p_offset(),
+        );
+        report.changed(
+            &format!("{name}: vaddr"),
+            old_segment.p_vaddr(),
+            new_segment.p_vaddr(),
+        );
+        report.changed(
+            &format!("{name}: filesz"),
+            old_segment.p_filesz().map_or(0, |size| size.get()),
+            new_segment.p_filesz().map_or(0, |size| size.get()),
+        );
+        report.changed(
+            &format!("{name}: memsz"),
+            old_segment.p_memsz().map_or(0, |size| size.get()),
+            new_segment.p_memsz().map_or(0, |size| size.get()),
+        );
+        report.changed(
+            &format!("{name}: flags"),
+            old_segment.p_flags(),
+            new_segment.p_flags(),
+        );
+        report.changed(
+            &format!("{name}: align"),
+            old_segment.p_align(),
+            new_segment.p_align(),
+        );
+    }
+
+    let old_keys: BTreeMap<_, _> = old_segments.into_iter().collect();
+    for (key, _) in typed_segments(new) {
+        if !old_keys.contains_key(&key) {
+            report.added(display_key(&key));
+        }
+    }
+
+    report
+}
+
+/// The named symbols of the symbol table of type `table`, with their sizes.
+/// Symbols whose names can't be read are skipped.
+fn named_symbols(file: &ElfFile, table: ElfSectionType) -> Result<Vec<((String, usize), u64)>> {
+    let Some(section) = file.section_by_type(table) else {
+        return Ok(Vec::new());
+    };
+
+    let symbols = file.symbol_table(section)?;
+    let strings = file.linked_string_table(section)?;
+
+    let mut named = Vec::new();
+    for symbol in symbols {
+        let symbol_type = symbol.symbol_type();
+        if symbol_type == ElfSymbolType::STT_SECTION || symbol_type == ElfSymbolType::STT_FILE {
+            continue;
+        }
+
+        let Ok(name) = strings.get(symbol.st_name()) else {
+            continue;
+        };
+        if !name.is_empty() {
+            named.push((name.to_owned(), symbol.st_size()));
+        }
+    }
+
+    Ok(keyed(named.into_iter()))
+}
+
+fn diff_symbols(old: &ElfFile, new: &ElfFile, table: ElfSectionType) -> Result<Report> {
+    let mut report = Report::default();
+    let old_symbols = named_symbols(old, table)?;
+    let new_symbols: BTreeMap<_, _> = named_symbols(new, table)?.into_iter().collect();
+
+    for (key, old_size) in &old_symbols {
+        let name = display_key(key);
+        match new_symbols.get(key) {
+            Some(new_size) => report.changed(&format!("{name}: size"), *old_size, *new_size),
+            None => report.removed(format!("{name} (size {old_size:#x})")),
+        }
+    }
+
+    let old_keys: BTreeMap<_, _> = old_symbols.into_iter().collect();
+    for (key, new_size) in named_symbols(new, table)? {
+        if !old_keys.contains_key(&key) {
+            report.added(format!("{} (size {new_size:#x})", display_key(&key)));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Prints the differences, returning whether there were any.
+fn diff(old_path: &str, new_path: &str) -> Result<bool> {
+    let old_buffer = read_file(old_path)?;
+    let new_buffer = read_file(new_path)?;
+    let old = ElfFile::parse(&old_buffer).map_err(|error| format!("{old_path}: {error}"))?;
+    let new = ElfFile::parse(&new_buffer).map_err(|error| format!("{new_path}: {error}"))?;
+
+    println!("--- {old_path}");
+    println!("+++ {new_path}");
+
+    let mut differs = false;
+    differs |= diff_headers(&old, &new).print("header");
+    differs |= diff_sections(&old, &new)?.print("sections");
+    differs |= diff_segments(&old, &new).print("segments");
+    differs |= diff_symbols(&old, &new, ElfSectionType::SHT_SYMTAB)?.print("symbols");
+    differs |= diff_symbols(&old, &new, ElfSectionType::SHT_DYNSYM)?.print("dynamic symbols");
+
+    Ok(differs)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let [old_path, new_path] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    match diff(old_path, new_path) {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::from(1),
+        Err(error) => {
+            eprintln!("elf-diff: error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testdata(name: &str) -> Vec<u8> {
+        read_file(&format!(
+            "{}/../../reindeer/testdata/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .expect("readable test file")
+    }
+
+    fn symbol_lines(old: &[u8], new: &[u8], table: ElfSectionType) -> Vec<String> {
+        let old = ElfFile::parse(old).expect("valid file");
+        let new = ElfFile::parse(new).expect("valid file");
+        diff_symbols(&old, &new, table)
+            .expect("readable symbols")
+            .lines
+    }
+
+    #[test]
+    fn finds_no_differences_in_the_same_file() {
+        let buffer = testdata("diff-symbols");
+        let file = ElfFile::parse(&buffer).expect("valid file");
+
+        assert!(diff_headers(&file, &file).lines.is_empty());
+        assert!(diff_sections(&file, &file)
+            .expect("readable sections")
+            .lines
+            .is_empty());
+        assert!(diff_segments(&file, &file).lines.is_empty());
+        for table in [ElfSectionType::SHT_SYMTAB, ElfSectionType::SHT_DYNSYM] {
+            assert!(symbol_lines(&buffer, &buffer, table).is_empty());
+        }
+    }
+
+    #[test]
+    fn compares_each_symbol_table_with_its_counterpart() {
+        let full = testdata("diff-symbols");
+        let stripped = testdata("diff-symbols-stripped");
+
+        assert_eq!(
+            symbol_lines(&full, &stripped, ElfSectionType::SHT_SYMTAB),
+            [
+                "  - helper (size 0x6)",
+                "  - _DYNAMIC (size 0x0)",
+                "  - exported (size 0x9)",
+            ]
+        );
+        assert!(symbol_lines(&full, &stripped, ElfSectionType::SHT_DYNSYM).is_empty());
+    }
+
+    #[test]
+    fn skips_symbols_with_bad_names() {
+        let full = testdata("diff-symbols");
+        let mut broken = full.clone();
+        let file = ElfFile::parse(&full).expect("valid file");
+        let symtab = file
+            .section_by_type(ElfSectionType::SHT_SYMTAB)
+            .expect("static symbol table");
+
+        // Points the st_name of `helper`, the third symbol, past the end of
+        // the string table.
+        let offset =
+            usize::try_from(symtab.sh_offset().saturating_add(2 * 24)).expect("offset in memory");
+        broken
+            .get_mut(offset..offset.saturating_add(4))
+            .expect("symbol in file")
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_eq!(
+            symbol_lines(&full, &broken, ElfSectionType::SHT_SYMTAB),
+            ["  - helper (size 0x6)"]
+        );
+    }
+}
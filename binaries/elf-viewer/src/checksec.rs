@@ -0,0 +1,63 @@
+//! The hardening report, selected with `--checksec`.
+
+use std::error::Error;
+
+use reindeer::{
+    elf_aux_structures::*,
+    hardening::{ElfHardening, ElfRelro},
+    ElfFile,
+};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+pub fn relro_name(relro: ElfRelro) -> &'static str {
+    match relro {
+        ElfRelro::None => "none",
+        ElfRelro::Partial => "partial",
+        ElfRelro::Full => "full",
+    }
+}
+
+fn field(label: &str, value: impl std::fmt::Display) {
+    println!("  {label:<20}{value}");
+}
+
+pub fn print_hardening(file: &ElfFile) -> Result {
+    let hardening = ElfHardening::parse(file)?;
+
+    println!("\nHardening:");
+    field("PIE:", yes_no(hardening.pie));
+    field("NX:", yes_no(hardening.nx));
+    field("RELRO:", relro_name(hardening.relro));
+    field("Stack protector:", yes_no(hardening.stack_protector));
+    match hardening.fortified_functions {
+        0 => field("FORTIFY:", "no"),
+        1 => field("FORTIFY:", "yes (1 fortified function)"),
+        count => field("FORTIFY:", format!("yes ({count} fortified functions)")),
+    }
+    field("TEXTREL:", yes_no(hardening.textrel));
+    field("RPATH:", hardening.rpath.unwrap_or("none"));
+    field("RUNPATH:", hardening.runpath.unwrap_or("none"));
+
+    match file.header().e_machine() {
+        ElfHeaderMachine::EM_X86_64 | ElfHeaderMachine::EM_386 => {
+            field("CET IBT:", yes_no(hardening.ibt()));
+            field("CET SHSTK:", yes_no(hardening.shstk()));
+        }
+        ElfHeaderMachine::EM_AARCH64 => {
+            field("BTI:", yes_no(hardening.bti()));
+            field("PAC:", yes_no(hardening.pac()));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! The `export` subcommand, writing the loadable contents of a file as a flat
+//! binary, Intel HEX or S-records for flashing.
+
+use std::{error::Error, fs, process::ExitCode};
+
+use reindeer::{export, ElfFile};
+
+const USAGE: &str = "\
+Usage: elf-viewer export <option(s)> elf-file output-file
+ Write the contents of the PT_LOAD segments at their physical addresses
+ Options are:
+  -O --format=<format>   The output format: binary, ihex or srec
+     --gap-fill=<byte>   Fill the gaps between segments in binary output
+                         with <byte>, rather than zero
+  -H --help              Display this information";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Binary,
+    Ihex,
+    Srec,
+}
+
+/// The options of the `export` subcommand.
+#[derive(Debug, Clone)]
+struct ExportOptions {
+    format: Format,
+    gap_fill: u8,
+    input: String,
+    output: String,
+}
+
+fn parse_format(format: &str) -> Result<Format, String> {
+    match format {
+        "binary" => Ok(Format::Binary),
+        "ihex" => Ok(Format::Ihex),
+        "srec" => Ok(Format::Srec),
+        _ => Err(format!("unrecognized format '{format}'")),
+    }
+}
+
+fn parse_byte(value: &str) -> Result<u8, String> {
+    let parsed = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| format!("invalid gap fill byte '{value}'"))
+}
+
+/// Parses the arguments after `export`, returning `None` for `--help`.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<ExportOptions>, String> {
+    let mut format = None;
+    let mut gap_fill = 0;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "-H" || arg == "--help" {
+            return Ok(None);
+        } else if arg == "-O" {
+            let value = args.next().ok_or("option '-O' requires a format")?;
+            format = Some(parse_format(&value)?);
+        } else if let Some(value) = arg.strip_prefix("--format=") {
+            format = Some(parse_format(value)?);
+        } else if let Some(value) = arg.strip_prefix("--gap-fill=") {
+            gap_fill = parse_byte(value)?;
+        } else if arg.starts_with('-') {
+            return Err(format!("unrecognized option '{arg}'"));
+        } else {
+            files.push(arg);
+        }
+    }
+
+    let format = format.ok_or("no output format given")?;
+    let [input, output] = <[String; 2]>::try_from(files)
+        .map_err(|_| "expected an input and an output file".to_string())?;
+
+    Ok(Some(ExportOptions {
+        format,
+        gap_fill,
+        input,
+        output,
+    }))
+}
+
+fn export_file(options: &ExportOptions) -> Result<(), Box<dyn Error>> {
+    let buffer = fs::read(&options.input)?;
+    let file = ElfFile::parse(&buffer)?;
+
+    let contents = match options.format {
+        Format::Binary => export::to_binary(&file, options.gap_fill)?,
+        Format::Ihex => export::to_ihex(&file)?.into_bytes(),
+        // objcopy puts the output file name in the S0 header record.
+        Format::Srec => export::to_srec(&file, &options.output)?.into_bytes(),
+    };
+
+    fs::write(&options.output, contents)?;
+    Ok(())
+}
+
+pub fn main(args: impl Iterator<Item = String>) -> ExitCode {
+    let options = match parse_args(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("elf-viewer: error: {message}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = export_file(&options) {
+        eprintln!("elf-viewer: error: {}: {error}", options.input);
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
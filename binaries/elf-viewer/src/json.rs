@@ -0,0 +1,285 @@
+//! Machine-readable output, selected with `--json`.
+//!
+//! Each file is written as one JSON object on its own line. Every enumerated
+//! field is an object holding the raw `value` alongside the constant's
+//! `name`, which is `null` if the value isn't known to reindeer, so that
+//! unknown values still round-trip. Addresses, offsets and sizes are plain
+//! numbers. Fields are only ever added to the schema, and
+//! `schema_version` is bumped if one is removed or changes meaning.
+//!
+//! ```text
+//! {
+//!   "schema_version": 1,
+//!   "file": "path/to/file",
+//!   "header": {
+//!     "class": Named, "data": Named, "os_abi": Named, "abi_version": u8,
+//!     "type": Named, "machine": Named, "version": Named, "entry": u64,
+//!     "flags": u32, "phoff": u64, "phentsize": u16, "phnum": u16,
+//!     "shoff": u64, "shentsize": u16, "shnum": u16, "shstrndx": u16
+//!   },
+//!   "sections": [{
+//!     "index": usize, "name": string | null, "type": Named, "flags": Flags,
+//!     "addr": u64, "offset": u64, "size": u64, "link": u32, "info": u32,
+//!     "addralign": u64, "entsize": u64
+//!   }],
+//!   "segments": [{
+//!     "index": usize, "type": Named, "flags": Flags, "offset": u64,
+//!     "vaddr": u64, "paddr": u64, "filesz": u64, "memsz": u64, "align": u64,
+//!     "sections": [usize]
+//!   }],
+//!   "symbols": [{
+//!     "table": usize, "index": usize, "name": string | null, "value": u64,
+//!     "size": u64, "type": Named, "binding": Named, "visibility": Named,
+//!     "shndx": Named
+//!   }],
+//!   "dynamic": [{ "tag": Named, "value": u64 }],
+//!   "notes": [{ "owner": string, "type": Named, "desc": hex string }],
+//!   "hardening": {
+//!     "pie": bool, "nx": bool, "relro": "none" | "partial" | "full",
+//!     "stack_protector": bool, "fortified_functions": usize,
+//!     "textrel": bool, "rpath": string | null, "runpath": string | null,
+//!     "x86_features": Flags | null, "aarch64_features": Flags | null
+//!   }
+//! }
+//!
+//! Named = { "value": number, "name": string | null }
+//! Flags = { "value": number, "names": [string] }
+//! ```
+//!
+//! A segment's `sections` are the indices of the sections readelf would map
+//! to it, and a symbol's `table` is the index of its symbol table section.
+//! Symbol names are read from the string table as is, so section symbols have
+//! an empty name. `names` lists the known flags that are set. Tables that
+//! aren't present in the file are empty arrays.
+
+use std::{error::Error, fmt::Write};
+
+use reindeer::{
+    elf_aux_structures::*, hardening::ElfHardening, mapping, notes::ElfNotes, ElfFile, ElfHeader,
+    ElfProgramHeader, ElfSectionHeader,
+};
+use serde_json::{json, Value};
+
+use crate::checksec::relro_name;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+fn named(value: impl Into<Value>, name: Option<&'static str>) -> Value {
+    json!({ "value": value.into(), "name": name })
+}
+
+/// Splits a flags value into its set bits, naming the known ones.
+fn flags(value: u64, name: impl Fn(u64) -> Option<&'static str>) -> Value {
+    let names: Vec<_> = (0..u64::BITS)
+        .map(|bit| 1 << bit)
+        .filter(|flag| value & flag != 0)
+        .filter_map(name)
+        .collect();
+
+    json!({ "value": value, "names": names })
+}
+
+fn header(header: ElfHeader) -> Value {
+    let ident = header.e_ident();
+
+    json!({
+        "class": named(ident.ei_class.0, ident.ei_class.name()),
+        "data": named(ident.ei_data.0, ident.ei_data.name()),
+        "os_abi": named(ident.ei_osabi.0, ident.ei_osabi.name()),
+        "abi_version": ident.ei_abiversion,
+        "type": named(header.e_type().0, header.e_type().name()),
+        "machine": named(header.e_machine().0, header.e_machine().name()),
+        "version": named(header.e_version().0, header.e_version().name()),
+        "entry": header.e_entry().map_or(0, |entry| entry.get()),
+        "flags": header.e_flags(),
+        "phoff": header.e_phoff().map_or(0, |off| off.get()),
+        "phentsize": header.e_phentsize(),
+        "phnum": header.e_phnum().map_or(0, |num| num.get()),
+        "shoff": header.e_shoff().map_or(0, |off| off.get()),
+        "shentsize": header.e_shentsize(),
+        "shnum": header.e_shnum().map_or(0, |num| num.get()),
+        "shstrndx": header.e_shstrndx().map_or(0, |index| index.get()),
+    })
+}
+
+fn section(file: &ElfFile, index: usize, section: ElfSectionHeader) -> Value {
+    json!({
+        "index": index,
+        "name": file.section_name(section).ok(),
+        "type": named(section.sh_type().0, section.sh_type().name()),
+        "flags": flags(section.sh_flags(), |flag| ElfSectionFlags(flag).name()),
+        "addr": section.sh_addr().map_or(0, |addr| addr.get()),
+        "offset": section.sh_offset(),
+        "size": section.sh_size(),
+        "link": section.sh_link(),
+        "info": section.sh_info(),
+        "addralign": section.sh_addralign(),
+        "entsize": section.sh_entsize().map_or(0, |size| size.get()),
+    })
+}
+
+fn segment(file: &ElfFile, index: usize, segment: ElfProgramHeader) -> Value {
+    let sections: Vec<_> = mapping::segment_sections(file, segment)
+        .map(|(index, _)| index)
+        .collect();
+
+    json!({
+        "index": index,
+        "type": named(segment.p_type().0, segment.p_type().name()),
+        "flags": flags(segment.p_flags().into(), |flag| {
+            ElfSegmentFlags(u32::try_from(flag).ok()?).name()
+        }),
+        "offset": segment.p_offset(),
+        "vaddr": segment.p_vaddr(),
+        "paddr": segment.p_paddr(),
+        "filesz": segment.p_filesz().map_or(0, |size| size.get()),
+        "memsz": segment.p_memsz().map_or(0, |size| size.get()),
+        "align": segment.p_align(),
+        "sections": sections,
+    })
+}
+
+fn symbols(file: &ElfFile) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+
+    for (table, section) in file.sections().enumerate() {
+        let section_type = section.sh_type();
+        if section_type != ElfSectionType::SHT_SYMTAB && section_type != ElfSectionType::SHT_DYNSYM
+        {
+            continue;
+        }
+
+        let symbols = file.symbol_table(section)?;
+        let strings = file.linked_string_table(section)?;
+
+        for (index, symbol) in symbols.into_iter().enumerate() {
+            values.push(json!({
+                "table": table,
+                "index": index,
+                "name": strings.get(symbol.st_name()).ok(),
+                "value": symbol.st_value(),
+                "size": symbol.st_size(),
+                "type": named(symbol.symbol_type().0, symbol.symbol_type().name()),
+                "binding": named(symbol.binding().0, symbol.binding().name()),
+                "visibility": named(symbol.visibility().0, symbol.visibility().name()),
+                "shndx": named(symbol.st_shndx().0, symbol.st_shndx().name()),
+            }));
+        }
+    }
+
+    Ok(values)
+}
+
+fn dynamic(file: &ElfFile) -> Result<Vec<Value>> {
+    let Some(dynamic) = file.dynamic_table()? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(dynamic
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "tag": named(entry.d_tag().0, entry.d_tag().name()),
+                "value": entry.d_val(),
+            })
+        })
+        .collect())
+}
+
+fn notes_in(values: &mut Vec<Value>, data: &[u8], align: u64) -> Result {
+    for note in ElfNotes::parse(data, align) {
+        let note = note?;
+        let desc = note.desc.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+
+        values.push(json!({
+            "owner": String::from_utf8_lossy(note.name),
+            "type": named(note.n_type.0, note.n_type.name()),
+            "desc": desc,
+        }));
+    }
+
+    Ok(())
+}
+
+fn notes(file: &ElfFile) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+
+    for section in file.sections() {
+        if section.sh_type() == ElfSectionType::SHT_NOTE {
+            notes_in(
+                &mut values,
+                file.section_data(section)?,
+                section.sh_addralign(),
+            )?;
+        }
+    }
+
+    // Without section headers, fall back to the note segments.
+    if file.section_headers().is_none() {
+        for segment in file.segments() {
+            if segment.p_type() == ElfSegmentType::PT_NOTE {
+                notes_in(&mut values, file.segment_data(segment)?, segment.p_align())?;
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+fn hardening(file: &ElfFile) -> Result<Value> {
+    let hardening = ElfHardening::parse(file)?;
+
+    Ok(json!({
+        "pie": hardening.pie,
+        "nx": hardening.nx,
+        "relro": relro_name(hardening.relro),
+        "stack_protector": hardening.stack_protector,
+        "fortified_functions": hardening.fortified_functions,
+        "textrel": hardening.textrel,
+        "rpath": hardening.rpath,
+        "runpath": hardening.runpath,
+        "x86_features": hardening.x86_features.map(|features| {
+            flags(features.0.into(), |flag| {
+                ElfX86Feature1(u32::try_from(flag).ok()?).name()
+            })
+        }),
+        "aarch64_features": hardening.aarch64_features.map(|features| {
+            flags(features.0.into(), |flag| {
+                ElfAarch64Feature1(u32::try_from(flag).ok()?).name()
+            })
+        }),
+    }))
+}
+
+pub fn print_file(path: &str, file: &ElfFile) -> Result {
+    let sections: Vec<_> = file
+        .sections()
+        .enumerate()
+        .map(|(index, header)| section(file, index, header))
+        .collect();
+    let segments: Vec<_> = file
+        .segments()
+        .enumerate()
+        .map(|(index, header)| segment(file, index, header))
+        .collect();
+
+    let value = json!({
+        "schema_version": SCHEMA_VERSION,
+        "file": path,
+        "header": header(file.header()),
+        "sections": sections,
+        "segments": segments,
+        "symbols": symbols(file)?,
+        "dynamic": dynamic(file)?,
+        "notes": notes(file)?,
+        "hardening": hardening(file)?,
+    });
+
+    println!("{value}");
+    Ok(())
+}
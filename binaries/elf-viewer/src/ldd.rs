@@ -0,0 +1,166 @@
+//! The `ldd` subcommand, listing the shared libraries a file needs as the
+//! dynamic loader would find them, without running it.
+
+use std::{error::Error, fs, path::Path, process::ExitCode};
+
+use reindeer::{
+    dependencies::{
+        ElfDependency, ElfDependencyGraph, ElfDependencyResolver, ElfDependencySource,
+        ElfRejection, ElfResolution,
+    },
+    ElfFile,
+};
+
+const USAGE: &str = "\
+Usage: elf-viewer ldd <option(s)> elf-file(s)
+ List the shared libraries needed by the files, and where they were found
+ Options are:
+     --sysroot=<dir>        Look up absolute paths under <dir>
+     --library-path=<dirs>  Search the colon-separated <dirs> like
+                            LD_LIBRARY_PATH
+  -v --verbose              Also list the files that were found but not used
+  -H --help                 Display this information";
+
+/// The options of the `ldd` subcommand.
+#[derive(Debug, Clone, Default)]
+struct LddOptions {
+    sysroot: Option<String>,
+    library_path: Option<String>,
+    verbose: bool,
+    files: Vec<String>,
+}
+
+/// Parses the arguments after `ldd`, returning `None` for `--help`.
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<LddOptions>, String> {
+    let mut options = LddOptions::default();
+
+    for arg in args {
+        if arg == "-H" || arg == "--help" {
+            return Ok(None);
+        } else if arg == "-v" || arg == "--verbose" {
+            options.verbose = true;
+        } else if let Some(value) = arg.strip_prefix("--sysroot=") {
+            options.sysroot = Some(value.into());
+        } else if let Some(value) = arg.strip_prefix("--library-path=") {
+            options.library_path = Some(value.into());
+        } else if arg.starts_with('-') {
+            return Err(format!("unrecognized option '{arg}'"));
+        } else {
+            options.files.push(arg);
+        }
+    }
+
+    if options.files.is_empty() {
+        return Err("no input files".into());
+    }
+
+    Ok(Some(options))
+}
+
+fn source_name(graph: &ElfDependencyGraph, source: ElfDependencySource) -> String {
+    match source {
+        ElfDependencySource::Root => "root".into(),
+        ElfDependencySource::Interpreter => "PT_INTERP".into(),
+        ElfDependencySource::Path => "path".into(),
+        ElfDependencySource::Rpath(0) => "RPATH".into(),
+        ElfDependencySource::Rpath(index) => match graph.objects.get(index) {
+            Some(loader) => format!("RPATH of {}", loader.name),
+            None => "RPATH".into(),
+        },
+        ElfDependencySource::LibraryPath => "library path".into(),
+        ElfDependencySource::Runpath => "RUNPATH".into(),
+        ElfDependencySource::LdSoConf => "ld.so.conf".into(),
+        ElfDependencySource::Default => "default path".into(),
+    }
+}
+
+fn rejection_reason(rejection: &ElfRejection) -> String {
+    match rejection {
+        ElfRejection::Malformed(error) => error.to_string(),
+        ElfRejection::WrongClass => "wrong ELF class".into(),
+        ElfRejection::WrongMachine(machine) => format!("wrong machine {machine:?}"),
+        ElfRejection::WrongType(file_type) => format!("not a shared object: {file_type:?}"),
+    }
+}
+
+fn print_dependency(graph: &ElfDependencyGraph, dependency: &ElfDependency, verbose: bool) {
+    match &dependency.resolution {
+        ElfResolution::Found {
+            path,
+            source: ElfDependencySource::Interpreter,
+        } => println!("\t{} (PT_INTERP)", path.display()),
+        ElfResolution::Found { path, source } => println!(
+            "\t{} => {} ({})",
+            dependency.name,
+            path.display(),
+            source_name(graph, *source)
+        ),
+        ElfResolution::Missing => println!("\t{} => not found", dependency.name),
+    }
+
+    if verbose {
+        for (path, rejection) in &dependency.rejected {
+            println!(
+                "\t\tskipped {}: {}",
+                path.display(),
+                rejection_reason(rejection)
+            );
+        }
+    }
+}
+
+fn print_file(path: &str, options: &LddOptions) -> Result<(), Box<dyn Error>> {
+    let buffer = fs::read(path)?;
+    let file = ElfFile::parse(&buffer)?;
+
+    let mut resolver = ElfDependencyResolver::new();
+    if let Some(sysroot) = &options.sysroot {
+        resolver = resolver.with_sysroot(sysroot);
+    }
+    if let Some(library_path) = &options.library_path {
+        resolver = resolver.with_library_path(library_path);
+    }
+    let graph = resolver.resolve(Path::new(path), &file)?;
+
+    // As in ldd, the interpreter is listed last.
+    let (interpreter, libraries): (Vec<_>, Vec<_>) = graph.objects.iter().skip(1).partition(
+        |object| matches!(object.resolution, ElfResolution::Found { source, .. } if source == ElfDependencySource::Interpreter),
+    );
+    for dependency in libraries.into_iter().chain(interpreter) {
+        print_dependency(&graph, dependency, options.verbose);
+    }
+
+    if graph.missing().next().is_some() {
+        return Err("some libraries were not found".into());
+    }
+    Ok(())
+}
+
+pub fn main(args: impl Iterator<Item = String>) -> ExitCode {
+    let options = match parse_args(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("elf-viewer: error: {message}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for path in &options.files {
+        if options.files.len() > 1 {
+            println!("{path}:");
+        }
+
+        if let Err(error) = print_file(path, &options) {
+            eprintln!("elf-viewer: error: {path}: {error}");
+            status = ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
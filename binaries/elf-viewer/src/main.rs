@@ -1,134 +1,251 @@
-use std::{env, error::Error, fs::File, io::Read};
+use std::{env, error::Error, fs::File, io::Read, process::ExitCode};
+
+use reindeer::{errors::ElfError, ElfFile};
+
+mod checksec;
+mod export;
+mod json;
+mod ldd;
+mod readelf;
+
+const USAGE: &str = "\
+Usage: elf-viewer <option(s)> elf-file(s)
+       elf-viewer export <option(s)> elf-file output-file
+       elf-viewer ldd <option(s)> elf-file(s)
+ Display information about the contents of ELF format files
+ Options are:
+  -a --all               Equivalent to: -h -l -S -s -r -d -V -n
+  -h --file-header       Display the ELF file header
+  -l --program-headers   Display the program headers
+     --segments          An alias for --program-headers
+  -S --section-headers   Display the sections' header
+     --sections          An alias for --section-headers
+  -e --headers           Equivalent to: -h -l -S
+  -s --syms              Display the symbol table
+     --symbols           An alias for --syms
+     --dyn-syms          Display the dynamic symbol table
+  -n --notes             Display the core notes (if present)
+  -r --relocs            Display the relocations (if present)
+  -d --dynamic           Display the dynamic section (if present)
+  -V --version-info      Display the version sections (if present)
+     --checksec          Display the hardening features, such as PIE, NX,
+                         RELRO, stack protector, FORTIFY and CET/BTI
+  -W --wide              Accepted for compatibility, output is always wide
+     --json              Display the header, sections, segments, symbols,
+                         dynamic entries and notes as JSON, one line per file
+  -H --help              Display this information
+
+The output matches `readelf -W` closely enough to diff.
+If no display options are given, -S -l is assumed.
+See `elf-viewer export --help` for writing binary, ihex or srec files,
+and `elf-viewer ldd --help` for listing the shared libraries a file needs.";
+
+/// Which parts of the file to display, mirroring readelf's options.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub file_header: bool,
+    pub program_headers: bool,
+    pub section_headers: bool,
+    pub symbols: bool,
+    pub dynamic_symbols: bool,
+    pub relocations: bool,
+    pub dynamic: bool,
+    pub notes: bool,
+    pub version_info: bool,
+    pub checksec: bool,
+    pub json: bool,
+}
 
-use reindeer::{
-    elf_aux_structures::ElfSegmentType, range::TryIntoRangeUsize, ElfHeader, ElfProgramHeader,
-    ElfSectionHeader, ElfSectionHeaders, ElfStringTable,
-};
+impl Options {
+    fn is_empty(&self) -> bool {
+        !(self.file_header
+            || self.program_headers
+            || self.section_headers
+            || self.symbols
+            || self.dynamic_symbols
+            || self.relocations
+            || self.dynamic
+            || self.notes
+            || self.version_info
+            || self.checksec)
+    }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = env::args().nth(1).unwrap_or("/bin/true".into());
-    let mut f = File::open(path)?;
-    let mut buffer = Vec::new();
-    f.read_to_end(&mut buffer)?;
+    fn set_all(&mut self) {
+        self.set_headers();
+        self.symbols = true;
+        self.relocations = true;
+        self.dynamic = true;
+        self.notes = true;
+        self.version_info = true;
+    }
 
-    let header = ElfHeader::parse(&buffer).unwrap();
-    let section_headers_location = header
-        .section_headers_location()
-        .unwrap()
-        .try_into_usize()?;
-    let section_headers = ElfSectionHeaders::parse(header, &buffer[section_headers_location])?;
-    let string_table_location = section_headers
-        .string_table_location(header)?
-        .unwrap()
-        .try_into_usize()?;
-    let _string_table = ElfStringTable::parse(&buffer[string_table_location])?;
-
-    // _print_segment_load_locations(header, &buffer)?;
-    _print_section_headers(section_headers, _string_table)?;
-    println!();
-    _print_program_headers(header, &buffer)?;
-
-    for n in 0..header.e_phnum().unwrap().get() {
-        let prog_header_loc = header
-            .program_header_location(n)
-            .unwrap()
-            .try_into_usize()?;
-        let prog_header = ElfProgramHeader::parse(header, &buffer[prog_header_loc]).unwrap();
-        if prog_header.p_type() != ElfSegmentType::PT_LOAD {
-            continue;
+    fn set_headers(&mut self) {
+        self.file_header = true;
+        self.program_headers = true;
+        self.section_headers = true;
+    }
+
+    fn set_short(&mut self, option: char) -> Result<(), String> {
+        match option {
+            'a' => self.set_all(),
+            'e' => self.set_headers(),
+            'h' => self.file_header = true,
+            'l' => self.program_headers = true,
+            'S' => self.section_headers = true,
+            's' => self.symbols = true,
+            'n' => self.notes = true,
+            'r' => self.relocations = true,
+            'd' => self.dynamic = true,
+            'V' => self.version_info = true,
+            'W' => {}
+            _ => return Err(format!("unrecognized option '-{option}'")),
         }
 
-        println!("{:?}", prog_header);
+        Ok(())
     }
 
-    Ok(())
+    fn set_long(&mut self, option: &str) -> Result<(), String> {
+        match option {
+            "all" => self.set_all(),
+            "headers" => self.set_headers(),
+            "file-header" => self.file_header = true,
+            "program-headers" | "segments" => self.program_headers = true,
+            "section-headers" | "sections" => self.section_headers = true,
+            "syms" | "symbols" => self.symbols = true,
+            "dyn-syms" => self.dynamic_symbols = true,
+            "notes" => self.notes = true,
+            "relocs" => self.relocations = true,
+            "dynamic" => self.dynamic = true,
+            "version-info" => self.version_info = true,
+            "checksec" => self.checksec = true,
+            "wide" => {}
+            "json" => self.json = true,
+            _ => return Err(format!("unrecognized option '--{option}'")),
+        }
+
+        Ok(())
+    }
 }
 
-fn _print_segment_load_locations(
-    header: ElfHeader<'_>,
-    buffer: &[u8],
-) -> Result<(), Box<dyn Error>> {
-    for n in 0..header.e_phnum().unwrap().get() {
-        let prog_header_loc = header
-            .program_header_location(n)
-            .unwrap()
-            .try_into_usize()?;
+enum Command {
+    Help,
+    Display(Options, Vec<String>),
+}
 
-        let program_header = ElfProgramHeader::parse(header, &buffer[prog_header_loc]).unwrap();
-        println!(
-            "{:?} into {:?}",
-            program_header.file_location(),
-            program_header.memory_location()
-        );
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut files = Vec::new();
+
+    for arg in args {
+        if arg == "-H" || arg == "--help" {
+            return Ok(Command::Help);
+        } else if let Some(long) = arg.strip_prefix("--") {
+            options.set_long(long)?;
+        } else if let Some(short) = arg.strip_prefix('-').filter(|short| !short.is_empty()) {
+            for option in short.chars() {
+                options.set_short(option)?;
+            }
+        } else {
+            files.push(arg);
+        }
     }
 
-    Ok(())
+    if files.is_empty() {
+        return Err("no input files".into());
+    }
+
+    if options.is_empty() {
+        options.section_headers = true;
+        options.program_headers = true;
+    }
+
+    Ok(Command::Display(options, files))
+}
+
+fn display_file(path: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut f = File::open(path)?;
+    let mut buffer = Vec::new();
+    f.read_to_end(&mut buffer)?;
+
+    let file = ElfFile::parse(&buffer)?;
+
+    // Errors about a header are rendered with the name of its section.
+    display_parsed(path, &file, options).map_err(|error| match error.downcast::<ElfError>() {
+        Ok(error) => error.display_in(&file).to_string().into(),
+        Err(error) => error,
+    })
 }
 
-fn _print_program_headers(header: ElfHeader<'_>, buffer: &[u8]) -> Result<(), Box<dyn Error>> {
-    println!(
-        "Type           Offset   VirtAddr           PhysAddr           FileSize MemSize  Flags Align"
-    );
-
-    for n in 0..header.e_phnum().unwrap().get() {
-        let prog_header_loc = header
-            .program_header_location(n)
-            .unwrap()
-            .try_into_usize()?;
-
-        let program_header = ElfProgramHeader::parse(header, &buffer[prog_header_loc]).unwrap();
-        let ElfProgramHeader::Elf64(prog_header) = program_header else {
-            panic!()
-        };
-
-        println!(
-            "{:<15} 0x{:06x} {:018x} {:018x} 0x{:06x} 0x{:06x} {:5} 0x{:<4x}",
-            program_header
-                .p_type()
-                .name()
-                .unwrap_or(&format!("{:#x}", program_header.p_type().0)),
-            prog_header.p_offset,
-            prog_header.p_vaddr,
-            prog_header.p_paddr,
-            prog_header.p_filesz.map(Into::into).unwrap_or(0),
-            prog_header.p_memsz.map(Into::into).unwrap_or(0),
-            prog_header.p_flags,
-            prog_header.p_align,
-        );
+fn display_parsed(path: &str, file: &ElfFile, options: &Options) -> Result<(), Box<dyn Error>> {
+    if options.json {
+        return json::print_file(path, file);
+    }
+
+    // The order matches readelf's, so that outputs can be compared.
+    if options.file_header {
+        readelf::print_file_header(file)?;
+    }
+    if options.section_headers {
+        readelf::print_section_headers(file, options)?;
+    }
+    if options.program_headers {
+        readelf::print_program_headers(file, options)?;
+    }
+    if options.dynamic {
+        readelf::print_dynamic(file)?;
+    }
+    if options.relocations {
+        readelf::print_relocations(file)?;
+    }
+    if options.symbols || options.dynamic_symbols {
+        readelf::print_symbols(file, options)?;
+    }
+    if options.version_info {
+        readelf::print_version_info(file)?;
+    }
+    if options.notes {
+        readelf::print_notes(file)?;
+    }
+    if options.checksec {
+        checksec::print_hardening(file)?;
     }
 
     Ok(())
 }
 
-fn _print_section_headers(
-    section_headers: ElfSectionHeaders,
-    string_table: ElfStringTable,
-) -> Result<(), Box<dyn Error>> {
-    println!(
-        "[Nr] Name                  Type            Address          Off    Size   Flags Align"
-    );
-    for (n, section_header) in section_headers.into_iter().enumerate() {
-        let name = string_table.section_name(section_header)?;
-
-        let ElfSectionHeader::Elf64(sec_header) = section_header else {
-            panic!()
-        };
-
-        println!(
-            "[{:02}] {:<21} {:<15} {:016x} {:06x} {:06x} {:5x} {:5}",
-            n,
-            name,
-            section_header
-                .sh_type()
-                .name()
-                .unwrap_or(&format!("{:#x}", section_header.sh_type().0)),
-            sec_header.sh_addr.map(|v| v.get()).unwrap_or(0),
-            sec_header.sh_offset,
-            sec_header.sh_size,
-            sec_header.sh_flags,
-            sec_header.sh_addralign,
-        );
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+    if args.next_if(|arg| arg == "export").is_some() {
+        return export::main(args);
+    }
+    if args.next_if(|arg| arg == "ldd").is_some() {
+        return ldd::main(args);
     }
 
-    Ok(())
+    let (options, files) = match parse_args(args) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Display(options, files)) => (options, files),
+        Err(message) => {
+            eprintln!("elf-viewer: error: {message}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for path in &files {
+        if files.len() > 1 && !options.json {
+            println!("\nFile: {path}");
+        }
+
+        if let Err(error) = display_file(path, &options) {
+            eprintln!("elf-viewer: error: {path}: {error}");
+            status = ExitCode::FAILURE;
+        }
+    }
+
+    status
 }
@@ -0,0 +1,1324 @@
+//! Text output in the style of binutils' `readelf -W`.
+//!
+//! The column layouts, headings and names follow readelf's closely enough
+//! that the output of both tools can be compared with `diff`.
+
+use std::{error::Error, fmt::Display};
+
+use reindeer::{
+    dynamic::ElfDynamic,
+    elf_aux_structures::*,
+    mapping,
+    notes::{ElfGnuProperties, ElfGnuProperty, ElfNote, ElfNotes},
+    packed_relocations::ElfRelrTable,
+    relocation_types::ElfRelocationType,
+    relocations::ElfRelocationTable,
+    symbols::ElfSymbol,
+    versions::{ElfVersionDefinitions, ElfVersionNeeds, ElfVersionSymbolTable},
+    ElfFile, ElfHeader, ElfSectionHeader, ElfStringTable,
+};
+use zerocopy::AsBytes;
+
+use crate::Options;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+/// Strips the prefix from a `declare_constants!` name, as readelf does.
+fn short_name(name: Option<&'static str>, prefix: &str) -> Option<&'static str> {
+    name.and_then(|name| name.strip_prefix(prefix))
+}
+
+/// Formats like C's `%#x`, which prints zero without the `0x` prefix.
+fn c_hex(value: u64) -> String {
+    if value == 0 {
+        "0".into()
+    } else {
+        format!("{value:#x}")
+    }
+}
+
+/// Formats like C's `%#06x`.
+fn c_hex06(value: usize) -> String {
+    if value == 0 {
+        "000000".into()
+    } else {
+        format!("{value:#06x}")
+    }
+}
+
+fn plural(count: usize, singular: &'static str, plural: &'static str) -> &'static str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
+fn field(label: &str, value: impl Display) {
+    println!("  {label:<35}{value}");
+}
+
+/// The number of hex digits readelf uses for addresses in this class.
+fn address_width(header: ElfHeader) -> usize {
+    match header {
+        ElfHeader::Elf32(_) => 8,
+        ElfHeader::Elf64(_) => 16,
+    }
+}
+
+fn class_name(header: ElfHeader) -> &'static str {
+    match header {
+        ElfHeader::Elf32(_) => "ELF32",
+        ElfHeader::Elf64(_) => "ELF64",
+    }
+}
+
+fn os_abi_name(os_abi: ElfIdentOsAbi) -> String {
+    let name = match os_abi {
+        ElfIdentOsAbi::OSABI_SYSV => "UNIX - System V",
+        ElfIdentOsAbi::OSABI_HPUX => "UNIX - HP-UX",
+        ElfIdentOsAbi::OSABI_NETBSD => "UNIX - NetBSD",
+        ElfIdentOsAbi::OSABI_GNU => "UNIX - GNU",
+        ElfIdentOsAbi::OSABI_SOLARIS => "UNIX - Solaris",
+        ElfIdentOsAbi::OSABI_AIX => "UNIX - AIX",
+        ElfIdentOsAbi::OSABI_IRIX => "UNIX - IRIX",
+        ElfIdentOsAbi::OSABI_FREEBSD => "UNIX - FreeBSD",
+        ElfIdentOsAbi::OSABI_TRU64 => "UNIX - TRU64",
+        ElfIdentOsAbi::OSABI_MODESTO => "Novell - Modesto",
+        ElfIdentOsAbi::OSABI_OPENBSD => "UNIX - OpenBSD",
+        ElfIdentOsAbi::OSABI_ARM_AEABI => "ARM EABI",
+        ElfIdentOsAbi::OSABI_ARM => "ARM",
+        ElfIdentOsAbi::OSABI_STANDALONE => "Standalone App",
+        ElfIdentOsAbi(other) => return format!("<unknown: {other:x}>"),
+    };
+
+    name.into()
+}
+
+fn machine_name(machine: ElfHeaderMachine) -> String {
+    let name = match machine {
+        ElfHeaderMachine::EM_NONE => "None",
+        ElfHeaderMachine::EM_M32 => "WE32100",
+        ElfHeaderMachine::EM_SPARC => "Sparc",
+        ElfHeaderMachine::EM_386 => "Intel 80386",
+        ElfHeaderMachine::EM_68K => "MC68000",
+        ElfHeaderMachine::EM_88K => "MC88000",
+        ElfHeaderMachine::EM_860 => "Intel 80860",
+        ElfHeaderMachine::EM_MIPS => "MIPS R3000",
+        ElfHeaderMachine::EM_PARISC => "HPPA",
+        ElfHeaderMachine::EM_SPARC32PLUS => "Sparc v8+",
+        ElfHeaderMachine::EM_PPC => "PowerPC",
+        ElfHeaderMachine::EM_PPC64 => "PowerPC64",
+        ElfHeaderMachine::EM_S390 => "IBM S/390",
+        ElfHeaderMachine::EM_ARM => "ARM",
+        ElfHeaderMachine::EM_SH => "Renesas / SuperH SH",
+        ElfHeaderMachine::EM_SPARCV9 => "Sparc v9",
+        ElfHeaderMachine::EM_IA_64 => "Intel IA-64",
+        ElfHeaderMachine::EM_X86_64 => "Advanced Micro Devices X86-64",
+        ElfHeaderMachine::EM_AVR => "Atmel AVR 8-bit microcontroller",
+        ElfHeaderMachine::EM_XTENSA => "Tensilica Xtensa Processor",
+        ElfHeaderMachine::EM_MSP430 => "Texas Instruments msp430 microcontroller",
+        ElfHeaderMachine::EM_AARCH64 => "AArch64",
+        ElfHeaderMachine::EM_RISCV => "RISC-V",
+        ElfHeaderMachine::EM_BPF => "Linux BPF",
+        ElfHeaderMachine::EM_LOONGARCH => "LoongArch",
+        ElfHeaderMachine(other) => return format!("<unknown>: {other:#x}"),
+    };
+
+    name.into()
+}
+
+fn is_pie(file: &ElfFile) -> bool {
+    let flags_1 = file
+        .dynamic_table()
+        .ok()
+        .flatten()
+        .and_then(|dynamic| dynamic.value(ElfDynamicTag::DT_FLAGS_1));
+
+    flags_1.is_some_and(|flags| ElfDynamicFlags1(flags).contains(ElfDynamicFlags1::DF_1_PIE))
+}
+
+fn file_type_name(file: &ElfFile) -> String {
+    let name = match file.header().e_type() {
+        ElfHeaderType::ET_NONE => "NONE (None)",
+        ElfHeaderType::ET_REL => "REL (Relocatable file)",
+        ElfHeaderType::ET_EXEC => "EXEC (Executable file)",
+        ElfHeaderType::ET_DYN if is_pie(file) => "DYN (Position-Independent Executable file)",
+        ElfHeaderType::ET_DYN => "DYN (Shared object file)",
+        ElfHeaderType::ET_CORE => "CORE (Core file)",
+        ElfHeaderType(other) if other >= ElfHeaderType::ET_LOPROC.0 => {
+            return format!("Processor Specific: ({other:x})")
+        }
+        ElfHeaderType(other) => return format!("<unknown>: {other:x}"),
+    };
+
+    name.into()
+}
+
+fn section_type_name(section_type: ElfSectionType) -> String {
+    if let Some(name) = short_name(section_type.name(), "SHT_") {
+        return name.into();
+    }
+
+    let ElfSectionType(value) = section_type;
+    if value >= ElfSectionType::SHT_LOUSER.0 {
+        format!("LOUSER+{:#x}", value - ElfSectionType::SHT_LOUSER.0)
+    } else if value >= ElfSectionType::SHT_LOPROC.0 {
+        format!("LOPROC+{:#x}", value - ElfSectionType::SHT_LOPROC.0)
+    } else if value >= ElfSectionType::SHT_LOOS.0 {
+        format!("LOOS+{:#x}", value - ElfSectionType::SHT_LOOS.0)
+    } else {
+        format!("<unknown>: {value:x}")
+    }
+}
+
+fn segment_type_name(segment_type: ElfSegmentType) -> String {
+    if let Some(name) = short_name(segment_type.name(), "PT_") {
+        return name.into();
+    }
+
+    let ElfSegmentType(value) = segment_type;
+    if (ElfSegmentType::PT_LOPROC.0..=ElfSegmentType::PT_HIPROC.0).contains(&value) {
+        format!("LOPROC+{:#x}", value - ElfSegmentType::PT_LOPROC.0)
+    } else if (ElfSegmentType::PT_LOOS.0..=ElfSegmentType::PT_HIOS.0).contains(&value) {
+        format!("LOOS+{:#x}", value - ElfSegmentType::PT_LOOS.0)
+    } else {
+        format!("<unknown>: {value:x}")
+    }
+}
+
+fn section_flags(machine: ElfHeaderMachine, flags: u64) -> String {
+    let mut letters = String::new();
+    let mut remaining = flags;
+
+    while remaining != 0 {
+        let flag = remaining & remaining.wrapping_neg();
+        remaining &= !flag;
+
+        let letter = match ElfSectionFlags(flag) {
+            ElfSectionFlags::SHF_WRITE => 'W',
+            ElfSectionFlags::SHF_ALLOC => 'A',
+            ElfSectionFlags::SHF_EXECINSTR => 'X',
+            ElfSectionFlags::SHF_MERGE => 'M',
+            ElfSectionFlags::SHF_STRINGS => 'S',
+            ElfSectionFlags::SHF_INFO_LINK => 'I',
+            ElfSectionFlags::SHF_LINK_ORDER => 'L',
+            ElfSectionFlags::SHF_OS_NONCONFORMING => 'O',
+            ElfSectionFlags::SHF_GROUP => 'G',
+            ElfSectionFlags::SHF_TLS => 'T',
+            ElfSectionFlags::SHF_COMPRESSED => 'C',
+            ElfSectionFlags::SHF_EXCLUDE => 'E',
+            ElfSectionFlags::SHF_GNU_RETAIN => 'R',
+            // SHF_X86_64_LARGE
+            ElfSectionFlags(0x10000000) if machine == ElfHeaderMachine::EM_X86_64 => 'l',
+            other if ElfSectionFlags::SHF_MASKOS.contains(other) => 'o',
+            other if ElfSectionFlags::SHF_MASKPROC.contains(other) => 'p',
+            _ => 'x',
+        };
+
+        letters.push(letter);
+    }
+
+    letters
+}
+
+fn segment_flags(flags: u32) -> String {
+    let flags = ElfSegmentFlags(flags);
+    let flag = |flag, letter| if flags.contains(flag) { letter } else { ' ' };
+
+    [
+        flag(ElfSegmentFlags::PF_R, 'R'),
+        flag(ElfSegmentFlags::PF_W, 'W'),
+        flag(ElfSegmentFlags::PF_X, 'E'),
+    ]
+    .iter()
+    .collect()
+}
+
+pub fn print_file_header(file: &ElfFile) -> Result {
+    let header = file.header();
+    let ident = header.e_ident();
+
+    println!("ELF Header:");
+    print!("  Magic:   ");
+    for byte in ident.as_bytes() {
+        print!("{byte:02x} ");
+    }
+    println!();
+
+    field("Class:", class_name(header));
+    field("Data:", "2's complement, little endian");
+    field("Version:", format!("{} (current)", ident.ei_version.0));
+    field("OS/ABI:", os_abi_name(ident.ei_osabi));
+    field("ABI Version:", ident.ei_abiversion);
+    field("Type:", file_type_name(file));
+    field("Machine:", machine_name(header.e_machine()));
+    field("Version:", format!("{:#x}", header.e_version().0));
+    field(
+        "Entry point address:",
+        format!("{:#x}", header.e_entry().map_or(0, |entry| entry.get())),
+    );
+    field(
+        "Start of program headers:",
+        format!(
+            "{} (bytes into file)",
+            header.e_phoff().map_or(0, |off| off.get())
+        ),
+    );
+    field(
+        "Start of section headers:",
+        format!(
+            "{} (bytes into file)",
+            header.e_shoff().map_or(0, |off| off.get())
+        ),
+    );
+    field("Flags:", format!("{:#x}", header.e_flags()));
+    field(
+        "Size of this header:",
+        format!("{} (bytes)", header.e_ehsize()),
+    );
+    field(
+        "Size of program headers:",
+        format!("{} (bytes)", header.e_phentsize()),
+    );
+    field(
+        "Number of program headers:",
+        header.e_phnum().map_or(0, |num| num.get()),
+    );
+    field(
+        "Size of section headers:",
+        format!("{} (bytes)", header.e_shentsize()),
+    );
+    field(
+        "Number of section headers:",
+        header.e_shnum().map_or(0, |num| num.get()),
+    );
+    field(
+        "Section header string table index:",
+        header.e_shstrndx().map_or(0, |index| index.get()),
+    );
+
+    Ok(())
+}
+
+pub(crate) fn section_name<'a>(file: &ElfFile<'a>, section: ElfSectionHeader) -> &'a str {
+    file.section_name(section).unwrap_or("<corrupt>")
+}
+
+pub fn print_section_headers(file: &ElfFile, options: &Options) -> Result {
+    let header = file.header();
+    let Some(sections) = file.section_headers() else {
+        println!("\nThere are no sections in this file.");
+        return Ok(());
+    };
+
+    if !options.file_header {
+        println!(
+            "There are {} section headers, starting at offset {:#x}:",
+            sections.len(),
+            header.e_shoff().map_or(0, |off| off.get())
+        );
+    }
+
+    let width = address_width(header);
+    println!("\nSection Headers:");
+    println!(
+        "  [Nr] Name              Type            {:<width$} Off    Size   ES Flg Lk Inf Al",
+        if width == 8 { "Addr" } else { "Address" }
+    );
+
+    for (n, section) in sections.into_iter().enumerate() {
+        println!(
+            "  [{:2}] {:<17} {:<15} {:0width$x} {:06x} {:06x} {:02x} {:>3} {:2} {:3} {:2}",
+            n,
+            section_name(file, section),
+            section_type_name(section.sh_type()),
+            section.sh_addr().map_or(0, |addr| addr.get()),
+            section.sh_offset(),
+            section.sh_size(),
+            section.sh_entsize().map_or(0, |size| size.get()),
+            section_flags(header.e_machine(), section.sh_flags()),
+            section.sh_link(),
+            section.sh_info(),
+            section.sh_addralign(),
+        );
+    }
+
+    println!("Key to Flags:");
+    println!("  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),");
+    println!("  L (link order), O (extra OS processing required), G (group), T (TLS),");
+    println!("  C (compressed), x (unknown), o (OS specific), E (exclude),");
+    print!("  ");
+    if matches!(
+        header.e_ident().ei_osabi,
+        ElfIdentOsAbi::OSABI_GNU | ElfIdentOsAbi::OSABI_FREEBSD
+    ) {
+        print!("R (retain), ");
+    }
+    print!("D (mbind), ");
+    match header.e_machine() {
+        ElfHeaderMachine::EM_X86_64 => print!("l (large), "),
+        ElfHeaderMachine::EM_ARM => print!("y (purecode), "),
+        ElfHeaderMachine::EM_PPC => print!("v (VLE), "),
+        _ => {}
+    }
+    println!("p (processor specific)");
+
+    Ok(())
+}
+
+pub fn print_program_headers(file: &ElfFile, options: &Options) -> Result {
+    let header = file.header();
+    let Some(segments) = file.program_headers() else {
+        println!("\nThere are no program headers in this file.");
+        return Ok(());
+    };
+
+    if !options.file_header {
+        println!("\nElf file type is {}", file_type_name(file));
+        println!(
+            "Entry point {:#x}",
+            header.e_entry().map_or(0, |entry| entry.get())
+        );
+        println!(
+            "There are {} program headers, starting at offset {}",
+            segments.len(),
+            header.e_phoff().map_or(0, |off| off.get())
+        );
+    }
+
+    // readelf prints 32-bit sizes with five digits and 64-bit ones with six.
+    let width = address_width(header);
+    let size_width: usize = if width == 8 { 5 } else { 6 };
+    println!("\nProgram Headers:");
+    println!(
+        "  Type           Offset   {:<w$} {:<w$} {:<s$} {:<s$} Flg Align",
+        "VirtAddr",
+        "PhysAddr",
+        "FileSiz",
+        "MemSiz",
+        w = width.saturating_add(2),
+        s = size_width.saturating_add(2),
+    );
+
+    for segment in segments {
+        println!(
+            "  {:<14} 0x{:06x} 0x{:0width$x} 0x{:0width$x} 0x{:0size_width$x} 0x{:0size_width$x} {} {}",
+            segment_type_name(segment.p_type()),
+            segment.p_offset(),
+            segment.p_vaddr(),
+            segment.p_paddr(),
+            segment.p_filesz().map_or(0, |size| size.get()),
+            segment.p_memsz().map_or(0, |size| size.get()),
+            segment_flags(segment.p_flags()),
+            c_hex(segment.p_align()),
+        );
+
+        if segment.p_type() == ElfSegmentType::PT_INTERP {
+            if let Ok(Some(interpreter)) = file.interpreter() {
+                println!("      [Requesting program interpreter: {interpreter}]");
+            }
+        }
+    }
+
+    if file.section_headers().is_none() {
+        return Ok(());
+    }
+
+    println!("\n Section to Segment mapping:");
+    println!("  Segment Sections...");
+    for (n, segment) in segments.into_iter().enumerate() {
+        print!("   {n:02}     ");
+        for (_, section) in mapping::segment_sections(file, segment) {
+            print!("{} ", section_name(file, section));
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+fn dynamic_offset(file: &ElfFile) -> Option<u64> {
+    if let Some(segment) = file
+        .segments()
+        .find(|segment| segment.p_type() == ElfSegmentType::PT_DYNAMIC)
+    {
+        return Some(segment.p_offset());
+    }
+
+    file.section_by_type(ElfSectionType::SHT_DYNAMIC)
+        .map(|section| section.sh_offset())
+}
+
+fn dynamic_value(entry: ElfDynamic, strings: Option<ElfStringTable>) -> String {
+    let value = entry.d_val();
+    let string = || {
+        let index = u32::try_from(value).ok()?;
+        strings?.get(index).ok()
+    };
+
+    match entry.d_tag() {
+        ElfDynamicTag::DT_NEEDED => {
+            format!("Shared library: [{}]", string().unwrap_or("<corrupt>"))
+        }
+        ElfDynamicTag::DT_SONAME => {
+            format!("Library soname: [{}]", string().unwrap_or("<corrupt>"))
+        }
+        ElfDynamicTag::DT_RPATH => format!("Library rpath: [{}]", string().unwrap_or("<corrupt>")),
+        ElfDynamicTag::DT_RUNPATH => {
+            format!("Library runpath: [{}]", string().unwrap_or("<corrupt>"))
+        }
+        ElfDynamicTag::DT_FLAGS => flag_names(value, |flag| {
+            short_name(ElfDynamicFlags(flag).name(), "DF_")
+        }),
+        ElfDynamicTag::DT_FLAGS_1 => format!(
+            "Flags: {}",
+            flag_names(value, |flag| short_name(
+                ElfDynamicFlags1(flag).name(),
+                "DF_1_"
+            ))
+        ),
+        ElfDynamicTag::DT_PLTREL => i64::try_from(value)
+            .ok()
+            .and_then(|tag| short_name(ElfDynamicTag(tag).name(), "DT_"))
+            .map_or_else(|| c_hex(value), Into::into),
+        ElfDynamicTag::DT_PLTRELSZ
+        | ElfDynamicTag::DT_RELASZ
+        | ElfDynamicTag::DT_STRSZ
+        | ElfDynamicTag::DT_RELSZ
+        | ElfDynamicTag::DT_RELAENT
+        | ElfDynamicTag::DT_SYMENT
+        | ElfDynamicTag::DT_RELENT
+        | ElfDynamicTag::DT_INIT_ARRAYSZ
+        | ElfDynamicTag::DT_FINI_ARRAYSZ
+        | ElfDynamicTag::DT_PREINIT_ARRAYSZ
+        | ElfDynamicTag::DT_RELRSZ
+        | ElfDynamicTag::DT_RELRENT => format!("{value} (bytes)"),
+        ElfDynamicTag::DT_VERDEFNUM
+        | ElfDynamicTag::DT_VERNEEDNUM
+        | ElfDynamicTag::DT_RELACOUNT
+        | ElfDynamicTag::DT_RELCOUNT => value.to_string(),
+        _ => format!("{value:#x}"),
+    }
+}
+
+fn flag_names(value: u64, name: impl Fn(u64) -> Option<&'static str>) -> String {
+    let mut names = Vec::new();
+    let mut remaining = value;
+
+    while remaining != 0 {
+        let flag = remaining & remaining.wrapping_neg();
+        remaining &= !flag;
+        names.push(name(flag).unwrap_or("unknown"));
+    }
+
+    names.join(" ")
+}
+
+pub fn print_dynamic(file: &ElfFile) -> Result {
+    let Some(dynamic) = file.dynamic_table()? else {
+        println!("\nThere is no dynamic section in this file.");
+        return Ok(());
+    };
+    let strings = file.dynamic_string_table().ok().flatten();
+
+    let width = address_width(file.header());
+    let count = dynamic.len();
+    println!(
+        "\nDynamic section at offset {:#x} contains {} {}:",
+        dynamic_offset(file).unwrap_or(0),
+        count,
+        plural(count, "entry", "entries")
+    );
+    println!("  Tag        Type                         Name/Value");
+
+    for entry in dynamic {
+        let tag = entry.d_tag();
+        let name = short_name(tag.name(), "DT_")
+            .map_or_else(|| format!("<unknown>: {:x}", tag.0), Into::into);
+        let padding = if width == 8 { 27usize } else { 19 };
+        let padding = padding.saturating_sub(name.len()).max(1);
+
+        // Tags are sign extended from 32 bits, so mask them back to the class width.
+        let tag_bits = match file.header() {
+            ElfHeader::Elf32(_) => tag.0 & 0xffff_ffff,
+            ElfHeader::Elf64(_) => tag.0,
+        };
+        println!(
+            " 0x{:0width$x} ({}){:padding$}{}",
+            tag_bits,
+            name,
+            "",
+            dynamic_value(entry, strings),
+        );
+    }
+
+    Ok(())
+}
+
+/// The symbol versioning tables, used to annotate dynamic symbol names.
+struct SymbolVersions<'buf> {
+    symbols: ElfVersionSymbolTable<'buf>,
+    needs: Option<(ElfVersionNeeds<'buf>, ElfStringTable<'buf>)>,
+    definitions: Option<(ElfVersionDefinitions<'buf>, ElfStringTable<'buf>)>,
+}
+
+enum SymbolVersion<'buf> {
+    Needed(&'buf str, u16),
+    Default(&'buf str),
+    Hidden(&'buf str),
+}
+
+impl<'buf> SymbolVersions<'buf> {
+    fn parse(file: &ElfFile<'buf>) -> Option<Self> {
+        let section = file.section_by_type(ElfSectionType::SHT_VERSYM)?;
+        let symbols = ElfVersionSymbolTable::parse(file.section_data(section).ok()?).ok()?;
+
+        let needs = file
+            .section_by_type(ElfSectionType::SHT_VERNEED)
+            .and_then(|section| {
+                let data = file.section_data(section).ok()?;
+                let strings = file.linked_string_table(section).ok()?;
+                Some((ElfVersionNeeds::parse(data, section.sh_info()), strings))
+            });
+
+        let definitions = file
+            .section_by_type(ElfSectionType::SHT_VERDEF)
+            .and_then(|section| {
+                let data = file.section_data(section).ok()?;
+                let strings = file.linked_string_table(section).ok()?;
+                Some((
+                    ElfVersionDefinitions::parse(data, section.sh_info()),
+                    strings,
+                ))
+            });
+
+        Some(Self {
+            symbols,
+            needs,
+            definitions,
+        })
+    }
+
+    fn needed_name(&self, version: u16) -> Option<&'buf str> {
+        let (needs, strings) = self.needs?;
+        let (_, aux) = needs.find(version)?;
+        strings.get(aux.vernaux.vna_name).ok()
+    }
+
+    fn definition_name(&self, version: u16) -> Option<&'buf str> {
+        let (definitions, strings) = self.definitions?;
+        let definition = definitions.find(version)?;
+        strings.get(definition.name()?).ok()
+    }
+
+    /// Mirrors readelf's `get_symbol_version_string`.
+    fn get(&self, index: usize, symbol: ElfSymbol) -> Option<SymbolVersion<'buf>> {
+        let raw = self.symbols.get(index)?;
+        let version = raw & ElfVersionSymbolTable::VERSYM_VERSION;
+        let hidden = raw & ElfVersionSymbolTable::VERSYM_HIDDEN != 0;
+
+        if version <= ElfVersionSymbolTable::VER_NDX_GLOBAL {
+            return None;
+        }
+
+        if !symbol.is_undefined() {
+            if let Some(name) = self.definition_name(version) {
+                return Some(if hidden {
+                    SymbolVersion::Hidden(name)
+                } else {
+                    SymbolVersion::Default(name)
+                });
+            }
+        }
+
+        self.needed_name(version)
+            .map(|name| SymbolVersion::Needed(name, version))
+    }
+
+    fn suffix(&self, index: usize, symbol: ElfSymbol, symbol_name: &str) -> String {
+        match self.get(index, symbol) {
+            Some(SymbolVersion::Needed(name, version)) => format!("@{name} ({version})"),
+            // The symbols naming each version definition aren't annotated.
+            Some(SymbolVersion::Default(name)) if name == symbol_name => String::new(),
+            Some(SymbolVersion::Default(name)) => format!("@@{name}"),
+            Some(SymbolVersion::Hidden(name)) => format!("@{name}"),
+            None => String::new(),
+        }
+    }
+
+    /// Like [`Self::suffix`], but without the version index of needed
+    /// versions, as readelf shows the symbols of relocations.
+    fn relocation_suffix(&self, index: usize, symbol: ElfSymbol, symbol_name: &str) -> String {
+        match self.get(index, symbol) {
+            Some(SymbolVersion::Needed(name, _)) => format!("@{name}"),
+            _ => self.suffix(index, symbol, symbol_name),
+        }
+    }
+}
+
+fn symbol_type_name(symbol_type: ElfSymbolType) -> String {
+    let name = symbol_type.name();
+    short_name(name, "STT_GNU_")
+        .or_else(|| short_name(name, "STT_"))
+        .map_or_else(|| format!("<unknown>: {}", symbol_type.0), Into::into)
+}
+
+fn symbol_binding_name(binding: ElfSymbolBinding) -> String {
+    let name = binding.name();
+    short_name(name, "STB_GNU_")
+        .or_else(|| short_name(name, "STB_"))
+        .map_or_else(|| format!("<unknown>: {}", binding.0), Into::into)
+}
+
+fn symbol_section_index(index: ElfSectionIndex) -> String {
+    match index {
+        ElfSectionIndex::SHN_UNDEF => "UND".into(),
+        ElfSectionIndex::SHN_ABS => "ABS".into(),
+        ElfSectionIndex::SHN_COMMON => "COM".into(),
+        ElfSectionIndex(index) if index >= ElfSectionIndex::SHN_LORESERVE.0 => {
+            format!("RSV[{index:#06x}]")
+        }
+        ElfSectionIndex(index) => format!("{index:3}"),
+    }
+}
+
+/// The name of a symbol. Section symbols are usually unnamed, and take the
+/// name of their section.
+fn symbol_name<'a>(file: &ElfFile<'a>, strings: ElfStringTable<'a>, symbol: ElfSymbol) -> &'a str {
+    if symbol.symbol_type() == ElfSymbolType::STT_SECTION && symbol.st_name() == 0 {
+        if let Some(section) = file
+            .section_headers()
+            .and_then(|sections| sections.get(symbol.st_shndx().0))
+        {
+            return section_name(file, section);
+        }
+    }
+
+    strings.get(symbol.st_name()).unwrap_or("<corrupt>")
+}
+
+pub fn print_symbols(file: &ElfFile, options: &Options) -> Result {
+    let width = address_width(file.header());
+    let heading = match file.header() {
+        ElfHeader::Elf32(_) => "   Num:    Value  Size Type    Bind   Vis      Ndx Name",
+        ElfHeader::Elf64(_) => "   Num:    Value          Size Type    Bind   Vis      Ndx Name",
+    };
+    let versions = SymbolVersions::parse(file);
+
+    for section in file.sections() {
+        let is_dynamic = section.sh_type() == ElfSectionType::SHT_DYNSYM;
+        let is_static = section.sh_type() == ElfSectionType::SHT_SYMTAB;
+        if !(is_dynamic || (is_static && options.symbols)) {
+            continue;
+        }
+
+        let symbols = file.symbol_table(section)?;
+        let strings = file.linked_string_table(section)?;
+
+        println!(
+            "\nSymbol table '{}' contains {} {}:",
+            section_name(file, section),
+            symbols.len(),
+            plural(symbols.len(), "entry", "entries")
+        );
+        println!("{heading}");
+
+        for (n, symbol) in symbols.into_iter().enumerate() {
+            let size = symbol.st_size();
+            let size = if size <= 99999 {
+                format!("{size:5}")
+            } else {
+                format!("{size:#x}")
+            };
+            let name = symbol_name(file, strings, symbol);
+            let version = match &versions {
+                Some(versions) if is_dynamic => versions.suffix(n, symbol, name),
+                _ => String::new(),
+            };
+
+            println!(
+                "{:6}: {:0width$x} {} {:<7} {:<6} {:<7} {:>4} {}{}",
+                n,
+                symbol.st_value(),
+                size,
+                symbol_type_name(symbol.symbol_type()),
+                symbol_binding_name(symbol.binding()),
+                short_name(symbol.visibility().name(), "STV_").unwrap_or_default(),
+                symbol_section_index(symbol.st_shndx()),
+                name,
+                version,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_relocations(file: &ElfFile) -> Result {
+    let header = file.header();
+    let width = address_width(header);
+    let versions = SymbolVersions::parse(file);
+    let mut found = false;
+
+    for section in file.sections() {
+        let section_type = section.sh_type();
+        if !matches!(
+            section_type,
+            ElfSectionType::SHT_REL
+                | ElfSectionType::SHT_RELA
+                | ElfSectionType::SHT_RELR
+                | ElfSectionType::SHT_ANDROID_REL
+                | ElfSectionType::SHT_ANDROID_RELA
+                | ElfSectionType::SHT_ANDROID_RELR
+        ) {
+            continue;
+        }
+        // Like readelf, empty tables aren't listed.
+        let data = file.section_data(section)?;
+        if data.is_empty() {
+            continue;
+        }
+        found = true;
+
+        let relocations = file.relocation_table(section)?;
+        if let ElfRelocationTable::Relr(entries, _) = relocations {
+            print_relr(file, section, entries);
+            continue;
+        }
+        let symbol_section = match section.sh_link() {
+            0 => None,
+            _ => Some(file.linked_section(section)?),
+        };
+        let symbols = match symbol_section {
+            Some(symbol_section) => Some((
+                file.symbol_table(symbol_section)?,
+                file.linked_string_table(symbol_section)?,
+                symbol_section.sh_type() == ElfSectionType::SHT_DYNSYM,
+            )),
+            None => None,
+        };
+
+        println!(
+            "\nRelocation section '{}' at offset {:#x} contains {} {}:",
+            section_name(file, section),
+            section.sh_offset(),
+            relocations.len(),
+            plural(relocations.len(), "entry", "entries")
+        );
+        let heading = match header {
+            ElfHeader::Elf32(_) => " Offset     Info    Type                Sym. Value  Symbol's Name",
+            ElfHeader::Elf64(_) => "    Offset             Info             Type               Symbol's Value  Symbol's Name",
+        };
+        if relocations.is_rela() {
+            println!("{heading} + Addend");
+        } else {
+            println!("{heading}");
+        }
+
+        for relocation in relocations {
+            let r_type = match ElfRelocationType::new(header.e_machine(), relocation.r_type).name()
+            {
+                Some(name) => name.to_string(),
+                None => format!("unrecognized: {:<7x}", relocation.r_type),
+            };
+            print!(
+                "{:0width$x}  {:0width$x} {:<22}",
+                relocation.r_offset,
+                relocation.r_info(header),
+                r_type,
+            );
+
+            let symbol = symbols.and_then(|(table, strings, is_dynamic)| {
+                let symbol = table
+                    .get(relocation.r_sym)
+                    .filter(|_| relocation.r_sym != 0)?;
+                Some((symbol, strings, is_dynamic))
+            });
+
+            match symbol {
+                Some((symbol, strings, is_dynamic)) => {
+                    let name = symbol_name(file, strings, symbol);
+                    let version = match &versions {
+                        Some(versions) if is_dynamic => {
+                            let index = usize::try_from(relocation.r_sym)?;
+                            versions.relocation_suffix(index, symbol, name)
+                        }
+                        _ => String::new(),
+                    };
+                    let gap = if width == 8 { "   " } else { " " };
+                    print!(" {:0width$x}{gap}{}{}", symbol.st_value(), name, version);
+                    match relocation.r_addend {
+                        Some(addend) if addend < 0 => print!(" - {:x}", addend.unsigned_abs()),
+                        Some(addend) => print!(" + {addend:x}"),
+                        None => {}
+                    }
+                }
+                None => match relocation.r_addend {
+                    Some(addend) if addend < 0 => {
+                        print!(
+                            "{:pad$}-{:x}",
+                            "",
+                            addend.unsigned_abs(),
+                            pad = width.saturating_add(4)
+                        )
+                    }
+                    Some(addend) => print!("{:pad$}{addend:x}", "", pad = width.saturating_add(4)),
+                    None => {}
+                },
+            }
+
+            println!();
+        }
+    }
+
+    if !found {
+        println!("\nThere are no relocations in this file.");
+    }
+
+    Ok(())
+}
+
+/// Lists the offsets of a `SHT_RELR` table, which has no symbols or types
+/// to show.
+fn print_relr(file: &ElfFile, section: ElfSectionHeader, entries: ElfRelrTable) {
+    let width = address_width(file.header());
+    let offsets = entries.offsets();
+    let count = offsets.clone().count();
+
+    println!(
+        "\nRelocation section '{}' at offset {:#x} contains {} {}:",
+        section_name(file, section),
+        section.sh_offset(),
+        entries.len(),
+        plural(entries.len(), "entry", "entries")
+    );
+    println!("  {count} {}", plural(count, "offset", "offsets"));
+    for offset in offsets {
+        println!("{offset:0width$x}");
+    }
+}
+
+fn version_flags(flags: u16) -> String {
+    if flags == 0 {
+        return "none".into();
+    }
+
+    let mut names = Vec::new();
+    if flags & ElfVersionDefinitions::VER_FLG_BASE != 0 {
+        names.push("BASE");
+    }
+    if flags & ElfVersionDefinitions::VER_FLG_WEAK != 0 {
+        names.push("WEAK");
+    }
+    // VER_FLG_INFO
+    if flags & 0x4 != 0 {
+        names.push("INFO");
+    }
+    if flags & !0x7 != 0 {
+        names.push("<unknown>");
+    }
+
+    names.join(" | ")
+}
+
+fn print_version_section_heading(
+    file: &ElfFile,
+    section: ElfSectionHeader,
+    kind: &str,
+    count: usize,
+) {
+    println!(
+        "\n{} section '{}' contains {} {}:",
+        kind,
+        section_name(file, section),
+        count,
+        plural(count, "entry", "entries")
+    );
+
+    let link_name = file
+        .linked_section(section)
+        .map(|linked| section_name(file, linked))
+        .unwrap_or("<corrupt>");
+    println!(
+        " Addr: 0x{:016x}  Offset: 0x{:08x}  Link: {} ({})",
+        section.sh_addr().map_or(0, |addr| addr.get()),
+        section.sh_offset(),
+        section.sh_link(),
+        link_name
+    );
+}
+
+pub fn print_version_info(file: &ElfFile) -> Result {
+    let versions = SymbolVersions::parse(file);
+    let mut found = false;
+
+    for section in file.sections() {
+        match section.sh_type() {
+            ElfSectionType::SHT_VERDEF => {
+                found = true;
+                let data = file.section_data(section)?;
+                let strings = file.linked_string_table(section)?;
+                let count = usize::try_from(section.sh_info())?;
+                print_version_section_heading(file, section, "Version definition", count);
+
+                for definition in ElfVersionDefinitions::parse(data, section.sh_info()).iter() {
+                    let definition = definition?;
+                    let verdef = definition.verdef;
+                    print!(
+                        "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  ",
+                        c_hex06(definition.offset),
+                        verdef.vd_version,
+                        version_flags(verdef.vd_flags),
+                        verdef.vd_ndx,
+                        verdef.vd_cnt
+                    );
+
+                    for (n, aux) in definition.auxiliaries().enumerate() {
+                        let aux = aux?;
+                        let name = strings.get(aux.verdaux.vda_name).unwrap_or("<corrupt>");
+                        if n == 0 {
+                            println!("Name: {name}");
+                        } else {
+                            println!("  {}: Parent {}: {}", c_hex06(aux.offset), n, name);
+                        }
+                    }
+                }
+            }
+            ElfSectionType::SHT_VERNEED => {
+                found = true;
+                let data = file.section_data(section)?;
+                let strings = file.linked_string_table(section)?;
+                let count = usize::try_from(section.sh_info())?;
+                print_version_section_heading(file, section, "Version needs", count);
+
+                for need in ElfVersionNeeds::parse(data, section.sh_info()).iter() {
+                    let need = need?;
+                    println!(
+                        "  {}: Version: {}  File: {}  Cnt: {}",
+                        c_hex06(need.offset),
+                        need.verneed.vn_version,
+                        strings.get(need.verneed.vn_file).unwrap_or("<corrupt>"),
+                        need.verneed.vn_cnt
+                    );
+
+                    for aux in need.auxiliaries() {
+                        let aux = aux?;
+                        println!(
+                            "  {}:   Name: {}  Flags: {}  Version: {}",
+                            c_hex06(aux.offset),
+                            strings.get(aux.vernaux.vna_name).unwrap_or("<corrupt>"),
+                            version_flags(aux.vernaux.vna_flags),
+                            aux.vernaux.vna_other
+                        );
+                    }
+                }
+            }
+            ElfSectionType::SHT_VERSYM => {
+                found = true;
+                let Some(versions) = &versions else {
+                    continue;
+                };
+                let count = versions.symbols.len();
+                print_version_section_heading(file, section, "Version symbols", count);
+
+                for start in (0..count).step_by(4) {
+                    print!("  {start:03x}:");
+                    for index in start..(start + 4).min(count) {
+                        let raw = versions.symbols.get(index).unwrap_or_default();
+                        match raw {
+                            0 => print!("   0 (*local*)    "),
+                            1 => print!("   1 (*global*)   "),
+                            _ => {
+                                let version = raw & ElfVersionSymbolTable::VERSYM_VERSION;
+                                let hidden = raw & ElfVersionSymbolTable::VERSYM_HIDDEN != 0;
+                                let name = versions
+                                    .needed_name(version)
+                                    .or_else(|| versions.definition_name(version))
+                                    .unwrap_or("???");
+                                // readelf pads with "%-*s" and a width of
+                                // 12 - strlen(name), which C treats as
+                                // left-justified even when negative.
+                                let entry = format!(
+                                    "{:4x}{}({}{:<width$}",
+                                    version,
+                                    if hidden { 'h' } else { ' ' },
+                                    name,
+                                    ")",
+                                    width = 12usize.abs_diff(name.len())
+                                );
+                                print!("{entry:<18}");
+                            }
+                        }
+                    }
+                    println!();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !found {
+        println!("\nNo version information found in this file.");
+    }
+
+    Ok(())
+}
+
+fn note_type_name(note: &ElfNote) -> String {
+    if !note.is_gnu() {
+        return format!("Unknown note type: ({:#010x})", note.n_type.0);
+    }
+
+    let name = match note.n_type {
+        ElfNoteType::NT_GNU_ABI_TAG => "NT_GNU_ABI_TAG (ABI version tag)",
+        ElfNoteType::NT_GNU_HWCAP => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)",
+        ElfNoteType::NT_GNU_BUILD_ID => "NT_GNU_BUILD_ID (unique build ID bitstring)",
+        ElfNoteType::NT_GNU_GOLD_VERSION => "NT_GNU_GOLD_VERSION (gold version)",
+        ElfNoteType::NT_GNU_PROPERTY_TYPE_0 => "NT_GNU_PROPERTY_TYPE_0",
+        ElfNoteType(other) => return format!("Unknown note type: ({other:#010x})"),
+    };
+
+    name.into()
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Names the set bits of a property bitmask like readelf's `decode_*`
+/// functions, which print `<None>` for an empty mask.
+fn property_bits(value: u32, name: impl Fn(u32) -> Option<&'static str>) -> String {
+    if value == 0 {
+        return "<None>".into();
+    }
+
+    (0..u32::BITS)
+        .map(|bit| 1 << bit)
+        .filter(|bit| value & bit != 0)
+        .map(|bit| name(bit).map_or_else(|| format!("<unknown: {bit:x}>"), Into::into))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Decodes a property the way readelf does, or returns `None` for the
+/// properties it prints as raw data.
+fn gnu_property(machine: ElfHeaderMachine, property: &ElfGnuProperty) -> Option<String> {
+    let is_x86 = matches!(
+        machine,
+        ElfHeaderMachine::EM_X86_64 | ElfHeaderMachine::EM_386
+    );
+
+    let text = match property.pr_type {
+        ElfGnuPropertyType::GNU_PROPERTY_STACK_SIZE => {
+            let size = match *property.data {
+                [a, b, c, d] => u32::from_le_bytes([a, b, c, d]).into(),
+                [a, b, c, d, e, f, g, h] => u64::from_le_bytes([a, b, c, d, e, f, g, h]),
+                _ => return Some("<corrupt length>".into()),
+            };
+            format!("stack size: {}", c_hex(size))
+        }
+        ElfGnuPropertyType::GNU_PROPERTY_NO_COPY_ON_PROTECTED if property.data.is_empty() => {
+            "no copy on protected ".into()
+        }
+        ElfGnuPropertyType::GNU_PROPERTY_1_NEEDED => {
+            let names = property_bits(property.value_u32()?, |bit| {
+                (bit == 1).then_some("indirect external access")
+            });
+            format!("1_needed: {names}")
+        }
+        pr_type if is_x86 => {
+            let value = property.value_u32()?;
+            let (label, names) = match pr_type {
+                ElfGnuPropertyType::GNU_PROPERTY_X86_ISA_1_USED => {
+                    ("x86 ISA used", isa_names(value))
+                }
+                ElfGnuPropertyType::GNU_PROPERTY_X86_ISA_1_NEEDED => {
+                    ("x86 ISA needed", isa_names(value))
+                }
+                ElfGnuPropertyType::GNU_PROPERTY_X86_FEATURE_1_AND => (
+                    "x86 feature",
+                    property_bits(value, |bit| {
+                        short_name(ElfX86Feature1(bit).name(), "GNU_PROPERTY_X86_FEATURE_1_")
+                    }),
+                ),
+                ElfGnuPropertyType::GNU_PROPERTY_X86_FEATURE_2_USED => {
+                    ("x86 feature used", feature_2_names(value))
+                }
+                ElfGnuPropertyType::GNU_PROPERTY_X86_FEATURE_2_NEEDED => {
+                    ("x86 feature needed", feature_2_names(value))
+                }
+                _ => return None,
+            };
+            format!("{label}: {names}")
+        }
+        ElfGnuPropertyType::GNU_PROPERTY_AARCH64_FEATURE_1_AND
+            if machine == ElfHeaderMachine::EM_AARCH64 =>
+        {
+            let names = property_bits(property.value_u32()?, |bit| {
+                short_name(
+                    ElfAarch64Feature1(bit).name(),
+                    "GNU_PROPERTY_AARCH64_FEATURE_1_",
+                )
+            });
+            format!("AArch64 feature: {names}")
+        }
+        _ => return None,
+    };
+
+    Some(text)
+}
+
+fn isa_names(value: u32) -> String {
+    property_bits(value, |bit| match ElfX86Isa1(bit) {
+        ElfX86Isa1::GNU_PROPERTY_X86_ISA_1_BASELINE => Some("x86-64-baseline"),
+        ElfX86Isa1::GNU_PROPERTY_X86_ISA_1_V2 => Some("x86-64-v2"),
+        ElfX86Isa1::GNU_PROPERTY_X86_ISA_1_V3 => Some("x86-64-v3"),
+        ElfX86Isa1::GNU_PROPERTY_X86_ISA_1_V4 => Some("x86-64-v4"),
+        _ => None,
+    })
+}
+
+fn feature_2_names(value: u32) -> String {
+    property_bits(value, |bit| {
+        short_name(ElfX86Feature2(bit).name(), "GNU_PROPERTY_X86_FEATURE_2_")
+    })
+}
+
+/// Prints the properties of a `NT_GNU_PROPERTY_TYPE_0` note, following
+/// readelf's `print_gnu_property_note`.
+fn print_gnu_properties(header: ElfHeader, note: &ElfNote) {
+    print!("\t      Properties: ");
+
+    let size = match header {
+        ElfHeader::Elf32(_) => 4,
+        ElfHeader::Elf64(_) => 8,
+    };
+    if note.desc.len() < 8 || !note.desc.len().is_multiple_of(size) {
+        println!("<corrupt GNU_PROPERTY_TYPE, size = {:#x}>", note.desc.len());
+        return;
+    }
+
+    let mut first = true;
+    for property in ElfGnuProperties::parse(header, note.desc) {
+        if !first {
+            print!(", ");
+        }
+        first = false;
+
+        let Ok(property) = property else {
+            print!("<corrupt property>");
+            break;
+        };
+
+        match gnu_property(header.e_machine(), &property) {
+            Some(text) => print!("{text}"),
+            None => {
+                let kind = if property.pr_type.0 < ElfGnuPropertyType::GNU_PROPERTY_LOPROC.0 {
+                    "unknown"
+                } else if property.pr_type.0 < ElfGnuPropertyType::GNU_PROPERTY_LOUSER.0 {
+                    "processor-specific"
+                } else {
+                    "application-specific"
+                };
+                print!("<{kind} type {:#x} data: ", property.pr_type.0);
+                for byte in property.data {
+                    print!("{byte:02x} ");
+                }
+                print!(">");
+            }
+        }
+    }
+    println!();
+}
+
+fn print_note(header: ElfHeader, note: &ElfNote) {
+    print!(
+        "  {:<20} 0x{:08x}\t{}",
+        String::from_utf8_lossy(note.name),
+        note.desc.len(),
+        note_type_name(note)
+    );
+
+    match note.n_type {
+        ElfNoteType::NT_GNU_BUILD_ID if note.is_gnu() => {
+            println!("\t    Build ID: {}", hex_bytes(note.desc));
+        }
+        ElfNoteType::NT_GNU_ABI_TAG if note.is_gnu() && note.desc.len() >= 16 => {
+            let word = |n: usize| {
+                let bytes = note.desc.get(n * 4..n * 4 + 4).unwrap_or(&[0; 4]);
+                u32::from_le_bytes(bytes.try_into().unwrap_or_default())
+            };
+            let os = match word(0) {
+                0 => "Linux".to_string(),
+                1 => "Hurd".to_string(),
+                2 => "Solaris".to_string(),
+                3 => "FreeBSD".to_string(),
+                4 => "NetBSD".to_string(),
+                5 => "Syllable".to_string(),
+                other => format!("Unknown OS: {other}"),
+            };
+            println!("\t    OS: {}, ABI: {}.{}.{}", os, word(1), word(2), word(3));
+        }
+        ElfNoteType::NT_GNU_GOLD_VERSION if note.is_gnu() => {
+            println!("\t    Version: {}", String::from_utf8_lossy(note.desc));
+        }
+        ElfNoteType::NT_GNU_PROPERTY_TYPE_0 if note.is_gnu() => print_gnu_properties(header, note),
+        _ => {
+            print!("\n   description data: ");
+            for byte in note.desc {
+                print!("{byte:02x} ");
+            }
+            println!();
+        }
+    }
+}
+
+fn print_notes_in(header: ElfHeader, data: &[u8], align: u64) -> Result {
+    println!("  Owner                Data size \tDescription");
+    for note in ElfNotes::parse(data, align) {
+        print_note(header, &note?);
+    }
+
+    Ok(())
+}
+
+pub fn print_notes(file: &ElfFile) -> Result {
+    for section in file.sections() {
+        if section.sh_type() != ElfSectionType::SHT_NOTE {
+            continue;
+        }
+
+        println!(
+            "\nDisplaying notes found in: {}",
+            section_name(file, section)
+        );
+        print_notes_in(
+            file.header(),
+            file.section_data(section)?,
+            section.sh_addralign(),
+        )?;
+    }
+
+    // Without section headers, fall back to the note segments.
+    if file.section_headers().is_none() {
+        for segment in file.segments() {
+            if segment.p_type() != ElfSegmentType::PT_NOTE {
+                continue;
+            }
+
+            println!(
+                "\nDisplaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+                segment.p_offset(),
+                segment.p_filesz().map_or(0, |size| size.get())
+            );
+            print_notes_in(
+                file.header(),
+                file.segment_data(segment)?,
+                segment.p_align(),
+            )?;
+        }
+    }
+
+    Ok(())
+}
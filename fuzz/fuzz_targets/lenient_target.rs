@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::{fuzz_target, Corpus};
+use reindeer::{validate, ElfFile};
+
+fuzz_target!(|buffer: &[u8]| -> Corpus {
+    let Ok((file, _warnings)) = ElfFile::parse_lenient(buffer) else {
+        return Corpus::Reject;
+    };
+
+    for section in file.sections() {
+        let _name = file.section_name(section);
+        let _data = file.section_data(section);
+    }
+    for segment in file.segments() {
+        let _memory_location = segment.memory_location();
+        let _data = file.segment_data(segment);
+    }
+    let _diagnostics = validate::validate(&file);
+
+    Corpus::Keep
+});
@@ -47,7 +47,7 @@ fn get_string_table<'a>(
         .try_into_usize()?;
     let string_table_header = ElfSectionHeader::parse(
         header,
-        &buffer
+        buffer
             .get(string_table_header_location)
             .ok_or("oob for string table header")?,
     )?;
@@ -56,7 +56,7 @@ fn get_string_table<'a>(
         .get(string_table_location)
         .ok_or("oob for string table itself")?;
 
-    Ok(ElfStringTable::parse(&string_table)?)
+    Ok(ElfStringTable::parse(string_table)?)
 }
 
 fn parse_program_headers<'a>(
@@ -74,7 +74,7 @@ fn parse_program_headers<'a>(
 
         let program_header = ElfProgramHeader::parse(
             header,
-            &buffer.get(prog_header_loc).ok_or("prog header oob")?,
+            buffer.get(prog_header_loc).ok_or("prog header oob")?,
         )?;
 
         headers.push(program_header);
@@ -98,7 +98,7 @@ fn parse_section_headers<'a>(
 
         let section_header = ElfSectionHeader::parse(
             header,
-            &buffer
+            buffer
                 .get(section_header_location)
                 .ok_or("section header oob")?,
         )?;
@@ -0,0 +1,311 @@
+//! Reading compressed sections, which are usually the debugging sections
+//! of a file linked with `--compress-debug-sections`.
+//!
+//! A `SHF_COMPRESSED` section starts with an [`Elf32CompressionHeader`] or
+//! [`Elf64CompressionHeader`] giving the algorithm and uncompressed size.
+//! The older GNU format instead renames `.debug_*` to `.zdebug_*`, and starts
+//! the data with `ZLIB` and the big-endian uncompressed size.
+//!
+//! zlib needs the `zlib` feature and zstd the `zstd` feature. Without them,
+//! such sections are an [`ElfError::UnsupportedCompression`]. The xz data of
+//! MiniDebugInfo needs the `xz` feature.
+
+use core::mem::size_of;
+use std::{borrow::Cow, vec::Vec};
+
+use zerocopy::FromBytes;
+
+use crate::{
+    dwarf::DwarfSections, elf_aux_structures::*, elf_structures::*, errors::ElfError, ElfFile,
+    ElfHeader, ElfSectionHeader,
+};
+
+const GNU_MAGIC: &[u8; 4] = b"ZLIB";
+
+/// The uncompressed contents of a section, which are borrowed from the file
+/// unless it is compressed.
+pub fn section_contents<'buf>(
+    file: &ElfFile<'buf>,
+    section: ElfSectionHeader,
+) -> Result<Cow<'buf, [u8]>, ElfError> {
+    let data = file.section_data(section)?;
+    if !ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_COMPRESSED) {
+        let is_gnu_compressed = file
+            .section_name(section)
+            .is_ok_and(|name| name.starts_with(".zdebug"));
+        return match data.strip_prefix(GNU_MAGIC) {
+            Some(rest) if is_gnu_compressed => decompress_gnu(rest).map(Cow::Owned),
+            _ => Ok(Cow::Borrowed(data)),
+        };
+    }
+
+    let (compression, size, compressed) = match file.header() {
+        ElfHeader::Elf32(_) => {
+            let header =
+                Elf32CompressionHeader::ref_from_prefix(data).ok_or(ElfError::ZeroCopyError)?;
+            let rest = data.get(size_of::<Elf32CompressionHeader>()..);
+            (header.ch_type, u64::from(header.ch_size), rest)
+        }
+        ElfHeader::Elf64(_) => {
+            let header =
+                Elf64CompressionHeader::ref_from_prefix(data).ok_or(ElfError::ZeroCopyError)?;
+            let rest = data.get(size_of::<Elf64CompressionHeader>()..);
+            (header.ch_type, header.ch_size, rest)
+        }
+    };
+
+    decompress(
+        compression,
+        compressed.unwrap_or_default(),
+        usize::try_from(size)?,
+    )
+    .map(Cow::Owned)
+}
+
+/// Finds a section by name, or the `.zdebug` name of a `.debug` section in
+/// the GNU format, and returns its uncompressed contents.
+pub fn section_contents_by_name<'buf>(
+    file: &ElfFile<'buf>,
+    name: &str,
+) -> Result<Option<Cow<'buf, [u8]>>, ElfError> {
+    let section = file.section_by_name(name).or_else(|| {
+        let suffix = name.strip_prefix(".debug")?;
+        file.section_by_name(&std::format!(".zdebug{suffix}"))
+    });
+
+    section
+        .map(|section| section_contents(file, section))
+        .transpose()
+}
+
+fn decompress_gnu(data: &[u8]) -> Result<Vec<u8>, ElfError> {
+    let error = || ElfError::MalformedCompressedData(ElfCompressionType::ELFCOMPRESS_ZLIB);
+    let size = data
+        .get(..8)
+        .and_then(|size| size.try_into().ok())
+        .map(u64::from_be_bytes)
+        .ok_or_else(error)?;
+    let compressed = data.get(8..).ok_or_else(error)?;
+
+    decompress(
+        ElfCompressionType::ELFCOMPRESS_ZLIB,
+        compressed,
+        usize::try_from(size)?,
+    )
+}
+
+/// Decompresses data that should be `size` bytes when uncompressed.
+#[cfg_attr(not(any(feature = "zlib", feature = "zstd")), allow(unused_variables))]
+pub fn decompress(
+    compression: ElfCompressionType,
+    data: &[u8],
+    size: usize,
+) -> Result<Vec<u8>, ElfError> {
+    let is_supported = (cfg!(feature = "zlib")
+        && compression == ElfCompressionType::ELFCOMPRESS_ZLIB)
+        || (cfg!(feature = "zstd") && compression == ElfCompressionType::ELFCOMPRESS_ZSTD);
+    if !is_supported {
+        return Err(ElfError::UnsupportedCompression(compression));
+    }
+
+    let decompressed: Option<Vec<u8>> = match compression {
+        #[cfg(feature = "zlib")]
+        ElfCompressionType::ELFCOMPRESS_ZLIB => {
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, size).ok()
+        }
+        #[cfg(feature = "zstd")]
+        ElfCompressionType::ELFCOMPRESS_ZSTD => decompress_zstd(data, size),
+        _ => None,
+    };
+
+    decompressed
+        .filter(|decompressed| decompressed.len() == size)
+        .ok_or(ElfError::MalformedCompressedData(compression))
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(mut data: &[u8], size: usize) -> Option<Vec<u8>> {
+    use ruzstd::decoding::{
+        errors::{FrameDecoderError, ReadFrameHeaderError},
+        BlockDecodingStrategy, FrameDecoder,
+    };
+
+    // The size is untrusted, so rather than allocating it up front, the data
+    // is decoded a block at a time, stopping once there is more than it.
+    let mut decoder = FrameDecoder::new();
+    let mut decompressed = Vec::new();
+    while !data.is_empty() {
+        match decoder.reset(&mut data) {
+            Ok(()) => {}
+            Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame {
+                length,
+                ..
+            })) => {
+                data = data.get(usize::try_from(length).ok()?..)?;
+                continue;
+            }
+            Err(_) => return None,
+        }
+
+        loop {
+            decoder
+                .decode_blocks(&mut data, BlockDecodingStrategy::UptoBlocks(1))
+                .ok()?;
+            if let Some(block) = decoder.collect() {
+                if block.len() > size.saturating_sub(decompressed.len()) {
+                    return None;
+                }
+                decompressed.extend_from_slice(&block);
+            }
+            if decoder.is_finished() {
+                break;
+            }
+        }
+    }
+
+    Some(decompressed)
+}
+
+/// Decompresses a complete xz stream, which must be no larger than `limit`
+/// bytes when uncompressed. Unlike the ELF compression header, xz gives no
+/// size up front.
+#[cfg_attr(not(feature = "xz"), allow(unused_variables))]
+pub fn decompress_xz(data: &[u8], limit: usize) -> Result<Vec<u8>, ElfError> {
+    #[cfg(feature = "xz")]
+    {
+        let mut output = LimitedWriter {
+            data: Vec::new(),
+            limit,
+        };
+        lzma_rs::xz_decompress(&mut &*data, &mut output).map_err(|_| ElfError::MalformedXzData)?;
+        Ok(output.data)
+    }
+
+    #[cfg(not(feature = "xz"))]
+    Err(ElfError::UnsupportedXzCompression)
+}
+
+/// Collects decompressed data, failing once there is more than `limit`
+/// bytes of it.
+#[cfg(feature = "xz")]
+struct LimitedWriter {
+    data: Vec<u8>,
+    limit: usize,
+}
+
+#[cfg(feature = "xz")]
+impl std::io::Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let remaining = self.limit.saturating_sub(self.data.len());
+        if buf.len() > remaining {
+            return Err(std::io::ErrorKind::OutOfMemory.into());
+        }
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The DWARF sections of a file, decompressed if need be.
+#[derive(Debug, Clone, Default)]
+pub struct ElfDebugSections<'buf> {
+    sections: Vec<(&'static str, Cow<'buf, [u8]>)>,
+}
+
+impl<'buf> ElfDebugSections<'buf> {
+    /// Reads and decompresses each of [`DwarfSections::NAMES`] that the
+    /// file has.
+    pub fn load(file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let mut sections = Vec::new();
+        for &name in DwarfSections::NAMES {
+            if let Some(contents) = section_contents_by_name(file, name)? {
+                sections.push((name, contents));
+            }
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// The uncompressed section with the given `.debug` name, if the file
+    /// has it.
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.sections
+            .iter()
+            .find(|(section, _)| *section == name)
+            .map(|(_, contents)| &**contents)
+    }
+
+    pub fn dwarf(&self) -> DwarfSections<'_> {
+        // Missing sections are empty, so this can't fail.
+        DwarfSections::load_with(|name| Ok(self.get(name).unwrap_or_default())).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_wrong_sizes_and_unknown_algorithms() {
+        assert!(matches!(
+            decompress(ElfCompressionType(7), &[], 0),
+            Err(ElfError::UnsupportedCompression(ElfCompressionType(7)))
+        ));
+        assert!(matches!(
+            decompress_gnu(b"\0\0\0"),
+            Err(ElfError::MalformedCompressedData(_))
+        ));
+
+        // "hello" compressed with zlib.
+        let data = b"\x78\x9c\xcb\x48\xcd\xc9\xc9\x07\x00\x06\x2c\x02\x15";
+        let result = decompress(ElfCompressionType::ELFCOMPRESS_ZLIB, data, 5);
+        if cfg!(feature = "zlib") {
+            assert_eq!(result.ok().as_deref(), Some(&b"hello"[..]));
+            assert!(matches!(
+                decompress(ElfCompressionType::ELFCOMPRESS_ZLIB, data, 6),
+                Err(ElfError::MalformedCompressedData(_))
+            ));
+        } else {
+            assert!(matches!(result, Err(ElfError::UnsupportedCompression(_))));
+        }
+
+        // "hello" compressed with zstd. A huge size is an error, rather than
+        // an allocation.
+        let data = b"\x28\xb5\x2f\xfd\x00\x58\x29\x00\x00\x68\x65\x6c\x6c\x6f";
+        let result = decompress(ElfCompressionType::ELFCOMPRESS_ZSTD, data, 5);
+        if cfg!(feature = "zstd") {
+            assert_eq!(result.ok().as_deref(), Some(&b"hello"[..]));
+            for size in [4, 6, usize::MAX] {
+                assert!(matches!(
+                    decompress(ElfCompressionType::ELFCOMPRESS_ZSTD, data, size),
+                    Err(ElfError::MalformedCompressedData(_))
+                ));
+            }
+        } else {
+            assert!(matches!(result, Err(ElfError::UnsupportedCompression(_))));
+        }
+
+        // "hello" compressed with xz.
+        let data = b"\xfd\x37\x7a\x58\x5a\x00\x00\x01\x69\x22\xde\x36\x04\xc0\x09\x05\
+            \x21\x01\x16\x00\x00\x00\x00\x00\x00\x00\x00\x00\xbf\x79\x25\x67\x01\x00\x04\
+            \x68\x65\x6c\x6c\x6f\x00\x00\x00\x00\x86\xa6\x10\x36\x00\x01\x21\x05\x47\x54\
+            \x73\xdc\x90\x42\x99\x0d\x01\x00\x00\x00\x00\x01\x59\x5a";
+        let result = decompress_xz(data, 5);
+        if cfg!(feature = "xz") {
+            assert_eq!(result.ok().as_deref(), Some(&b"hello"[..]));
+            assert!(matches!(
+                decompress_xz(data, 4),
+                Err(ElfError::MalformedXzData)
+            ));
+            assert!(matches!(
+                decompress_xz(data.get(..40).unwrap_or_default(), 5),
+                Err(ElfError::MalformedXzData)
+            ));
+        } else {
+            assert!(matches!(result, Err(ElfError::UnsupportedXzCompression)));
+        }
+    }
+}
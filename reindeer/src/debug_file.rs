@@ -0,0 +1,233 @@
+//! Finding the separate debug file of a stripped file, as installed by
+//! distribution debug packages or written by
+//! [`split_debug`](crate::strip::split_debug).
+//!
+//! As in GDB, a file with a build ID is looked up as
+//! `<root>/.build-id/ab/cdef….debug` under each debug root, and the debug
+//! file must have the same build ID. Then the `.gnu_debuglink` name is looked
+//! up in the directory of the file, its `.debug` subdirectory, and
+//! `<root>/<directory of the file>`, and the debug file must have the CRC-32
+//! given with the name. The only debug root is `/usr/lib/debug`, unless more
+//! are added with [`ElfDebugFileLocator::with_root`].
+
+use core::fmt::Write;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    string::String,
+    vec::Vec,
+};
+
+use crate::{errors::ElfError, notes, strip::crc32, ElfFile};
+
+const DEBUGLINK: &str = ".gnu_debuglink";
+
+/// The contents of a `.gnu_debuglink` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfDebugLink<'buf> {
+    /// The file name of the debug file, without a directory.
+    pub name: &'buf str,
+    /// The CRC-32 of the whole debug file, as computed by [`crc32`].
+    pub crc: u32,
+}
+
+impl<'buf> ElfDebugLink<'buf> {
+    /// The name is NUL-terminated and padded to 4 bytes, followed by the
+    /// little-endian CRC.
+    pub fn parse(data: &'buf [u8]) -> Result<Self, ElfError> {
+        let name = core::ffi::CStr::from_bytes_until_nul(data)?;
+        let crc_start = name
+            .to_bytes_with_nul()
+            .len()
+            .checked_next_multiple_of(4)
+            .ok_or(ElfError::ZeroCopyError)?;
+        let crc = data
+            .get(crc_start..)
+            .and_then(|crc| crc.get(..4))
+            .and_then(|crc| crc.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(ElfError::ZeroCopyError)?;
+
+        Ok(Self {
+            name: name.to_str()?,
+            crc,
+        })
+    }
+
+    /// Reads the `.gnu_debuglink` section, if the file has one.
+    pub fn from_file(file: &ElfFile<'buf>) -> Result<Option<Self>, ElfError> {
+        file.section_by_name(DEBUGLINK)
+            .map(|section| Self::parse(file.section_data(section)?))
+            .transpose()
+    }
+}
+
+/// A debug file found by [`ElfDebugFileLocator::locate`].
+#[derive(Debug, Clone)]
+pub struct ElfDebugFile {
+    path: PathBuf,
+    buffer: Vec<u8>,
+}
+
+impl ElfDebugFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    pub fn file(&self) -> Result<ElfFile<'_>, ElfError> {
+        ElfFile::parse(&self.buffer)
+    }
+}
+
+/// How a candidate debug file is checked against the stripped file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElfDebugFileCheck<'buf> {
+    BuildId(&'buf [u8]),
+    Crc(u32),
+}
+
+/// Searches the debug roots and the directory of a stripped file for its
+/// debug file.
+#[derive(Debug, Clone)]
+pub struct ElfDebugFileLocator {
+    roots: Vec<PathBuf>,
+    system_root: bool,
+}
+
+impl Default for ElfDebugFileLocator {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            system_root: true,
+        }
+    }
+}
+
+impl ElfDebugFileLocator {
+    pub const SYSTEM_ROOT: &'static str = "/usr/lib/debug";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a debug root, which is searched after those already added and
+    /// before [`Self::SYSTEM_ROOT`].
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.roots.push(root.into());
+        self
+    }
+
+    /// Sets whether [`Self::SYSTEM_ROOT`] is searched, which it is by
+    /// default.
+    pub fn with_system_root(mut self, system_root: bool) -> Self {
+        self.system_root = system_root;
+        self
+    }
+
+    fn roots(&self) -> impl Iterator<Item = &Path> {
+        let system_root = self.system_root.then_some(Path::new(Self::SYSTEM_ROOT));
+        self.roots.iter().map(PathBuf::as_path).chain(system_root)
+    }
+
+    /// The paths searched for the debug file of `file`, read from `path`, in
+    /// order, with how each is checked.
+    fn candidates<'buf>(
+        &self,
+        path: &Path,
+        file: &ElfFile<'buf>,
+    ) -> Result<Vec<(PathBuf, ElfDebugFileCheck<'buf>)>, ElfError> {
+        let mut candidates = Vec::new();
+
+        if let Some(build_id @ [first, rest @ ..]) = notes::build_id(file)? {
+            if !rest.is_empty() {
+                let mut name = String::new();
+                for byte in rest {
+                    let _ = write!(name, "{byte:02x}");
+                }
+                name.push_str(".debug");
+
+                for root in self.roots() {
+                    let path = root
+                        .join(".build-id")
+                        .join(std::format!("{first:02x}"))
+                        .join(&name);
+                    candidates.push((path, ElfDebugFileCheck::BuildId(build_id)));
+                }
+            }
+        }
+
+        if let Some(link) = ElfDebugLink::from_file(file)? {
+            // The link is only a file name, anything else would escape the
+            // searched directories.
+            let is_file_name = !link.name.is_empty() && !link.name.contains('/');
+            let directory = fs::canonicalize(path)
+                .unwrap_or_else(|_| path.to_path_buf())
+                .parent()
+                .map(Path::to_path_buf);
+
+            if let Some(directory) = directory.filter(|_| is_file_name) {
+                let check = ElfDebugFileCheck::Crc(link.crc);
+                candidates.push((directory.join(link.name), check));
+                candidates.push((directory.join(".debug").join(link.name), check));
+                if directory.is_absolute() {
+                    let relative = directory.strip_prefix("/").unwrap_or(&directory);
+                    for root in self.roots() {
+                        candidates.push((root.join(relative).join(link.name), check));
+                    }
+                }
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Finds the debug file of `file`, which was read from `path`. Candidates
+    /// that can't be read or don't match are skipped, so the only errors are
+    /// for a malformed build ID note or `.gnu_debuglink` in `file`.
+    pub fn locate(&self, path: &Path, file: &ElfFile) -> Result<Option<ElfDebugFile>, ElfError> {
+        for (candidate, check) in self.candidates(path, file)? {
+            let Ok(buffer) = fs::read(&candidate) else {
+                continue;
+            };
+
+            let matches = match check {
+                ElfDebugFileCheck::BuildId(build_id) => ElfFile::parse(&buffer)
+                    .and_then(|debug| notes::build_id(&debug))
+                    .is_ok_and(|debug_id| debug_id == Some(build_id)),
+                ElfDebugFileCheck::Crc(crc) => crc32(&buffer) == crc,
+            };
+
+            if matches {
+                return Ok(Some(ElfDebugFile {
+                    path: candidate,
+                    buffer,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_debuglink() {
+        let link = ElfDebugLink::parse(b"ls.debug\0\0\0\0\x78\x56\x34\x12").expect("valid link");
+        assert_eq!(link.name, "ls.debug");
+        assert_eq!(link.crc, 0x1234_5678);
+
+        let link = ElfDebugLink::parse(b"abc\0\x01\0\0\0").expect("valid link");
+        assert_eq!(link.name, "abc");
+        assert_eq!(link.crc, 1);
+
+        assert!(ElfDebugLink::parse(b"abc\0\x01\0\0").is_err());
+        assert!(ElfDebugLink::parse(b"abc").is_err());
+    }
+}
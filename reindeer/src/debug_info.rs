@@ -0,0 +1,802 @@
+//! Compile units, from `.debug_info` and `.debug_abbrev`, and the table of
+//! their addresses in `.debug_aranges`.
+//!
+//! Each unit in `.debug_info` is a header followed by a tree of debugging
+//! information entries, each a tag and attributes laid out as described by
+//! an abbreviation in `.debug_abbrev`. Only the first entry, describing the
+//! unit itself, is read here: which compiler produced it, from which source
+//! file and directory, in which language, and the addresses of its code.
+//!
+//! `.debug_aranges` maps address ranges to units without reading them, so
+//! [`ElfDebugInfo::find_unit`] uses it when the compiler emitted it.
+
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::{
+    debug_line::{ElfDebugLine, ElfLineLocation, ElfLineProgram},
+    dwarf::{
+        DwarfAttribute, DwarfEncoding, DwarfForm, DwarfLanguage, DwarfRangeListEntry, DwarfReader,
+        DwarfSections, DwarfTag, DwarfUnitType, DwarfValue,
+    },
+    errors::ElfError,
+};
+
+const DEBUG_INFO: &str = ".debug_info";
+const DEBUG_ABBREV: &str = ".debug_abbrev";
+const DEBUG_ARANGES: &str = ".debug_aranges";
+
+/// The header of a unit in `.debug_info`.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfCompileUnit<'data> {
+    /// The offset of the unit in `.debug_info`.
+    pub offset: usize,
+    pub version: u16,
+    pub is_64: bool,
+    /// The kind of unit, which before DWARF 5 is always `DW_UT_COMPILE`.
+    pub unit_type: DwarfUnitType,
+    pub address_size: u8,
+    /// The offset of the unit's abbreviations in `.debug_abbrev`.
+    pub debug_abbrev_offset: u64,
+    /// The ID linking a skeleton unit and its split unit.
+    pub dwo_id: Option<u64>,
+    entries: DwarfReader<'data>,
+    sections: DwarfSections<'data>,
+    end: usize,
+}
+
+impl<'data> ElfCompileUnit<'data> {
+    /// Parses the header of the unit at `offset` in `.debug_info`.
+    pub fn parse(sections: DwarfSections<'data>, offset: usize) -> Result<Self, ElfError> {
+        let mut reader = DwarfReader::new(DEBUG_INFO, sections.debug_info, 0);
+        reader.seek(offset)?;
+        let (length, is_64) = reader.initial_length()?;
+        let mut unit = reader.split(usize::try_from(length)?)?;
+
+        let version = unit.u16()?;
+        let mut dwo_id = None;
+        let (unit_type, address_size, debug_abbrev_offset) = match version {
+            2..=4 => {
+                let debug_abbrev_offset = unit.offset_value(is_64)?;
+                (
+                    DwarfUnitType::DW_UT_COMPILE,
+                    unit.u8()?,
+                    debug_abbrev_offset,
+                )
+            }
+            5 => {
+                let unit_type = DwarfUnitType(unit.u8()?);
+                let address_size = unit.u8()?;
+                let debug_abbrev_offset = unit.offset_value(is_64)?;
+                match unit_type {
+                    DwarfUnitType::DW_UT_SKELETON | DwarfUnitType::DW_UT_SPLIT_COMPILE => {
+                        dwo_id = Some(unit.u64()?);
+                    }
+                    DwarfUnitType::DW_UT_TYPE | DwarfUnitType::DW_UT_SPLIT_TYPE => {
+                        let _type_signature = unit.u64()?;
+                        let _type_offset = unit.offset_value(is_64)?;
+                    }
+                    _ => {}
+                }
+                (unit_type, address_size, debug_abbrev_offset)
+            }
+            _ => return Err(ElfError::MalformedDwarf(DEBUG_INFO, offset)),
+        };
+
+        Ok(Self {
+            offset,
+            version,
+            is_64,
+            unit_type,
+            address_size,
+            debug_abbrev_offset,
+            dwo_id,
+            entries: unit,
+            sections,
+            end: reader.offset(),
+        })
+    }
+
+    pub fn encoding(&self) -> DwarfEncoding {
+        DwarfEncoding {
+            version: self.version,
+            is_64: self.is_64,
+            address_size: self.address_size,
+        }
+    }
+
+    /// The first entry of the unit, which describes the unit itself.
+    pub fn root(&self) -> Result<ElfDie<'data>, ElfError> {
+        let mut values = self.entries;
+        let offset = values.offset();
+        let code = values.uleb128()?;
+        if code == 0 {
+            return Err(ElfError::MalformedDwarf(DEBUG_INFO, offset));
+        }
+
+        let abbreviation = self.abbreviation(code)?;
+        Ok(ElfDie {
+            offset,
+            tag: abbreviation.tag,
+            has_children: abbreviation.has_children,
+            specifications: abbreviation.specifications,
+            values,
+            encoding: self.encoding(),
+        })
+    }
+
+    /// Finds an abbreviation by its code among the unit's.
+    fn abbreviation(&self, code: u64) -> Result<ElfAbbreviation<'data>, ElfError> {
+        let mut reader = DwarfReader::new(DEBUG_ABBREV, self.sections.debug_abbrev, 0);
+        reader.seek(usize::try_from(self.debug_abbrev_offset)?)?;
+
+        loop {
+            let start = reader.offset();
+            let error = || ElfError::MalformedDwarf(DEBUG_ABBREV, start);
+            let found = reader.uleb128()?;
+            // The unit's abbreviations end with a zero code.
+            if found == 0 {
+                return Err(error());
+            }
+            let tag = DwarfTag(u16::try_from(reader.uleb128()?).map_err(|_| error())?);
+            let has_children = reader.u8()? != 0;
+
+            let specifications = reader.offset();
+            loop {
+                let name = reader.uleb128()?;
+                let form = reader.uleb128()?;
+                if form == u64::from(DwarfForm::DW_FORM_IMPLICIT_CONST.0) {
+                    reader.sleb128()?;
+                }
+                if name == 0 && form == 0 {
+                    break;
+                }
+            }
+
+            if found == code {
+                let mut specification_reader = reader;
+                specification_reader.seek(specifications)?;
+                return Ok(ElfAbbreviation {
+                    tag,
+                    has_children,
+                    specifications: specification_reader
+                        .split(reader.offset().saturating_sub(specifications))?,
+                });
+            }
+        }
+    }
+
+    /// Decodes the attributes of the unit's entry.
+    pub fn info(&self) -> Result<ElfCompileUnitInfo<'data>, ElfError> {
+        let root = self.root()?;
+        let mut info = ElfCompileUnitInfo {
+            tag: root.tag,
+            producer: None,
+            name: None,
+            comp_dir: None,
+            language: None,
+            low_pc: None,
+            high_pc: None,
+            ranges: None,
+            stmt_list: None,
+            str_offsets_base: None,
+            addr_base: None,
+            rnglists_base: None,
+            unit: *self,
+        };
+
+        // Values given by index can only be looked up once the bases are
+        // known, which may come later in the entry.
+        let (mut producer, mut name, mut comp_dir) = (None, None, None);
+        let (mut low_pc, mut high_pc, mut ranges) = (None, None, None);
+        for attribute in root.attributes() {
+            let attribute = attribute?;
+            let value = attribute.value;
+            match attribute.name {
+                DwarfAttribute::DW_AT_PRODUCER => producer = Some(value),
+                DwarfAttribute::DW_AT_NAME => name = Some(value),
+                DwarfAttribute::DW_AT_COMP_DIR => comp_dir = Some(value),
+                DwarfAttribute::DW_AT_LANGUAGE => {
+                    info.language = value
+                        .unsigned()
+                        .and_then(|language| u16::try_from(language).ok())
+                        .map(DwarfLanguage);
+                }
+                DwarfAttribute::DW_AT_LOW_PC => low_pc = Some(value),
+                DwarfAttribute::DW_AT_HIGH_PC => high_pc = Some(value),
+                DwarfAttribute::DW_AT_RANGES => ranges = Some(value),
+                DwarfAttribute::DW_AT_STMT_LIST => info.stmt_list = value.unsigned(),
+                DwarfAttribute::DW_AT_STR_OFFSETS_BASE => info.str_offsets_base = value.unsigned(),
+                DwarfAttribute::DW_AT_ADDR_BASE | DwarfAttribute::DW_AT_GNU_ADDR_BASE => {
+                    info.addr_base = value.unsigned();
+                }
+                DwarfAttribute::DW_AT_RNGLISTS_BASE => info.rnglists_base = value.unsigned(),
+                _ => {}
+            }
+        }
+
+        let string = |value: Option<DwarfValue<'data>>| {
+            value
+                .map(|value| info.string(value))
+                .transpose()
+                .map(Option::flatten)
+        };
+        let (producer, name, comp_dir) = (string(producer)?, string(name)?, string(comp_dir)?);
+        (info.producer, info.name, info.comp_dir) = (producer, name, comp_dir);
+
+        info.low_pc = low_pc
+            .map(|value| info.address(value))
+            .transpose()?
+            .flatten();
+        info.high_pc = match high_pc {
+            // DWARF 4 allows the end to be given as the size.
+            Some(DwarfValue::Unsigned(size)) => info.low_pc.map(|low_pc| low_pc.wrapping_add(size)),
+            Some(value) => info.address(value)?,
+            None => None,
+        };
+        info.ranges = match ranges {
+            Some(DwarfValue::RangeListIndex(index)) => Some(info.range_list_offset(index)?),
+            Some(value) => value.unsigned(),
+            None => None,
+        };
+
+        Ok(info)
+    }
+
+    /// The size of the header of a unit's contribution to `.debug_addr`,
+    /// `.debug_str_offsets` and `.debug_rnglists`, which their bases point
+    /// past.
+    fn contribution_header_size(&self, extra: u64) -> u64 {
+        let length: u64 = if self.is_64 { 12 } else { 4 };
+        // The length and version, then padding or the address and segment
+        // selector sizes.
+        length.saturating_add(4).saturating_add(extra)
+    }
+
+    fn offset_size(&self) -> u64 {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// The attributes of a unit's entry as they are laid out in its values.
+#[derive(Debug, Clone, Copy)]
+struct ElfAbbreviation<'data> {
+    tag: DwarfTag,
+    has_children: bool,
+    specifications: DwarfReader<'data>,
+}
+
+/// A debugging information entry.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDie<'data> {
+    /// The offset of the entry in `.debug_info`.
+    pub offset: usize,
+    pub tag: DwarfTag,
+    pub has_children: bool,
+    specifications: DwarfReader<'data>,
+    values: DwarfReader<'data>,
+    encoding: DwarfEncoding,
+}
+
+/// An attribute of an [`ElfDie`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfAttribute<'data> {
+    pub name: DwarfAttribute,
+    pub form: DwarfForm,
+    pub value: DwarfValue<'data>,
+}
+
+impl<'data> ElfDie<'data> {
+    pub fn attributes(&self) -> impl Iterator<Item = Result<ElfAttribute<'data>, ElfError>> {
+        let (mut specifications, mut values) = (self.specifications, self.values);
+        let encoding = self.encoding;
+        let mut is_done = false;
+
+        core::iter::from_fn(move || {
+            if is_done {
+                return None;
+            }
+
+            let attribute = read_attribute(&mut specifications, &mut values, encoding);
+            is_done = !matches!(attribute, Ok(Some(_)));
+            attribute.transpose()
+        })
+    }
+
+    /// The value of the first attribute with the given name.
+    pub fn attribute(&self, name: DwarfAttribute) -> Result<Option<DwarfValue<'data>>, ElfError> {
+        for attribute in self.attributes() {
+            let attribute = attribute?;
+            if attribute.name == name {
+                return Ok(Some(attribute.value));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn read_attribute<'data>(
+    specifications: &mut DwarfReader<'data>,
+    values: &mut DwarfReader<'data>,
+    encoding: DwarfEncoding,
+) -> Result<Option<ElfAttribute<'data>>, ElfError> {
+    let start = specifications.offset();
+    let error = || ElfError::MalformedDwarf(DEBUG_ABBREV, start);
+    let name = specifications.uleb128()?;
+    let form = specifications.uleb128()?;
+    if name == 0 && form == 0 {
+        return Ok(None);
+    }
+
+    let name = DwarfAttribute(u16::try_from(name).map_err(|_| error())?);
+    let form = DwarfForm(u16::try_from(form).map_err(|_| error())?);
+    let value = match form {
+        DwarfForm::DW_FORM_IMPLICIT_CONST => DwarfValue::Signed(specifications.sleb128()?),
+        _ => values.value(form, encoding)?,
+    };
+
+    Ok(Some(ElfAttribute { name, form, value }))
+}
+
+/// The attributes of a unit's entry that describe the unit, with strings
+/// and addresses looked up.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfCompileUnitInfo<'data> {
+    pub tag: DwarfTag,
+    /// The compiler, often with its version and options.
+    pub producer: Option<&'data [u8]>,
+    /// The path of the primary source file.
+    pub name: Option<&'data [u8]>,
+    /// The directory the compiler was run in.
+    pub comp_dir: Option<&'data [u8]>,
+    pub language: Option<DwarfLanguage>,
+    pub low_pc: Option<u64>,
+    /// The end of the unit's code, when it is a single range.
+    pub high_pc: Option<u64>,
+    /// The offset of the unit's range list in `.debug_ranges`, or in
+    /// `.debug_rnglists` for DWARF 5.
+    pub ranges: Option<u64>,
+    /// The offset of the unit's line number program in `.debug_line`.
+    pub stmt_list: Option<u64>,
+    pub str_offsets_base: Option<u64>,
+    pub addr_base: Option<u64>,
+    pub rnglists_base: Option<u64>,
+    unit: ElfCompileUnit<'data>,
+}
+
+impl<'data> ElfCompileUnitInfo<'data> {
+    /// Reads the entry at `index` of a table of offsets or addresses.
+    fn indexed(
+        &self,
+        section: &'static str,
+        data: &'data [u8],
+        base: u64,
+        index: u64,
+        size: u64,
+    ) -> Result<u64, ElfError> {
+        let position = index
+            .checked_mul(size)
+            .and_then(|offset| offset.checked_add(base))
+            .ok_or(ElfError::MalformedDwarf(section, 0))?;
+        let mut reader = DwarfReader::new(section, data, 0);
+        reader.seek(usize::try_from(position)?)?;
+        reader.sized(u8::try_from(size)?)
+    }
+
+    /// The string a value refers to, or `None` if it isn't a string.
+    pub fn string(&self, value: DwarfValue<'data>) -> Result<Option<&'data [u8]>, ElfError> {
+        let unit = &self.unit;
+        let DwarfValue::StringIndex(index) = value else {
+            return unit.sections.string(value);
+        };
+
+        let base = self
+            .str_offsets_base
+            .unwrap_or_else(|| unit.contribution_header_size(0));
+        let offset = self.indexed(
+            ".debug_str_offsets",
+            unit.sections.debug_str_offsets,
+            base,
+            index,
+            unit.offset_size(),
+        )?;
+        unit.sections.string(DwarfValue::StringOffset(offset))
+    }
+
+    /// The address a value refers to, or `None` if it isn't an address.
+    pub fn address(&self, value: DwarfValue<'data>) -> Result<Option<u64>, ElfError> {
+        match value {
+            DwarfValue::Address(address) => Ok(Some(address)),
+            DwarfValue::AddressIndex(index) => {
+                let unit = &self.unit;
+                let base = self
+                    .addr_base
+                    .unwrap_or_else(|| unit.contribution_header_size(0));
+                self.indexed(
+                    ".debug_addr",
+                    unit.sections.debug_addr,
+                    base,
+                    index,
+                    u64::from(unit.address_size),
+                )
+                .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The offset in `.debug_rnglists` of the range list at `index`, which
+    /// the table after the header gives relative to the base.
+    fn range_list_offset(&self, index: u64) -> Result<u64, ElfError> {
+        let unit = &self.unit;
+        let base = self
+            .rnglists_base
+            .unwrap_or_else(|| unit.contribution_header_size(4));
+        let offset = self.indexed(
+            ".debug_rnglists",
+            unit.sections.debug_rnglists,
+            base,
+            index,
+            unit.offset_size(),
+        )?;
+        Ok(base.wrapping_add(offset))
+    }
+
+    /// Calls `visit` with each range of addresses of the unit's code.
+    pub fn ranges_with(&self, mut visit: impl FnMut(Range<u64>)) -> Result<(), ElfError> {
+        let mut visit = |range: Range<u64>| {
+            if !range.is_empty() {
+                visit(range);
+            }
+        };
+
+        match (self.ranges, self.low_pc, self.high_pc) {
+            (Some(offset), ..) if self.unit.version >= 5 => self.range_list_with(offset, visit),
+            (Some(offset), ..) => self.debug_ranges_with(offset, visit),
+            (None, Some(low_pc), Some(high_pc)) => {
+                visit(low_pc..high_pc);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The ranges of addresses of the unit's code.
+    #[cfg(feature = "std")]
+    pub fn ranges(&self) -> Result<Vec<Range<u64>>, ElfError> {
+        let mut ranges = Vec::new();
+        self.ranges_with(|range| ranges.push(range))?;
+        Ok(ranges)
+    }
+
+    pub fn contains(&self, address: u64) -> Result<bool, ElfError> {
+        let mut contains = false;
+        self.ranges_with(|range| contains |= range.contains(&address))?;
+        Ok(contains)
+    }
+
+    /// Reads a DWARF 2 to 4 range list, of pairs of addresses relative to
+    /// the base address, which is the unit's low address unless changed by
+    /// a pair starting with the largest address.
+    fn debug_ranges_with(
+        &self,
+        offset: u64,
+        mut visit: impl FnMut(Range<u64>),
+    ) -> Result<(), ElfError> {
+        let size = self.unit.address_size;
+        let largest = match size {
+            4 => u64::from(u32::MAX),
+            _ => u64::MAX,
+        };
+
+        let mut reader = DwarfReader::new(".debug_ranges", self.unit.sections.debug_ranges, 0);
+        reader.seek(usize::try_from(offset)?)?;
+        let mut base = self.low_pc.unwrap_or_default();
+        loop {
+            let start = reader.sized(size)?;
+            let end = reader.sized(size)?;
+            match (start, end) {
+                (0, 0) => return Ok(()),
+                (start, end) if start == largest => base = end,
+                (start, end) => visit(base.wrapping_add(start)..base.wrapping_add(end)),
+            }
+        }
+    }
+
+    /// Reads a DWARF 5 range list, of entries of several kinds.
+    fn range_list_with(
+        &self,
+        offset: u64,
+        mut visit: impl FnMut(Range<u64>),
+    ) -> Result<(), ElfError> {
+        let size = self.unit.address_size;
+        let mut reader = DwarfReader::new(".debug_rnglists", self.unit.sections.debug_rnglists, 0);
+        reader.seek(usize::try_from(offset)?)?;
+        let mut base = self.low_pc.unwrap_or_default();
+
+        loop {
+            let start = reader.offset();
+            let error = || ElfError::MalformedDwarf(".debug_rnglists", start);
+            let index = |reader: &mut DwarfReader<'data>| -> Result<u64, ElfError> {
+                let index = reader.uleb128()?;
+                self.address(DwarfValue::AddressIndex(index))?
+                    .ok_or_else(error)
+            };
+
+            let range = match DwarfRangeListEntry(reader.u8()?) {
+                DwarfRangeListEntry::DW_RLE_END_OF_LIST => return Ok(()),
+                DwarfRangeListEntry::DW_RLE_BASE_ADDRESSX => {
+                    base = index(&mut reader)?;
+                    continue;
+                }
+                DwarfRangeListEntry::DW_RLE_BASE_ADDRESS => {
+                    base = reader.sized(size)?;
+                    continue;
+                }
+                DwarfRangeListEntry::DW_RLE_STARTX_ENDX => index(&mut reader)?..index(&mut reader)?,
+                DwarfRangeListEntry::DW_RLE_STARTX_LENGTH => {
+                    let start = index(&mut reader)?;
+                    start..start.wrapping_add(reader.uleb128()?)
+                }
+                DwarfRangeListEntry::DW_RLE_OFFSET_PAIR => {
+                    base.wrapping_add(reader.uleb128()?)..base.wrapping_add(reader.uleb128()?)
+                }
+                DwarfRangeListEntry::DW_RLE_START_END => reader.sized(size)?..reader.sized(size)?,
+                DwarfRangeListEntry::DW_RLE_START_LENGTH => {
+                    let start = reader.sized(size)?;
+                    start..start.wrapping_add(reader.uleb128()?)
+                }
+                _ => return Err(error()),
+            };
+            visit(range);
+        }
+    }
+
+    /// The unit's line number program, which knows the unit's directory.
+    pub fn line_program(&self) -> Result<Option<ElfLineProgram<'data>>, ElfError> {
+        let Some(offset) = self.stmt_list else {
+            return Ok(None);
+        };
+
+        let program = ElfLineProgram::parse(self.unit.sections, usize::try_from(offset)?)?;
+        Ok(Some(match self.comp_dir {
+            Some(directory) => program.with_compilation_directory(directory),
+            None => program,
+        }))
+    }
+}
+
+/// A set of address ranges in `.debug_aranges`, all in one unit.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfArangeSet<'data> {
+    /// The offset of the set in `.debug_aranges`.
+    pub offset: usize,
+    pub version: u16,
+    /// The offset of the unit in `.debug_info`.
+    pub debug_info_offset: u64,
+    pub address_size: u8,
+    pub segment_selector_size: u8,
+    tuples: DwarfReader<'data>,
+    end: usize,
+}
+
+impl<'data> ElfArangeSet<'data> {
+    pub fn parse(data: &'data [u8], offset: usize) -> Result<Self, ElfError> {
+        let mut reader = DwarfReader::new(DEBUG_ARANGES, data, 0);
+        reader.seek(offset)?;
+        let (length, is_64) = reader.initial_length()?;
+        let mut set = reader.split(usize::try_from(length)?)?;
+
+        let version = set.u16()?;
+        let debug_info_offset = set.offset_value(is_64)?;
+        let address_size = set.u8()?;
+        let segment_selector_size = set.u8()?;
+
+        // The tuples are aligned to twice the address size from the start
+        // of the set.
+        let alignment = usize::from(address_size).saturating_mul(2).max(1);
+        let header_size = set.offset().saturating_sub(offset);
+        let misalignment = header_size.checked_rem(alignment).unwrap_or(0);
+        if misalignment != 0 {
+            set.bytes(alignment.saturating_sub(misalignment))?;
+        }
+
+        Ok(Self {
+            offset,
+            version,
+            debug_info_offset,
+            address_size,
+            segment_selector_size,
+            tuples: set,
+            end: reader.offset(),
+        })
+    }
+
+    /// The address ranges, which end at a tuple of zeroes.
+    pub fn ranges(&self) -> impl Iterator<Item = Result<Range<u64>, ElfError>> + 'data {
+        let mut tuples = self.tuples;
+        let (address_size, segment_selector_size) = (self.address_size, self.segment_selector_size);
+        let mut is_done = false;
+
+        core::iter::from_fn(move || loop {
+            if is_done || tuples.is_empty() {
+                return None;
+            }
+
+            let tuple = (|| {
+                if segment_selector_size != 0 {
+                    tuples.sized(segment_selector_size)?;
+                }
+                Ok((tuples.sized(address_size)?, tuples.sized(address_size)?))
+            })();
+            match tuple {
+                Ok((0, 0)) => is_done = true,
+                Ok((_, 0)) => {}
+                Ok((address, length)) => return Some(Ok(address..address.saturating_add(length))),
+                Err(error) => {
+                    is_done = true;
+                    return Some(Err(error));
+                }
+            }
+        })
+    }
+}
+
+/// The `.debug_aranges` section.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDebugAranges<'data> {
+    data: &'data [u8],
+}
+
+impl<'data> ElfDebugAranges<'data> {
+    pub fn new(sections: DwarfSections<'data>) -> Self {
+        Self {
+            data: sections.debug_aranges,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn sets(&self) -> impl Iterator<Item = Result<ElfArangeSet<'data>, ElfError>> + 'data {
+        let data = self.data;
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            if offset >= data.len() {
+                return None;
+            }
+            let set = ElfArangeSet::parse(data, offset);
+            offset = match &set {
+                Ok(set) => set.end,
+                Err(_) => data.len(),
+            };
+            Some(set)
+        })
+    }
+
+    /// The offset in `.debug_info` of the unit containing an address.
+    pub fn find_unit(&self, address: u64) -> Result<Option<u64>, ElfError> {
+        for set in self.sets() {
+            let set = set?;
+            for range in set.ranges() {
+                if range?.contains(&address) {
+                    return Ok(Some(set.debug_info_offset));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// The `.debug_info` section, holding the units.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDebugInfo<'data> {
+    sections: DwarfSections<'data>,
+}
+
+impl<'data> ElfDebugInfo<'data> {
+    pub fn new(sections: DwarfSections<'data>) -> Self {
+        Self { sections }
+    }
+
+    /// The units, in the order they appear in the section.
+    pub fn units(&self) -> impl Iterator<Item = Result<ElfCompileUnit<'data>, ElfError>> {
+        let sections = self.sections;
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            if offset >= sections.debug_info.len() {
+                return None;
+            }
+            let unit = ElfCompileUnit::parse(sections, offset);
+            offset = match &unit {
+                Ok(unit) => unit.end,
+                Err(_) => sections.debug_info.len(),
+            };
+            Some(unit)
+        })
+    }
+
+    /// The unit containing an address, found with `.debug_aranges` if it
+    /// covers the address, and otherwise from the ranges of each unit.
+    pub fn find_unit(&self, address: u64) -> Result<Option<ElfCompileUnit<'data>>, ElfError> {
+        if let Some(offset) = ElfDebugAranges::new(self.sections).find_unit(address)? {
+            return ElfCompileUnit::parse(self.sections, usize::try_from(offset)?).map(Some);
+        }
+
+        for unit in self.units() {
+            let unit = unit?;
+            if unit.info()?.contains(address)? {
+                return Ok(Some(unit));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The file, line and column of the code at an address, from the line
+    /// number program of its unit. Without a unit, every program is tried.
+    pub fn find_location(&self, address: u64) -> Result<Option<ElfLineLocation<'data>>, ElfError> {
+        let Some(unit) = self.find_unit(address)? else {
+            return ElfDebugLine::new(self.sections).find_location(address);
+        };
+
+        match unit.info()?.line_program()? {
+            Some(program) => program.find_location(address),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_unit_entry_and_aranges() {
+        let sections = DwarfSections {
+            debug_abbrev: b"\x01\x11\x00\x25\x08\x13\x0b\x03\x0e\x11\x01\x12\x06\x00\x00\x00",
+            // A DWARF 4 unit from "GNU C", in C99, for a.c at 0x1000 to
+            // 0x1040.
+            debug_info: b"\
+                \x1f\x00\x00\x00\x04\x00\x00\x00\x00\x00\x08\
+                \x01GNU C\x00\x0c\x00\x00\x00\x00\
+                \x00\x10\x00\x00\x00\x00\x00\x00\x40\x00\x00\x00",
+            debug_str: b"a.c\x00",
+            debug_aranges: b"\
+                \x2c\x00\x00\x00\x02\x00\x00\x00\x00\x00\x08\x00\x00\x00\x00\x00\
+                \x00\x10\x00\x00\x00\x00\x00\x00\x40\x00\x00\x00\x00\x00\x00\x00\
+                \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+            ..Default::default()
+        };
+
+        let debug_info = ElfDebugInfo::new(sections);
+        let unit = debug_info
+            .units()
+            .next()
+            .and_then(Result::ok)
+            .expect("unit should parse");
+        assert_eq!((unit.version, unit.address_size), (4, 8));
+
+        let info = unit.info().expect("unit entry should parse");
+        assert_eq!(info.tag, DwarfTag::DW_TAG_COMPILE_UNIT);
+        assert_eq!(info.producer, Some(&b"GNU C"[..]));
+        assert_eq!(info.name, Some(&b"a.c"[..]));
+        assert_eq!(info.language, Some(DwarfLanguage::DW_LANG_C99));
+        assert_eq!((info.low_pc, info.high_pc), (Some(0x1000), Some(0x1040)));
+        assert!(matches!(info.contains(0x103f), Ok(true)));
+
+        let aranges = ElfDebugAranges::new(sections);
+        assert_eq!(aranges.find_unit(0x1010).ok(), Some(Some(0)));
+        assert_eq!(aranges.find_unit(0x1040).ok(), Some(None));
+        assert!(matches!(debug_info.find_unit(0x1020), Ok(Some(_))));
+    }
+}
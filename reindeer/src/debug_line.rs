@@ -0,0 +1,700 @@
+//! Line number information, from `.debug_line`.
+//!
+//! Each compile unit has a line number program: a header listing its
+//! directories and source files, followed by opcodes for a state machine
+//! whose rows map instruction addresses to a file, line and column. Rows
+//! come in sequences of increasing addresses, each ending with a row that
+//! marks the first address after it. Versions 2 to 5 are supported. DWARF 5
+//! headers describe their entries with forms, and may keep the paths in
+//! `.debug_line_str` or `.debug_str`.
+//!
+//! The sections are given as [`DwarfSections`], which
+//! `compression::ElfDebugSections` decompresses when they are compressed.
+
+#[cfg(feature = "std")]
+use std::string::String;
+
+use crate::{
+    dwarf::{
+        DwarfEncoding, DwarfForm, DwarfLineContentType, DwarfLineExtendedInstruction,
+        DwarfLineInstruction, DwarfReader, DwarfSections, DwarfValue,
+    },
+    errors::ElfError,
+};
+
+const DEBUG_LINE: &str = ".debug_line";
+
+/// A directory or file entry in the header of a line number program. A
+/// directory only has a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ElfLineFile<'data> {
+    pub path: &'data [u8],
+    /// The index of the directory the path is relative to, in the
+    /// numbering of [`ElfLineProgram::directory`].
+    pub directory_index: u64,
+    /// The modification time, or zero if unknown.
+    pub modification_time: u64,
+    /// The size in bytes, or zero if unknown.
+    pub size: u64,
+    pub md5: Option<&'data [u8; 16]>,
+}
+
+/// A row of the line number table, which applies from its address up to
+/// that of the next row in the same sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfLineRow {
+    pub address: u64,
+    /// The operation within a VLIW instruction, which is otherwise zero.
+    pub op_index: u64,
+    /// The index of the file, in the numbering of [`ElfLineProgram::file`].
+    pub file: u64,
+    /// The line, counting from one, or zero if the code has no line.
+    pub line: u64,
+    /// The column, counting from one, or zero for the whole line.
+    pub column: u64,
+    /// Whether the address is a recommended breakpoint for the line.
+    pub is_stmt: bool,
+    pub basic_block: bool,
+    /// Whether this row marks the end of its sequence, rather than
+    /// describing any code.
+    pub end_sequence: bool,
+    pub prologue_end: bool,
+    pub epilogue_begin: bool,
+    pub isa: u64,
+    /// Tells apart the blocks that share a line.
+    pub discriminator: u64,
+}
+
+impl ElfLineRow {
+    fn new(default_is_stmt: bool) -> Self {
+        Self {
+            address: 0,
+            op_index: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            basic_block: false,
+            end_sequence: false,
+            prologue_end: false,
+            epilogue_begin: false,
+            isa: 0,
+            discriminator: 0,
+        }
+    }
+}
+
+/// The directories or files of a header. The entries of DWARF 5 are
+/// described by a list of content types and forms, and those of earlier
+/// versions are fixed.
+#[derive(Debug, Clone, Copy)]
+struct ElfLineEntries<'data> {
+    format: Option<DwarfReader<'data>>,
+    entries: DwarfReader<'data>,
+    is_directory: bool,
+}
+
+/// The line number program of one compile unit.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfLineProgram<'data> {
+    /// The offset of the program in `.debug_line`.
+    pub offset: usize,
+    pub version: u16,
+    pub is_64: bool,
+    /// The size of an address, which is only given by DWARF 5.
+    pub address_size: u8,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    standard_opcode_lengths: &'data [u8],
+    directories: ElfLineEntries<'data>,
+    files: ElfLineEntries<'data>,
+    program: DwarfReader<'data>,
+    sections: DwarfSections<'data>,
+    compilation_directory: Option<&'data [u8]>,
+    end: usize,
+}
+
+impl<'data> ElfLineProgram<'data> {
+    /// Parses the header of the program at `offset` in `.debug_line`.
+    pub fn parse(sections: DwarfSections<'data>, offset: usize) -> Result<Self, ElfError> {
+        let mut reader = DwarfReader::new(DEBUG_LINE, sections.debug_line, 0);
+        reader.seek(offset)?;
+        let (length, is_64) = reader.initial_length()?;
+        let mut unit = reader.split(usize::try_from(length)?)?;
+
+        let version = unit.u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(ElfError::MalformedDwarf(DEBUG_LINE, offset));
+        }
+        let address_size = if version >= 5 {
+            let address_size = unit.u8()?;
+            let _segment_selector_size = unit.u8()?;
+            address_size
+        } else {
+            0
+        };
+
+        let header_length = unit.offset_value(is_64)?;
+        let mut header = unit.split(usize::try_from(header_length)?)?;
+        let minimum_instruction_length = header.u8()?;
+        let maximum_operations_per_instruction = if version >= 4 { header.u8()? } else { 1 };
+        let default_is_stmt = header.u8()? != 0;
+        let line_base = i8::from_le_bytes([header.u8()?]);
+        let line_range = header.u8()?;
+        let opcode_base = header.u8()?;
+        let standard_opcode_lengths = header.bytes(usize::from(opcode_base.saturating_sub(1)))?;
+
+        let mut program = Self {
+            offset,
+            version,
+            is_64,
+            address_size,
+            minimum_instruction_length,
+            maximum_operations_per_instruction,
+            default_is_stmt,
+            line_base,
+            line_range,
+            opcode_base,
+            standard_opcode_lengths,
+            directories: ElfLineEntries::empty(header, true),
+            files: ElfLineEntries::empty(header, false),
+            program: unit,
+            sections,
+            compilation_directory: None,
+            end: reader.offset(),
+        };
+
+        program.directories = program.entry_table(&mut header, true)?;
+        program.files = program.entry_table(&mut header, false)?;
+        Ok(program)
+    }
+
+    fn encoding(&self) -> DwarfEncoding {
+        DwarfEncoding {
+            version: self.version,
+            is_64: self.is_64,
+            address_size: self.address_size,
+        }
+    }
+
+    /// Reads the directory or file table at the reader, leaving it after
+    /// the table.
+    fn entry_table(
+        &self,
+        header: &mut DwarfReader<'data>,
+        is_directory: bool,
+    ) -> Result<ElfLineEntries<'data>, ElfError> {
+        let mut table = ElfLineEntries::empty(*header, is_directory);
+
+        if self.version >= 5 {
+            let format_count = header.u8()?;
+            let start = header.offset();
+            for _ in 0..format_count {
+                header.uleb128()?;
+                header.uleb128()?;
+            }
+            let mut format = *header;
+            format.seek(start)?;
+            table.format = Some(format.split(header.offset().saturating_sub(start))?);
+
+            let count = header.uleb128()?;
+            let start = header.offset();
+            for _ in 0..count {
+                self.read_entry(&table, header)?;
+            }
+            let mut entries = *header;
+            entries.seek(start)?;
+            table.entries = entries.split(header.offset().saturating_sub(start))?;
+        } else {
+            // The tables end with an empty path.
+            let start = header.offset();
+            let mut end = start;
+            while self.read_entry(&table, header)?.path != b"" {
+                end = header.offset();
+            }
+            let mut entries = *header;
+            entries.seek(start)?;
+            table.entries = entries.split(end.saturating_sub(start))?;
+        }
+
+        Ok(table)
+    }
+
+    fn read_entry(
+        &self,
+        table: &ElfLineEntries<'data>,
+        reader: &mut DwarfReader<'data>,
+    ) -> Result<ElfLineFile<'data>, ElfError> {
+        let mut entry = ElfLineFile::default();
+
+        let Some(mut format) = table.format else {
+            entry.path = reader.cstr()?;
+            if !table.is_directory && !entry.path.is_empty() {
+                entry.directory_index = reader.uleb128()?;
+                entry.modification_time = reader.uleb128()?;
+                entry.size = reader.uleb128()?;
+            }
+            return Ok(entry);
+        };
+
+        let start = reader.offset();
+        let error = || ElfError::MalformedDwarf(DEBUG_LINE, start);
+        while !format.is_empty() {
+            let content = format.uleb128()?;
+            let form = DwarfForm(u16::try_from(format.uleb128()?).map_err(|_| error())?);
+            let value = reader.value(form, self.encoding())?;
+
+            let content = DwarfLineContentType(u16::try_from(content).unwrap_or(u16::MAX));
+            match content {
+                DwarfLineContentType::DW_LNCT_PATH => {
+                    entry.path = self.sections.string(value)?.ok_or_else(error)?;
+                }
+                DwarfLineContentType::DW_LNCT_DIRECTORY_INDEX => {
+                    entry.directory_index = value.unsigned().ok_or_else(error)?;
+                }
+                DwarfLineContentType::DW_LNCT_TIMESTAMP => {
+                    entry.modification_time = value.unsigned().unwrap_or_default();
+                }
+                DwarfLineContentType::DW_LNCT_SIZE => {
+                    entry.size = value.unsigned().unwrap_or_default();
+                }
+                DwarfLineContentType::DW_LNCT_MD5 => {
+                    if let DwarfValue::Data16(md5) = value {
+                        entry.md5 = Some(md5);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(entry)
+    }
+
+    fn entries(
+        &self,
+        table: ElfLineEntries<'data>,
+    ) -> impl Iterator<Item = Result<ElfLineFile<'data>, ElfError>> + 'data {
+        let program = *self;
+        let mut reader = table.entries;
+        core::iter::from_fn(move || {
+            if reader.is_empty() {
+                return None;
+            }
+            let entry = program.read_entry(&table, &mut reader);
+            if entry.is_err() {
+                reader.rest();
+            }
+            Some(entry)
+        })
+    }
+
+    /// The include directories, in order.
+    pub fn directories(&self) -> impl Iterator<Item = Result<&'data [u8], ElfError>> + 'data {
+        self.entries(self.directories)
+            .map(|entry| entry.map(|entry| entry.path))
+    }
+
+    /// The source files, in order.
+    pub fn files(&self) -> impl Iterator<Item = Result<ElfLineFile<'data>, ElfError>> + 'data {
+        self.entries(self.files)
+    }
+
+    /// The position in the tables of an index. DWARF 5 numbers entries
+    /// from zero, where entry zero is the compile unit's own directory or
+    /// file. Earlier versions number them from one, and leave zero to mean
+    /// the compile unit's, which is only known from `.debug_info`.
+    fn position(&self, index: u64) -> Option<usize> {
+        let index = usize::try_from(index).ok()?;
+        if self.version >= 5 {
+            Some(index)
+        } else {
+            index.checked_sub(1)
+        }
+    }
+
+    /// The directory with the given index, as used by
+    /// [`ElfLineFile::directory_index`].
+    pub fn directory(&self, index: u64) -> Result<Option<&'data [u8]>, ElfError> {
+        let Some(position) = self.position(index) else {
+            return Ok(None);
+        };
+        self.directories().nth(position).transpose()
+    }
+
+    /// The file with the given index, as used by [`ElfLineRow::file`].
+    pub fn file(&self, index: u64) -> Result<Option<ElfLineFile<'data>>, ElfError> {
+        let Some(position) = self.position(index) else {
+            return Ok(None);
+        };
+        self.files().nth(position).transpose()
+    }
+
+    /// Sets the compile unit's directory, its `DW_AT_comp_dir`, which
+    /// versions before DWARF 5 leave out of the header.
+    pub fn with_compilation_directory(mut self, directory: &'data [u8]) -> Self {
+        self.compilation_directory = Some(directory);
+        self
+    }
+
+    /// The path of a file joined to its directory, and then to the compile
+    /// unit's directory if that is still relative. DWARF 5 gives the
+    /// compile unit's directory as the first, and earlier versions need it
+    /// from [`ElfLineProgram::with_compilation_directory`].
+    #[cfg(feature = "std")]
+    pub fn path(&self, file: &ElfLineFile<'data>) -> Result<String, ElfError> {
+        fn join(directory: &[u8], path: &mut String) {
+            if !path.starts_with('/') && !directory.is_empty() {
+                let directory = String::from_utf8_lossy(directory);
+                *path = std::format!("{}/{path}", directory.trim_end_matches('/'));
+            }
+        }
+
+        let mut path = String::from_utf8_lossy(file.path).into_owned();
+        if let Some(directory) = self.directory(file.directory_index)? {
+            join(directory, &mut path);
+        }
+
+        let compilation_directory = match self.version {
+            5.. if file.directory_index != 0 => self.directory(0)?,
+            5.. => None,
+            _ => self.compilation_directory,
+        };
+        if let Some(directory) = compilation_directory {
+            join(directory, &mut path);
+        }
+
+        Ok(path)
+    }
+
+    /// The rows of the line number table.
+    pub fn rows(&self) -> ElfLineRows<'data> {
+        ElfLineRows {
+            program: *self,
+            reader: self.program,
+            state: ElfLineRow::new(self.default_is_stmt),
+            is_done: false,
+        }
+    }
+
+    /// The row describing the code at an address.
+    pub fn find_row(&self, address: u64) -> Result<Option<ElfLineRow>, ElfError> {
+        let mut previous: Option<ElfLineRow> = None;
+        for row in self.rows() {
+            let row = row?;
+            if let Some(previous) = previous {
+                if (previous.address..row.address).contains(&address) {
+                    return Ok(Some(previous));
+                }
+            }
+            previous = (!row.end_sequence).then_some(row);
+        }
+
+        Ok(None)
+    }
+
+    /// The file, line and column of the code at an address.
+    pub fn find_location(&self, address: u64) -> Result<Option<ElfLineLocation<'data>>, ElfError> {
+        let Some(row) = self.find_row(address)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(ElfLineLocation {
+            program: *self,
+            file: self.file(row.file)?,
+            line: row.line,
+            column: row.column,
+        }))
+    }
+}
+
+impl<'data> ElfLineEntries<'data> {
+    fn empty(reader: DwarfReader<'data>, is_directory: bool) -> Self {
+        let mut entries = reader;
+        entries.rest();
+        Self {
+            format: None,
+            entries,
+            is_directory,
+        }
+    }
+}
+
+/// The source location of an address.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfLineLocation<'data> {
+    /// The program the location was found in, which can resolve the
+    /// directory of the file.
+    pub program: ElfLineProgram<'data>,
+    /// The file, or `None` if the index of the row is outside the table.
+    pub file: Option<ElfLineFile<'data>>,
+    pub line: u64,
+    pub column: u64,
+}
+
+impl ElfLineLocation<'_> {
+    /// The path of the file, joined to its directory.
+    #[cfg(feature = "std")]
+    pub fn path(&self) -> Result<Option<String>, ElfError> {
+        self.file
+            .as_ref()
+            .map(|file| self.program.path(file))
+            .transpose()
+    }
+}
+
+/// Runs a line number program, producing its rows.
+#[derive(Debug, Clone)]
+pub struct ElfLineRows<'data> {
+    program: ElfLineProgram<'data>,
+    reader: DwarfReader<'data>,
+    state: ElfLineRow,
+    is_done: bool,
+}
+
+impl ElfLineRows<'_> {
+    /// Advances the address by a number of operations.
+    fn advance(&mut self, operations: u64) {
+        let program = &self.program;
+        let length = u64::from(program.minimum_instruction_length);
+        let maximum = u64::from(program.maximum_operations_per_instruction.max(1));
+
+        let operations = self.state.op_index.wrapping_add(operations);
+        let instructions = operations.checked_div(maximum).unwrap_or(operations);
+        self.state.address = self
+            .state
+            .address
+            .wrapping_add(length.wrapping_mul(instructions));
+        self.state.op_index = operations.checked_rem(maximum).unwrap_or(0);
+    }
+
+    /// Appends a row with the current state, and clears the flags that
+    /// only apply to one row.
+    fn emit(&mut self) -> ElfLineRow {
+        let row = self.state;
+        if row.end_sequence {
+            self.state = ElfLineRow::new(self.program.default_is_stmt);
+        } else {
+            self.state.basic_block = false;
+            self.state.prologue_end = false;
+            self.state.epilogue_begin = false;
+            self.state.discriminator = 0;
+        }
+        row
+    }
+
+    fn step(&mut self) -> Result<Option<ElfLineRow>, ElfError> {
+        let opcode_base = self.program.opcode_base;
+        while !self.reader.is_empty() {
+            let start = self.reader.offset();
+            let error = || ElfError::MalformedDwarf(DEBUG_LINE, start);
+            let opcode = self.reader.u8()?;
+
+            if opcode >= opcode_base {
+                let adjusted = opcode.wrapping_sub(opcode_base);
+                let line_range = self.program.line_range;
+                let operations = adjusted.checked_div(line_range).ok_or_else(error)?;
+                let line = i64::from(self.program.line_base)
+                    .wrapping_add(i64::from(adjusted.checked_rem(line_range).unwrap_or(0)));
+                self.advance(u64::from(operations));
+                self.state.line = self.state.line.wrapping_add_signed(line);
+                return Ok(Some(self.emit()));
+            }
+
+            match DwarfLineInstruction(opcode) {
+                DwarfLineInstruction::DW_LNS_EXTENDED_OP => {
+                    let length = usize::try_from(self.reader.uleb128()?)?;
+                    let mut operands = self.reader.split(length)?;
+                    let opcode = DwarfLineExtendedInstruction(operands.u8()?);
+                    match opcode {
+                        DwarfLineExtendedInstruction::DW_LNE_END_SEQUENCE => {
+                            self.state.end_sequence = true;
+                            return Ok(Some(self.emit()));
+                        }
+                        DwarfLineExtendedInstruction::DW_LNE_SET_ADDRESS => {
+                            let size =
+                                u8::try_from(length.saturating_sub(1)).map_err(|_| error())?;
+                            self.state.address = operands.sized(size)?;
+                            self.state.op_index = 0;
+                        }
+                        DwarfLineExtendedInstruction::DW_LNE_SET_DISCRIMINATOR => {
+                            self.state.discriminator = operands.uleb128()?;
+                        }
+                        // DW_LNE_define_file was removed in DWARF 5, and
+                        // isn't emitted by current compilers.
+                        _ => {}
+                    }
+                }
+                DwarfLineInstruction::DW_LNS_COPY => return Ok(Some(self.emit())),
+                DwarfLineInstruction::DW_LNS_ADVANCE_PC => {
+                    let operations = self.reader.uleb128()?;
+                    self.advance(operations);
+                }
+                DwarfLineInstruction::DW_LNS_ADVANCE_LINE => {
+                    let line = self.reader.sleb128()?;
+                    self.state.line = self.state.line.wrapping_add_signed(line);
+                }
+                DwarfLineInstruction::DW_LNS_SET_FILE => self.state.file = self.reader.uleb128()?,
+                DwarfLineInstruction::DW_LNS_SET_COLUMN => {
+                    self.state.column = self.reader.uleb128()?;
+                }
+                DwarfLineInstruction::DW_LNS_NEGATE_STMT => {
+                    self.state.is_stmt = !self.state.is_stmt;
+                }
+                DwarfLineInstruction::DW_LNS_SET_BASIC_BLOCK => self.state.basic_block = true,
+                DwarfLineInstruction::DW_LNS_CONST_ADD_PC => {
+                    let adjusted = 255u8.wrapping_sub(opcode_base);
+                    let operations = adjusted
+                        .checked_div(self.program.line_range)
+                        .ok_or_else(error)?;
+                    self.advance(u64::from(operations));
+                }
+                DwarfLineInstruction::DW_LNS_FIXED_ADVANCE_PC => {
+                    let delta = self.reader.u16()?;
+                    self.state.address = self.state.address.wrapping_add(u64::from(delta));
+                    self.state.op_index = 0;
+                }
+                DwarfLineInstruction::DW_LNS_SET_PROLOGUE_END => self.state.prologue_end = true,
+                DwarfLineInstruction::DW_LNS_SET_EPILOGUE_BEGIN => {
+                    self.state.epilogue_begin = true;
+                }
+                DwarfLineInstruction::DW_LNS_SET_ISA => self.state.isa = self.reader.uleb128()?,
+                // Opcodes this reader doesn't know are skipped, using the
+                // number of operands the header gives them.
+                _ => {
+                    let index = usize::from(opcode.wrapping_sub(1));
+                    let operands = self
+                        .program
+                        .standard_opcode_lengths
+                        .get(index)
+                        .ok_or_else(error)?;
+                    for _ in 0..*operands {
+                        self.reader.uleb128()?;
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl Iterator for ElfLineRows<'_> {
+    type Item = Result<ElfLineRow, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        match self.step() {
+            Ok(Some(row)) => Some(Ok(row)),
+            Ok(None) => {
+                self.is_done = true;
+                None
+            }
+            Err(error) => {
+                self.is_done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// The `.debug_line` section, holding the line number programs of every
+/// compile unit.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDebugLine<'data> {
+    sections: DwarfSections<'data>,
+}
+
+impl<'data> ElfDebugLine<'data> {
+    pub fn new(sections: DwarfSections<'data>) -> Self {
+        Self { sections }
+    }
+
+    /// The programs, in the order they appear in the section.
+    pub fn programs(&self) -> impl Iterator<Item = Result<ElfLineProgram<'data>, ElfError>> {
+        let sections = self.sections;
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            if offset >= sections.debug_line.len() {
+                return None;
+            }
+            let program = ElfLineProgram::parse(sections, offset);
+            offset = match &program {
+                Ok(program) => program.end,
+                Err(_) => sections.debug_line.len(),
+            };
+            Some(program)
+        })
+    }
+
+    /// The file, line and column of the code at an address, found by
+    /// running each program in turn.
+    pub fn find_location(&self, address: u64) -> Result<Option<ElfLineLocation<'data>>, ElfError> {
+        for program in self.programs() {
+            if let Some(location) = program?.find_location(address)? {
+                return Ok(Some(location));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_a_line_program() {
+        // A DWARF 3 program for one file, "a.c" in "src", with rows at
+        // 0x1000 line 1, 0x1004 line 3 column 5 and 0x1010 line 2, ending
+        // at 0x1014.
+        let debug_line = b"\
+            \x3f\x00\x00\x00\x03\x00\x1e\x00\x00\x00\x01\x01\xfb\x0e\x0d\
+            \x00\x01\x01\x01\x01\x00\x00\x00\x01\x00\x00\x01\
+            src\x00\x00\
+            a.c\x00\x01\x00\x00\x00\
+            \x00\x09\x02\x00\x10\x00\x00\x00\x00\x00\x00\
+            \x01\
+            \x05\x05\x4c\
+            \x05\x00\x03\x7f\x02\x0c\x01\
+            \x02\x04\x00\x01\x01";
+        let sections = DwarfSections {
+            debug_line,
+            ..Default::default()
+        };
+        let program = ElfLineProgram::parse(sections, 0).expect("header should parse");
+        assert_eq!(program.directory(1).ok().flatten(), Some(&b"src"[..]));
+        let file = program.file(1).ok().flatten().expect("file 1 should exist");
+        assert_eq!((file.path, file.directory_index), (&b"a.c"[..], 1));
+
+        let rows = program.rows().map(|row| {
+            row.ok()
+                .map(|row| (row.address, row.line, row.column, row.end_sequence))
+        });
+        assert!(rows.eq([
+            Some((0x1000, 1, 0, false)),
+            Some((0x1004, 3, 5, false)),
+            Some((0x1010, 2, 0, false)),
+            Some((0x1014, 2, 0, true)),
+        ]));
+
+        let lines = ElfDebugLine::new(sections);
+        let location = |address| {
+            lines
+                .find_location(address)
+                .ok()
+                .flatten()
+                .map(|location| (location.line, location.column))
+        };
+        assert_eq!(location(0x1008), Some((3, 5)));
+        assert_eq!(location(0x1013), Some((2, 0)));
+        assert_eq!(location(0x1014), None);
+    }
+}
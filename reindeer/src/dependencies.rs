@@ -0,0 +1,972 @@
+//! Resolving the shared libraries a file depends on without running it, in
+//! the way glibc's `ld.so` would, for an `ldd` that works on a sysroot.
+//!
+//! A `DT_NEEDED` name containing a slash is a path. Other names are looked
+//! up in the `DT_RPATH` directories of the object needing them and of the
+//! objects that loaded it, unless the object has a `DT_RUNPATH`, then in the
+//! library path, the object's `DT_RUNPATH` directories, the directories
+//! listed in `ld.so.conf` and the default directories. The last two stand
+//! in for `ld.so.cache`, and are skipped for objects with `DF_1_NODEFLIB`.
+//! A file is only used if it is a shared object of the same class and
+//! machine as the file being resolved; otherwise the search goes on, and the
+//! file is recorded as rejected.
+//!
+//! `$ORIGIN`, `$LIB` and `$PLATFORM` are expanded in search paths. Absolute
+//! paths are looked up under the sysroot, except for those starting from
+//! `$ORIGIN`, which is the directory of the object as found. Symbolic links
+//! in the sysroot are followed as if it were the root directory, so that
+//! absolute links and `..` don't lead out of it.
+//!
+//! Like `ld.so`, libraries are loaded breadth first, and a name that matches
+//! an object already loaded, by the name it was needed by or its
+//! `DT_SONAME`, is not looked up again.
+
+use core::iter;
+use std::{
+    borrow::ToOwned,
+    collections::VecDeque,
+    fs,
+    path::{Component, Path, PathBuf},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{elf_aux_structures::*, errors::ElfError, ElfFile, ElfHeader};
+
+/// How deeply `include` directives in `ld.so.conf` are followed.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// How many symbolic links are followed in resolving a path, as in Linux.
+const MAX_SYMLINKS: usize = 40;
+
+/// Where a file in the graph came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfDependencySource {
+    /// The file being resolved.
+    Root,
+    /// The program interpreter named by `PT_INTERP`.
+    Interpreter,
+    /// The `DT_NEEDED` name contains a slash, so is the path of the library.
+    Path,
+    /// A `DT_RPATH` directory of the object with the given index.
+    Rpath(usize),
+    /// A directory of [`ElfDependencyResolver::with_library_path`].
+    LibraryPath,
+    /// A `DT_RUNPATH` directory of the object needing the library.
+    Runpath,
+    /// A directory listed in `ld.so.conf`.
+    LdSoConf,
+    /// One of [`ElfDependencyResolver::with_default_paths`].
+    Default,
+}
+
+/// Whether a file was found, and how.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfResolution {
+    /// The file was found at the path, which includes the sysroot.
+    Found {
+        path: PathBuf,
+        source: ElfDependencySource,
+    },
+    /// No usable file was found.
+    Missing,
+}
+
+/// Why a file found while searching for a library was not used.
+#[derive(Debug)]
+pub enum ElfRejection {
+    /// The file is not a valid ELF file, or its dynamic array is malformed.
+    Malformed(ElfError),
+    /// The file is ELF32 where ELF64 is needed, or the other way round.
+    WrongClass,
+    WrongMachine(ElfHeaderMachine),
+    /// The file is not a shared object.
+    WrongType(ElfHeaderType),
+}
+
+/// A file in the dependency graph.
+#[derive(Debug)]
+pub struct ElfDependency {
+    /// The `DT_NEEDED` name the file was first needed by, or the path of the
+    /// file being resolved or of the interpreter.
+    pub name: String,
+    pub resolution: ElfResolution,
+    /// The `DT_SONAME` of the file, if it was found and has one.
+    pub soname: Option<String>,
+    /// The files found while searching, but not used, in the order they
+    /// were tried.
+    pub rejected: Vec<(PathBuf, ElfRejection)>,
+    /// The indices in [`ElfDependencyGraph::objects`] of the libraries the
+    /// file needs, in the order of its `DT_NEEDED` entries.
+    pub needed: Vec<usize>,
+}
+
+impl ElfDependency {
+    pub fn path(&self) -> Option<&Path> {
+        match &self.resolution {
+            ElfResolution::Found { path, .. } => Some(path),
+            ElfResolution::Missing => None,
+        }
+    }
+}
+
+/// The files a file depends on, found by [`ElfDependencyResolver::resolve`].
+#[derive(Debug)]
+pub struct ElfDependencyGraph {
+    /// The file being resolved, then its interpreter if it has one, then the
+    /// libraries in the order `ld.so` would load them.
+    pub objects: Vec<ElfDependency>,
+}
+
+impl ElfDependencyGraph {
+    /// The libraries that were not found.
+    pub fn missing(&self) -> impl Iterator<Item = &ElfDependency> {
+        self.objects
+            .iter()
+            .filter(|object| object.resolution == ElfResolution::Missing)
+    }
+}
+
+/// The dynamic array entries of a found file that the search uses.
+#[derive(Debug, Clone, Default)]
+struct ObjectInfo {
+    needed: Vec<String>,
+    soname: Option<String>,
+    rpath: Option<String>,
+    runpath: Option<String>,
+    nodeflib: bool,
+}
+
+impl ObjectInfo {
+    fn read(file: &ElfFile) -> Result<Self, ElfError> {
+        let mut info = Self::default();
+        let (Some(dynamic), Some(strings)) = (file.dynamic_table()?, file.dynamic_string_table()?)
+        else {
+            return Ok(info);
+        };
+
+        for entry in dynamic {
+            let string = || -> Result<String, ElfError> {
+                Ok(strings.get(u32::try_from(entry.d_val())?)?.to_string())
+            };
+            match entry.d_tag() {
+                ElfDynamicTag::DT_NEEDED => info.needed.push(string()?),
+                ElfDynamicTag::DT_SONAME => info.soname = Some(string()?),
+                ElfDynamicTag::DT_RPATH => info.rpath = Some(string()?),
+                ElfDynamicTag::DT_RUNPATH => info.runpath = Some(string()?),
+                ElfDynamicTag::DT_FLAGS_1 => {
+                    let flags = ElfDynamicFlags1(entry.d_val());
+                    info.nodeflib = flags.contains(ElfDynamicFlags1::DF_1_NODEFLIB);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// A file of the graph, with what is needed to search for its libraries.
+struct Object {
+    dependency: ElfDependency,
+    info: ObjectInfo,
+    /// The canonical path of the file, to tell whether two paths are the
+    /// same file.
+    canonical: Option<PathBuf>,
+    /// The directory of the file, which `$ORIGIN` stands for.
+    origin: PathBuf,
+    /// The object that first needed this one.
+    loader: Option<usize>,
+    /// The names the object can be needed by.
+    names: Vec<String>,
+}
+
+/// A directory to search, and how it was configured.
+struct SearchDirectory {
+    path: PathBuf,
+    source: ElfDependencySource,
+}
+
+/// Finds the dependencies of a file as `ld.so` would, in a sysroot.
+#[derive(Debug, Clone)]
+pub struct ElfDependencyResolver {
+    sysroot: PathBuf,
+    library_path: Vec<String>,
+    ld_so_conf: Option<PathBuf>,
+    default_paths: Vec<String>,
+    lib: Option<String>,
+    platform: Option<String>,
+}
+
+impl Default for ElfDependencyResolver {
+    fn default() -> Self {
+        Self {
+            sysroot: PathBuf::from("/"),
+            library_path: Vec::new(),
+            ld_so_conf: Some(PathBuf::from(Self::LD_SO_CONF)),
+            default_paths: Self::DEFAULT_PATHS
+                .iter()
+                .map(|&path| path.into())
+                .collect(),
+            lib: None,
+            platform: None,
+        }
+    }
+}
+
+impl ElfDependencyResolver {
+    pub const LD_SO_CONF: &'static str = "/etc/ld.so.conf";
+    pub const DEFAULT_PATHS: &'static [&'static str] = &["/$LIB", "/usr/$LIB"];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory that absolute paths are looked up in, which is `/`
+    /// by default.
+    pub fn with_sysroot(mut self, sysroot: impl Into<PathBuf>) -> Self {
+        self.sysroot = sysroot.into();
+        self
+    }
+
+    /// Adds directories searched like those of `LD_LIBRARY_PATH`, separated
+    /// by colons or semicolons, after those already added.
+    pub fn with_library_path(mut self, paths: &str) -> Self {
+        self.library_path.extend(split_paths(paths).map(Into::into));
+        self
+    }
+
+    /// Sets the `ld.so.conf` file, which is [`Self::LD_SO_CONF`] by default.
+    pub fn with_ld_so_conf(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ld_so_conf = Some(path.into());
+        self
+    }
+
+    /// Searches no `ld.so.conf` directories.
+    pub fn without_ld_so_conf(mut self) -> Self {
+        self.ld_so_conf = None;
+        self
+    }
+
+    /// Sets the directories searched last, [`Self::DEFAULT_PATHS`] by
+    /// default.
+    pub fn with_default_paths<S: Into<String>>(
+        mut self,
+        paths: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.default_paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the value of `$LIB`, which is otherwise `lib64` for ELF64 files
+    /// and `lib` for ELF32 files.
+    pub fn with_lib(mut self, lib: impl Into<String>) -> Self {
+        self.lib = Some(lib.into());
+        self
+    }
+
+    /// Sets the value of `$PLATFORM`, which is otherwise the name of the
+    /// file's machine as given by `AT_PLATFORM`, where known.
+    pub fn with_platform(mut self, platform: impl Into<String>) -> Self {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    /// Resolves the dependencies of `file`, which was read from `path`.
+    /// Files that can't be read or used are skipped, so the only errors are
+    /// for a malformed dynamic array or interpreter in `file`.
+    pub fn resolve(&self, path: &Path, file: &ElfFile) -> Result<ElfDependencyGraph, ElfError> {
+        let header = file.header();
+        let search = Search {
+            resolver: self,
+            root: fs::canonicalize(&self.sysroot).unwrap_or_else(|_| self.sysroot.clone()),
+            header,
+            lib: self.lib.clone().unwrap_or_else(|| {
+                match header {
+                    ElfHeader::Elf32(_) => "lib",
+                    ElfHeader::Elf64(_) => "lib64",
+                }
+                .into()
+            }),
+            platform: self
+                .platform
+                .clone()
+                .or_else(|| platform(header).map(Into::into)),
+        };
+
+        let info = ObjectInfo::read(file)?;
+        let (canonical, origin) = search.locate(path);
+        let mut objects = Vec::new();
+        objects.push(Object {
+            dependency: ElfDependency {
+                name: path.display().to_string(),
+                resolution: ElfResolution::Found {
+                    path: path.to_path_buf(),
+                    source: ElfDependencySource::Root,
+                },
+                soname: info.soname.clone(),
+                rejected: Vec::new(),
+                needed: Vec::new(),
+            },
+            names: info.soname.iter().cloned().collect(),
+            canonical,
+            origin,
+            info,
+            loader: None,
+        });
+
+        if let Some(interpreter) = file.interpreter()? {
+            let mut object = search.find(interpreter, 0, Vec::new());
+            object.loader = None;
+            object.dependency.resolution = match object.dependency.resolution {
+                ElfResolution::Found { path, .. } => ElfResolution::Found {
+                    path,
+                    source: ElfDependencySource::Interpreter,
+                },
+                ElfResolution::Missing => ElfResolution::Missing,
+            };
+            // The interpreter is already loaded, and its own dependencies
+            // aren't.
+            object.info.needed.clear();
+            object.names.push(interpreter.to_owned());
+            objects.push(object);
+        }
+
+        let ld_so_conf = match &self.ld_so_conf {
+            Some(path) => search.ld_so_conf(path),
+            None => Vec::new(),
+        };
+
+        let mut queue = VecDeque::from([0]);
+        while let Some(index) = queue.pop_front() {
+            let needed = objects
+                .get(index)
+                .map(|object| object.info.needed.clone())
+                .unwrap_or_default();
+
+            for name in needed {
+                let needed_index = match objects
+                    .iter()
+                    .position(|object| object.names.contains(&name))
+                {
+                    Some(loaded) => loaded,
+                    None => {
+                        let directories = search.directories(&objects, index, &ld_so_conf);
+                        let object = search.find(&name, index, directories);
+                        // A file found by another name is only loaded once.
+                        let loaded = object.canonical.as_ref().and_then(|canonical| {
+                            objects
+                                .iter()
+                                .position(|loaded| loaded.canonical.as_ref() == Some(canonical))
+                        });
+
+                        if let Some(loaded) = loaded {
+                            if let Some(loaded) = objects.get_mut(loaded) {
+                                loaded.names.push(name);
+                            }
+                            loaded
+                        } else {
+                            queue.push_back(objects.len());
+                            objects.push(object);
+                            objects.len().saturating_sub(1)
+                        }
+                    }
+                };
+
+                if let Some(object) = objects.get_mut(index) {
+                    object.dependency.needed.push(needed_index);
+                }
+            }
+        }
+
+        Ok(ElfDependencyGraph {
+            objects: objects
+                .into_iter()
+                .map(|object| object.dependency)
+                .collect(),
+        })
+    }
+}
+
+/// The settings of one [`ElfDependencyResolver::resolve`], for the class
+/// and machine of the file being resolved.
+struct Search<'a, 'buf> {
+    resolver: &'a ElfDependencyResolver,
+    /// The canonical path of the sysroot.
+    root: PathBuf,
+    header: ElfHeader<'buf>,
+    lib: String,
+    platform: Option<String>,
+}
+
+impl Search<'_, '_> {
+    /// Looks up an absolute path under the sysroot. Returns `None` if there
+    /// are too many symbolic links to resolve it.
+    fn in_sysroot(&self, path: &Path) -> Option<PathBuf> {
+        match path.strip_prefix("/") {
+            Ok(relative) => self.resolve_in_root(relative),
+            Err(_) => Some(path.to_path_buf()),
+        }
+    }
+
+    /// Resolves the symbolic links in `path`, which is relative to the
+    /// sysroot, as if the sysroot were the root directory. Components that
+    /// don't exist are kept as they are.
+    fn resolve_in_root(&self, path: &Path) -> Option<PathBuf> {
+        let mut resolved = self.root.clone();
+        let mut rest = path.to_path_buf();
+        let mut links = 0usize;
+
+        loop {
+            let mut components = rest.components();
+            let Some(component) = components.next() else {
+                return Some(resolved);
+            };
+            let after = components.as_path().to_path_buf();
+
+            match component {
+                Component::Prefix(_) | Component::RootDir => resolved.clone_from(&self.root),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if resolved != self.root {
+                        resolved.pop();
+                    }
+                }
+                Component::Normal(name) => {
+                    let next = resolved.join(name);
+                    let is_link = fs::symlink_metadata(&next)
+                        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                    let target = is_link.then(|| fs::read_link(&next).ok()).flatten();
+                    if let Some(target) = target {
+                        links = links.saturating_add(1);
+                        if links > MAX_SYMLINKS {
+                            return None;
+                        }
+                        // An absolute target restarts from the sysroot.
+                        rest = target.join(after);
+                        continue;
+                    }
+                    resolved = next;
+                }
+            }
+            rest = after;
+        }
+    }
+
+    /// The path of a file with its symbolic links resolved, following them
+    /// within the sysroot for files in it, or `None` if it can't be
+    /// resolved.
+    fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        match path
+            .strip_prefix(&self.resolver.sysroot)
+            .or_else(|_| path.strip_prefix(&self.root))
+        {
+            Ok(relative) => self.resolve_in_root(relative),
+            Err(_) => fs::canonicalize(path).ok(),
+        }
+    }
+
+    /// The canonical path of a file, if it exists, and its directory, which
+    /// `$ORIGIN` stands for.
+    fn locate(&self, path: &Path) -> (Option<PathBuf>, PathBuf) {
+        let canonical = self.resolve(path).filter(|resolved| resolved.exists());
+        let origin = match canonical.as_deref().unwrap_or(path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        (canonical, origin)
+    }
+
+    /// Expands the dynamic string tokens in a search path, for an object in
+    /// `origin`. Returns `None` if a token has no value, in which case
+    /// `ld.so` ignores the path.
+    fn expand(&self, path: &str, origin: &Path) -> Option<PathBuf> {
+        let mut expanded = String::new();
+        let mut from_origin = false;
+        let mut rest = path;
+
+        while let Some((before, after)) = rest.split_once('$') {
+            expanded.push_str(before);
+            let braced = after.starts_with('{');
+            let (name, after) = match after.strip_prefix('{') {
+                Some(braced) => braced.split_once('}')?,
+                None => {
+                    let end = after
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(after.len());
+                    after.split_at(end)
+                }
+            };
+
+            match name {
+                "ORIGIN" => {
+                    from_origin = true;
+                    expanded.push_str(origin.to_str()?);
+                }
+                "LIB" => expanded.push_str(&self.lib),
+                "PLATFORM" => expanded.push_str(self.platform.as_deref()?),
+                _ if braced => {
+                    expanded.push_str("${");
+                    expanded.push_str(name);
+                    expanded.push('}');
+                }
+                _ => {
+                    expanded.push('$');
+                    expanded.push_str(name);
+                }
+            }
+            rest = after;
+        }
+        expanded.push_str(rest);
+
+        // An empty path is the current directory.
+        if expanded.is_empty() {
+            expanded.push('.');
+        }
+
+        let expanded = PathBuf::from(expanded);
+        match from_origin {
+            true => Some(expanded),
+            false => self.in_sysroot(&expanded),
+        }
+    }
+
+    fn add_directories(
+        &self,
+        directories: &mut Vec<SearchDirectory>,
+        paths: &str,
+        origin: &Path,
+        source: ElfDependencySource,
+    ) {
+        for path in split_paths(paths) {
+            if let Some(path) = self.expand(path, origin) {
+                directories.push(SearchDirectory { path, source });
+            }
+        }
+    }
+
+    /// The directories searched for the libraries of the object at `index`,
+    /// in order.
+    fn directories(
+        &self,
+        objects: &[Object],
+        index: usize,
+        ld_so_conf: &[PathBuf],
+    ) -> Vec<SearchDirectory> {
+        let mut directories = Vec::new();
+        let Some(object) = objects.get(index) else {
+            return directories;
+        };
+
+        if object.info.runpath.is_none() {
+            let mut loader = Some(index);
+            while let Some(loader_index) = loader {
+                let Some(loader_object) = objects.get(loader_index) else {
+                    break;
+                };
+                // The DT_RPATH of an object with a DT_RUNPATH is ignored.
+                if let (Some(rpath), None) =
+                    (&loader_object.info.rpath, &loader_object.info.runpath)
+                {
+                    let source = ElfDependencySource::Rpath(loader_index);
+                    self.add_directories(&mut directories, rpath, &loader_object.origin, source);
+                }
+                loader = loader_object.loader;
+            }
+        }
+
+        // As in ld.so, $ORIGIN in the library path is the directory of the
+        // executable.
+        let executable_origin = objects.first().map_or(Path::new("."), |root| &root.origin);
+        for path in &self.resolver.library_path {
+            if let Some(path) = self.expand(path, executable_origin) {
+                let source = ElfDependencySource::LibraryPath;
+                directories.push(SearchDirectory { path, source });
+            }
+        }
+
+        if let Some(runpath) = &object.info.runpath {
+            let source = ElfDependencySource::Runpath;
+            self.add_directories(&mut directories, runpath, &object.origin, source);
+        }
+
+        if !object.info.nodeflib {
+            directories.extend(ld_so_conf.iter().map(|path| SearchDirectory {
+                path: path.clone(),
+                source: ElfDependencySource::LdSoConf,
+            }));
+            for path in &self.resolver.default_paths {
+                let source = ElfDependencySource::Default;
+                self.add_directories(&mut directories, path, &object.origin, source);
+            }
+        }
+
+        directories
+    }
+
+    /// Checks that the file in `buffer` can be loaded with the file being
+    /// resolved.
+    fn check(&self, buffer: &[u8]) -> Result<ObjectInfo, ElfRejection> {
+        let file = ElfFile::parse(buffer).map_err(ElfRejection::Malformed)?;
+        let header = file.header();
+
+        let same_class = matches!(
+            (header, self.header),
+            (ElfHeader::Elf32(_), ElfHeader::Elf32(_)) | (ElfHeader::Elf64(_), ElfHeader::Elf64(_))
+        );
+        if !same_class {
+            return Err(ElfRejection::WrongClass);
+        }
+        if header.e_machine() != self.header.e_machine() {
+            return Err(ElfRejection::WrongMachine(header.e_machine()));
+        }
+        if header.e_type() != ElfHeaderType::ET_DYN {
+            return Err(ElfRejection::WrongType(header.e_type()));
+        }
+
+        ObjectInfo::read(&file).map_err(ElfRejection::Malformed)
+    }
+
+    /// Tries each candidate path for `name` in turn.
+    fn find(&self, name: &str, loader: usize, directories: Vec<SearchDirectory>) -> Object {
+        let candidates: Vec<(PathBuf, ElfDependencySource)> = if name.contains('/') {
+            let path = match Path::new(name).is_absolute() {
+                true => self.resolver.sysroot.join(name.trim_start_matches('/')),
+                false => PathBuf::from(name),
+            };
+            Vec::from([(path, ElfDependencySource::Path)])
+        } else {
+            directories
+                .into_iter()
+                .map(|directory| (directory.path.join(name), directory.source))
+                .collect()
+        };
+
+        let mut rejected = Vec::new();
+        for (path, source) in candidates {
+            // The file is read where its links lead, but reported by the
+            // path it was found at, like `ldd` does.
+            let Some(buffer) = self
+                .resolve(&path)
+                .and_then(|resolved| fs::read(resolved).ok())
+            else {
+                continue;
+            };
+
+            match self.check(&buffer) {
+                Ok(info) => {
+                    let mut names = Vec::from([name.to_owned()]);
+                    names.extend(info.soname.iter().cloned());
+                    let (canonical, origin) = self.locate(&path);
+                    return Object {
+                        dependency: ElfDependency {
+                            name: name.to_owned(),
+                            soname: info.soname.clone(),
+                            resolution: ElfResolution::Found {
+                                path: path.clone(),
+                                source,
+                            },
+                            rejected,
+                            needed: Vec::new(),
+                        },
+                        canonical,
+                        origin,
+                        info,
+                        loader: Some(loader),
+                        names,
+                    };
+                }
+                Err(rejection) => rejected.push((path, rejection)),
+            }
+        }
+
+        Object {
+            dependency: ElfDependency {
+                name: name.to_owned(),
+                resolution: ElfResolution::Missing,
+                soname: None,
+                rejected,
+                needed: Vec::new(),
+            },
+            info: ObjectInfo::default(),
+            canonical: None,
+            origin: PathBuf::new(),
+            loader: Some(loader),
+            names: Vec::from([name.to_owned()]),
+        }
+    }
+
+    /// The directories listed in an `ld.so.conf` file and the files it
+    /// includes, under the sysroot.
+    fn ld_so_conf(&self, path: &Path) -> Vec<PathBuf> {
+        let mut directories = Vec::new();
+        self.read_ld_so_conf(path, 0, &mut directories);
+        directories
+    }
+
+    fn read_ld_so_conf(&self, path: &Path, depth: usize, directories: &mut Vec<PathBuf>) {
+        let Some(Ok(text)) = self.in_sysroot(path).map(fs::read_to_string) else {
+            return;
+        };
+        let parent = path.parent().unwrap_or(Path::new("/"));
+
+        for line in parse_ld_so_conf(&text) {
+            match line {
+                LdSoConfLine::Directory(directory) => {
+                    let Some(directory) = self.in_sysroot(Path::new(directory)) else {
+                        continue;
+                    };
+                    if !directories.contains(&directory) {
+                        directories.push(directory);
+                    }
+                }
+                LdSoConfLine::Include(pattern) if depth < MAX_INCLUDE_DEPTH => {
+                    for include in self.glob(&parent.join(pattern)) {
+                        self.read_ld_so_conf(&include, depth.saturating_add(1), directories);
+                    }
+                }
+                LdSoConfLine::Include(_) => {}
+            }
+        }
+    }
+
+    /// The files matching `pattern`, whose last component may contain `*`
+    /// and `?` wildcards, sorted like `glob` does. The paths returned are
+    /// outside the sysroot, like the pattern.
+    fn glob(&self, pattern: &Path) -> Vec<PathBuf> {
+        let (Some(directory), Some(name)) = (pattern.parent(), pattern.file_name()) else {
+            return Vec::new();
+        };
+        let name = name.as_encoded_bytes();
+        if !name.contains(&b'*') && !name.contains(&b'?') {
+            return Vec::from([pattern.to_path_buf()]);
+        }
+
+        let Some(Ok(entries)) = self.in_sysroot(directory).map(fs::read_dir) else {
+            return Vec::new();
+        };
+        let mut matches: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name())
+            .filter(|file_name| {
+                let file_name = file_name.as_encoded_bytes();
+                // Wildcards don't match a leading dot.
+                (name.first() == Some(&b'.') || file_name.first() != Some(&b'.'))
+                    && wildcard(name, file_name)
+            })
+            .map(|file_name| directory.join(file_name))
+            .collect();
+        matches.sort();
+        matches
+    }
+}
+
+/// A line of `ld.so.conf` that names directories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LdSoConfLine<'a> {
+    Directory(&'a str),
+    /// A pattern of files to read, relative to the including file's
+    /// directory.
+    Include(&'a str),
+}
+
+/// Parses `ld.so.conf`, which lists directories separated by whitespace,
+/// colons or commas, with `#` comments and `include` lines. `hwcap` lines
+/// are ignored, and a `=TYPE` suffix on a directory is dropped.
+fn parse_ld_so_conf(text: &str) -> Vec<LdSoConfLine<'_>> {
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        let line = line.split_once('#').map_or(line, |(line, _)| line);
+        let mut words = line
+            .split(|c: char| c.is_whitespace() || c == ':' || c == ',')
+            .filter(|word| !word.is_empty());
+
+        match words.next() {
+            Some("include") => lines.extend(words.map(LdSoConfLine::Include)),
+            Some("hwcap") | None => {}
+            Some(first) => lines.extend(iter::once(first).chain(words).map(|word| {
+                LdSoConfLine::Directory(word.split_once('=').map_or(word, |(word, _)| word))
+            })),
+        }
+    }
+
+    lines
+}
+
+fn split_paths(paths: &str) -> impl Iterator<Item = &str> {
+    paths.split([':', ';'])
+}
+
+/// The `AT_PLATFORM` string of the kernel for the file's machine, where it
+/// doesn't depend on the processor model.
+fn platform(header: ElfHeader) -> Option<&'static str> {
+    match (header.e_machine(), header) {
+        (ElfHeaderMachine::EM_X86_64, ElfHeader::Elf64(_)) => Some("x86_64"),
+        (ElfHeaderMachine::EM_AARCH64, _) => Some("aarch64"),
+        (ElfHeaderMachine::EM_RISCV, ElfHeader::Elf64(_)) => Some("riscv64"),
+        (ElfHeaderMachine::EM_LOONGARCH, _) => Some("loongarch64"),
+        _ => None,
+    }
+}
+
+fn wildcard(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=name.len()).any(|skip| name.get(skip..).is_some_and(|name| wildcard(rest, name)))
+        }
+        Some((b'?', rest)) => name
+            .split_first()
+            .is_some_and(|(_, name)| wildcard(rest, name)),
+        Some((byte, rest)) => name
+            .split_first()
+            .is_some_and(|(first, name)| first == byte && wildcard(rest, name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zerocopy::FromZeroes;
+
+    use super::*;
+
+    #[test]
+    fn expands_tokens_and_parses_ld_so_conf() {
+        let resolver = ElfDependencyResolver::new().with_sysroot("/sysroot");
+        let header = crate::elf_structures::Elf64Header::new_zeroed();
+        let search = Search {
+            resolver: &resolver,
+            root: "/sysroot".into(),
+            header: ElfHeader::Elf64(&header),
+            lib: "lib64".into(),
+            platform: None,
+        };
+        let origin = Path::new("/build/bin");
+
+        let expand = |path| search.expand(path, origin);
+        assert_eq!(expand("$ORIGIN/../lib"), Some("/build/bin/../lib".into()));
+        assert_eq!(expand("${ORIGIN}"), Some("/build/bin".into()));
+        assert_eq!(expand("/usr/$LIB"), Some("/sysroot/usr/lib64".into()));
+        assert_eq!(expand("/opt/$PLATFORM"), None);
+        assert_eq!(
+            expand("/opt/$ORIGINAL"),
+            Some("/sysroot/opt/$ORIGINAL".into())
+        );
+        assert_eq!(expand("relative"), Some("relative".into()));
+
+        let conf =
+            "# comment\ninclude ld.so.conf.d/*.conf\n/usr/local/lib:/opt/lib=ELF\nhwcap 0 x\n";
+        assert_eq!(
+            parse_ld_so_conf(conf),
+            [
+                LdSoConfLine::Include("ld.so.conf.d/*.conf"),
+                LdSoConfLine::Directory("/usr/local/lib"),
+                LdSoConfLine::Directory("/opt/lib"),
+            ]
+        );
+
+        assert!(wildcard(b"*.conf", b"libc.conf"));
+        assert!(wildcard(b"lib?.conf", b"libc.conf"));
+        assert!(!wildcard(b"*.conf", b"libc.conf~"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_in_a_sysroot() {
+        use crate::testdata::{
+            DEPENDENCIES_APP, DEPENDENCIES_FIRST, DEPENDENCIES_PLAIN, DEPENDENCIES_PLAIN32,
+        };
+
+        let sysroot =
+            std::env::temp_dir().join(std::format!("reindeer-sysroot-{}", std::process::id()));
+        let write = |path: &str, contents: &[u8]| {
+            let path = sysroot.join(path);
+            fs::create_dir_all(path.parent().expect("in a directory")).expect("writable");
+            fs::write(path, contents).expect("writable");
+        };
+
+        let mut aarch64 = DEPENDENCIES_PLAIN;
+        aarch64
+            .0
+            .get_mut(18..20)
+            .expect("in the header")
+            .copy_from_slice(&ElfHeaderMachine::EM_AARCH64.0.to_le_bytes());
+
+        write("app", &DEPENDENCIES_APP.0);
+        // The DT_RPATH of the executable comes before the library path.
+        write("rpath/libfirst.so", &DEPENDENCIES_FIRST.0);
+        write("libpath/libfirst.so", &DEPENDENCIES_PLAIN.0);
+        // libfirst.so has a DT_RUNPATH, so the DT_RPATH isn't searched for
+        // its libraries, and the library path comes before the DT_RUNPATH.
+        // The absolute link has to be followed within the sysroot.
+        write("rpath/libthird.so", &DEPENDENCIES_PLAIN.0);
+        write("real/libthird.so", &DEPENDENCIES_PLAIN.0);
+        std::os::unix::fs::symlink("/real/libthird.so", sysroot.join("libpath/libthird.so"))
+            .expect("writable");
+        write("runpath/libthird.so", &DEPENDENCIES_PLAIN.0);
+        // Files of the wrong class or machine are passed over.
+        write("libpath/libsecond.so", &DEPENDENCIES_PLAIN32.0);
+        write("wrong/libsecond.so", &aarch64.0);
+        write("usr/lib/libsecond.so", &DEPENDENCIES_PLAIN.0);
+
+        let file = ElfFile::parse(&DEPENDENCIES_APP.0).expect("valid file");
+        let graph = ElfDependencyResolver::new()
+            .with_sysroot(&sysroot)
+            .with_library_path("/libpath")
+            .without_ld_so_conf()
+            .with_default_paths(["/wrong", "/usr/lib"])
+            .resolve(&sysroot.join("app"), &file);
+        fs::remove_dir_all(&sysroot).expect("removable");
+        let graph = graph.expect("valid dynamic array");
+
+        let found = |name: &str, path: &str, source| {
+            let object = graph
+                .objects
+                .iter()
+                .find(|object| object.name == name)
+                .expect("in the graph");
+            assert_eq!(
+                object.resolution,
+                ElfResolution::Found {
+                    path: sysroot.join(path),
+                    source
+                }
+            );
+            object
+        };
+
+        let first = found(
+            "libfirst.so",
+            "rpath/libfirst.so",
+            ElfDependencySource::Rpath(0),
+        );
+        assert_eq!(first.soname.as_deref(), Some("libfirst.so.1"));
+        found(
+            "libthird.so",
+            "libpath/libthird.so",
+            ElfDependencySource::LibraryPath,
+        );
+        let second = found(
+            "libsecond.so",
+            "usr/lib/libsecond.so",
+            ElfDependencySource::Default,
+        );
+        assert!(matches!(
+            second.rejected.as_slice(),
+            [
+                (_, ElfRejection::WrongClass),
+                (_, ElfRejection::WrongMachine(ElfHeaderMachine::EM_AARCH64)),
+            ]
+        ));
+
+        // libfirst.so.1 is the DT_SONAME of libfirst.so, so isn't loaded
+        // again.
+        assert_eq!(graph.objects.len(), 4);
+        assert_eq!(
+            graph.objects.first().map(|app| app.needed.as_slice()),
+            Some(&[1, 1, 2][..])
+        );
+        assert_eq!(graph.missing().count(), 0);
+    }
+}
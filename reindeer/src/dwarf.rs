@@ -0,0 +1,933 @@
+//! Primitives shared by the DWARF readers: LEB128 numbers, the initial
+//! length of 32 and 64-bit DWARF, the pointer encodings of `.eh_frame`, and
+//! the attribute forms of the debugging sections.
+
+use core::ffi::CStr;
+
+use zerocopy::{AsBytes, FromBytes, FromZeroes};
+
+use crate::{
+    elf_aux_structures::{ElfCompressionType, ElfSectionFlags},
+    errors::ElfError,
+    macros::declare_constants,
+    ElfFile,
+};
+
+/// How a pointer in `.eh_frame` and `.eh_frame_hdr` is stored: the low four
+/// bits give the format, the next three what it is relative to, and the top
+/// bit whether it points to the value rather than being it.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfPointerEncoding(pub u8);
+
+declare_constants!(DwarfPointerEncoding, {
+    DW_EH_PE_ABSPTR = 0x00,
+    DW_EH_PE_ULEB128 = 0x01,
+    DW_EH_PE_UDATA2 = 0x02,
+    DW_EH_PE_UDATA4 = 0x03,
+    DW_EH_PE_UDATA8 = 0x04,
+    DW_EH_PE_SLEB128 = 0x09,
+    DW_EH_PE_SDATA2 = 0x0a,
+    DW_EH_PE_SDATA4 = 0x0b,
+    DW_EH_PE_SDATA8 = 0x0c,
+    DW_EH_PE_PCREL = 0x10,
+    DW_EH_PE_TEXTREL = 0x20,
+    DW_EH_PE_DATAREL = 0x30,
+    DW_EH_PE_FUNCREL = 0x40,
+    DW_EH_PE_ALIGNED = 0x50,
+    DW_EH_PE_INDIRECT = 0x80,
+    DW_EH_PE_OMIT = 0xff,
+});
+
+impl DwarfPointerEncoding {
+    pub fn format(self) -> Self {
+        Self(self.0 & 0x0f)
+    }
+
+    pub fn application(self) -> Self {
+        Self(self.0 & 0x70)
+    }
+
+    pub fn is_indirect(self) -> bool {
+        self != Self::DW_EH_PE_OMIT && self.0 & Self::DW_EH_PE_INDIRECT.0 != 0
+    }
+
+    /// The size of a pointer in this format, or `None` for the variable
+    /// length LEB128 formats.
+    pub fn fixed_size(self, address_size: u8) -> Option<usize> {
+        match self.format() {
+            Self::DW_EH_PE_ABSPTR => Some(usize::from(address_size)),
+            Self::DW_EH_PE_UDATA2 | Self::DW_EH_PE_SDATA2 => Some(2),
+            Self::DW_EH_PE_UDATA4 | Self::DW_EH_PE_SDATA4 => Some(4),
+            Self::DW_EH_PE_UDATA8 | Self::DW_EH_PE_SDATA8 => Some(8),
+            _ => None,
+        }
+    }
+}
+
+/// A call frame instruction. The three with an operand in their low six bits
+/// are given with those bits clear.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfCallFrameInstruction(pub u8);
+
+declare_constants!(DwarfCallFrameInstruction, {
+    DW_CFA_NOP = 0x00,
+    DW_CFA_SET_LOC = 0x01,
+    DW_CFA_ADVANCE_LOC1 = 0x02,
+    DW_CFA_ADVANCE_LOC2 = 0x03,
+    DW_CFA_ADVANCE_LOC4 = 0x04,
+    DW_CFA_OFFSET_EXTENDED = 0x05,
+    DW_CFA_RESTORE_EXTENDED = 0x06,
+    DW_CFA_UNDEFINED = 0x07,
+    DW_CFA_SAME_VALUE = 0x08,
+    DW_CFA_REGISTER = 0x09,
+    DW_CFA_REMEMBER_STATE = 0x0a,
+    DW_CFA_RESTORE_STATE = 0x0b,
+    DW_CFA_DEF_CFA = 0x0c,
+    DW_CFA_DEF_CFA_REGISTER = 0x0d,
+    DW_CFA_DEF_CFA_OFFSET = 0x0e,
+    DW_CFA_DEF_CFA_EXPRESSION = 0x0f,
+    DW_CFA_EXPRESSION = 0x10,
+    DW_CFA_OFFSET_EXTENDED_SF = 0x11,
+    DW_CFA_DEF_CFA_SF = 0x12,
+    DW_CFA_DEF_CFA_OFFSET_SF = 0x13,
+    DW_CFA_VAL_OFFSET = 0x14,
+    DW_CFA_VAL_OFFSET_SF = 0x15,
+    DW_CFA_VAL_EXPRESSION = 0x16,
+    DW_CFA_AARCH64_NEGATE_RA_STATE = 0x2d,
+    DW_CFA_GNU_ARGS_SIZE = 0x2e,
+    DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED = 0x2f,
+    DW_CFA_ADVANCE_LOC = 0x40,
+    DW_CFA_OFFSET = 0x80,
+    DW_CFA_RESTORE = 0xc0,
+});
+
+/// A DWARF expression operation. The ranges `DW_OP_LIT0..=DW_OP_LIT31`,
+/// `DW_OP_REG0..=DW_OP_REG31` and `DW_OP_BREG0..=DW_OP_BREG31` are given by
+/// their first.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfOperation(pub u8);
+
+declare_constants!(DwarfOperation, {
+    DW_OP_ADDR = 0x03,
+    DW_OP_DEREF = 0x06,
+    DW_OP_CONST1U = 0x08,
+    DW_OP_CONST1S = 0x09,
+    DW_OP_CONST2U = 0x0a,
+    DW_OP_CONST2S = 0x0b,
+    DW_OP_CONST4U = 0x0c,
+    DW_OP_CONST4S = 0x0d,
+    DW_OP_CONST8U = 0x0e,
+    DW_OP_CONST8S = 0x0f,
+    DW_OP_CONSTU = 0x10,
+    DW_OP_CONSTS = 0x11,
+    DW_OP_DUP = 0x12,
+    DW_OP_DROP = 0x13,
+    DW_OP_OVER = 0x14,
+    DW_OP_PICK = 0x15,
+    DW_OP_SWAP = 0x16,
+    DW_OP_ROT = 0x17,
+    DW_OP_XDEREF = 0x18,
+    DW_OP_ABS = 0x19,
+    DW_OP_AND = 0x1a,
+    DW_OP_DIV = 0x1b,
+    DW_OP_MINUS = 0x1c,
+    DW_OP_MOD = 0x1d,
+    DW_OP_MUL = 0x1e,
+    DW_OP_NEG = 0x1f,
+    DW_OP_NOT = 0x20,
+    DW_OP_OR = 0x21,
+    DW_OP_PLUS = 0x22,
+    DW_OP_PLUS_UCONST = 0x23,
+    DW_OP_SHL = 0x24,
+    DW_OP_SHR = 0x25,
+    DW_OP_SHRA = 0x26,
+    DW_OP_XOR = 0x27,
+    DW_OP_BRA = 0x28,
+    DW_OP_EQ = 0x29,
+    DW_OP_GE = 0x2a,
+    DW_OP_GT = 0x2b,
+    DW_OP_LE = 0x2c,
+    DW_OP_LT = 0x2d,
+    DW_OP_NE = 0x2e,
+    DW_OP_SKIP = 0x2f,
+    DW_OP_LIT0 = 0x30,
+    DW_OP_REG0 = 0x50,
+    DW_OP_BREG0 = 0x70,
+    DW_OP_REGX = 0x90,
+    DW_OP_FBREG = 0x91,
+    DW_OP_BREGX = 0x92,
+    DW_OP_PIECE = 0x93,
+    DW_OP_DEREF_SIZE = 0x94,
+    DW_OP_NOP = 0x96,
+    DW_OP_CALL_FRAME_CFA = 0x9c,
+});
+
+/// A standard opcode of a line number program. Opcodes from the header's
+/// `opcode_base` up are special opcodes, and `DW_LNS_EXTENDED_OP`
+/// introduces a [`DwarfLineExtendedInstruction`].
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfLineInstruction(pub u8);
+
+declare_constants!(DwarfLineInstruction, {
+    DW_LNS_EXTENDED_OP = 0x00,
+    DW_LNS_COPY = 0x01,
+    DW_LNS_ADVANCE_PC = 0x02,
+    DW_LNS_ADVANCE_LINE = 0x03,
+    DW_LNS_SET_FILE = 0x04,
+    DW_LNS_SET_COLUMN = 0x05,
+    DW_LNS_NEGATE_STMT = 0x06,
+    DW_LNS_SET_BASIC_BLOCK = 0x07,
+    DW_LNS_CONST_ADD_PC = 0x08,
+    DW_LNS_FIXED_ADVANCE_PC = 0x09,
+    DW_LNS_SET_PROLOGUE_END = 0x0a,
+    DW_LNS_SET_EPILOGUE_BEGIN = 0x0b,
+    DW_LNS_SET_ISA = 0x0c,
+});
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfLineExtendedInstruction(pub u8);
+
+declare_constants!(DwarfLineExtendedInstruction, {
+    DW_LNE_END_SEQUENCE = 0x01,
+    DW_LNE_SET_ADDRESS = 0x02,
+    DW_LNE_DEFINE_FILE = 0x03,
+    DW_LNE_SET_DISCRIMINATOR = 0x04,
+});
+
+/// What a field of a directory or file entry in a DWARF 5 line table
+/// header holds.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfLineContentType(pub u16);
+
+declare_constants!(DwarfLineContentType, {
+    DW_LNCT_PATH = 0x1,
+    DW_LNCT_DIRECTORY_INDEX = 0x2,
+    DW_LNCT_TIMESTAMP = 0x3,
+    DW_LNCT_SIZE = 0x4,
+    DW_LNCT_MD5 = 0x5,
+    DW_LNCT_LLVM_SOURCE = 0x2001,
+});
+
+/// The kind of a unit in `.debug_info`, which DWARF 5 gives in the unit
+/// header.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfUnitType(pub u8);
+
+declare_constants!(DwarfUnitType, {
+    DW_UT_COMPILE = 0x01,
+    DW_UT_TYPE = 0x02,
+    DW_UT_PARTIAL = 0x03,
+    DW_UT_SKELETON = 0x04,
+    DW_UT_SPLIT_COMPILE = 0x05,
+    DW_UT_SPLIT_TYPE = 0x06,
+});
+
+/// The tag of a debugging information entry. Only those of unit entries
+/// are listed.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfTag(pub u16);
+
+declare_constants!(DwarfTag, {
+    DW_TAG_COMPILE_UNIT = 0x11,
+    DW_TAG_PARTIAL_UNIT = 0x3c,
+    DW_TAG_TYPE_UNIT = 0x41,
+    DW_TAG_SKELETON_UNIT = 0x4a,
+});
+
+/// The name of an attribute. Only those of unit entries are listed.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfAttribute(pub u16);
+
+declare_constants!(DwarfAttribute, {
+    DW_AT_NAME = 0x03,
+    DW_AT_STMT_LIST = 0x10,
+    DW_AT_LOW_PC = 0x11,
+    DW_AT_HIGH_PC = 0x12,
+    DW_AT_LANGUAGE = 0x13,
+    DW_AT_COMP_DIR = 0x1b,
+    DW_AT_PRODUCER = 0x25,
+    DW_AT_ENTRY_PC = 0x52,
+    DW_AT_USE_UTF8 = 0x53,
+    DW_AT_RANGES = 0x55,
+    DW_AT_MAIN_SUBPROGRAM = 0x6a,
+    DW_AT_STR_OFFSETS_BASE = 0x72,
+    DW_AT_ADDR_BASE = 0x73,
+    DW_AT_RNGLISTS_BASE = 0x74,
+    DW_AT_DWO_NAME = 0x76,
+    DW_AT_LOCLISTS_BASE = 0x8c,
+    DW_AT_GNU_DWO_NAME = 0x2130,
+    DW_AT_GNU_DWO_ID = 0x2131,
+    DW_AT_GNU_RANGES_BASE = 0x2132,
+    DW_AT_GNU_ADDR_BASE = 0x2133,
+    DW_AT_GNU_PUBNAMES = 0x2134,
+});
+
+/// The source language of a compile unit.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfLanguage(pub u16);
+
+declare_constants!(DwarfLanguage, {
+    DW_LANG_C89 = 0x01,
+    DW_LANG_C = 0x02,
+    DW_LANG_ADA83 = 0x03,
+    DW_LANG_C_PLUS_PLUS = 0x04,
+    DW_LANG_COBOL74 = 0x05,
+    DW_LANG_COBOL85 = 0x06,
+    DW_LANG_FORTRAN77 = 0x07,
+    DW_LANG_FORTRAN90 = 0x08,
+    DW_LANG_PASCAL83 = 0x09,
+    DW_LANG_MODULA2 = 0x0a,
+    DW_LANG_JAVA = 0x0b,
+    DW_LANG_C99 = 0x0c,
+    DW_LANG_ADA95 = 0x0d,
+    DW_LANG_FORTRAN95 = 0x0e,
+    DW_LANG_PLI = 0x0f,
+    DW_LANG_OBJC = 0x10,
+    DW_LANG_OBJC_PLUS_PLUS = 0x11,
+    DW_LANG_UPC = 0x12,
+    DW_LANG_D = 0x13,
+    DW_LANG_PYTHON = 0x14,
+    DW_LANG_OPENCL = 0x15,
+    DW_LANG_GO = 0x16,
+    DW_LANG_MODULA3 = 0x17,
+    DW_LANG_HASKELL = 0x18,
+    DW_LANG_C_PLUS_PLUS_03 = 0x19,
+    DW_LANG_C_PLUS_PLUS_11 = 0x1a,
+    DW_LANG_OCAML = 0x1b,
+    DW_LANG_RUST = 0x1c,
+    DW_LANG_C11 = 0x1d,
+    DW_LANG_SWIFT = 0x1e,
+    DW_LANG_JULIA = 0x1f,
+    DW_LANG_DYLAN = 0x20,
+    DW_LANG_C_PLUS_PLUS_14 = 0x21,
+    DW_LANG_FORTRAN03 = 0x22,
+    DW_LANG_FORTRAN08 = 0x23,
+    DW_LANG_RENDERSCRIPT = 0x24,
+    DW_LANG_BLISS = 0x25,
+    DW_LANG_KOTLIN = 0x26,
+    DW_LANG_ZIG = 0x27,
+    DW_LANG_CRYSTAL = 0x28,
+    DW_LANG_C_PLUS_PLUS_17 = 0x2a,
+    DW_LANG_C_PLUS_PLUS_20 = 0x2b,
+    DW_LANG_C17 = 0x2c,
+    DW_LANG_FORTRAN18 = 0x2d,
+    DW_LANG_ADA2005 = 0x2e,
+    DW_LANG_ADA2012 = 0x2f,
+    DW_LANG_HIP = 0x30,
+    DW_LANG_ASSEMBLY = 0x31,
+    DW_LANG_C_SHARP = 0x32,
+    DW_LANG_MOJO = 0x33,
+    DW_LANG_MIPS_ASSEMBLER = 0x8001,
+    DW_LANG_GOOGLE_RENDERSCRIPT = 0x8e57,
+});
+
+/// An entry of a DWARF 5 range list in `.debug_rnglists`.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfRangeListEntry(pub u8);
+
+declare_constants!(DwarfRangeListEntry, {
+    DW_RLE_END_OF_LIST = 0x00,
+    DW_RLE_BASE_ADDRESSX = 0x01,
+    DW_RLE_STARTX_ENDX = 0x02,
+    DW_RLE_STARTX_LENGTH = 0x03,
+    DW_RLE_OFFSET_PAIR = 0x04,
+    DW_RLE_BASE_ADDRESS = 0x05,
+    DW_RLE_START_END = 0x06,
+    DW_RLE_START_LENGTH = 0x07,
+});
+
+/// How an attribute value, or an entry in a DWARF 5 line table header, is
+/// stored.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct DwarfForm(pub u16);
+
+declare_constants!(DwarfForm, {
+    DW_FORM_ADDR = 0x01,
+    DW_FORM_BLOCK2 = 0x03,
+    DW_FORM_BLOCK4 = 0x04,
+    DW_FORM_DATA2 = 0x05,
+    DW_FORM_DATA4 = 0x06,
+    DW_FORM_DATA8 = 0x07,
+    DW_FORM_STRING = 0x08,
+    DW_FORM_BLOCK = 0x09,
+    DW_FORM_BLOCK1 = 0x0a,
+    DW_FORM_DATA1 = 0x0b,
+    DW_FORM_FLAG = 0x0c,
+    DW_FORM_SDATA = 0x0d,
+    DW_FORM_STRP = 0x0e,
+    DW_FORM_UDATA = 0x0f,
+    DW_FORM_REF_ADDR = 0x10,
+    DW_FORM_REF1 = 0x11,
+    DW_FORM_REF2 = 0x12,
+    DW_FORM_REF4 = 0x13,
+    DW_FORM_REF8 = 0x14,
+    DW_FORM_REF_UDATA = 0x15,
+    DW_FORM_INDIRECT = 0x16,
+    DW_FORM_SEC_OFFSET = 0x17,
+    DW_FORM_EXPRLOC = 0x18,
+    DW_FORM_FLAG_PRESENT = 0x19,
+    DW_FORM_STRX = 0x1a,
+    DW_FORM_ADDRX = 0x1b,
+    DW_FORM_REF_SUP4 = 0x1c,
+    DW_FORM_STRP_SUP = 0x1d,
+    DW_FORM_DATA16 = 0x1e,
+    DW_FORM_LINE_STRP = 0x1f,
+    DW_FORM_REF_SIG8 = 0x20,
+    DW_FORM_IMPLICIT_CONST = 0x21,
+    DW_FORM_LOCLISTX = 0x22,
+    DW_FORM_RNGLISTX = 0x23,
+    DW_FORM_REF_SUP8 = 0x24,
+    DW_FORM_STRX1 = 0x25,
+    DW_FORM_STRX2 = 0x26,
+    DW_FORM_STRX3 = 0x27,
+    DW_FORM_STRX4 = 0x28,
+    DW_FORM_ADDRX1 = 0x29,
+    DW_FORM_ADDRX2 = 0x2a,
+    DW_FORM_ADDRX3 = 0x2b,
+    DW_FORM_ADDRX4 = 0x2c,
+    DW_FORM_GNU_ADDR_INDEX = 0x1f01,
+    DW_FORM_GNU_STR_INDEX = 0x1f02,
+    DW_FORM_GNU_REF_ALT = 0x1f20,
+    DW_FORM_GNU_STRP_ALT = 0x1f21,
+});
+
+/// What the forms of a unit depend on: its version, whether it is 64-bit
+/// DWARF, and the size of an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DwarfEncoding {
+    pub version: u16,
+    pub is_64: bool,
+    pub address_size: u8,
+}
+
+/// A value read in some [`DwarfForm`]. Values that refer to another section
+/// are left as the offset or index, to be looked up by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DwarfValue<'data> {
+    Address(u64),
+    /// An index into the unit's addresses in `.debug_addr`.
+    AddressIndex(u64),
+    Unsigned(u64),
+    Signed(i64),
+    Flag(bool),
+    Block(&'data [u8]),
+    Expression(&'data [u8]),
+    Data16(&'data [u8; 16]),
+    String(&'data [u8]),
+    /// An offset into `.debug_str`.
+    StringOffset(u64),
+    /// An offset into `.debug_line_str`.
+    LineStringOffset(u64),
+    /// An index into the unit's string offsets in `.debug_str_offsets`.
+    StringIndex(u64),
+    /// An offset into the `.debug_str` of the supplementary object file.
+    SupplementaryStringOffset(u64),
+    /// An offset from the start of the unit.
+    UnitReference(u64),
+    /// An offset into `.debug_info`.
+    InfoReference(u64),
+    /// An offset into the `.debug_info` of the supplementary object file.
+    SupplementaryReference(u64),
+    /// The signature of a type unit.
+    Signature(u64),
+    /// An offset into another section, such as `.debug_line`.
+    SectionOffset(u64),
+    LocationListIndex(u64),
+    RangeListIndex(u64),
+}
+
+impl DwarfValue<'_> {
+    /// The value as an unsigned number, for the forms that hold one.
+    pub fn unsigned(&self) -> Option<u64> {
+        match *self {
+            Self::Address(value) | Self::Unsigned(value) | Self::SectionOffset(value) => {
+                Some(value)
+            }
+            Self::Signed(value) => u64::try_from(value).ok(),
+            Self::Flag(value) => Some(u64::from(value)),
+            _ => None,
+        }
+    }
+}
+
+/// The sections a DWARF reader needs, any of which may be empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DwarfSections<'data> {
+    pub debug_abbrev: &'data [u8],
+    pub debug_addr: &'data [u8],
+    pub debug_aranges: &'data [u8],
+    pub debug_info: &'data [u8],
+    pub debug_line: &'data [u8],
+    pub debug_line_str: &'data [u8],
+    pub debug_ranges: &'data [u8],
+    pub debug_rnglists: &'data [u8],
+    pub debug_str: &'data [u8],
+    pub debug_str_offsets: &'data [u8],
+}
+
+impl<'data> DwarfSections<'data> {
+    /// The names of the sections, as given to [`DwarfSections::load_with`].
+    pub const NAMES: &'static [&'static str] = &[
+        ".debug_abbrev",
+        ".debug_addr",
+        ".debug_aranges",
+        ".debug_info",
+        ".debug_line",
+        ".debug_line_str",
+        ".debug_ranges",
+        ".debug_rnglists",
+        ".debug_str",
+        ".debug_str_offsets",
+    ];
+
+    /// Takes each section from `section`, given its name.
+    pub fn load_with(
+        mut section: impl FnMut(&'static str) -> Result<&'data [u8], ElfError>,
+    ) -> Result<Self, ElfError> {
+        Ok(Self {
+            debug_abbrev: section(".debug_abbrev")?,
+            debug_addr: section(".debug_addr")?,
+            debug_aranges: section(".debug_aranges")?,
+            debug_info: section(".debug_info")?,
+            debug_line: section(".debug_line")?,
+            debug_line_str: section(".debug_line_str")?,
+            debug_ranges: section(".debug_ranges")?,
+            debug_rnglists: section(".debug_rnglists")?,
+            debug_str: section(".debug_str")?,
+            debug_str_offsets: section(".debug_str_offsets")?,
+        })
+    }
+
+    /// Borrows the sections of a file, which must not be compressed. See
+    /// `compression::ElfDebugSections` for files that may be.
+    pub fn from_file(file: &ElfFile<'data>) -> Result<Self, ElfError> {
+        Self::load_with(|name| match file.section_by_name(name) {
+            Some(section)
+                if ElfSectionFlags(section.sh_flags())
+                    .contains(ElfSectionFlags::SHF_COMPRESSED) =>
+            {
+                let ch_type = ElfCompressionType::read_from_prefix(file.section_data(section)?);
+                Err(ElfError::UnsupportedCompression(
+                    ch_type.unwrap_or(ElfCompressionType(0)),
+                ))
+            }
+            Some(section) => file.section_data(section),
+            None => Ok(&[]),
+        })
+    }
+
+    /// The string a value refers to, or `None` if it isn't a string or is
+    /// an index, which needs the unit's string offsets.
+    pub fn string(&self, value: DwarfValue<'data>) -> Result<Option<&'data [u8]>, ElfError> {
+        let (name, section, offset) = match value {
+            DwarfValue::String(string) => return Ok(Some(string)),
+            DwarfValue::StringOffset(offset) => (".debug_str", self.debug_str, offset),
+            DwarfValue::LineStringOffset(offset) => {
+                (".debug_line_str", self.debug_line_str, offset)
+            }
+            _ => return Ok(None),
+        };
+
+        let mut reader = DwarfReader::new(name, section, 0);
+        reader.seek(usize::try_from(offset)?)?;
+        reader.cstr().map(Some)
+    }
+}
+
+/// The addresses that `DW_EH_PE_TEXTREL` and `DW_EH_PE_DATAREL` pointers
+/// are relative to. `DW_EH_PE_PCREL` pointers are relative to their own
+/// address, which the reader knows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DwarfPointerBases {
+    pub text: Option<u64>,
+    pub data: Option<u64>,
+    pub function: Option<u64>,
+}
+
+/// A little-endian cursor over a DWARF section, which reports errors as
+/// [`ElfError::MalformedDwarf`] with the section name and offset.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DwarfReader<'buf> {
+    section: &'static str,
+    buffer: &'buf [u8],
+    offset: usize,
+    /// The address of the start of the buffer, for PC-relative pointers.
+    address: u64,
+}
+
+impl<'buf> DwarfReader<'buf> {
+    /// `section` names the data in errors.
+    pub fn new(section: &'static str, buffer: &'buf [u8], address: u64) -> Self {
+        Self {
+            section,
+            buffer,
+            offset: 0,
+            address,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.buffer.len()
+    }
+
+    /// The address of the next byte to be read.
+    pub fn address(&self) -> u64 {
+        self.address
+            .wrapping_add(u64::try_from(self.offset).unwrap_or(u64::MAX))
+    }
+
+    pub fn error(&self) -> ElfError {
+        ElfError::MalformedDwarf(self.section, self.offset)
+    }
+
+    pub fn seek(&mut self, offset: usize) -> Result<(), ElfError> {
+        if offset > self.buffer.len() {
+            return Err(ElfError::MalformedDwarf(self.section, offset));
+        }
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// A reader limited to the next `length` bytes, which are skipped in
+    /// this one. Offsets remain those within the section.
+    pub fn split(&mut self, length: usize) -> Result<Self, ElfError> {
+        let start = self.offset;
+        self.bytes(length)?;
+        Ok(Self {
+            buffer: self.buffer.get(..self.offset).unwrap_or_default(),
+            offset: start,
+            ..*self
+        })
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Result<&'buf [u8], ElfError> {
+        let end = self
+            .offset
+            .checked_add(length)
+            .ok_or_else(|| self.error())?;
+        let bytes = self
+            .buffer
+            .get(self.offset..end)
+            .ok_or_else(|| self.error())?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    /// The rest of the buffer, leaving the reader empty.
+    pub fn rest(&mut self) -> &'buf [u8] {
+        let rest = self.buffer.get(self.offset..).unwrap_or_default();
+        self.offset = self.buffer.len();
+        rest
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ElfError> {
+        let error = self.error();
+        self.bytes(N)?.try_into().map_err(|_| error)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ElfError> {
+        Ok(u8::from_le_bytes(self.array()?))
+    }
+
+    pub fn u16(&mut self) -> Result<u16, ElfError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, ElfError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, ElfError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    /// An unsigned value of 1, 2, 4 or 8 bytes.
+    pub fn sized(&mut self, size: u8) -> Result<u64, ElfError> {
+        match size {
+            1 => self.u8().map(u64::from),
+            2 => self.u16().map(u64::from),
+            4 => self.u32().map(u64::from),
+            8 => self.u64(),
+            _ => Err(self.error()),
+        }
+    }
+
+    pub fn uleb128(&mut self) -> Result<u64, ElfError> {
+        let start = self.offset;
+        let mut value = 0u64;
+        let mut shift = 0u32;
+        loop {
+            let byte = self.u8()?;
+            let bits = u64::from(byte & 0x7f);
+            if shift < 64 {
+                value |= bits.checked_shl(shift).unwrap_or(0);
+            } else if bits != 0 {
+                return Err(ElfError::MalformedDwarf(self.section, start));
+            }
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift = shift.saturating_add(7);
+        }
+    }
+
+    pub fn sleb128(&mut self) -> Result<i64, ElfError> {
+        let mut value = 0u64;
+        let mut shift = 0u32;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f).checked_shl(shift).unwrap_or(0);
+            }
+            shift = shift.saturating_add(7);
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= u64::MAX.checked_shl(shift).unwrap_or(0);
+                }
+                return Ok(i64::from_le_bytes(value.to_le_bytes()));
+            }
+        }
+    }
+
+    /// A null-terminated string, without the terminator.
+    pub fn cstr(&mut self) -> Result<&'buf [u8], ElfError> {
+        let rest = self.buffer.get(self.offset..).unwrap_or_default();
+        let string = CStr::from_bytes_until_nul(rest).map_err(|_| self.error())?;
+        let bytes = string.to_bytes();
+        self.offset = self
+            .offset
+            .checked_add(bytes.len())
+            .and_then(|offset| offset.checked_add(1))
+            .ok_or_else(|| self.error())?;
+        Ok(bytes)
+    }
+
+    /// The initial length of a unit, and whether it is 64-bit DWARF.
+    pub fn initial_length(&mut self) -> Result<(u64, bool), ElfError> {
+        match self.u32()? {
+            0xffff_ffff => Ok((self.u64()?, true)),
+            // 0xfffffff0 to 0xfffffffe are reserved.
+            0xffff_fff0.. => Err(ElfError::MalformedDwarf(
+                self.section,
+                self.offset.saturating_sub(4),
+            )),
+            length => Ok((u64::from(length), false)),
+        }
+    }
+
+    /// A section offset, which is 8 bytes in 64-bit DWARF and 4 otherwise.
+    pub fn offset_value(&mut self, is_64: bool) -> Result<u64, ElfError> {
+        if is_64 {
+            self.u64()
+        } else {
+            self.u32().map(u64::from)
+        }
+    }
+
+    /// A value in the given form. `DW_FORM_IMPLICIT_CONST` values are stored
+    /// with the abbreviation rather than here, and are an error.
+    pub fn value(
+        &mut self,
+        form: DwarfForm,
+        encoding: DwarfEncoding,
+    ) -> Result<DwarfValue<'buf>, ElfError> {
+        let mut form = form;
+        // An indirect form is given before the value, and could in turn be
+        // indirect, which is pointless and so limited.
+        for _ in 0..4 {
+            if form != DwarfForm::DW_FORM_INDIRECT {
+                break;
+            }
+            form = DwarfForm(u16::try_from(self.uleb128()?).map_err(|_| self.error())?);
+        }
+
+        let is_64 = encoding.is_64;
+        Ok(match form {
+            DwarfForm::DW_FORM_ADDR => DwarfValue::Address(self.sized(encoding.address_size)?),
+            DwarfForm::DW_FORM_ADDRX | DwarfForm::DW_FORM_GNU_ADDR_INDEX => {
+                DwarfValue::AddressIndex(self.uleb128()?)
+            }
+            DwarfForm::DW_FORM_ADDRX1 => DwarfValue::AddressIndex(self.sized(1)?),
+            DwarfForm::DW_FORM_ADDRX2 => DwarfValue::AddressIndex(self.sized(2)?),
+            DwarfForm::DW_FORM_ADDRX3 => DwarfValue::AddressIndex(self.u24()?),
+            DwarfForm::DW_FORM_ADDRX4 => DwarfValue::AddressIndex(self.sized(4)?),
+            DwarfForm::DW_FORM_DATA1 => DwarfValue::Unsigned(self.sized(1)?),
+            DwarfForm::DW_FORM_DATA2 => DwarfValue::Unsigned(self.sized(2)?),
+            DwarfForm::DW_FORM_DATA4 => DwarfValue::Unsigned(self.sized(4)?),
+            DwarfForm::DW_FORM_DATA8 => DwarfValue::Unsigned(self.sized(8)?),
+            DwarfForm::DW_FORM_UDATA => DwarfValue::Unsigned(self.uleb128()?),
+            DwarfForm::DW_FORM_SDATA => DwarfValue::Signed(self.sleb128()?),
+            DwarfForm::DW_FORM_DATA16 => {
+                let error = self.error();
+                DwarfValue::Data16(self.bytes(16)?.try_into().map_err(|_| error)?)
+            }
+            DwarfForm::DW_FORM_FLAG => DwarfValue::Flag(self.u8()? != 0),
+            DwarfForm::DW_FORM_FLAG_PRESENT => DwarfValue::Flag(true),
+            DwarfForm::DW_FORM_BLOCK1 => {
+                let length = self.u8()?;
+                DwarfValue::Block(self.bytes(usize::from(length))?)
+            }
+            DwarfForm::DW_FORM_BLOCK2 => {
+                let length = self.u16()?;
+                DwarfValue::Block(self.bytes(usize::from(length))?)
+            }
+            DwarfForm::DW_FORM_BLOCK4 => {
+                let length = self.u32()?;
+                DwarfValue::Block(self.bytes(usize::try_from(length)?)?)
+            }
+            DwarfForm::DW_FORM_BLOCK => {
+                let length = self.uleb128()?;
+                DwarfValue::Block(self.bytes(usize::try_from(length)?)?)
+            }
+            DwarfForm::DW_FORM_EXPRLOC => {
+                let length = self.uleb128()?;
+                DwarfValue::Expression(self.bytes(usize::try_from(length)?)?)
+            }
+            DwarfForm::DW_FORM_STRING => DwarfValue::String(self.cstr()?),
+            DwarfForm::DW_FORM_STRP => DwarfValue::StringOffset(self.offset_value(is_64)?),
+            DwarfForm::DW_FORM_LINE_STRP => DwarfValue::LineStringOffset(self.offset_value(is_64)?),
+            DwarfForm::DW_FORM_STRP_SUP | DwarfForm::DW_FORM_GNU_STRP_ALT => {
+                DwarfValue::SupplementaryStringOffset(self.offset_value(is_64)?)
+            }
+            DwarfForm::DW_FORM_STRX | DwarfForm::DW_FORM_GNU_STR_INDEX => {
+                DwarfValue::StringIndex(self.uleb128()?)
+            }
+            DwarfForm::DW_FORM_STRX1 => DwarfValue::StringIndex(self.sized(1)?),
+            DwarfForm::DW_FORM_STRX2 => DwarfValue::StringIndex(self.sized(2)?),
+            DwarfForm::DW_FORM_STRX3 => DwarfValue::StringIndex(self.u24()?),
+            DwarfForm::DW_FORM_STRX4 => DwarfValue::StringIndex(self.sized(4)?),
+            DwarfForm::DW_FORM_REF1 => DwarfValue::UnitReference(self.sized(1)?),
+            DwarfForm::DW_FORM_REF2 => DwarfValue::UnitReference(self.sized(2)?),
+            DwarfForm::DW_FORM_REF4 => DwarfValue::UnitReference(self.sized(4)?),
+            DwarfForm::DW_FORM_REF8 => DwarfValue::UnitReference(self.sized(8)?),
+            DwarfForm::DW_FORM_REF_UDATA => DwarfValue::UnitReference(self.uleb128()?),
+            // DWARF 2 gave this the size of an address rather than an offset.
+            DwarfForm::DW_FORM_REF_ADDR if encoding.version <= 2 => {
+                DwarfValue::InfoReference(self.sized(encoding.address_size)?)
+            }
+            DwarfForm::DW_FORM_REF_ADDR => DwarfValue::InfoReference(self.offset_value(is_64)?),
+            DwarfForm::DW_FORM_REF_SUP4 => DwarfValue::SupplementaryReference(self.sized(4)?),
+            DwarfForm::DW_FORM_REF_SUP8 => DwarfValue::SupplementaryReference(self.sized(8)?),
+            DwarfForm::DW_FORM_GNU_REF_ALT => {
+                DwarfValue::SupplementaryReference(self.offset_value(is_64)?)
+            }
+            DwarfForm::DW_FORM_REF_SIG8 => DwarfValue::Signature(self.u64()?),
+            DwarfForm::DW_FORM_SEC_OFFSET => DwarfValue::SectionOffset(self.offset_value(is_64)?),
+            DwarfForm::DW_FORM_LOCLISTX => DwarfValue::LocationListIndex(self.uleb128()?),
+            DwarfForm::DW_FORM_RNGLISTX => DwarfValue::RangeListIndex(self.uleb128()?),
+            _ => return Err(self.error()),
+        })
+    }
+
+    fn u24(&mut self) -> Result<u64, ElfError> {
+        let [low, middle, high] = self.array()?;
+        Ok(u64::from(u32::from_le_bytes([low, middle, high, 0])))
+    }
+
+    /// A pointer in one of the `.eh_frame` encodings. The value of an
+    /// indirect pointer is the address it is read from, which only a loaded
+    /// image can dereference.
+    pub fn pointer(
+        &mut self,
+        encoding: DwarfPointerEncoding,
+        address_size: u8,
+        bases: &DwarfPointerBases,
+    ) -> Result<u64, ElfError> {
+        if encoding == DwarfPointerEncoding::DW_EH_PE_OMIT {
+            return Ok(0);
+        }
+
+        let (section, start) = (self.section, self.offset);
+        let error = || ElfError::MalformedDwarf(section, start);
+
+        if encoding.application() == DwarfPointerEncoding::DW_EH_PE_ALIGNED {
+            let size = u64::from(address_size.max(1));
+            let misalignment = self.address().checked_rem(size).unwrap_or(0);
+            let padding = size
+                .saturating_sub(misalignment)
+                .checked_rem(size)
+                .unwrap_or(0);
+            let padding = usize::try_from(padding)?;
+            self.bytes(padding)?;
+        }
+
+        let field = self.address();
+        let value = match encoding.format() {
+            DwarfPointerEncoding::DW_EH_PE_ABSPTR => self.sized(address_size)?,
+            DwarfPointerEncoding::DW_EH_PE_ULEB128 => self.uleb128()?,
+            DwarfPointerEncoding::DW_EH_PE_UDATA2 => u64::from(self.u16()?),
+            DwarfPointerEncoding::DW_EH_PE_UDATA4 => u64::from(self.u32()?),
+            DwarfPointerEncoding::DW_EH_PE_UDATA8 => self.u64()?,
+            DwarfPointerEncoding::DW_EH_PE_SLEB128 => sign_extend(self.sleb128()?),
+            DwarfPointerEncoding::DW_EH_PE_SDATA2 => {
+                sign_extend(i64::from(i16::from_le_bytes(self.u16()?.to_le_bytes())))
+            }
+            DwarfPointerEncoding::DW_EH_PE_SDATA4 => {
+                sign_extend(i64::from(i32::from_le_bytes(self.u32()?.to_le_bytes())))
+            }
+            DwarfPointerEncoding::DW_EH_PE_SDATA8 => self.u64()?,
+            _ => return Err(error()),
+        };
+
+        let base = match encoding.application() {
+            DwarfPointerEncoding::DW_EH_PE_ABSPTR | DwarfPointerEncoding::DW_EH_PE_ALIGNED => 0,
+            DwarfPointerEncoding::DW_EH_PE_PCREL => field,
+            DwarfPointerEncoding::DW_EH_PE_TEXTREL => bases.text.ok_or_else(error)?,
+            DwarfPointerEncoding::DW_EH_PE_DATAREL => bases.data.ok_or_else(error)?,
+            DwarfPointerEncoding::DW_EH_PE_FUNCREL => bases.function.ok_or_else(error)?,
+            _ => return Err(error()),
+        };
+        let value = base.wrapping_add(value);
+
+        // Narrow to the address size, so that a negative offset from a
+        // 32-bit address wraps within 32 bits.
+        Ok(match address_size {
+            4 => value & 0xffff_ffff,
+            _ => value,
+        })
+    }
+}
+
+fn sign_extend(value: i64) -> u64 {
+    u64::from_le_bytes(value.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_leb128_and_pointers() {
+        let bytes = [0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78, 0xf0, 0xff, 0xff, 0xff];
+        let mut reader = DwarfReader::new(".test", &bytes, 0x1000);
+        assert_eq!(reader.uleb128().ok(), Some(624_485));
+        assert_eq!(reader.sleb128().ok(), Some(-123_456));
+
+        let encoding = DwarfPointerEncoding(
+            DwarfPointerEncoding::DW_EH_PE_PCREL.0 | DwarfPointerEncoding::DW_EH_PE_SDATA4.0,
+        );
+        let bases = DwarfPointerBases::default();
+        assert_eq!(reader.pointer(encoding, 8, &bases).ok(), Some(0xff6));
+        assert_eq!(reader.offset(), bytes.len());
+        assert!(matches!(
+            reader.u8(),
+            Err(ElfError::MalformedDwarf(".test", 10))
+        ));
+    }
+}
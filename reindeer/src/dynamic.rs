@@ -0,0 +1,118 @@
+//! The dynamic array, as found in the `SHT_DYNAMIC` section and the
+//! `PT_DYNAMIC` segment.
+
+use core::mem::size_of;
+
+use zerocopy::FromBytes;
+
+use crate::{elf_aux_structures::*, elf_structures::*, errors::ElfError, ElfHeader};
+
+/// A dynamic array entry, representing either 64 or 32 bit entries.
+#[derive(Debug, Clone, Copy)]
+pub enum ElfDynamic<'buf> {
+    Elf32(&'buf Elf32Dynamic),
+    Elf64(&'buf Elf64Dynamic),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ElfDynamicTable<'buf> {
+    Elf32(&'buf [Elf32Dynamic]),
+    Elf64(&'buf [Elf64Dynamic]),
+}
+
+impl<'buf> ElfDynamicTable<'buf> {
+    pub fn parse(header: ElfHeader, bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        // The section or segment may be padded past the DT_NULL terminator,
+        // so trailing bytes that do not form a whole entry are ignored.
+        let table = match header {
+            ElfHeader::Elf32(_) => {
+                let count = bytes
+                    .len()
+                    .checked_div(size_of::<Elf32Dynamic>())
+                    .unwrap_or_default();
+                Self::Elf32(
+                    Elf32Dynamic::slice_from_prefix(bytes, count)
+                        .ok_or(ElfError::ZeroCopyError)?
+                        .0,
+                )
+            }
+            ElfHeader::Elf64(_) => {
+                let count = bytes
+                    .len()
+                    .checked_div(size_of::<Elf64Dynamic>())
+                    .unwrap_or_default();
+                Self::Elf64(
+                    Elf64Dynamic::slice_from_prefix(bytes, count)
+                        .ok_or(ElfError::ZeroCopyError)?
+                        .0,
+                )
+            }
+        };
+
+        Ok(table)
+    }
+
+    pub fn get(&self, index: usize) -> Option<ElfDynamic<'buf>> {
+        match self {
+            Self::Elf32(entries) => entries.get(index).map(ElfDynamic::Elf32),
+            Self::Elf64(entries) => entries.get(index).map(ElfDynamic::Elf64),
+        }
+    }
+
+    /// The number of entries in the table, up to and including the first
+    /// `DT_NULL` entry.
+    pub fn len(&self) -> usize {
+        self.into_iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Finds the first entry with the given tag.
+    pub fn find(&self, tag: ElfDynamicTag) -> Option<ElfDynamic<'buf>> {
+        self.into_iter().find(|entry| entry.d_tag() == tag)
+    }
+
+    /// Finds the value of the first entry with the given tag.
+    pub fn value(&self, tag: ElfDynamicTag) -> Option<u64> {
+        self.find(tag).map(|entry| entry.d_val())
+    }
+}
+
+impl<'buf> IntoIterator for ElfDynamicTable<'buf> {
+    type Item = ElfDynamic<'buf>;
+    type IntoIter = ElfDynamicTableIter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ElfDynamicTableIter {
+            table: self,
+            index: 0,
+            finished: false,
+        }
+    }
+}
+
+/// Iterates over the dynamic array. The `DT_NULL` entry marking the end of
+/// the array is yielded, and nothing after it.
+pub struct ElfDynamicTableIter<'buf> {
+    table: ElfDynamicTable<'buf>,
+    index: usize,
+    finished: bool,
+}
+
+impl<'buf> Iterator for ElfDynamicTableIter<'buf> {
+    type Item = ElfDynamic<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let entry = self.table.get(self.index)?;
+        self.index = self.index.saturating_add(1);
+        self.finished = entry.d_tag() == ElfDynamicTag::DT_NULL;
+
+        Some(entry)
+    }
+}
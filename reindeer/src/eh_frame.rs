@@ -0,0 +1,769 @@
+//! Call frame information, from `.eh_frame` and `.debug_frame`.
+//!
+//! Each entry is either a CIE, holding what a group of functions have in
+//! common, or an FDE describing the address range of one function and how
+//! to unwind it. `.eh_frame` is needed for exceptions, so survives stripping,
+//! and its FDEs list every function even when `.symtab` is gone. The
+//! `.eh_frame_hdr` section, found through the `PT_GNU_EH_FRAME` segment,
+//! holds a table of the FDEs sorted by address.
+
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::{
+    dwarf::{DwarfPointerBases, DwarfPointerEncoding, DwarfReader},
+    elf_aux_structures::*,
+    errors::ElfError,
+    ElfFile, ElfHeader, ElfProgramHeader,
+};
+
+/// Which of the two sections the call frame information is from. They differ
+/// in how a CIE is told apart from an FDE, and how an FDE refers to its CIE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfCallFrameFormat {
+    EhFrame,
+    DebugFrame,
+}
+
+impl ElfCallFrameFormat {
+    fn section_name(self) -> &'static str {
+        match self {
+            Self::EhFrame => ".eh_frame",
+            Self::DebugFrame => ".debug_frame",
+        }
+    }
+}
+
+/// A Common Information Entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfCie<'buf> {
+    /// The offset of the entry in its section.
+    pub offset: usize,
+    pub version: u8,
+    /// The augmentation string, such as `zR` or `zPLR`.
+    pub augmentation: &'buf [u8],
+    pub address_size: u8,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u64,
+    /// How the FDEs using this CIE store their addresses.
+    pub fde_encoding: DwarfPointerEncoding,
+    /// How the FDEs using this CIE store their LSDA pointer, or
+    /// `DW_EH_PE_OMIT` if they have none.
+    pub lsda_encoding: DwarfPointerEncoding,
+    /// The address of the personality routine, or of the pointer to it if
+    /// its encoding is indirect.
+    pub personality: Option<u64>,
+    pub personality_encoding: DwarfPointerEncoding,
+    /// Whether the functions are signal trampolines, for which the return
+    /// address is that of the interrupted instruction itself.
+    pub is_signal_frame: bool,
+    pub initial_instructions: &'buf [u8],
+}
+
+impl ElfCie<'_> {
+    fn has_augmentation_data(&self) -> bool {
+        self.augmentation.first() == Some(&b'z')
+    }
+}
+
+/// A Frame Description Entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfFde<'buf> {
+    /// The offset of the entry in its section.
+    pub offset: usize,
+    pub cie: ElfCie<'buf>,
+    pub initial_location: u64,
+    pub address_range: u64,
+    /// The address of the language-specific data area, as used by C++
+    /// exception handling.
+    pub lsda: Option<u64>,
+    pub instructions: &'buf [u8],
+}
+
+impl ElfFde<'_> {
+    /// The addresses of the function the FDE describes.
+    pub fn range(&self) -> Range<u64> {
+        Range {
+            start: self.initial_location,
+            end: self.initial_location.saturating_add(self.address_range),
+        }
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        self.range().contains(&address)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfCallFrameEntry<'buf> {
+    Cie(ElfCie<'buf>),
+    Fde(ElfFde<'buf>),
+}
+
+/// The contents of an `.eh_frame` or `.debug_frame` section.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfCallFrameInfo<'buf> {
+    format: ElfCallFrameFormat,
+    data: &'buf [u8],
+    address: u64,
+    address_size: u8,
+    bases: DwarfPointerBases,
+}
+
+fn address_size(header: ElfHeader) -> u8 {
+    match header {
+        ElfHeader::Elf32(_) => 4,
+        ElfHeader::Elf64(_) => 8,
+    }
+}
+
+/// The loaded bytes from `address` to the end of the `PT_LOAD` segment
+/// containing it, for finding data in a file without section headers.
+fn data_from_address<'buf>(file: &ElfFile<'buf>, address: u64) -> Result<&'buf [u8], ElfError> {
+    let segment = file
+        .segments()
+        .filter(|segment| segment.p_type() == ElfSegmentType::PT_LOAD)
+        .find(|segment| {
+            let filesz = segment.p_filesz().map_or(0, |size| size.get());
+            address
+                .checked_sub(segment.p_vaddr())
+                .is_some_and(|delta| delta < filesz)
+        })
+        .ok_or(ElfError::MissingSegment(ElfSegmentType::PT_LOAD))?;
+
+    let delta = usize::try_from(address.saturating_sub(segment.p_vaddr()))?;
+    Ok(file.segment_data(segment)?.get(delta..).unwrap_or_default())
+}
+
+impl<'buf> ElfCallFrameInfo<'buf> {
+    /// `address` is that of the start of `data`, which PC-relative pointers
+    /// are relative to. `address_size` is 4 for ELF32 and 8 for ELF64.
+    pub fn new(
+        format: ElfCallFrameFormat,
+        data: &'buf [u8],
+        address: u64,
+        address_size: u8,
+    ) -> Self {
+        Self {
+            format,
+            data,
+            address,
+            address_size,
+            bases: DwarfPointerBases::default(),
+        }
+    }
+
+    /// Sets the bases for text and data-relative pointers, which some
+    /// targets use in `.eh_frame`.
+    pub fn with_bases(mut self, bases: DwarfPointerBases) -> Self {
+        self.bases = bases;
+        self
+    }
+
+    /// The `.eh_frame` section, or in a file without section headers, the
+    /// one that `.eh_frame_hdr` points to.
+    pub fn from_eh_frame(file: &ElfFile<'buf>) -> Result<Option<Self>, ElfError> {
+        let address_size = address_size(file.header());
+
+        if let Some(section) = file.section_by_name(".eh_frame") {
+            let address = section.sh_addr().map_or(0, |address| address.get());
+            return Ok(Some(Self::new(
+                ElfCallFrameFormat::EhFrame,
+                file.section_data(section)?,
+                address,
+                address_size,
+            )));
+        }
+
+        // The section ends with a zero terminator, so reading on to the end
+        // of the segment is harmless.
+        let Some(header) = ElfEhFrameHeader::from_file(file)? else {
+            return Ok(None);
+        };
+        let address = header.eh_frame_ptr;
+        Ok(Some(Self::new(
+            ElfCallFrameFormat::EhFrame,
+            data_from_address(file, address)?,
+            address,
+            address_size,
+        )))
+    }
+
+    /// The `.debug_frame` section, which is never loaded, so has no address.
+    pub fn from_debug_frame(file: &ElfFile<'buf>) -> Result<Option<Self>, ElfError> {
+        let Some(section) = file.section_by_name(".debug_frame") else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::new(
+            ElfCallFrameFormat::DebugFrame,
+            file.section_data(section)?,
+            0,
+            address_size(file.header()),
+        )))
+    }
+
+    pub fn format(&self) -> ElfCallFrameFormat {
+        self.format
+    }
+
+    fn reader(&self) -> DwarfReader<'buf> {
+        DwarfReader::new(self.format.section_name(), self.data, self.address)
+    }
+
+    /// Iterates over the CIEs and FDEs, stopping at the first error or at a
+    /// zero terminator.
+    pub fn entries(&self) -> ElfCallFrameEntries<'buf> {
+        ElfCallFrameEntries {
+            info: *self,
+            offset: 0,
+        }
+    }
+
+    /// Iterates over the FDEs, stopping at the first error.
+    pub fn fdes(&self) -> impl Iterator<Item = Result<ElfFde<'buf>, ElfError>> {
+        self.entries().filter_map(|entry| match entry {
+            Ok(ElfCallFrameEntry::Cie(_)) => None,
+            Ok(ElfCallFrameEntry::Fde(fde)) => Some(Ok(fde)),
+            Err(error) => Some(Err(error)),
+        })
+    }
+
+    /// The FDE describing the function containing `address`, found by a
+    /// linear search. [`ElfEhFrameHeader::find`] is faster, where available.
+    pub fn find_fde(&self, address: u64) -> Result<Option<ElfFde<'buf>>, ElfError> {
+        for fde in self.fdes() {
+            let fde = fde?;
+            if fde.contains(address) {
+                return Ok(Some(fde));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The CIE at `offset` in the section.
+    pub fn cie_at(&self, offset: usize) -> Result<ElfCie<'buf>, ElfError> {
+        // Only CIEs are parsed, so a loop of FDEs pointing at each other
+        // can't recurse.
+        match self.raw_entry_at(offset)?.0 {
+            RawEntry::Cie(entry) => self.parse_cie(offset, entry),
+            _ => Err(ElfError::MalformedDwarf(self.format.section_name(), offset)),
+        }
+    }
+
+    /// The FDE at `offset` in the section.
+    pub fn fde_at(&self, offset: usize) -> Result<ElfFde<'buf>, ElfError> {
+        match self.entry_at(offset)? {
+            (Some(ElfCallFrameEntry::Fde(fde)), _) => Ok(fde),
+            _ => Err(ElfError::MalformedDwarf(self.format.section_name(), offset)),
+        }
+    }
+
+    /// The FDE at `address`, as found in `.eh_frame_hdr`.
+    pub fn fde_at_address(&self, address: u64) -> Result<ElfFde<'buf>, ElfError> {
+        let offset = address
+            .checked_sub(self.address)
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or(ElfError::MalformedDwarf(self.format.section_name(), 0))?;
+        self.fde_at(offset)
+    }
+
+    /// Splits off the entry at `offset`, returning what it is and the
+    /// offset of the next entry.
+    fn raw_entry_at(&self, offset: usize) -> Result<(RawEntry<'buf>, usize), ElfError> {
+        let mut reader = self.reader();
+        reader.seek(offset)?;
+
+        let (length, is_64) = reader.initial_length()?;
+        if length == 0 {
+            return Ok((RawEntry::Terminator, reader.offset()));
+        }
+        let id_offset = reader.offset();
+        let mut entry = reader.split(usize::try_from(length)?)?;
+        let next = reader.offset();
+
+        let id = entry.offset_value(is_64)?;
+        let cie_id = match (self.format, is_64) {
+            (ElfCallFrameFormat::EhFrame, _) => 0,
+            (ElfCallFrameFormat::DebugFrame, false) => 0xffff_ffff,
+            (ElfCallFrameFormat::DebugFrame, true) => u64::MAX,
+        };
+        if id == cie_id {
+            return Ok((RawEntry::Cie(entry), next));
+        }
+
+        // In .eh_frame, the CIE pointer is relative to its own position.
+        let cie_offset = match self.format {
+            ElfCallFrameFormat::EhFrame => u64::try_from(id_offset)?.checked_sub(id),
+            ElfCallFrameFormat::DebugFrame => Some(id),
+        }
+        .and_then(|offset| usize::try_from(offset).ok())
+        .ok_or(ElfError::MalformedDwarf(
+            self.format.section_name(),
+            id_offset,
+        ))?;
+
+        Ok((RawEntry::Fde(cie_offset, entry), next))
+    }
+
+    /// Parses the entry at `offset`, returning it, or `None` for a zero
+    /// terminator, and the offset of the next entry.
+    fn entry_at(
+        &self,
+        offset: usize,
+    ) -> Result<(Option<ElfCallFrameEntry<'buf>>, usize), ElfError> {
+        let (entry, next) = self.raw_entry_at(offset)?;
+        let entry = match entry {
+            RawEntry::Terminator => None,
+            RawEntry::Cie(entry) => Some(ElfCallFrameEntry::Cie(self.parse_cie(offset, entry)?)),
+            RawEntry::Fde(cie_offset, entry) => {
+                let cie = self.cie_at(cie_offset)?;
+                Some(ElfCallFrameEntry::Fde(self.parse_fde(offset, cie, entry)?))
+            }
+        };
+        Ok((entry, next))
+    }
+
+    fn parse_cie(
+        &self,
+        offset: usize,
+        mut entry: DwarfReader<'buf>,
+    ) -> Result<ElfCie<'buf>, ElfError> {
+        let version = entry.u8()?;
+        if !matches!(version, 1 | 3 | 4) {
+            return Err(ElfError::MalformedDwarf(self.format.section_name(), offset));
+        }
+
+        let augmentation = entry.cstr()?;
+        let mut address_size = self.address_size;
+        // GCC 2 stored the address of its exception table here.
+        if augmentation == b"eh" {
+            entry.bytes(usize::from(address_size))?;
+        }
+        if version >= 4 {
+            address_size = entry.u8()?;
+            let _segment_selector_size = entry.u8()?;
+        }
+
+        let code_alignment_factor = entry.uleb128()?;
+        let data_alignment_factor = entry.sleb128()?;
+        let return_address_register = if version == 1 {
+            u64::from(entry.u8()?)
+        } else {
+            entry.uleb128()?
+        };
+
+        let mut cie = ElfCie {
+            offset,
+            version,
+            augmentation,
+            address_size,
+            code_alignment_factor,
+            data_alignment_factor,
+            return_address_register,
+            fde_encoding: DwarfPointerEncoding::DW_EH_PE_ABSPTR,
+            lsda_encoding: DwarfPointerEncoding::DW_EH_PE_OMIT,
+            personality: None,
+            personality_encoding: DwarfPointerEncoding::DW_EH_PE_OMIT,
+            is_signal_frame: false,
+            initial_instructions: &[],
+        };
+
+        if cie.has_augmentation_data() {
+            let length = usize::try_from(entry.uleb128()?)?;
+            let mut data = entry.split(length)?;
+            for character in augmentation.iter().skip(1) {
+                match character {
+                    b'L' => cie.lsda_encoding = DwarfPointerEncoding(data.u8()?),
+                    b'P' => {
+                        cie.personality_encoding = DwarfPointerEncoding(data.u8()?);
+                        cie.personality = Some(data.pointer(
+                            cie.personality_encoding,
+                            address_size,
+                            &self.bases,
+                        )?);
+                    }
+                    b'R' => cie.fde_encoding = DwarfPointerEncoding(data.u8()?),
+                    b'S' => cie.is_signal_frame = true,
+                    // The rest are described by the augmentation length, so
+                    // can be skipped.
+                    _ => break,
+                }
+            }
+        } else if !augmentation.is_empty() && augmentation != b"eh" {
+            // Without a length, the instructions can't be found.
+            return Err(ElfError::MalformedDwarf(self.format.section_name(), offset));
+        }
+
+        cie.initial_instructions = entry.rest();
+        Ok(cie)
+    }
+
+    fn parse_fde(
+        &self,
+        offset: usize,
+        cie: ElfCie<'buf>,
+        mut entry: DwarfReader<'buf>,
+    ) -> Result<ElfFde<'buf>, ElfError> {
+        let initial_location = entry.pointer(cie.fde_encoding, cie.address_size, &self.bases)?;
+        // The range is a length, so only the format of the encoding applies.
+        let address_range = entry.pointer(
+            cie.fde_encoding.format(),
+            cie.address_size,
+            &DwarfPointerBases::default(),
+        )?;
+
+        let mut lsda = None;
+        if cie.has_augmentation_data() {
+            let length = usize::try_from(entry.uleb128()?)?;
+            let mut data = entry.split(length)?;
+            if cie.lsda_encoding != DwarfPointerEncoding::DW_EH_PE_OMIT {
+                let bases = DwarfPointerBases {
+                    function: Some(initial_location),
+                    ..self.bases
+                };
+                lsda = Some(data.pointer(cie.lsda_encoding, cie.address_size, &bases)?)
+                    .filter(|&lsda| lsda != 0);
+            }
+        }
+
+        Ok(ElfFde {
+            offset,
+            cie,
+            initial_location,
+            address_range,
+            lsda,
+            instructions: entry.rest(),
+        })
+    }
+}
+
+/// An entry before its contents are parsed.
+enum RawEntry<'buf> {
+    Terminator,
+    Cie(DwarfReader<'buf>),
+    /// The offset of the CIE, and the FDE's contents.
+    Fde(usize, DwarfReader<'buf>),
+}
+
+/// Iterates over the entries of an [`ElfCallFrameInfo`].
+#[derive(Debug, Clone)]
+pub struct ElfCallFrameEntries<'buf> {
+    info: ElfCallFrameInfo<'buf>,
+    offset: usize,
+}
+
+impl<'buf> Iterator for ElfCallFrameEntries<'buf> {
+    type Item = Result<ElfCallFrameEntry<'buf>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.info.data.len() {
+            return None;
+        }
+
+        match self.info.entry_at(self.offset) {
+            Ok((Some(entry), next)) => {
+                self.offset = next;
+                Some(Ok(entry))
+            }
+            Ok((None, _)) => {
+                self.offset = self.info.data.len();
+                None
+            }
+            Err(error) => {
+                self.offset = self.info.data.len();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// The `.eh_frame_hdr` section: a pointer to `.eh_frame` and a table of its
+/// FDEs sorted by address.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfEhFrameHeader<'buf> {
+    pub version: u8,
+    /// The address of the `.eh_frame` section.
+    pub eh_frame_ptr: u64,
+    /// The number of entries in the table, if there is one.
+    pub fde_count: Option<u64>,
+    pub table_encoding: DwarfPointerEncoding,
+    address: u64,
+    address_size: u8,
+    /// Positioned at the start of the table.
+    table: DwarfReader<'buf>,
+}
+
+impl<'buf> ElfEhFrameHeader<'buf> {
+    /// `address` is that of the start of `data`, which the table's entries
+    /// are relative to.
+    pub fn parse(data: &'buf [u8], address: u64, address_size: u8) -> Result<Self, ElfError> {
+        let mut reader = DwarfReader::new(".eh_frame_hdr", data, address);
+        let version = reader.u8()?;
+        if version != 1 {
+            return Err(ElfError::MalformedDwarf(".eh_frame_hdr", 0));
+        }
+
+        let eh_frame_ptr_encoding = DwarfPointerEncoding(reader.u8()?);
+        let fde_count_encoding = DwarfPointerEncoding(reader.u8()?);
+        let table_encoding = DwarfPointerEncoding(reader.u8()?);
+        let bases = DwarfPointerBases {
+            data: Some(address),
+            ..DwarfPointerBases::default()
+        };
+
+        let eh_frame_ptr = reader.pointer(eh_frame_ptr_encoding, address_size, &bases)?;
+        let fde_count = (fde_count_encoding != DwarfPointerEncoding::DW_EH_PE_OMIT
+            && table_encoding != DwarfPointerEncoding::DW_EH_PE_OMIT)
+            .then(|| reader.pointer(fde_count_encoding, address_size, &bases))
+            .transpose()?;
+
+        Ok(Self {
+            version,
+            eh_frame_ptr,
+            fde_count,
+            table_encoding,
+            address,
+            address_size,
+            table: reader,
+        })
+    }
+
+    /// The `PT_GNU_EH_FRAME` segment, or otherwise the `.eh_frame_hdr`
+    /// section.
+    pub fn from_file(file: &ElfFile<'buf>) -> Result<Option<Self>, ElfError> {
+        let address_size = address_size(file.header());
+
+        let segment = file
+            .program_headers()
+            .and_then(|headers| headers.find_by_type(ElfSegmentType::PT_GNU_EH_FRAME));
+        if let Some(segment) = segment {
+            return Self::from_segment(file, segment, address_size).map(Some);
+        }
+
+        let Some(section) = file.section_by_name(".eh_frame_hdr") else {
+            return Ok(None);
+        };
+        let address = section.sh_addr().map_or(0, |address| address.get());
+        Self::parse(file.section_data(section)?, address, address_size).map(Some)
+    }
+
+    fn from_segment(
+        file: &ElfFile<'buf>,
+        segment: ElfProgramHeader,
+        address_size: u8,
+    ) -> Result<Self, ElfError> {
+        Self::parse(file.segment_data(segment)?, segment.p_vaddr(), address_size)
+    }
+
+    fn read_entry(&self, reader: &mut DwarfReader<'buf>) -> Result<(u64, u64), ElfError> {
+        let bases = DwarfPointerBases {
+            data: Some(self.address),
+            ..DwarfPointerBases::default()
+        };
+        let location = reader.pointer(self.table_encoding, self.address_size, &bases)?;
+        let fde = reader.pointer(self.table_encoding, self.address_size, &bases)?;
+        Ok((location, fde))
+    }
+
+    /// Iterates over the table, as pairs of a function's start address and
+    /// the address of its FDE.
+    pub fn entries(&self) -> impl Iterator<Item = Result<(u64, u64), ElfError>> + 'buf {
+        let header = *self;
+        let mut reader = self.table;
+        let mut remaining = self.fde_count.unwrap_or(0);
+
+        core::iter::from_fn(move || {
+            remaining = remaining.checked_sub(1)?;
+            let result = header.read_entry(&mut reader);
+            if result.is_err() {
+                remaining = 0;
+            }
+            Some(result)
+        })
+    }
+
+    /// The address of the FDE for the function that may contain `address`,
+    /// by binary search of the table. The FDE's range must still be checked,
+    /// as the address may be past the end of the function.
+    pub fn find(&self, address: u64) -> Result<Option<u64>, ElfError> {
+        let Some(entry_size) = self
+            .table_encoding
+            .fixed_size(self.address_size)
+            .and_then(|size| size.checked_mul(2))
+        else {
+            // Variable length entries can only be searched in order.
+            let mut found = None;
+            for entry in self.entries() {
+                let (location, fde) = entry?;
+                if location > address {
+                    break;
+                }
+                found = Some(fde);
+            }
+            return Ok(found);
+        };
+
+        let count = usize::try_from(self.fde_count.unwrap_or(0))?;
+        let entry = |index: usize| -> Result<(u64, u64), ElfError> {
+            let mut reader = self.table;
+            let offset = index
+                .checked_mul(entry_size)
+                .and_then(|offset| offset.checked_add(self.table.offset()))
+                .ok_or_else(|| self.table.error())?;
+            reader.seek(offset)?;
+            self.read_entry(&mut reader)
+        };
+
+        // The number of entries whose location is at most the address.
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let middle = low.saturating_add(high.saturating_sub(low) / 2);
+            if entry(middle)?.0 <= address {
+                low = middle.saturating_add(1);
+            } else {
+                high = middle;
+            }
+        }
+
+        match low.checked_sub(1) {
+            Some(index) => Ok(Some(entry(index)?.1)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Calls `function` with the address range of each function that
+/// `.eh_frame` describes, in the order of the section. Empty ranges, as left
+/// behind by discarded sections, are skipped.
+pub fn function_ranges_with(
+    file: &ElfFile,
+    mut function: impl FnMut(Range<u64>),
+) -> Result<(), ElfError> {
+    let Some(info) = ElfCallFrameInfo::from_eh_frame(file)? else {
+        return Ok(());
+    };
+
+    for fde in info.fdes() {
+        let range = fde?.range();
+        if !range.is_empty() {
+            function(range);
+        }
+    }
+    Ok(())
+}
+
+/// The address ranges of the functions that `.eh_frame` describes, sorted.
+#[cfg(feature = "std")]
+pub fn function_ranges(file: &ElfFile) -> Result<Vec<Range<u64>>, ElfError> {
+    let mut ranges = Vec::new();
+    function_ranges_with(file, |range| ranges.push(range))?;
+    ranges.sort_by_key(|range| (range.start, range.end));
+    ranges.dedup();
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::UNWIND_X86_64;
+
+    #[test]
+    fn parses_cie_and_fde() {
+        // A CIE with version 1, augmentation "zR", code and data alignment 1
+        // and -8, the return address in register 16 and FDEs using
+        // pcrel|sdata4. Then an FDE for 0x1000..0x1040, relative to its
+        // address of 0x2020, and a zero terminator.
+        let data = b"\
+            \x14\0\0\0\0\0\0\0\x01zR\0\x01\x78\x10\x01\x1b\x0c\x07\x08\x90\x01\0\0\
+            \x10\0\0\0\x1c\0\0\0\xe0\xef\xff\xff\x40\0\0\0\0\0\0\0\
+            \0\0\0\0";
+        let info = ElfCallFrameInfo::new(ElfCallFrameFormat::EhFrame, data, 0x2000, 8);
+
+        let fde = info
+            .find_fde(0x103f)
+            .expect("parses")
+            .expect("address is covered");
+        assert_eq!(fde.offset, 0x18);
+        assert_eq!(fde.range(), 0x1000..0x1040);
+        assert_eq!(fde.cie.augmentation, b"zR");
+        assert_eq!(fde.cie.data_alignment_factor, -8);
+        assert_eq!(fde.cie.return_address_register, 16);
+        assert_eq!(fde.cie.initial_instructions, [0x0c, 7, 8, 0x90, 1, 0, 0]);
+        assert_eq!(info.entries().count(), 2);
+        assert!(matches!(info.find_fde(0x1040), Ok(None)));
+    }
+
+    #[test]
+    fn searches_the_eh_frame_header() {
+        let file = ElfFile::parse(&UNWIND_X86_64.0).expect("valid file");
+        let header = ElfEhFrameHeader::from_file(&file)
+            .expect("parses")
+            .expect("file has a header");
+        assert_eq!(header.version, 1);
+        assert_eq!(header.eh_frame_ptr, 0x400178);
+        assert_eq!(header.fde_count, Some(3));
+
+        let mut entries = header.entries();
+        assert!(matches!(entries.next(), Some(Ok((0x4000f0, 0x400190)))));
+        assert!(matches!(entries.next(), Some(Ok((0x400100, 0x4001a4)))));
+        assert!(matches!(entries.next(), Some(Ok((0x400140, 0x4001d8)))));
+        assert!(entries.next().is_none());
+
+        assert!(matches!(header.find(0x4000ef), Ok(None)));
+        assert!(matches!(header.find(0x4000f0), Ok(Some(0x400190))));
+        // Past the end of leaf, so its FDE doesn't contain the address.
+        assert!(matches!(header.find(0x4000f8), Ok(Some(0x400190))));
+        assert!(matches!(header.find(0x40013c), Ok(Some(0x4001a4))));
+        assert!(matches!(header.find(0x400140), Ok(Some(0x4001d8))));
+        assert!(matches!(header.find(u64::MAX), Ok(Some(0x4001d8))));
+
+        let info = ElfCallFrameInfo::from_eh_frame(&file)
+            .expect("parses")
+            .expect("file has call frame information");
+        let fde = info.fde_at_address(0x4001a4).expect("FDE");
+        assert_eq!(fde.range(), 0x400100..0x40013d);
+        assert!(!info
+            .fde_at_address(0x400190)
+            .expect("FDE")
+            .contains(0x4000f8));
+    }
+
+    #[test]
+    fn searches_variable_length_tables_in_order() {
+        // A header with a udata4 .eh_frame pointer and count, followed by a
+        // table of datarel|uleb128 entries, which can't be binary searched.
+        let data = b"\x01\x03\x03\x31\x00\x30\0\0\x02\0\0\0\x10\x40\x90\x01\xc0\x01";
+        let header = ElfEhFrameHeader::parse(data, 0x1000, 8).expect("parses");
+        assert_eq!(header.eh_frame_ptr, 0x3000);
+
+        assert!(matches!(header.find(0x100f), Ok(None)));
+        assert!(matches!(header.find(0x108f), Ok(Some(0x1040))));
+        assert!(matches!(header.find(0x1090), Ok(Some(0x10c0))));
+    }
+
+    #[test]
+    fn lists_function_ranges() {
+        let file = ElfFile::parse(&UNWIND_X86_64.0).expect("valid file");
+        let mut ranges = [0..0, 0..0, 0..0];
+        let mut count = 0usize;
+        function_ranges_with(&file, |range| {
+            if let Some(slot) = ranges.get_mut(count) {
+                *slot = range;
+            }
+            count = count.saturating_add(1);
+        })
+        .expect("parses");
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            ranges,
+            [0x4000f0..0x4000f4, 0x400100..0x40013d, 0x400140..0x400150]
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(function_ranges(&file).expect("parses"), ranges);
+    }
+}
@@ -1,6 +1,6 @@
 use zerocopy::{AsBytes, FromBytes, FromZeroes};
 
-use crate::{macros::*, *};
+use crate::{dynamic::ElfDynamic, macros::*, symbols::ElfSymbol, *};
 
 impl ElfIdent {
     pub const ELF_MAGIC: [u8; 4] = *b"\x7fELF";
@@ -35,6 +35,27 @@ declare_constants!(ElfIdentVersion, {
     EV_CURRENT = 1,
 });
 
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfIdentOsAbi(pub u8);
+
+declare_constants!(ElfIdentOsAbi, {
+    OSABI_SYSV = 0,
+    OSABI_HPUX = 1,
+    OSABI_NETBSD = 2,
+    OSABI_GNU = 3,
+    OSABI_SOLARIS = 6,
+    OSABI_AIX = 7,
+    OSABI_IRIX = 8,
+    OSABI_FREEBSD = 9,
+    OSABI_TRU64 = 10,
+    OSABI_MODESTO = 11,
+    OSABI_OPENBSD = 12,
+    OSABI_ARM_AEABI = 64,
+    OSABI_ARM = 97,
+    OSABI_STANDALONE = 255,
+});
+
 #[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
 #[repr(transparent)]
 pub struct ElfHeaderType(pub u16);
@@ -57,6 +78,30 @@ pub struct ElfHeaderMachine(pub u16);
 
 declare_constants!(ElfHeaderMachine, {
     EM_NONE = 0,
+    EM_M32 = 1,
+    EM_SPARC = 2,
+    EM_386 = 3,
+    EM_68K = 4,
+    EM_88K = 5,
+    EM_860 = 7,
+    EM_MIPS = 8,
+    EM_PARISC = 15,
+    EM_SPARC32PLUS = 18,
+    EM_PPC = 20,
+    EM_PPC64 = 21,
+    EM_S390 = 22,
+    EM_ARM = 40,
+    EM_SH = 42,
+    EM_SPARCV9 = 43,
+    EM_IA_64 = 50,
+    EM_X86_64 = 62,
+    EM_AVR = 83,
+    EM_XTENSA = 94,
+    EM_MSP430 = 105,
+    EM_AARCH64 = 183,
+    EM_RISCV = 243,
+    EM_BPF = 247,
+    EM_LOONGARCH = 258,
 });
 
 #[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
@@ -71,6 +116,7 @@ declare_constants!(ElfHeaderVersion, {
 impl ElfHeader<'_> {
     enum_getter!(&e_ident, &ElfIdent);
     enum_getter!(e_type, ElfHeaderType);
+    enum_getter!(e_machine, ElfHeaderMachine);
     enum_getter!(e_version, ElfHeaderVersion);
     enum_getter!(e_entry, Option<NonZeroU64>);
     enum_getter!(e_phoff, Option<NonZeroU64>);
@@ -103,9 +149,71 @@ declare_constants!(ElfSectionType, {
     SHT_DYNSYM = 11,
     SHT_INIT_ARRAY = 14,
     SHT_FINI_ARRAY = 15,
+    SHT_PREINIT_ARRAY = 16,
+    SHT_GROUP = 17,
+    SHT_SYMTAB_SHNDX = 18,
+    SHT_RELR = 19,
+    SHT_ANDROID_REL = 0x60000001,
+    SHT_ANDROID_RELA = 0x60000002,
+    SHT_LLVM_ADDRSIG = 0x6fff4c03,
+    SHT_ANDROID_RELR = 0x6fffff00,
+    SHT_GNU_ATTRIBUTES = 0x6ffffff5,
     SHT_GNU_HASH = 0x6ffffff6,
+    SHT_GNU_LIBLIST = 0x6ffffff7,
+    SHT_VERDEF = 0x6ffffffd,
     SHT_VERNEED = 0x6ffffffe,
     SHT_VERSYM = 0x6fffffff,
+
+    // TODO: These are really ranges...
+    SHT_LOOS = 0x60000000,
+    SHT_LOPROC = 0x70000000,
+    SHT_HIPROC = 0x7fffffff,
+    SHT_LOUSER = 0x80000000,
+    SHT_HIUSER = 0xffffffff,
+});
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfSectionFlags(pub u64);
+
+declare_flags!(ElfSectionFlags, {
+    SHF_WRITE = 0x1,
+    SHF_ALLOC = 0x2,
+    SHF_EXECINSTR = 0x4,
+    SHF_MERGE = 0x10,
+    SHF_STRINGS = 0x20,
+    SHF_INFO_LINK = 0x40,
+    SHF_LINK_ORDER = 0x80,
+    SHF_OS_NONCONFORMING = 0x100,
+    SHF_GROUP = 0x200,
+    SHF_TLS = 0x400,
+    SHF_COMPRESSED = 0x800,
+    SHF_GNU_RETAIN = 0x200000,
+    SHF_MASKOS = 0x0ff00000,
+    SHF_EXCLUDE = 0x80000000,
+    SHF_MASKPROC = 0xf0000000,
+});
+
+/// The algorithm a `SHF_COMPRESSED` section is compressed with, from the
+/// `ch_type` of its compression header.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfCompressionType(pub u32);
+
+declare_constants!(ElfCompressionType, {
+    ELFCOMPRESS_ZLIB = 1,
+    ELFCOMPRESS_ZSTD = 2,
+});
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfSectionIndex(pub u16);
+
+declare_constants!(ElfSectionIndex, {
+    SHN_UNDEF = 0,
+    SHN_LORESERVE = 0xff00,
+    SHN_ABS = 0xfff1,
+    SHN_COMMON = 0xfff2,
+    SHN_XINDEX = 0xffff,
 });
 
 impl ElfSectionHeader<'_> {
@@ -139,12 +247,24 @@ declare_constants!(ElfSegmentType, {
     PT_GNU_STACK = 0x6474e551,
     PT_GNU_RELRO = 0x6474e552,
     PT_GNU_PROPERTY = 0x6474e553,
+    PT_GNU_SFRAME = 0x6474e554,
 
-    // TODO: This is really a range...
+    // TODO: These are really ranges...
+    PT_LOOS = 0x60000000,
+    PT_HIOS = 0x6fffffff,
     PT_LOPROC = 0x70000000,
     PT_HIPROC = 0x7fffffff,
 });
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfSegmentFlags(pub u32);
+
+declare_flags!(ElfSegmentFlags, {
+    PF_X = 0x1,
+    PF_W = 0x2,
+    PF_R = 0x4,
+});
+
 impl ElfProgramHeader<'_> {
     enum_getter!(p_type, ElfSegmentType);
     enum_getter!(p_offset, u64);
@@ -155,3 +275,263 @@ impl ElfProgramHeader<'_> {
     enum_getter!(p_flags, u32);
     enum_getter!(p_align, u64);
 }
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfSymbolBinding(pub u8);
+
+declare_constants!(ElfSymbolBinding, {
+    STB_LOCAL = 0,
+    STB_GLOBAL = 1,
+    STB_WEAK = 2,
+    STB_GNU_UNIQUE = 10,
+});
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfSymbolType(pub u8);
+
+declare_constants!(ElfSymbolType, {
+    STT_NOTYPE = 0,
+    STT_OBJECT = 1,
+    STT_FUNC = 2,
+    STT_SECTION = 3,
+    STT_FILE = 4,
+    STT_COMMON = 5,
+    STT_TLS = 6,
+    STT_GNU_IFUNC = 10,
+});
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfSymbolVisibility(pub u8);
+
+declare_constants!(ElfSymbolVisibility, {
+    STV_DEFAULT = 0,
+    STV_INTERNAL = 1,
+    STV_HIDDEN = 2,
+    STV_PROTECTED = 3,
+});
+
+impl ElfSymbol<'_> {
+    enum_getter!(st_name, u32);
+    enum_getter!(st_value, u64);
+    enum_getter!(st_size, u64);
+    enum_getter!(st_info, u8);
+    enum_getter!(st_other, u8);
+    enum_getter!(st_shndx, ElfSectionIndex);
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfDynamicTag(pub i64);
+
+declare_constants!(ElfDynamicTag, {
+    DT_NULL = 0,
+    DT_NEEDED = 1,
+    DT_PLTRELSZ = 2,
+    DT_PLTGOT = 3,
+    DT_HASH = 4,
+    DT_STRTAB = 5,
+    DT_SYMTAB = 6,
+    DT_RELA = 7,
+    DT_RELASZ = 8,
+    DT_RELAENT = 9,
+    DT_STRSZ = 10,
+    DT_SYMENT = 11,
+    DT_INIT = 12,
+    DT_FINI = 13,
+    DT_SONAME = 14,
+    DT_RPATH = 15,
+    DT_SYMBOLIC = 16,
+    DT_REL = 17,
+    DT_RELSZ = 18,
+    DT_RELENT = 19,
+    DT_PLTREL = 20,
+    DT_DEBUG = 21,
+    DT_TEXTREL = 22,
+    DT_JMPREL = 23,
+    DT_BIND_NOW = 24,
+    DT_INIT_ARRAY = 25,
+    DT_FINI_ARRAY = 26,
+    DT_INIT_ARRAYSZ = 27,
+    DT_FINI_ARRAYSZ = 28,
+    DT_RUNPATH = 29,
+    DT_FLAGS = 30,
+    DT_PREINIT_ARRAY = 32,
+    DT_PREINIT_ARRAYSZ = 33,
+    DT_SYMTAB_SHNDX = 34,
+    DT_RELRSZ = 35,
+    DT_RELR = 36,
+    DT_RELRENT = 37,
+    DT_ANDROID_REL = 0x6000000f,
+    DT_ANDROID_RELSZ = 0x60000010,
+    DT_ANDROID_RELA = 0x60000011,
+    DT_ANDROID_RELASZ = 0x60000012,
+    DT_ANDROID_RELR = 0x6fffe000,
+    DT_ANDROID_RELRSZ = 0x6fffe001,
+    DT_ANDROID_RELRENT = 0x6fffe003,
+    DT_GNU_HASH = 0x6ffffef5,
+    DT_TLSDESC_PLT = 0x6ffffef6,
+    DT_TLSDESC_GOT = 0x6ffffef7,
+    DT_VERSYM = 0x6ffffff0,
+    DT_RELACOUNT = 0x6ffffff9,
+    DT_RELCOUNT = 0x6ffffffa,
+    DT_FLAGS_1 = 0x6ffffffb,
+    DT_VERDEF = 0x6ffffffc,
+    DT_VERDEFNUM = 0x6ffffffd,
+    DT_VERNEED = 0x6ffffffe,
+    DT_VERNEEDNUM = 0x6fffffff,
+    DT_AUXILIARY = 0x7ffffffd,
+    DT_FILTER = 0x7fffffff,
+});
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfDynamicFlags(pub u64);
+
+declare_flags!(ElfDynamicFlags, {
+    DF_ORIGIN = 0x1,
+    DF_SYMBOLIC = 0x2,
+    DF_TEXTREL = 0x4,
+    DF_BIND_NOW = 0x8,
+    DF_STATIC_TLS = 0x10,
+});
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfDynamicFlags1(pub u64);
+
+declare_flags!(ElfDynamicFlags1, {
+    DF_1_NOW = 0x1,
+    DF_1_GLOBAL = 0x2,
+    DF_1_GROUP = 0x4,
+    DF_1_NODELETE = 0x8,
+    DF_1_LOADFLTR = 0x10,
+    DF_1_INITFIRST = 0x20,
+    DF_1_NOOPEN = 0x40,
+    DF_1_ORIGIN = 0x80,
+    DF_1_DIRECT = 0x100,
+    DF_1_TRANS = 0x200,
+    DF_1_INTERPOSE = 0x400,
+    DF_1_NODEFLIB = 0x800,
+    DF_1_NODUMP = 0x1000,
+    DF_1_CONFALT = 0x2000,
+    DF_1_ENDFILTEE = 0x4000,
+    DF_1_DISPRELDNE = 0x8000,
+    DF_1_DISPRELPND = 0x10000,
+    DF_1_NODIRECT = 0x20000,
+    DF_1_IGNMULDEF = 0x40000,
+    DF_1_NOKSYMS = 0x80000,
+    DF_1_NOHDR = 0x100000,
+    DF_1_EDITED = 0x200000,
+    DF_1_NORELOC = 0x400000,
+    DF_1_SYMINTPOSE = 0x800000,
+    DF_1_GLOBAUDIT = 0x1000000,
+    DF_1_SINGLETON = 0x2000000,
+    DF_1_STUB = 0x4000000,
+    DF_1_PIE = 0x8000000,
+});
+
+impl ElfDynamic<'_> {
+    #[inline]
+    pub fn d_tag(&self) -> ElfDynamicTag {
+        match self {
+            Self::Elf32(dynamic) => ElfDynamicTag(dynamic.d_tag.into()),
+            Self::Elf64(dynamic) => ElfDynamicTag(dynamic.d_tag),
+        }
+    }
+
+    enum_getter!(d_val, u64);
+}
+
+/// Note types, as interpreted for notes with the owner name `"GNU"`.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfNoteType(pub u32);
+
+declare_constants!(ElfNoteType, {
+    NT_GNU_ABI_TAG = 1,
+    NT_GNU_HWCAP = 2,
+    NT_GNU_BUILD_ID = 3,
+    NT_GNU_GOLD_VERSION = 4,
+    NT_GNU_PROPERTY_TYPE_0 = 5,
+});
+
+/// Property types, found in the descriptor of `NT_GNU_PROPERTY_TYPE_0` notes.
+///
+/// The processor-specific types are only meaningful for their own machines.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfGnuPropertyType(pub u32);
+
+declare_constants!(ElfGnuPropertyType, {
+    GNU_PROPERTY_STACK_SIZE = 1,
+    GNU_PROPERTY_NO_COPY_ON_PROTECTED = 2,
+    GNU_PROPERTY_1_NEEDED = 0xb0008000,
+    GNU_PROPERTY_AARCH64_FEATURE_1_AND = 0xc0000000,
+    GNU_PROPERTY_AARCH64_FEATURE_PAUTH = 0xc0000001,
+    GNU_PROPERTY_X86_FEATURE_1_AND = 0xc0000002,
+    GNU_PROPERTY_X86_FEATURE_2_NEEDED = 0xc0008001,
+    GNU_PROPERTY_X86_ISA_1_NEEDED = 0xc0008002,
+    GNU_PROPERTY_X86_FEATURE_2_USED = 0xc0010001,
+    GNU_PROPERTY_X86_ISA_1_USED = 0xc0010002,
+});
+
+impl ElfGnuPropertyType {
+    pub const GNU_PROPERTY_LOPROC: Self = Self(0xc0000000);
+    pub const GNU_PROPERTY_HIPROC: Self = Self(0xdfffffff);
+    pub const GNU_PROPERTY_LOUSER: Self = Self(0xe0000000);
+    pub const GNU_PROPERTY_HIUSER: Self = Self(0xffffffff);
+}
+
+/// The bits of the `GNU_PROPERTY_X86_FEATURE_1_AND` property, set only if
+/// every input object supports the feature.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfX86Feature1(pub u32);
+
+declare_flags!(ElfX86Feature1, {
+    GNU_PROPERTY_X86_FEATURE_1_IBT = 0x1,
+    GNU_PROPERTY_X86_FEATURE_1_SHSTK = 0x2,
+    GNU_PROPERTY_X86_FEATURE_1_LAM_U48 = 0x4,
+    GNU_PROPERTY_X86_FEATURE_1_LAM_U57 = 0x8,
+});
+
+/// The bits of the `GNU_PROPERTY_X86_FEATURE_2_NEEDED` and
+/// `GNU_PROPERTY_X86_FEATURE_2_USED` properties.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfX86Feature2(pub u32);
+
+declare_flags!(ElfX86Feature2, {
+    GNU_PROPERTY_X86_FEATURE_2_X86 = 0x1,
+    GNU_PROPERTY_X86_FEATURE_2_X87 = 0x2,
+    GNU_PROPERTY_X86_FEATURE_2_MMX = 0x4,
+    GNU_PROPERTY_X86_FEATURE_2_XMM = 0x8,
+    GNU_PROPERTY_X86_FEATURE_2_YMM = 0x10,
+    GNU_PROPERTY_X86_FEATURE_2_ZMM = 0x20,
+    GNU_PROPERTY_X86_FEATURE_2_FXSR = 0x40,
+    GNU_PROPERTY_X86_FEATURE_2_XSAVE = 0x80,
+    GNU_PROPERTY_X86_FEATURE_2_XSAVEOPT = 0x100,
+    GNU_PROPERTY_X86_FEATURE_2_XSAVEC = 0x200,
+    GNU_PROPERTY_X86_FEATURE_2_TMM = 0x400,
+    GNU_PROPERTY_X86_FEATURE_2_MASK = 0x800,
+});
+
+/// The bits of the `GNU_PROPERTY_X86_ISA_1_NEEDED` and
+/// `GNU_PROPERTY_X86_ISA_1_USED` properties.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfX86Isa1(pub u32);
+
+declare_flags!(ElfX86Isa1, {
+    GNU_PROPERTY_X86_ISA_1_BASELINE = 0x1,
+    GNU_PROPERTY_X86_ISA_1_V2 = 0x2,
+    GNU_PROPERTY_X86_ISA_1_V3 = 0x4,
+    GNU_PROPERTY_X86_ISA_1_V4 = 0x8,
+});
+
+/// The bits of the `GNU_PROPERTY_AARCH64_FEATURE_1_AND` property.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ElfAarch64Feature1(pub u32);
+
+declare_flags!(ElfAarch64Feature1, {
+    GNU_PROPERTY_AARCH64_FEATURE_1_BTI = 0x1,
+    GNU_PROPERTY_AARCH64_FEATURE_1_PAC = 0x2,
+});
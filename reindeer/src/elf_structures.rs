@@ -32,6 +32,22 @@ const_assert!(size_of::<Elf32SectionHeader>() == 40);
 const_assert!(size_of::<Elf64SectionHeader>() == 64);
 const_assert!(size_of::<Elf32ProgramHeader>() == 32);
 const_assert!(size_of::<Elf64ProgramHeader>() == 56);
+const_assert!(size_of::<Elf32Symbol>() == 16);
+const_assert!(size_of::<Elf64Symbol>() == 24);
+const_assert!(size_of::<Elf32Dynamic>() == 8);
+const_assert!(size_of::<Elf64Dynamic>() == 16);
+const_assert!(size_of::<Elf32Rel>() == 8);
+const_assert!(size_of::<Elf32Rela>() == 12);
+const_assert!(size_of::<Elf64Rel>() == 16);
+const_assert!(size_of::<Elf64Rela>() == 24);
+const_assert!(size_of::<Elf32CompressionHeader>() == 12);
+const_assert!(size_of::<Elf64CompressionHeader>() == 24);
+const_assert!(size_of::<ElfNoteHeader>() == 12);
+const_assert!(size_of::<ElfGnuPropertyHeader>() == 8);
+const_assert!(size_of::<ElfVerneed>() == 16);
+const_assert!(size_of::<ElfVernaux>() == 16);
+const_assert!(size_of::<ElfVerdef>() == 20);
+const_assert!(size_of::<ElfVerdaux>() == 8);
 
 #[derive(FromBytes, FromZeroes, AsBytes, Debug)]
 #[repr(C)]
@@ -44,8 +60,12 @@ pub struct ElfIdent {
     pub ei_data: ElfIdentData,
     /// EI_VERSION specifies the ELF header version number.
     pub ei_version: ElfIdentVersion,
+    /// EI_OSABI identifies the OS- or ABI-specific ELF extensions used by this file.
+    pub ei_osabi: ElfIdentOsAbi,
+    /// EI_ABIVERSION identifies the version of the ABI to which the object is targeted.
+    pub ei_abiversion: u8,
     /// Padding bytes.
-    pub ei_pad: [u8; 9],
+    pub ei_pad: [u8; 7],
 }
 
 #[derive(FromBytes, FromZeroes, AsBytes, Debug)]
@@ -275,3 +295,219 @@ pub struct Elf64ProgramHeader {
     /// and in the file.
     pub p_align: u64,
 }
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf32Symbol {
+    /// This member holds an index into the object file's symbol string table,
+    /// which holds the character representations of the symbol names.
+    pub st_name: u32,
+    /// This member gives the value of the associated symbol. Depending on the
+    /// context, this may be an absolute value, an address, and so on.
+    pub st_value: u32,
+    /// Many symbols have associated sizes. This member holds 0 if the symbol
+    /// has no size or an unknown size.
+    pub st_size: u32,
+    /// This member specifies the symbol's type and binding attributes.
+    pub st_info: u8,
+    /// This member currently specifies a symbol's visibility.
+    pub st_other: u8,
+    /// Every symbol table entry is "defined" in relation to some section; this
+    /// member holds the relevant section header table index.
+    pub st_shndx: ElfSectionIndex,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf64Symbol {
+    /// This member holds an index into the object file's symbol string table,
+    /// which holds the character representations of the symbol names.
+    pub st_name: u32,
+    /// This member specifies the symbol's type and binding attributes.
+    pub st_info: u8,
+    /// This member currently specifies a symbol's visibility.
+    pub st_other: u8,
+    /// Every symbol table entry is "defined" in relation to some section; this
+    /// member holds the relevant section header table index.
+    pub st_shndx: ElfSectionIndex,
+    /// This member gives the value of the associated symbol. Depending on the
+    /// context, this may be an absolute value, an address, and so on.
+    pub st_value: u64,
+    /// Many symbols have associated sizes. This member holds 0 if the symbol
+    /// has no size or an unknown size.
+    pub st_size: u64,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf32Dynamic {
+    /// This member controls the interpretation of `d_val`.
+    pub d_tag: i32,
+    /// This member represents integer values or program virtual addresses,
+    /// depending on the tag.
+    pub d_val: u32,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf64Dynamic {
+    /// This member controls the interpretation of `d_val`.
+    pub d_tag: i64,
+    /// This member represents integer values or program virtual addresses,
+    /// depending on the tag.
+    pub d_val: u64,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf32Rel {
+    /// This member gives the location at which to apply the relocation action.
+    pub r_offset: u32,
+    /// This member gives both the symbol table index with respect to which the
+    /// relocation must be made, and the type of relocation to apply.
+    pub r_info: u32,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf32Rela {
+    /// This member gives the location at which to apply the relocation action.
+    pub r_offset: u32,
+    /// This member gives both the symbol table index with respect to which the
+    /// relocation must be made, and the type of relocation to apply.
+    pub r_info: u32,
+    /// This member specifies a constant addend used to compute the value to be
+    /// stored into the relocatable field.
+    pub r_addend: i32,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf64Rel {
+    /// This member gives the location at which to apply the relocation action.
+    pub r_offset: u64,
+    /// This member gives both the symbol table index with respect to which the
+    /// relocation must be made, and the type of relocation to apply.
+    pub r_info: u64,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf64Rela {
+    /// This member gives the location at which to apply the relocation action.
+    pub r_offset: u64,
+    /// This member gives both the symbol table index with respect to which the
+    /// relocation must be made, and the type of relocation to apply.
+    pub r_info: u64,
+    /// This member specifies a constant addend used to compute the value to be
+    /// stored into the relocatable field.
+    pub r_addend: i64,
+}
+
+/// The header at the start of the data of a `SHF_COMPRESSED` section,
+/// followed by the compressed contents.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf32CompressionHeader {
+    pub ch_type: ElfCompressionType,
+    /// The size of the uncompressed data.
+    pub ch_size: u32,
+    /// The alignment of the uncompressed data.
+    pub ch_addralign: u32,
+}
+
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct Elf64CompressionHeader {
+    pub ch_type: ElfCompressionType,
+    pub ch_reserved: u32,
+    pub ch_size: u64,
+    pub ch_addralign: u64,
+}
+
+/// The fixed-size header of an entry in a `SHT_NOTE` section or `PT_NOTE`
+/// segment. It is identical for ELF32 and ELF64, and is followed by the
+/// owner name and the descriptor, each padded to the note alignment.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfNoteHeader {
+    /// The length of the owner name, including the terminating null byte.
+    pub n_namesz: u32,
+    /// The length of the descriptor.
+    pub n_descsz: u32,
+    /// The type of the note, interpreted with respect to the owner name.
+    pub n_type: ElfNoteType,
+}
+
+/// The header of a property in the descriptor of a `NT_GNU_PROPERTY_TYPE_0`
+/// note. It is followed by `pr_datasz` bytes of data, padded to 8 bytes on
+/// ELF64 and to 4 bytes on ELF32.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfGnuPropertyHeader {
+    pub pr_type: ElfGnuPropertyType,
+    pub pr_datasz: u32,
+}
+
+/// An entry in a `SHT_VERNEED` (`.gnu.version_r`) section.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfVerneed {
+    /// Version of structure, should be 1.
+    pub vn_version: u16,
+    /// Number of associated `ElfVernaux` entries.
+    pub vn_cnt: u16,
+    /// Offset into the linked string table of the needed file name.
+    pub vn_file: u32,
+    /// Offset in bytes from this entry to its first `ElfVernaux` entry.
+    pub vn_aux: u32,
+    /// Offset in bytes from this entry to the next `ElfVerneed` entry, or 0.
+    pub vn_next: u32,
+}
+
+/// An auxiliary entry of a `ElfVerneed`, naming a single needed version.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfVernaux {
+    /// Hash value of the version name.
+    pub vna_hash: u32,
+    /// Version information flags.
+    pub vna_flags: u16,
+    /// The version index used in the `SHT_VERSYM` table.
+    pub vna_other: u16,
+    /// Offset into the linked string table of the version name.
+    pub vna_name: u32,
+    /// Offset in bytes from this entry to the next `ElfVernaux` entry, or 0.
+    pub vna_next: u32,
+}
+
+/// An entry in a `SHT_VERDEF` (`.gnu.version_d`) section.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfVerdef {
+    /// Version of structure, should be 1.
+    pub vd_version: u16,
+    /// Version information flags.
+    pub vd_flags: u16,
+    /// The version index used in the `SHT_VERSYM` table.
+    pub vd_ndx: u16,
+    /// Number of associated `ElfVerdaux` entries.
+    pub vd_cnt: u16,
+    /// Hash value of the version name.
+    pub vd_hash: u32,
+    /// Offset in bytes from this entry to its first `ElfVerdaux` entry.
+    pub vd_aux: u32,
+    /// Offset in bytes from this entry to the next `ElfVerdef` entry, or 0.
+    pub vd_next: u32,
+}
+
+/// An auxiliary entry of a `ElfVerdef`. The first names the version itself,
+/// subsequent entries name its parents.
+#[derive(FromBytes, FromZeroes, AsBytes, Debug)]
+#[repr(C)]
+pub struct ElfVerdaux {
+    /// Offset into the linked string table of the version name.
+    pub vda_name: u32,
+    /// Offset in bytes from this entry to the next `ElfVerdaux` entry, or 0.
+    pub vda_next: u32,
+}
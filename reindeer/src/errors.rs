@@ -6,11 +6,13 @@
 
 use core::{
     ffi::FromBytesUntilNulError,
+    fmt,
     num::{NonZeroU16, TryFromIntError},
+    ops::Range,
     str::Utf8Error,
 };
 
-use crate::{elf_aux_structures::*, elf_structures::ElfIdent};
+use crate::{elf_aux_structures::*, elf_structures::ElfIdent, ElfFile};
 
 #[derive(err_derive::Error, Debug)]
 pub enum ElfError {
@@ -60,4 +62,190 @@ pub enum ElfError {
 
     #[error(display = "the elf file has no section header table")]
     NoSectionHeaders,
+    #[error(display = "the elf file has no section name string table")]
+    NoSectionNames,
+    #[error(display = "table entry size {} does not match the elf class", _0)]
+    UnexpectedEntrySize(u16),
+    #[error(display = "range {:?} is outside the file", _0)]
+    DataOutOfBounds(Range<u64>),
+    #[error(display = "{}: {} {:#x} {}", _0, _1, _2, _3)]
+    InvalidField(ElfStructure, &'static str, u64, ElfFieldProblem),
+    #[error(display = "section link {} is outside the section table", _0)]
+    SectionLinkOutOfBounds(u32),
+    #[error(display = "header index {} is outside the table", _0)]
+    HeaderIndexOutOfBounds(u16),
+    #[error(display = "{} is misaligned, so reads as a null entry", _0)]
+    UnreadableEntry(ElfStructure),
+    #[error(display = "value {:#x} does not fit in the ELF32 {} field", _1, _0)]
+    ValueOutOfRange(&'static str, u64),
+
+    #[error(display = "the elf file has no {:?} segment", _0)]
+    MissingSegment(ElfSegmentType),
+    #[error(display = "the dynamic array has no {:?} entry", _0)]
+    MissingDynamicEntry(ElfDynamicTag),
+    #[error(
+        display = "the dynamic array has no spare DT_NULL entry to add a {:?} entry",
+        _0
+    )]
+    NoSpareDynamicEntry(ElfDynamicTag),
+    #[error(display = "string contains a NUL byte")]
+    EmbeddedNul,
+    #[error(display = "{:?} files are not supported", _0)]
+    UnsupportedFileType(ElfHeaderType),
+    #[error(
+        display = "stripping would renumber section {}, which an allocated symbol table refers to",
+        _0
+    )]
+    StripWouldRenumber(u16),
+    #[error(display = "segments overlap at load address {:#x}", _0)]
+    OverlappingSegments(u64),
+
+    #[error(display = "section type {:?} does not hold relocations", _0)]
+    NotARelocationSection(ElfSectionType),
+    #[error(display = "note entry is truncated or malformed")]
+    MalformedNote,
+    #[error(
+        display = "version entry at offset {:#x} is truncated or malformed",
+        _0
+    )]
+    MalformedVersionEntry(usize),
+    #[error(display = "{}: truncated or malformed data at offset {:#x}", _0, _1)]
+    MalformedDwarf(&'static str, usize),
+    #[error(display = "{:?} is not supported", _0)]
+    UnsupportedMachine(ElfHeaderMachine),
+    #[error(display = "the value of register {} is unknown", _0)]
+    UnknownRegister(u16),
+    #[error(display = "memory at {:#x} could not be read", _0)]
+    UnreadableMemory(u64),
+    #[error(display = "memory at {:#x} could not be written", _0)]
+    UnwritableMemory(u64),
+    #[error(display = "{:?} compressed sections are not supported", _0)]
+    UnsupportedCompression(ElfCompressionType),
+    #[error(display = "section data is not valid {:?} compressed data", _0)]
+    MalformedCompressedData(ElfCompressionType),
+    #[error(display = "xz compressed data is not supported")]
+    UnsupportedXzCompression,
+    #[error(display = "data is not valid xz compressed data, or is too large")]
+    MalformedXzData,
+    #[error(display = "the loaded sections would not fit in the address space")]
+    ImageTooLarge,
+    #[error(display = "relocation type {} is not supported", _0)]
+    UnsupportedRelocation(u32),
+    #[error(display = "relocation at {:#x} does not fit in its field", _0)]
+    RelocationOverflow(u64),
+    #[error(display = "symbol {} is undefined, or not in a loaded section", _0)]
+    UnresolvedSymbol(u32),
+    #[error(display = "relative relocation at {:#x} is not aligned to a word", _0)]
+    UnalignedRelocation(u64),
+    #[error(
+        display = "packed relocations are truncated or malformed at offset {:#x}",
+        _0
+    )]
+    MalformedPackedRelocations(usize),
+}
+
+/// A header in the file, identifying where an error was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfStructure {
+    ElfHeader,
+    ProgramHeader(u16),
+    SectionHeader(u16),
+}
+
+impl fmt::Display for ElfStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ElfHeader => write!(f, "ELF header"),
+            Self::ProgramHeader(index) => write!(f, "program header {index}"),
+            Self::SectionHeader(index) => write!(f, "section header {index}"),
+        }
+    }
+}
+
+/// What is wrong with the value of a header field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfFieldProblem {
+    /// The data the field locates runs past the end of the file, which is
+    /// the given size.
+    BeyondEndOfFile(u64),
+    /// The value is larger than `p_memsz`, which is given.
+    LargerThanMemSz(u64),
+    /// The value is not congruent to `p_offset` modulo `p_align`, which are
+    /// given.
+    IncongruentWithOffset(u64, u64),
+    /// The value is outside the string table, which is the given size.
+    OutsideStringTable(u64),
+    /// The value is not a section index, with the given number of sections.
+    OutsideSectionTable(u64),
+    /// The value is not the size of the table's entries, which is given.
+    NotEntrySize(u64),
+    /// The value is not a multiple of the size of the table's entries,
+    /// which is given.
+    NotMultipleOfEntrySize(u64),
+}
+
+impl fmt::Display for ElfFieldProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BeyondEndOfFile(size) => write!(f, "beyond end of file {size:#x}"),
+            Self::LargerThanMemSz(size) => write!(f, "larger than p_memsz {size:#x}"),
+            Self::IncongruentWithOffset(offset, align) => {
+                write!(f, "not congruent to p_offset {offset:#x} modulo {align:#x}")
+            }
+            Self::OutsideStringTable(size) => {
+                write!(f, "outside the string table of size {size:#x}")
+            }
+            Self::OutsideSectionTable(count) => {
+                write!(f, "outside the section table of {count} entries")
+            }
+            Self::NotEntrySize(size) => write!(f, "not the entry size {size:#x}"),
+            Self::NotMultipleOfEntrySize(size) => {
+                write!(f, "not a multiple of the entry size {size:#x}")
+            }
+        }
+    }
+}
+
+impl ElfError {
+    /// The header the error was found in, if known.
+    pub fn structure(&self) -> Option<ElfStructure> {
+        match self {
+            Self::InvalidField(structure, ..) => Some(*structure),
+            _ => None,
+        }
+    }
+
+    /// Renders the error with the name of the section it concerns, such as
+    /// "section header 12 (.rela.dyn): sh_offset 0x3000 beyond end of file
+    /// 0x2000".
+    pub fn display_in<'a>(&'a self, file: &'a ElfFile) -> impl fmt::Display + 'a {
+        ElfErrorInFile { error: self, file }
+    }
+}
+
+struct ElfErrorInFile<'a, 'buf> {
+    error: &'a ElfError,
+    file: &'a ElfFile<'buf>,
+}
+
+impl fmt::Display for ElfErrorInFile<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ElfError::InvalidField(structure, field, value, problem) = self.error else {
+            return write!(f, "{}", self.error);
+        };
+
+        write!(f, "{structure}")?;
+        if let ElfStructure::SectionHeader(index) = structure {
+            let name = self
+                .file
+                .section_headers()
+                .and_then(|sections| sections.get(*index))
+                .and_then(|section| self.file.section_name(section).ok())
+                .filter(|name| !name.is_empty());
+            if let Some(name) = name {
+                write!(f, " ({name})")?;
+            }
+        }
+        write!(f, ": {field} {value:#x} {problem}")
+    }
 }
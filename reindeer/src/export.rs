@@ -0,0 +1,220 @@
+//! Exporting the loadable contents of a file for flashing, in the formats of
+//! `objcopy -O binary`, `-O ihex` and `-O srec`.
+//!
+//! The contents are taken from the `PT_LOAD` segments at their physical
+//! address (`p_paddr`), the load memory address where the data is stored,
+//! which may differ from the virtual address it runs at. Only the bytes in
+//! the file are exported, so zero-initialised memory past `p_filesz` is left
+//! out.
+//!
+//! `objcopy` exports sections rather than segments, so a segment holding
+//! only the ELF and program headers is left out of its output but included
+//! here. Linker scripts for bare-metal targets usually keep the headers out
+//! of the loaded image, in which case the outputs match.
+
+use core::{fmt::Write, num::NonZeroU64};
+use std::{string::String, vec::Vec};
+
+use crate::{elf_aux_structures::*, errors::ElfError, ElfFile};
+
+/// The number of data bytes in each Intel HEX and S-record line, as used by
+/// `objcopy`.
+const RECORD_LENGTH: usize = 16;
+
+/// The contents of a `PT_LOAD` segment, and where they are loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfLoadRegion<'buf> {
+    /// The physical address, `p_paddr`.
+    pub address: u64,
+    pub data: &'buf [u8],
+}
+
+impl ElfLoadRegion<'_> {
+    fn end(&self) -> Result<u64, ElfError> {
+        self.address
+            .checked_add(u64::try_from(self.data.len())?)
+            .ok_or(ElfError::ValueOutOfRange("p_paddr", self.address))
+    }
+}
+
+/// The non-empty `PT_LOAD` segments, sorted by physical address.
+pub fn load_regions<'buf>(file: &ElfFile<'buf>) -> Result<Vec<ElfLoadRegion<'buf>>, ElfError> {
+    let mut regions = Vec::new();
+    for segment in file.segments() {
+        if segment.p_type() != ElfSegmentType::PT_LOAD || segment.p_filesz().is_none() {
+            continue;
+        }
+
+        regions.push(ElfLoadRegion {
+            address: segment.p_paddr(),
+            data: file.segment_data(segment)?,
+        });
+    }
+    regions.sort_by_key(|region| region.address);
+
+    for pair in regions.windows(2) {
+        if let [first, second] = pair {
+            if first.end()? > second.address {
+                return Err(ElfError::OverlappingSegments(second.address));
+            }
+        }
+    }
+
+    Ok(regions)
+}
+
+/// A flat image from the lowest to the highest loaded address, with the
+/// gaps between segments filled with `gap_fill`, like `objcopy -O binary`.
+pub fn to_binary(file: &ElfFile, gap_fill: u8) -> Result<Vec<u8>, ElfError> {
+    let regions = load_regions(file)?;
+    let Some(start) = regions.first().map(|region| region.address) else {
+        return Ok(Vec::new());
+    };
+
+    let mut image = Vec::new();
+    for region in &regions {
+        let offset = usize::try_from(region.address.saturating_sub(start))?;
+        image.resize(offset, gap_fill);
+        image.extend_from_slice(region.data);
+    }
+
+    Ok(image)
+}
+
+/// Finishes a line with the hex bytes of `record` and `checksum`. Lines end
+/// in CRLF, as `objcopy` writes them.
+fn push_record(out: &mut String, record: &[u8], checksum: u8) {
+    for byte in record.iter().chain([&checksum]) {
+        let _ = write!(out, "{byte:02X}");
+    }
+    out.push_str("\r\n");
+}
+
+fn address_u32(address: u64) -> Result<u32, ElfError> {
+    u32::try_from(address).map_err(|_| ElfError::ValueOutOfRange("p_paddr", address))
+}
+
+fn low_byte(value: usize) -> u8 {
+    value.to_le_bytes().first().copied().unwrap_or_default()
+}
+
+fn ihex_record(out: &mut String, address: u16, record_type: u8, data: &[u8]) {
+    let [high, low] = address.to_be_bytes();
+    let mut record = Vec::from([low_byte(data.len()), high, low, record_type]);
+    record.extend_from_slice(data);
+
+    // The checksum is the two's complement of the sum of the other bytes.
+    let sum = record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    out.push(':');
+    push_record(out, &record, sum.wrapping_neg());
+}
+
+/// Intel HEX, using extended linear address records for addresses above
+/// 64 KiB and a start linear address record for the entry point, like
+/// `objcopy -O ihex`.
+pub fn to_ihex(file: &ElfFile) -> Result<String, ElfError> {
+    let mut out = String::new();
+    let mut upper = 0;
+
+    for region in load_regions(file)? {
+        let mut address = address_u32(region.address)?;
+        address_u32(region.end()?.saturating_sub(1))?;
+        let mut data = region.data;
+
+        while !data.is_empty() {
+            let [b3, b2, b1, b0] = address.to_be_bytes();
+            let (high, low) = (u16::from_be_bytes([b3, b2]), u16::from_be_bytes([b1, b0]));
+            if high != upper {
+                ihex_record(&mut out, 0, 4, &high.to_be_bytes());
+                upper = high;
+            }
+
+            // A record can't cross into the next 64 KiB.
+            let to_boundary = usize::try_from(0x1_0000u32.saturating_sub(u32::from(low)))?;
+            let (record, rest) = data.split_at(data.len().min(RECORD_LENGTH).min(to_boundary));
+            ihex_record(&mut out, low, 0, record);
+
+            address = address.saturating_add(u32::try_from(record.len())?);
+            data = rest;
+        }
+    }
+
+    if let Some(entry) = file.header().e_entry().map(NonZeroU64::get) {
+        ihex_record(&mut out, 0, 5, &address_u32(entry)?.to_be_bytes());
+    }
+    ihex_record(&mut out, 0, 1, &[]);
+
+    Ok(out)
+}
+
+fn srec_record(out: &mut String, record_type: u8, address_size: usize, address: u32, data: &[u8]) {
+    let address = address.to_be_bytes();
+    let address = address
+        .get(address.len().saturating_sub(address_size)..)
+        .unwrap_or_default();
+
+    // The count covers the address, data and checksum bytes.
+    let count = address.len().saturating_add(data.len()).saturating_add(1);
+    let mut record = Vec::from([low_byte(count)]);
+    record.extend_from_slice(address);
+    record.extend_from_slice(data);
+
+    // The checksum is the one's complement of the sum of the other bytes.
+    let sum = record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    let _ = write!(out, "S{record_type}");
+    push_record(out, &record, !sum);
+}
+
+/// Motorola S-records, with `header` in the S0 record. The narrowest of the
+/// S1, S2 and S3 data records that holds every address is used, like
+/// `objcopy -O srec`.
+pub fn to_srec(file: &ElfFile, header: &str) -> Result<String, ElfError> {
+    let regions = load_regions(file)?;
+    let entry = file.header().e_entry().map_or(0, NonZeroU64::get);
+
+    let mut highest = address_u32(entry)?;
+    for region in &regions {
+        highest = highest.max(address_u32(region.end()?.saturating_sub(1))?);
+    }
+    let (data_type, address_size) = match highest {
+        0..=0xffff => (1, 2),
+        0x1_0000..=0xff_ffff => (2, 3),
+        _ => (3, 4),
+    };
+
+    let mut out = String::new();
+    srec_record(&mut out, 0, 2, 0, header.as_bytes());
+
+    for region in &regions {
+        let mut address = address_u32(region.address)?;
+        for record in region.data.chunks(RECORD_LENGTH) {
+            srec_record(&mut out, data_type, address_size, address, record);
+            address = address.saturating_add(u32::try_from(record.len())?);
+        }
+    }
+
+    // The termination record matching S1, S2 and S3 is S9, S8 and S7.
+    let end_type = 10u8.saturating_sub(data_type);
+    srec_record(&mut out, end_type, address_size, address_u32(entry)?, &[]);
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_records_like_objcopy() {
+        let mut out = String::new();
+        ihex_record(&mut out, 0, 4, &[0x08, 0x04]);
+        ihex_record(&mut out, 0, 1, &[]);
+        srec_record(&mut out, 0, 2, 0, b"t32.srec");
+        srec_record(&mut out, 7, 4, 0x0804_900a, &[]);
+
+        assert_eq!(
+            out,
+            ":020000040804EE\r\n:00000001FF\r\nS00B00007433322E7372656340\r\nS7050804900A54\r\n"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Exploit mitigations an ELF file was built with, in the style of
+//! `checksec`.
+
+use crate::{
+    elf_aux_structures::*,
+    errors::ElfError,
+    notes::{ElfGnuProperty, ElfNotes},
+    ElfFile,
+};
+
+/// How much of the file is made read-only after relocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfRelro {
+    /// There is no `PT_GNU_RELRO` segment.
+    None,
+    /// There is a `PT_GNU_RELRO` segment, but symbols are bound lazily, so
+    /// the PLT's GOT entries remain writable.
+    Partial,
+    /// There is a `PT_GNU_RELRO` segment, and symbols are bound at load time.
+    Full,
+}
+
+/// The hardening features of a file, as reported by `checksec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfHardening<'buf> {
+    /// The file is a position-independent executable: `ET_DYN` with
+    /// `DF_1_PIE` set, or with a program interpreter for older linkers.
+    pub pie: bool,
+    /// There is a `PT_GNU_STACK` segment, and it isn't executable.
+    pub nx: bool,
+    pub relro: ElfRelro,
+    /// The file references `__stack_chk_fail` or `__stack_chk_guard`.
+    /// Only undefined symbols count, as libc defines them, so statically
+    /// linked files report neither this nor FORTIFY functions.
+    pub stack_protector: bool,
+    /// The number of undefined `__*_chk` functions, which `_FORTIFY_SOURCE`
+    /// substitutes for their unchecked counterparts.
+    pub fortified_functions: usize,
+    /// The dynamic array has `DT_TEXTREL` or `DF_TEXTREL`, so the loader
+    /// has to write to the text segment.
+    pub textrel: bool,
+    pub rpath: Option<&'buf str>,
+    pub runpath: Option<&'buf str>,
+    /// The `GNU_PROPERTY_X86_FEATURE_1_AND` property, holding the CET
+    /// features (IBT and SHSTK) supported by every input object.
+    pub x86_features: Option<ElfX86Feature1>,
+    /// The `GNU_PROPERTY_AARCH64_FEATURE_1_AND` property, holding the BTI and
+    /// PAC features supported by every input object.
+    pub aarch64_features: Option<ElfAarch64Feature1>,
+}
+
+impl<'buf> ElfHardening<'buf> {
+    pub fn parse(file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let header = file.header();
+        let segment = |segment_type| {
+            file.segments()
+                .find(|segment| segment.p_type() == segment_type)
+        };
+
+        let dynamic = file.dynamic_table()?;
+        let value = |tag| dynamic.and_then(|dynamic| dynamic.value(tag));
+        let flags = ElfDynamicFlags(value(ElfDynamicTag::DT_FLAGS).unwrap_or_default());
+        let flags_1 = ElfDynamicFlags1(value(ElfDynamicTag::DT_FLAGS_1).unwrap_or_default());
+
+        let pie = header.e_type() == ElfHeaderType::ET_DYN
+            && (flags_1.contains(ElfDynamicFlags1::DF_1_PIE)
+                || segment(ElfSegmentType::PT_INTERP).is_some());
+
+        let nx = segment(ElfSegmentType::PT_GNU_STACK)
+            .is_some_and(|stack| !ElfSegmentFlags(stack.p_flags()).contains(ElfSegmentFlags::PF_X));
+
+        let bind_now = value(ElfDynamicTag::DT_BIND_NOW).is_some()
+            || flags.contains(ElfDynamicFlags::DF_BIND_NOW)
+            || flags_1.contains(ElfDynamicFlags1::DF_1_NOW);
+        let relro = match (segment(ElfSegmentType::PT_GNU_RELRO), bind_now) {
+            (None, _) => ElfRelro::None,
+            (Some(_), false) => ElfRelro::Partial,
+            (Some(_), true) => ElfRelro::Full,
+        };
+
+        let textrel = value(ElfDynamicTag::DT_TEXTREL).is_some()
+            || flags.contains(ElfDynamicFlags::DF_TEXTREL);
+
+        let strings = file.dynamic_string_table()?;
+        let string = |tag| -> Result<Option<&'buf str>, ElfError> {
+            match (value(tag), strings) {
+                (Some(offset), Some(strings)) => Ok(Some(strings.get(u32::try_from(offset)?)?)),
+                _ => Ok(None),
+            }
+        };
+
+        let mut hardening = Self {
+            pie,
+            nx,
+            relro,
+            stack_protector: false,
+            fortified_functions: 0,
+            textrel,
+            rpath: string(ElfDynamicTag::DT_RPATH)?,
+            runpath: string(ElfDynamicTag::DT_RUNPATH)?,
+            x86_features: None,
+            aarch64_features: None,
+        };
+
+        hardening.parse_symbols(file)?;
+        hardening.parse_properties(file)?;
+
+        Ok(hardening)
+    }
+
+    /// Looks for references to the stack protector and FORTIFY functions in
+    /// the symbol tables. The static symbol table is preferred, as it also
+    /// covers relocatable files.
+    fn parse_symbols(&mut self, file: &ElfFile<'buf>) -> Result<(), ElfError> {
+        let Some(section) = file
+            .section_by_type(ElfSectionType::SHT_SYMTAB)
+            .or_else(|| file.section_by_type(ElfSectionType::SHT_DYNSYM))
+        else {
+            return Ok(());
+        };
+
+        let strings = file.linked_string_table(section)?;
+        for symbol in file.symbol_table(section)? {
+            // Definitions are the functions themselves, not uses of them.
+            if !symbol.is_undefined() {
+                continue;
+            }
+            // A symbol with a bad name can't be one of the functions.
+            let Ok(name) = strings.get(symbol.st_name()) else {
+                continue;
+            };
+            // Versioned names in the static symbol table carry the version.
+            let name = name.split_once('@').map_or(name, |(name, _)| name);
+
+            if name == "__stack_chk_fail" || name == "__stack_chk_guard" {
+                self.stack_protector = true;
+            } else if name.starts_with("__") && name.ends_with("_chk") {
+                self.fortified_functions = self.fortified_functions.saturating_add(1);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the CET and BTI features from the GNU property note, found by
+    /// the `PT_GNU_PROPERTY` segment or the `.note.gnu.property` section.
+    fn parse_properties(&mut self, file: &ElfFile<'buf>) -> Result<(), ElfError> {
+        let (data, align) = if let Some(segment) = file
+            .segments()
+            .find(|segment| segment.p_type() == ElfSegmentType::PT_GNU_PROPERTY)
+        {
+            (file.segment_data(segment)?, segment.p_align())
+        } else if let Some(section) = file.section_by_name(".note.gnu.property") {
+            (file.section_data(section)?, section.sh_addralign())
+        } else {
+            return Ok(());
+        };
+
+        for note in ElfNotes::parse(data, align) {
+            let Some(properties) = note?.gnu_properties(file.header()) else {
+                continue;
+            };
+
+            for property in properties {
+                self.parse_property(file.header().e_machine(), property?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Processor-specific property types overlap, so are only interpreted
+    /// for the machine they belong to.
+    fn parse_property(&mut self, machine: ElfHeaderMachine, property: ElfGnuProperty) {
+        let is_x86 = machine == ElfHeaderMachine::EM_X86_64 || machine == ElfHeaderMachine::EM_386;
+
+        match property.pr_type {
+            ElfGnuPropertyType::GNU_PROPERTY_X86_FEATURE_1_AND if is_x86 => {
+                self.x86_features = property.value_u32().map(ElfX86Feature1);
+            }
+            ElfGnuPropertyType::GNU_PROPERTY_AARCH64_FEATURE_1_AND
+                if machine == ElfHeaderMachine::EM_AARCH64 =>
+            {
+                self.aarch64_features = property.value_u32().map(ElfAarch64Feature1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Indirect branch tracking, from Intel CET.
+    pub fn ibt(&self) -> bool {
+        self.x86_features.is_some_and(|features| {
+            features.contains(ElfX86Feature1::GNU_PROPERTY_X86_FEATURE_1_IBT)
+        })
+    }
+
+    /// Shadow stacks, from Intel CET.
+    pub fn shstk(&self) -> bool {
+        self.x86_features.is_some_and(|features| {
+            features.contains(ElfX86Feature1::GNU_PROPERTY_X86_FEATURE_1_SHSTK)
+        })
+    }
+
+    /// Branch target identification, on AArch64.
+    pub fn bti(&self) -> bool {
+        self.aarch64_features.is_some_and(|features| {
+            features.contains(ElfAarch64Feature1::GNU_PROPERTY_AARCH64_FEATURE_1_BTI)
+        })
+    }
+
+    /// Pointer authentication, on AArch64.
+    pub fn pac(&self) -> bool {
+        self.aarch64_features.is_some_and(|features| {
+            features.contains(ElfAarch64Feature1::GNU_PROPERTY_AARCH64_FEATURE_1_PAC)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{HARDENING_AARCH64, HARDENING_PIE, HARDENING_TEXTREL, HARDENING_X86_64};
+
+    #[test]
+    fn detects_x86_hardening() {
+        let file = ElfFile::parse(&HARDENING_X86_64.0).expect("valid file");
+        let hardening = ElfHardening::parse(&file).expect("readable file");
+
+        assert!(hardening.stack_protector);
+        assert_eq!(hardening.fortified_functions, 2);
+        assert!(hardening.ibt());
+        assert!(hardening.shstk());
+        assert!(!hardening.bti());
+        assert!(!hardening.pac());
+        assert!(!hardening.pie);
+        assert_eq!(hardening.relro, ElfRelro::None);
+    }
+
+    #[test]
+    fn detects_aarch64_hardening() {
+        let file = ElfFile::parse(&HARDENING_AARCH64.0).expect("valid file");
+        let hardening = ElfHardening::parse(&file).expect("readable file");
+
+        assert!(hardening.bti());
+        assert!(hardening.pac());
+        assert!(!hardening.ibt());
+        assert!(!hardening.shstk());
+        assert!(!hardening.stack_protector);
+    }
+
+    #[test]
+    fn skips_symbols_with_bad_names() {
+        let mut buffer = HARDENING_X86_64;
+        let file = ElfFile::parse(&buffer.0).expect("valid file");
+        let symtab = file
+            .section_by_type(ElfSectionType::SHT_SYMTAB)
+            .expect("symbol table");
+        // Point the name of `__memcpy_chk`, the second symbol, outside the
+        // string table.
+        let offset = symtab
+            .sh_offset()
+            .checked_add(
+                symtab
+                    .sh_entsize()
+                    .map_or(0, |size| size.get())
+                    .saturating_mul(2),
+            )
+            .and_then(|offset| usize::try_from(offset).ok())
+            .expect("small offset");
+
+        let name = buffer
+            .0
+            .get_mut(offset..offset.saturating_add(4))
+            .expect("in bounds");
+        name.copy_from_slice(&0xffffu32.to_le_bytes());
+
+        let file = ElfFile::parse(&buffer.0).expect("valid file");
+        let hardening = ElfHardening::parse(&file).expect("readable file");
+        assert!(hardening.stack_protector);
+        assert_eq!(hardening.fortified_functions, 1);
+    }
+
+    #[test]
+    fn detects_linked_hardening() {
+        let file = ElfFile::parse(&HARDENING_PIE.0).expect("valid file");
+        let hardening = ElfHardening::parse(&file).expect("readable file");
+
+        assert!(hardening.pie);
+        assert!(hardening.nx);
+        assert_eq!(hardening.relro, ElfRelro::Full);
+        assert!(!hardening.textrel);
+        assert!(hardening.stack_protector);
+        assert_eq!(hardening.fortified_functions, 2);
+        assert_eq!(hardening.rpath, Some("/opt/lib"));
+        assert_eq!(hardening.runpath, None);
+    }
+
+    #[test]
+    fn detects_missing_hardening() {
+        // A shared object, rather than a PIE, which defines the stack
+        // protector and a FORTIFY function rather than using them.
+        let file = ElfFile::parse(&HARDENING_TEXTREL.0).expect("valid file");
+        let hardening = ElfHardening::parse(&file).expect("readable file");
+
+        assert!(!hardening.pie);
+        assert!(!hardening.nx);
+        assert_eq!(hardening.relro, ElfRelro::None);
+        assert!(hardening.textrel);
+        assert!(!hardening.stack_protector);
+        assert_eq!(hardening.fortified_functions, 0);
+        assert_eq!(hardening.rpath, None);
+        assert_eq!(hardening.runpath, Some("/opt/lib"));
+    }
+}
@@ -9,24 +9,68 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "std")]
+pub mod compression;
+#[cfg(feature = "std")]
+pub mod debug_file;
+pub mod debug_info;
+pub mod debug_line;
+#[cfg(feature = "std")]
+pub mod dependencies;
+pub mod dwarf;
+pub mod dynamic;
+pub mod eh_frame;
 pub mod elf_aux_structures;
 pub mod elf_structures;
 pub mod errors;
+#[cfg(feature = "std")]
+pub mod export;
+pub mod hardening;
+pub mod mapping;
+#[cfg(feature = "std")]
+pub mod mini_debug_info;
+pub mod mutable;
+pub mod notes;
+pub mod object_loader;
+pub mod packed_relocations;
 pub mod range;
+pub mod relocation_types;
+pub mod relocations;
+#[cfg(feature = "std")]
+pub mod rewrite;
+pub mod self_relocate;
+#[cfg(feature = "std")]
+pub mod strip;
+#[cfg(feature = "std")]
+pub mod symbolize;
+pub mod symbols;
+pub mod unwind;
+pub mod validate;
+pub mod versions;
 
 mod macros;
+#[cfg(test)]
+mod testdata;
 
 use core::{
     ffi::CStr,
     mem::size_of,
     num::{NonZeroU16, NonZeroU64},
     ops::Range,
+    ptr,
 };
 
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use dynamic::ElfDynamicTable;
 use elf_aux_structures::*;
 use elf_structures::*;
-use errors::ElfError;
-use zerocopy::FromBytes;
+use errors::{ElfError, ElfFieldProblem, ElfStructure};
+use range::TryIntoRangeUsize;
+use relocations::ElfRelocationTable;
+use symbols::ElfSymbolTable;
+use zerocopy::{AsBytes, FromBytes};
 
 /// An Elf header type, representing either 64 or 32 bit little-endian ELFs.
 #[derive(Debug, Clone, Copy)]
@@ -81,11 +125,11 @@ impl<'buf> ElfHeader<'buf> {
 
     pub fn section_headers_location(&self) -> Option<Range<u64>> {
         let start = self.e_shoff()?.get();
-        let length = self.e_shentsize().saturating_mul(self.e_shnum()?.get());
+        let length = u64::from(self.e_shentsize()).saturating_mul(u64::from(self.e_shnum()?.get()));
 
         Some(Range {
             start,
-            end: start.saturating_add(u64::from(length)),
+            end: start.saturating_add(length),
         })
     }
 
@@ -93,6 +137,16 @@ impl<'buf> ElfHeader<'buf> {
         self.section_header_location(self.e_shstrndx()?.get())
     }
 
+    pub fn program_headers_location(&self) -> Option<Range<u64>> {
+        let start = self.e_phoff()?.get();
+        let length = u64::from(self.e_phentsize()).saturating_mul(u64::from(self.e_phnum()?.get()));
+
+        Some(Range {
+            start,
+            end: start.saturating_add(length),
+        })
+    }
+
     pub fn program_header_location(&self, header_number: u16) -> Option<Range<u64>> {
         if header_number >= self.e_phnum()?.get() {
             return None;
@@ -219,6 +273,12 @@ impl<'buf> ElfProgramHeader<'buf> {
 pub enum ElfSectionHeaders<'buf> {
     Elf32(&'buf [Elf32SectionHeader]),
     Elf64(&'buf [Elf64SectionHeader]),
+    /// Entries the given number of bytes apart, as read by
+    /// [`ElfFile::parse_lenient_with`] when `e_shentsize` isn't the size of
+    /// the structures or the table is misaligned. An entry that can't be read
+    /// reads as a `SHT_NULL` section, so that the others keep their indices.
+    Elf32Strided(&'buf [u8], usize),
+    Elf64Strided(&'buf [u8], usize),
 }
 
 impl<'buf> ElfSectionHeaders<'buf> {
@@ -226,6 +286,16 @@ impl<'buf> ElfSectionHeaders<'buf> {
     // todo: better to use slice_from_prefix?
     // todo: should require the length?
     pub fn parse(header: ElfHeader, bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        let entry_size = match header {
+            ElfHeader::Elf32(_) => size_of::<Elf32SectionHeader>(),
+            ElfHeader::Elf64(_) => size_of::<Elf64SectionHeader>(),
+        };
+
+        // slice_from assumes the stride matches size_of.
+        if usize::from(header.e_shentsize()) != entry_size {
+            return Err(ElfError::UnexpectedEntrySize(header.e_shentsize()));
+        }
+
         let section_headers = match header {
             ElfHeader::Elf32(_) => {
                 Self::Elf32(Elf32SectionHeader::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
@@ -235,24 +305,47 @@ impl<'buf> ElfSectionHeaders<'buf> {
             }
         };
 
-        #[allow(clippy::as_conversions)]
-        if header.e_shentsize() as usize != size_of::<Elf32SectionHeader>()
-            && header.e_shentsize() as usize != size_of::<Elf64SectionHeader>()
-        {
-            todo!("slice_from assumes the stride matches size_of");
-        }
-
         // Note: We don't need to do any further checks, as ElfSectionHeader::parse and ElfProgramHeader::parse
         //       are just wrappers around ref_from_prefix(), and so this is fine..
 
         Ok(section_headers)
     }
 
+    /// Reads the table entry by entry, `e_shentsize` bytes apart, which
+    /// can't be done if that's smaller than the structures.
+    pub fn parse_strided(header: ElfHeader, bytes: &'buf [u8]) -> Option<Self> {
+        let stride = usize::from(header.e_shentsize());
+        match header {
+            ElfHeader::Elf32(_) if stride >= size_of::<Elf32SectionHeader>() => {
+                Some(Self::Elf32Strided(bytes, stride))
+            }
+            ElfHeader::Elf64(_) if stride >= size_of::<Elf64SectionHeader>() => {
+                Some(Self::Elf64Strided(bytes, stride))
+            }
+            _ => None,
+        }
+    }
+
+    /// The indices of the entries of a strided table that are misaligned,
+    /// and so read as `SHT_NULL` sections.
+    pub fn unreadable_entries(&self) -> impl Iterator<Item = u16> + 'buf {
+        let (bytes, stride, is_readable): (&[u8], usize, IsReadable) = match *self {
+            Self::Elf32Strided(bytes, stride) => (bytes, stride, |entry| {
+                Elf32SectionHeader::ref_from_prefix(entry).is_some()
+            }),
+            Self::Elf64Strided(bytes, stride) => (bytes, stride, |entry| {
+                Elf64SectionHeader::ref_from_prefix(entry).is_some()
+            }),
+            Self::Elf32(_) | Self::Elf64(_) => (&[], 1, |_| true),
+        };
+        unreadable_entries(bytes, stride, is_readable)
+    }
+
     pub fn find_by_name(
         &self,
         string_table: ElfStringTable,
         name: &str,
-    ) -> Option<ElfSectionHeader> {
+    ) -> Option<ElfSectionHeader<'buf>> {
         self.into_iter().find(|header| {
             string_table
                 .section_name(*header)
@@ -260,14 +353,34 @@ impl<'buf> ElfSectionHeaders<'buf> {
         })
     }
 
-    pub fn get(&self, index: u16) -> Option<ElfSectionHeader> {
+    pub fn get(&self, index: u16) -> Option<ElfSectionHeader<'buf>> {
         let index = usize::from(index);
         match self {
             Self::Elf32(headers) => headers.get(index).map(ElfSectionHeader::Elf32),
             Self::Elf64(headers) => headers.get(index).map(ElfSectionHeader::Elf64),
+            Self::Elf32Strided(bytes, stride) => {
+                strided_entry(bytes, *stride, index).map(ElfSectionHeader::Elf32)
+            }
+            Self::Elf64Strided(bytes, stride) => {
+                strided_entry(bytes, *stride, index).map(ElfSectionHeader::Elf64)
+            }
         }
     }
 
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Elf32(headers) => headers.len(),
+            Self::Elf64(headers) => headers.len(),
+            Self::Elf32Strided(bytes, stride) | Self::Elf64Strided(bytes, stride) => {
+                bytes.len().checked_div(*stride).unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn string_table_location(&self, header: ElfHeader) -> Result<Option<Range<u64>>, ElfError> {
         let Some(string_table_index) = header.e_shstrndx() else {
             return Ok(None);
@@ -289,6 +402,7 @@ impl<'buf> IntoIterator for ElfSectionHeaders<'buf> {
         match self {
             ElfSectionHeaders::Elf32(headers) => Self::IntoIter::Elf32(headers.iter()),
             ElfSectionHeaders::Elf64(headers) => Self::IntoIter::Elf64(headers.iter()),
+            headers => Self::IntoIter::Strided(headers, 0),
         }
     }
 }
@@ -296,6 +410,8 @@ impl<'buf> IntoIterator for ElfSectionHeaders<'buf> {
 pub enum ElfSectionHeadersIter<'buf> {
     Elf32(core::slice::Iter<'buf, Elf32SectionHeader>),
     Elf64(core::slice::Iter<'buf, Elf64SectionHeader>),
+    /// A strided table, and the index of the next entry.
+    Strided(ElfSectionHeaders<'buf>, u16),
 }
 
 impl<'buf> Iterator for ElfSectionHeadersIter<'buf> {
@@ -305,10 +421,184 @@ impl<'buf> Iterator for ElfSectionHeadersIter<'buf> {
         match self {
             Self::Elf32(iter) => iter.next().map(Self::Item::Elf32),
             Self::Elf64(iter) => iter.next().map(Self::Item::Elf64),
+            Self::Strided(headers, index) => {
+                let header = headers.get(*index)?;
+                *index = index.checked_add(1)?;
+                Some(header)
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum ElfProgramHeaders<'buf> {
+    Elf32(&'buf [Elf32ProgramHeader]),
+    Elf64(&'buf [Elf64ProgramHeader]),
+    /// Entries the given number of bytes apart, like
+    /// [`ElfSectionHeaders::Elf32Strided`]. An entry that can't be read reads
+    /// as a `PT_NULL` segment.
+    Elf32Strided(&'buf [u8], usize),
+    Elf64Strided(&'buf [u8], usize),
+}
+
+impl<'buf> ElfProgramHeaders<'buf> {
+    pub fn parse(header: ElfHeader, bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        let entry_size = match header {
+            ElfHeader::Elf32(_) => size_of::<Elf32ProgramHeader>(),
+            ElfHeader::Elf64(_) => size_of::<Elf64ProgramHeader>(),
+        };
+
+        // slice_from assumes the stride matches size_of.
+        if usize::from(header.e_phentsize()) != entry_size {
+            return Err(ElfError::UnexpectedEntrySize(header.e_phentsize()));
+        }
+
+        let program_headers = match header {
+            ElfHeader::Elf32(_) => {
+                Self::Elf32(Elf32ProgramHeader::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            ElfHeader::Elf64(_) => {
+                Self::Elf64(Elf64ProgramHeader::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+        };
+
+        Ok(program_headers)
+    }
+
+    /// Reads the table entry by entry, `e_phentsize` bytes apart, which
+    /// can't be done if that's smaller than the structures.
+    pub fn parse_strided(header: ElfHeader, bytes: &'buf [u8]) -> Option<Self> {
+        let stride = usize::from(header.e_phentsize());
+        match header {
+            ElfHeader::Elf32(_) if stride >= size_of::<Elf32ProgramHeader>() => {
+                Some(Self::Elf32Strided(bytes, stride))
+            }
+            ElfHeader::Elf64(_) if stride >= size_of::<Elf64ProgramHeader>() => {
+                Some(Self::Elf64Strided(bytes, stride))
+            }
+            _ => None,
+        }
+    }
+
+    /// The indices of the entries of a strided table that are misaligned,
+    /// and so read as `PT_NULL` segments.
+    pub fn unreadable_entries(&self) -> impl Iterator<Item = u16> + 'buf {
+        let (bytes, stride, is_readable): (&[u8], usize, IsReadable) = match *self {
+            Self::Elf32Strided(bytes, stride) => (bytes, stride, |entry| {
+                Elf32ProgramHeader::ref_from_prefix(entry).is_some()
+            }),
+            Self::Elf64Strided(bytes, stride) => (bytes, stride, |entry| {
+                Elf64ProgramHeader::ref_from_prefix(entry).is_some()
+            }),
+            Self::Elf32(_) | Self::Elf64(_) => (&[], 1, |_| true),
+        };
+        unreadable_entries(bytes, stride, is_readable)
+    }
+
+    pub fn get(&self, index: u16) -> Option<ElfProgramHeader<'buf>> {
+        let index = usize::from(index);
+        match self {
+            Self::Elf32(headers) => headers.get(index).map(ElfProgramHeader::Elf32),
+            Self::Elf64(headers) => headers.get(index).map(ElfProgramHeader::Elf64),
+            Self::Elf32Strided(bytes, stride) => {
+                strided_entry(bytes, *stride, index).map(ElfProgramHeader::Elf32)
+            }
+            Self::Elf64Strided(bytes, stride) => {
+                strided_entry(bytes, *stride, index).map(ElfProgramHeader::Elf64)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Elf32(headers) => headers.len(),
+            Self::Elf64(headers) => headers.len(),
+            Self::Elf32Strided(bytes, stride) | Self::Elf64Strided(bytes, stride) => {
+                bytes.len().checked_div(*stride).unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Finds the first segment of the given type.
+    pub fn find_by_type(&self, segment_type: ElfSegmentType) -> Option<ElfProgramHeader<'buf>> {
+        self.into_iter()
+            .find(|header| header.p_type() == segment_type)
+    }
+}
+
+impl<'buf> IntoIterator for ElfProgramHeaders<'buf> {
+    type Item = ElfProgramHeader<'buf>;
+    type IntoIter = ElfProgramHeadersIter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            ElfProgramHeaders::Elf32(headers) => Self::IntoIter::Elf32(headers.iter()),
+            ElfProgramHeaders::Elf64(headers) => Self::IntoIter::Elf64(headers.iter()),
+            headers => Self::IntoIter::Strided(headers, 0),
+        }
+    }
+}
+
+pub enum ElfProgramHeadersIter<'buf> {
+    Elf32(core::slice::Iter<'buf, Elf32ProgramHeader>),
+    Elf64(core::slice::Iter<'buf, Elf64ProgramHeader>),
+    /// A strided table, and the index of the next entry.
+    Strided(ElfProgramHeaders<'buf>, u16),
+}
+
+impl<'buf> Iterator for ElfProgramHeadersIter<'buf> {
+    type Item = ElfProgramHeader<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Elf32(iter) => iter.next().map(Self::Item::Elf32),
+            Self::Elf64(iter) => iter.next().map(Self::Item::Elf64),
+            Self::Strided(headers, index) => {
+                let header = headers.get(*index)?;
+                *index = index.checked_add(1)?;
+                Some(header)
+            }
+        }
+    }
+}
+
+/// Stands in for an entry of a strided table that can't be read, being
+/// large and aligned enough for any header structure.
+static UNREADABLE_ENTRY: [u64; 8] = [0; 8];
+
+/// The bytes of the entry at `index` of a table whose entries are `stride`
+/// bytes apart.
+fn strided_bytes(bytes: &[u8], stride: usize, index: usize) -> Option<&[u8]> {
+    let start = index.checked_mul(stride)?;
+    bytes.get(start..start.checked_add(stride)?)
+}
+
+/// The entry at `index` of a strided table, or a zeroed entry if it is
+/// misaligned.
+fn strided_entry<T: FromBytes>(bytes: &[u8], stride: usize, index: usize) -> Option<&T> {
+    let entry = strided_bytes(bytes, stride, index)?;
+    T::ref_from_prefix(entry).or_else(|| T::ref_from_prefix(UNREADABLE_ENTRY.as_bytes()))
+}
+
+/// Whether the bytes of an entry can be read as a header structure.
+type IsReadable = fn(&[u8]) -> bool;
+
+/// The indices of the entries of a strided table that `is_readable` rejects.
+fn unreadable_entries(
+    bytes: &[u8],
+    stride: usize,
+    is_readable: IsReadable,
+) -> impl Iterator<Item = u16> + '_ {
+    (0..=u16::MAX)
+        .map_while(move |index| Some((index, strided_bytes(bytes, stride, usize::from(index))?)))
+        .filter(move |&(_, entry)| !is_readable(entry))
+        .map(|(index, _)| index)
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct ElfStringTable<'buf> {
     buffer: &'buf [u8],
@@ -327,20 +617,602 @@ impl<'buf> ElfStringTable<'buf> {
         }
     }
 
-    pub fn section_name(&self, header: ElfSectionHeader) -> Result<&str, ElfError> {
+    /// Like [`Self::parse`], but accepts a table that doesn't start and end
+    /// with a NUL byte. A string running off the end of the table can't be
+    /// read, but the others can.
+    pub fn parse_lenient(buffer: &'buf [u8]) -> ElfStringTable<'buf> {
+        Self { buffer }
+    }
+
+    pub fn section_name(&self, header: ElfSectionHeader) -> Result<&'buf str, ElfError> {
+        self.get(header.sh_name())
+    }
+
+    /// The size of the string table in bytes.
+    pub fn size(&self) -> u64 {
+        u64::try_from(self.buffer.len()).unwrap_or(u64::MAX)
+    }
+
+    /// Gets the null-terminated string starting at `index`.
+    pub fn get(&self, index: u32) -> Result<&'buf str, ElfError> {
         // This should be fine on almost any platform, unless the string
         // table is absolutely huge.
-        let sh_name_index = header.sh_name().try_into()?;
+        let index = index.try_into()?;
 
         let null_terminated = self
             .buffer
-            .get(sh_name_index..)
-            .ok_or(ElfError::StringTableOutOfBounds(sh_name_index))?;
+            .get(index..)
+            .ok_or(ElfError::StringTableOutOfBounds(index))?;
 
         Ok(CStr::from_bytes_until_nul(null_terminated)?.to_str()?)
     }
 }
 
+/// A header field to blame for an error, and its value.
+type ElfField = (ElfStructure, &'static str, u64);
+
+/// A parsed ELF file, bundling the file's buffer with its header and, where
+/// present, its program header table, section header table and section name
+/// string table.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfFile<'buf> {
+    buffer: &'buf [u8],
+    header: ElfHeader<'buf>,
+    program_headers: Option<ElfProgramHeaders<'buf>>,
+    section_headers: Option<ElfSectionHeaders<'buf>>,
+    section_names: Option<ElfStringTable<'buf>>,
+}
+
+impl<'buf> ElfFile<'buf> {
+    pub fn parse(buffer: &'buf [u8]) -> Result<Self, ElfError> {
+        let header = ElfHeader::parse(buffer)?;
+
+        let program_headers = match header.program_headers_location() {
+            Some(location) => Some(ElfProgramHeaders::parse(
+                header,
+                Self::slice_field(buffer, location, Self::phoff_field(header))?,
+            )?),
+            None => None,
+        };
+
+        let section_headers = match header.section_headers_location() {
+            Some(location) => Some(ElfSectionHeaders::parse(
+                header,
+                Self::slice_field(buffer, location, Self::shoff_field(header))?,
+            )?),
+            None => None,
+        };
+
+        let section_names = match section_headers {
+            Some(section_headers) => match section_headers.string_table_location(header)? {
+                Some(location) => Some(ElfStringTable::parse(Self::slice_field(
+                    buffer,
+                    location.clone(),
+                    Self::shstrtab_field(header, location),
+                )?)?),
+                None => None,
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            buffer,
+            header,
+            program_headers,
+            section_headers,
+            section_names,
+        })
+    }
+
+    /// Parses as much of a corrupted file as possible, calling `warn` with
+    /// each problem rather than failing on the first one. Only a bad ELF
+    /// header is an error.
+    ///
+    /// Header tables and the section name table running past the end of the
+    /// file are cut short to the entries that fit, a section name table
+    /// without NUL termination is accepted, and unusable tables are left
+    /// out, so that a bogus `e_shoff` leaves just the segments. Header tables
+    /// with an unexpected entry size or alignment are read entry by entry,
+    /// with a warning for each that can't be read, which reads as a null
+    /// entry. Entries in the tables are not checked, see
+    /// [`validate::validate_with`] for that.
+    pub fn parse_lenient_with(
+        buffer: &'buf [u8],
+        mut warn: impl FnMut(ElfError),
+    ) -> Result<Self, ElfError> {
+        let header = ElfHeader::parse(buffer)?;
+
+        let program_headers = header
+            .program_headers_location()
+            .and_then(|location| {
+                let field = Self::phoff_field(header);
+                Self::slice_lenient(buffer, location, header.e_phentsize(), field, &mut warn)
+            })
+            .and_then(|bytes| {
+                ElfProgramHeaders::parse(header, bytes)
+                    .map_err(&mut warn)
+                    .ok()
+                    .or_else(|| ElfProgramHeaders::parse_strided(header, bytes))
+            })
+            .inspect(|headers| {
+                for index in headers.unreadable_entries() {
+                    warn(ElfError::UnreadableEntry(ElfStructure::ProgramHeader(
+                        index,
+                    )));
+                }
+            });
+
+        let section_headers = header
+            .section_headers_location()
+            .and_then(|location| {
+                let field = Self::shoff_field(header);
+                Self::slice_lenient(buffer, location, header.e_shentsize(), field, &mut warn)
+            })
+            .and_then(|bytes| {
+                ElfSectionHeaders::parse(header, bytes)
+                    .map_err(&mut warn)
+                    .ok()
+                    .or_else(|| ElfSectionHeaders::parse_strided(header, bytes))
+            })
+            .inspect(|headers| {
+                for index in headers.unreadable_entries() {
+                    warn(ElfError::UnreadableEntry(ElfStructure::SectionHeader(
+                        index,
+                    )));
+                }
+            });
+
+        let section_names = section_headers
+            .and_then(|section_headers| {
+                section_headers
+                    .string_table_location(header)
+                    .map_err(&mut warn)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|location| {
+                let field = Self::shstrtab_field(header, location.clone());
+                Self::slice_lenient(buffer, location, 1, field, &mut warn)
+            })
+            .map(|bytes| {
+                ElfStringTable::parse(bytes).unwrap_or_else(|error| {
+                    warn(error);
+                    ElfStringTable::parse_lenient(bytes)
+                })
+            });
+
+        Ok(Self {
+            buffer,
+            header,
+            program_headers,
+            section_headers,
+            section_names,
+        })
+    }
+
+    /// Parses as much of a corrupted file as possible, returning the
+    /// problems found along with it. See [`Self::parse_lenient_with`].
+    #[cfg(feature = "std")]
+    pub fn parse_lenient(buffer: &'buf [u8]) -> Result<(Self, Vec<ElfError>), ElfError> {
+        let mut warnings = Vec::new();
+        let file = Self::parse_lenient_with(buffer, |warning| warnings.push(warning))?;
+        Ok((file, warnings))
+    }
+
+    fn slice(buffer: &'buf [u8], location: Range<u64>) -> Result<&'buf [u8], ElfError> {
+        buffer
+            .get(location.clone().try_into_usize()?)
+            .ok_or(ElfError::DataOutOfBounds(location))
+    }
+
+    /// Like [`Self::slice`], but blames the header field that gave the
+    /// location if it's outside the file.
+    fn slice_field(
+        buffer: &'buf [u8],
+        location: Range<u64>,
+        (structure, field, value): ElfField,
+    ) -> Result<&'buf [u8], ElfError> {
+        Self::slice(buffer, location).map_err(|_| {
+            let size = u64::try_from(buffer.len()).unwrap_or(u64::MAX);
+            ElfError::InvalidField(
+                structure,
+                field,
+                value,
+                ElfFieldProblem::BeyondEndOfFile(size),
+            )
+        })
+    }
+
+    fn phoff_field(header: ElfHeader) -> ElfField {
+        let phoff = header.e_phoff().map_or(0, NonZeroU64::get);
+        (ElfStructure::ElfHeader, "e_phoff", phoff)
+    }
+
+    fn shoff_field(header: ElfHeader) -> ElfField {
+        let shoff = header.e_shoff().map_or(0, NonZeroU64::get);
+        (ElfStructure::ElfHeader, "e_shoff", shoff)
+    }
+
+    fn shstrtab_field(header: ElfHeader, location: Range<u64>) -> ElfField {
+        let index = header.e_shstrndx().map_or(0, NonZeroU16::get);
+        (
+            ElfStructure::SectionHeader(index),
+            "sh_offset",
+            location.start,
+        )
+    }
+
+    /// Like [`Self::slice_field`], but cuts a table running past the end of
+    /// the file down to the whole entries of `entry_size` that fit.
+    fn slice_lenient(
+        buffer: &'buf [u8],
+        location: Range<u64>,
+        entry_size: u16,
+        field: ElfField,
+        warn: &mut impl FnMut(ElfError),
+    ) -> Option<&'buf [u8]> {
+        Self::slice_field(buffer, location.clone(), field)
+            .map_err(&mut *warn)
+            .ok()
+            .or_else(|| {
+                let bytes = buffer.get(usize::try_from(location.start).ok()?..)?;
+                let length = bytes
+                    .len()
+                    .checked_rem(usize::from(entry_size))
+                    .and_then(|partial| bytes.len().checked_sub(partial))?;
+                bytes.get(..length)
+            })
+    }
+
+    pub fn buffer(&self) -> &'buf [u8] {
+        self.buffer
+    }
+
+    pub fn header(&self) -> ElfHeader<'buf> {
+        self.header
+    }
+
+    pub fn program_headers(&self) -> Option<ElfProgramHeaders<'buf>> {
+        self.program_headers
+    }
+
+    pub fn section_headers(&self) -> Option<ElfSectionHeaders<'buf>> {
+        self.section_headers
+    }
+
+    pub fn section_names(&self) -> Option<ElfStringTable<'buf>> {
+        self.section_names
+    }
+
+    /// Iterates over the section headers, which is empty if there is no
+    /// section header table.
+    pub fn sections(&self) -> impl Iterator<Item = ElfSectionHeader<'buf>> {
+        self.section_headers.into_iter().flatten()
+    }
+
+    /// Iterates over the program headers, which is empty if there is no
+    /// program header table.
+    pub fn segments(&self) -> impl Iterator<Item = ElfProgramHeader<'buf>> {
+        self.program_headers.into_iter().flatten()
+    }
+
+    /// The index of a section header from this file's section header table.
+    pub fn section_index(&self, section: ElfSectionHeader) -> Option<u16> {
+        let index = self.sections().position(|other| match (section, other) {
+            (ElfSectionHeader::Elf32(a), ElfSectionHeader::Elf32(b)) => ptr::eq(a, b),
+            (ElfSectionHeader::Elf64(a), ElfSectionHeader::Elf64(b)) => ptr::eq(a, b),
+            _ => false,
+        })?;
+        u16::try_from(index).ok()
+    }
+
+    /// The index of a program header from this file's program header table.
+    pub fn segment_index(&self, segment: ElfProgramHeader) -> Option<u16> {
+        let index = self.segments().position(|other| match (segment, other) {
+            (ElfProgramHeader::Elf32(a), ElfProgramHeader::Elf32(b)) => ptr::eq(a, b),
+            (ElfProgramHeader::Elf64(a), ElfProgramHeader::Elf64(b)) => ptr::eq(a, b),
+            _ => false,
+        })?;
+        u16::try_from(index).ok()
+    }
+
+    /// Turns `error` into [`ElfError::InvalidField`] blaming `field` of
+    /// `section`, if the section is from this file.
+    fn blame_section(
+        &self,
+        section: ElfSectionHeader,
+        field: &'static str,
+        value: u64,
+        problem: ElfFieldProblem,
+        error: ElfError,
+    ) -> ElfError {
+        match self.section_index(section) {
+            Some(index) => {
+                ElfError::InvalidField(ElfStructure::SectionHeader(index), field, value, problem)
+            }
+            None => error,
+        }
+    }
+
+    /// Turns a failure to read `section` as a table of `entry_size` byte
+    /// entries into [`ElfError::InvalidField`], blaming `sh_entsize` if it
+    /// isn't the size of the entries, or `sh_size` if it isn't a multiple of
+    /// it.
+    fn blame_table(
+        &self,
+        section: ElfSectionHeader,
+        entry_size: usize,
+        error: ElfError,
+    ) -> ElfError {
+        if !matches!(error, ElfError::ZeroCopyError) {
+            return error;
+        }
+
+        let entry_size = u64::try_from(entry_size).unwrap_or(u64::MAX);
+        let entsize = section.sh_entsize().map_or(0, NonZeroU64::get);
+        let size = section.sh_size();
+        if entsize != 0 && entsize != entry_size {
+            self.blame_section(
+                section,
+                "sh_entsize",
+                entsize,
+                ElfFieldProblem::NotEntrySize(entry_size),
+                error,
+            )
+        } else if size.checked_rem(entry_size).is_some_and(|rest| rest != 0) {
+            self.blame_section(
+                section,
+                "sh_size",
+                size,
+                ElfFieldProblem::NotMultipleOfEntrySize(entry_size),
+                error,
+            )
+        } else {
+            error
+        }
+    }
+
+    fn file_size(&self) -> u64 {
+        u64::try_from(self.buffer.len()).unwrap_or(u64::MAX)
+    }
+
+    pub fn section_name(&self, section: ElfSectionHeader) -> Result<&'buf str, ElfError> {
+        let names = self.section_names.ok_or(ElfError::NoSectionNames)?;
+        names.section_name(section).map_err(|error| match error {
+            ElfError::StringTableOutOfBounds(_) => self.blame_section(
+                section,
+                "sh_name",
+                u64::from(section.sh_name()),
+                ElfFieldProblem::OutsideStringTable(names.size()),
+                error,
+            ),
+            error => error,
+        })
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<ElfSectionHeader<'buf>> {
+        self.section_headers?
+            .find_by_name(self.section_names?, name)
+    }
+
+    /// Finds the first section of the given type.
+    pub fn section_by_type(&self, section_type: ElfSectionType) -> Option<ElfSectionHeader<'buf>> {
+        self.sections()
+            .find(|section| section.sh_type() == section_type)
+    }
+
+    /// The contents of a section. `SHT_NOBITS` sections occupy no space in
+    /// the file, and so are empty.
+    pub fn section_data(&self, section: ElfSectionHeader) -> Result<&'buf [u8], ElfError> {
+        if section.sh_type() == ElfSectionType::SHT_NOBITS {
+            return Ok(&[]);
+        }
+
+        Self::slice(self.buffer, section.location()).map_err(|error| {
+            self.blame_section(
+                section,
+                "sh_offset",
+                section.sh_offset(),
+                ElfFieldProblem::BeyondEndOfFile(self.file_size()),
+                error,
+            )
+        })
+    }
+
+    /// The contents of a segment in the file.
+    pub fn segment_data(&self, segment: ElfProgramHeader) -> Result<&'buf [u8], ElfError> {
+        let Some(location) = segment.file_location() else {
+            return Ok(&[]);
+        };
+
+        match self.segment_index(segment) {
+            Some(index) => Self::slice_field(
+                self.buffer,
+                location,
+                (
+                    ElfStructure::ProgramHeader(index),
+                    "p_offset",
+                    segment.p_offset(),
+                ),
+            ),
+            None => Self::slice(self.buffer, location),
+        }
+    }
+
+    /// Like [`ElfProgramHeader::memory_location`], but an error names the
+    /// program header and the values involved.
+    pub fn segment_memory_location(
+        &self,
+        segment: ElfProgramHeader,
+    ) -> Result<Option<Range<u64>>, ElfError> {
+        segment.memory_location().map_err(|error| {
+            let Some(index) = self.segment_index(segment) else {
+                return error;
+            };
+            let structure = ElfStructure::ProgramHeader(index);
+            let memsz = segment.p_memsz().map_or(0, NonZeroU64::get);
+            let filesz = segment.p_filesz().map_or(0, NonZeroU64::get);
+
+            match error {
+                ElfError::FileSzLargerThanMemSz => ElfError::InvalidField(
+                    structure,
+                    "p_filesz",
+                    filesz,
+                    ElfFieldProblem::LargerThanMemSz(memsz),
+                ),
+                ElfError::IncongurentSegmentAlignment => ElfError::InvalidField(
+                    structure,
+                    "p_vaddr",
+                    segment.p_vaddr(),
+                    ElfFieldProblem::IncongruentWithOffset(segment.p_offset(), segment.p_align()),
+                ),
+                error => error,
+            }
+        })
+    }
+
+    /// The section that `section`'s `sh_link` refers to.
+    pub fn linked_section(
+        &self,
+        section: ElfSectionHeader,
+    ) -> Result<ElfSectionHeader<'buf>, ElfError> {
+        let link = section.sh_link();
+        u16::try_from(link)
+            .ok()
+            .and_then(|link| self.section_headers?.get(link))
+            .ok_or_else(|| {
+                let count = self.section_headers.map_or(0, |headers| headers.len());
+                self.blame_section(
+                    section,
+                    "sh_link",
+                    u64::from(link),
+                    ElfFieldProblem::OutsideSectionTable(u64::try_from(count).unwrap_or(u64::MAX)),
+                    ElfError::SectionLinkOutOfBounds(link),
+                )
+            })
+    }
+
+    /// The string table that `section`'s `sh_link` refers to, as used by
+    /// symbol tables, dynamic sections and version sections.
+    pub fn linked_string_table(
+        &self,
+        section: ElfSectionHeader,
+    ) -> Result<ElfStringTable<'buf>, ElfError> {
+        ElfStringTable::parse(self.section_data(self.linked_section(section)?)?)
+    }
+
+    pub fn symbol_table(
+        &self,
+        section: ElfSectionHeader,
+    ) -> Result<ElfSymbolTable<'buf>, ElfError> {
+        let entry_size = match self.header {
+            ElfHeader::Elf32(_) => size_of::<Elf32Symbol>(),
+            ElfHeader::Elf64(_) => size_of::<Elf64Symbol>(),
+        };
+        ElfSymbolTable::parse(self.header, self.section_data(section)?)
+            .map_err(|error| self.blame_table(section, entry_size, error))
+    }
+
+    /// The relocations of a `SHT_REL`, `SHT_RELA`, `SHT_RELR` or Android
+    /// packed relocation section.
+    pub fn relocation_table(
+        &self,
+        section: ElfSectionHeader,
+    ) -> Result<ElfRelocationTable<'buf>, ElfError> {
+        let section_type = section.sh_type();
+        let entry_size = match (self.header, section_type) {
+            (ElfHeader::Elf32(_), ElfSectionType::SHT_REL) => size_of::<Elf32Rel>(),
+            (ElfHeader::Elf32(_), ElfSectionType::SHT_RELA) => size_of::<Elf32Rela>(),
+            (ElfHeader::Elf64(_), ElfSectionType::SHT_REL) => size_of::<Elf64Rel>(),
+            (ElfHeader::Elf64(_), ElfSectionType::SHT_RELA) => size_of::<Elf64Rela>(),
+            (ElfHeader::Elf32(_), _) => size_of::<u32>(),
+            (ElfHeader::Elf64(_), _) => size_of::<u64>(),
+        };
+        ElfRelocationTable::parse(self.header, section_type, self.section_data(section)?)
+            .map_err(|error| self.blame_table(section, entry_size, error))
+    }
+
+    /// Translates a virtual address into a file offset, using the `PT_LOAD`
+    /// segments.
+    pub fn virtual_address_to_offset(&self, address: u64) -> Option<u64> {
+        self.segments()
+            .filter(|segment| segment.p_type() == ElfSegmentType::PT_LOAD)
+            .find_map(|segment| {
+                let delta = address.checked_sub(segment.p_vaddr())?;
+                if delta >= segment.p_filesz()?.get() {
+                    return None;
+                }
+                segment.p_offset().checked_add(delta)
+            })
+    }
+
+    /// The dynamic array, from the `PT_DYNAMIC` segment or otherwise the
+    /// `SHT_DYNAMIC` section.
+    pub fn dynamic_table(&self) -> Result<Option<ElfDynamicTable<'buf>>, ElfError> {
+        if let Some(segment) = self
+            .program_headers
+            .and_then(|headers| headers.find_by_type(ElfSegmentType::PT_DYNAMIC))
+        {
+            return Ok(Some(ElfDynamicTable::parse(
+                self.header,
+                self.segment_data(segment)?,
+            )?));
+        }
+
+        let Some(section) = self.section_by_type(ElfSectionType::SHT_DYNAMIC) else {
+            return Ok(None);
+        };
+        let entry_size = match self.header {
+            ElfHeader::Elf32(_) => size_of::<Elf32Dynamic>(),
+            ElfHeader::Elf64(_) => size_of::<Elf64Dynamic>(),
+        };
+        ElfDynamicTable::parse(self.header, self.section_data(section)?)
+            .map(Some)
+            .map_err(|error| self.blame_table(section, entry_size, error))
+    }
+
+    /// The string table used by the dynamic array, located by `DT_STRTAB`
+    /// and `DT_STRSZ`, or otherwise by the `SHT_DYNAMIC` section's `sh_link`.
+    pub fn dynamic_string_table(&self) -> Result<Option<ElfStringTable<'buf>>, ElfError> {
+        let Some(dynamic) = self.dynamic_table()? else {
+            return Ok(None);
+        };
+
+        let address = dynamic.value(ElfDynamicTag::DT_STRTAB);
+        let size = dynamic.value(ElfDynamicTag::DT_STRSZ);
+        if let (Some(address), Some(size)) = (address, size) {
+            if let Some(start) = self.virtual_address_to_offset(address) {
+                let location = Range {
+                    start,
+                    end: start.saturating_add(size),
+                };
+                return Ok(Some(ElfStringTable::parse(Self::slice(
+                    self.buffer,
+                    location,
+                )?)?));
+            }
+        }
+
+        match self.section_by_type(ElfSectionType::SHT_DYNAMIC) {
+            Some(section) => Ok(Some(self.linked_string_table(section)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The path of the program interpreter, from the `PT_INTERP` segment.
+    pub fn interpreter(&self) -> Result<Option<&'buf str>, ElfError> {
+        let Some(segment) = self
+            .program_headers
+            .and_then(|headers| headers.find_by_type(ElfSegmentType::PT_INTERP))
+        else {
+            return Ok(None);
+        };
+
+        let data = self.segment_data(segment)?;
+        Ok(Some(CStr::from_bytes_until_nul(data)?.to_str()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -410,4 +1282,170 @@ mod tests {
             ElfHeader::parse(&buffer).is_err_and(|e| matches!(e, ElfError::InvalidDataEncoding(_)))
         );
     }
+
+    #[test]
+    fn parses_more_than_1024_sections() {
+        // An ELF64 header followed by 1111 empty section headers, whose table
+        // is larger than a u16 can hold.
+        let mut buffer = [0u64; 8 + 1111 * 8];
+        buffer[0] = u64::from_le_bytes(*b"\x7fELF\x02\x01\x01\x00");
+        let bytes = zerocopy::AsBytes::as_bytes_mut(&mut buffer);
+
+        let mut header = mutable::ElfHeaderMut::parse(bytes).expect("valid header");
+        assert!(header.set_e_shoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_shentsize(64);
+        header.set_e_shnum(NonZeroU16::new(1111));
+
+        let file = ElfFile::parse(bytes).expect("the section header table fits");
+        assert_eq!(file.sections().count(), 1111);
+    }
+
+    #[test]
+    fn blames_symbol_table_size() {
+        let mut buffer = testdata::HARDENING_X86_64;
+        let file = ElfFile::parse(&buffer.0).expect("valid file");
+        let symtab = file
+            .section_by_type(ElfSectionType::SHT_SYMTAB)
+            .expect("symbol table");
+        let index = file.section_index(symtab).expect("from the file");
+
+        let mut section =
+            mutable::ElfSectionHeaderMut::from_file(&mut buffer.0, index).expect("in bounds");
+        assert!(section.set_sh_size(0x77).is_ok());
+        let file = ElfFile::parse(&buffer.0).expect("valid file");
+        let symtab = file
+            .section_headers()
+            .and_then(|headers| headers.get(index));
+        assert!(matches!(
+            symtab.map(|symtab| file.symbol_table(symtab)),
+            Some(Err(ElfError::InvalidField(
+                ElfStructure::SectionHeader(i),
+                "sh_size",
+                0x77,
+                ElfFieldProblem::NotMultipleOfEntrySize(0x18)
+            ))) if i == index
+        ));
+
+        let mut section =
+            mutable::ElfSectionHeaderMut::from_file(&mut buffer.0, index).expect("in bounds");
+        assert!(section.set_sh_entsize(NonZeroU64::new(0x20)).is_ok());
+        let file = ElfFile::parse(&buffer.0).expect("valid file");
+        let symtab = file
+            .section_headers()
+            .and_then(|headers| headers.get(index));
+        assert!(matches!(
+            symtab.map(|symtab| file.symbol_table(symtab)),
+            Some(Err(ElfError::InvalidField(
+                ElfStructure::SectionHeader(_),
+                "sh_entsize",
+                0x20,
+                ElfFieldProblem::NotEntrySize(0x18)
+            )))
+        ));
+    }
+
+    #[test]
+    fn lenient_parse_recovers_from_bad_tables() {
+        // An ELF64 header followed by one and a half program headers.
+        let mut buffer = [0u64; 18];
+        buffer[0] = u64::from_le_bytes(*b"\x7fELF\x02\x01\x01\x00");
+        let bytes = zerocopy::AsBytes::as_bytes_mut(&mut buffer);
+
+        let mut header = mutable::ElfHeaderMut::parse(bytes).expect("valid header");
+        assert!(header.set_e_phoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_phentsize(56);
+        header.set_e_phnum(NonZeroU16::new(3));
+        assert!(header.set_e_shoff(NonZeroU64::new(0x1_0000)).is_ok());
+        header.set_e_shentsize(64);
+        header.set_e_shnum(NonZeroU16::new(4));
+
+        assert!(ElfFile::parse(bytes).is_err());
+
+        let mut warnings = 0;
+        let file = ElfFile::parse_lenient_with(bytes, |error| {
+            assert!(matches!(
+                error,
+                ElfError::InvalidField(ElfStructure::ElfHeader, _, _, _)
+            ));
+            warnings += 1;
+        })
+        .expect("the ELF header is valid");
+
+        assert_eq!(warnings, 2);
+        assert_eq!(file.segments().count(), 1);
+        assert!(file.section_headers().is_none());
+    }
+
+    #[test]
+    fn lenient_parse_reads_tables_with_other_entry_sizes() {
+        // An ELF64 header followed by three section headers, 0x48 bytes
+        // apart.
+        let mut buffer = [0u64; 8 + 3 * 9];
+        buffer[0] = u64::from_le_bytes(*b"\x7fELF\x02\x01\x01\x00");
+        let bytes = zerocopy::AsBytes::as_bytes_mut(&mut buffer);
+
+        let mut header = mutable::ElfHeaderMut::parse(bytes).expect("valid header");
+        assert!(header.set_e_shoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_shentsize(0x48);
+        header.set_e_shnum(NonZeroU16::new(3));
+
+        let types = [
+            ElfSectionType::SHT_PROGBITS,
+            ElfSectionType::SHT_SYMTAB,
+            ElfSectionType::SHT_STRTAB,
+        ];
+        let set_types = |bytes: &mut [u8], stride: usize| {
+            for (index, section_type) in (0..).zip(types) {
+                let offset = 64usize.saturating_add(stride.saturating_mul(index));
+                let field = bytes
+                    .get_mut(offset.saturating_add(4)..offset.saturating_add(8))
+                    .expect("in bounds");
+                field.copy_from_slice(&section_type.0.to_le_bytes());
+            }
+        };
+        set_types(bytes, 0x48);
+
+        assert!(ElfFile::parse(bytes).is_err());
+
+        let mut warnings = 0;
+        let file = ElfFile::parse_lenient_with(bytes, |error| {
+            assert!(matches!(error, ElfError::UnexpectedEntrySize(0x48)));
+            warnings += 1;
+        })
+        .expect("the ELF header is valid");
+        assert_eq!(warnings, 1);
+        assert!(file.sections().map(|section| section.sh_type()).eq(types));
+
+        // With entries 0x44 bytes apart, the second is misaligned, so reads
+        // as a null section, but the third is still found at its index.
+        bytes.fill(0);
+        bytes
+            .get_mut(..8)
+            .expect("in bounds")
+            .copy_from_slice(b"\x7fELF\x02\x01\x01\x00");
+        let mut header = mutable::ElfHeaderMut::parse(bytes).expect("valid header");
+        assert!(header.set_e_shoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_shentsize(0x44);
+        header.set_e_shnum(NonZeroU16::new(3));
+        set_types(bytes, 0x44);
+
+        let mut unreadable = None;
+        let file = ElfFile::parse_lenient_with(bytes, |error| {
+            if let ElfError::UnreadableEntry(structure) = error {
+                assert!(unreadable.replace(structure).is_none());
+            }
+        })
+        .expect("the ELF header is valid");
+        assert_eq!(unreadable, Some(ElfStructure::SectionHeader(1)));
+        let sections = file.section_headers().expect("a section table");
+        assert_eq!(sections.len(), 3);
+        assert_eq!(
+            sections.get(1).map(|section| section.sh_type()),
+            Some(ElfSectionType::SHT_NULL)
+        );
+        assert_eq!(
+            sections.get(2).map(|section| section.sh_type()),
+            Some(ElfSectionType::SHT_STRTAB)
+        );
+    }
 }
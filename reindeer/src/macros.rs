@@ -28,6 +28,52 @@ macro_rules! enum_getter {
     };
 }
 
+/// Generates a class-agnostic setter for the mutable header views, taking
+/// the same type as the `enum_getter!` getter. Fields that are narrower on
+/// ELF32 return `ElfError::ValueOutOfRange` if the value doesn't fit.
+macro_rules! enum_setter {
+    ($setter:ident, $property:ident, Option<NonZeroU64>) => {
+        #[inline]
+        pub fn $setter(&mut self, value: Option<NonZeroU64>) -> Result<(), ElfError> {
+            match self {
+                Self::Elf32(header) => {
+                    header.$property =
+                        value.map(NonZeroU32::try_from).transpose().map_err(|_| {
+                            ElfError::ValueOutOfRange(
+                                stringify!($property),
+                                value.map_or(0, NonZeroU64::get),
+                            )
+                        })?
+                }
+                Self::Elf64(header) => header.$property = value,
+            }
+            Ok(())
+        }
+    };
+    ($setter:ident, $property:ident, u64) => {
+        #[inline]
+        pub fn $setter(&mut self, value: u64) -> Result<(), ElfError> {
+            match self {
+                Self::Elf32(header) => {
+                    header.$property = u32::try_from(value)
+                        .map_err(|_| ElfError::ValueOutOfRange(stringify!($property), value))?
+                }
+                Self::Elf64(header) => header.$property = value,
+            }
+            Ok(())
+        }
+    };
+    ($setter:ident, $property:ident, $type:ty) => {
+        #[inline]
+        pub fn $setter(&mut self, value: $type) {
+            match self {
+                Self::Elf32(header) => header.$property = value,
+                Self::Elf64(header) => header.$property = value,
+            }
+        }
+    };
+}
+
 macro_rules! declare_constants {
     ($typ:ty, {$($name:ident = $value:literal),* $(,)?}) => {
         impl $typ {
@@ -47,5 +93,63 @@ macro_rules! declare_constants {
     };
 }
 
+macro_rules! declare_flags {
+    ($typ:ty, {$($name:ident = $value:literal),* $(,)?}) => {
+        declare_constants!($typ, {$($name = $value),*});
+
+        impl $typ {
+            /// Returns true if every bit set in `flag` is also set in `self`.
+            pub fn contains(self, flag: Self) -> bool {
+                self.0 & flag.0 == flag.0
+            }
+        }
+    };
+}
+
+/// Declares relocation types like `declare_constants!`, along with the
+/// width and kind of each. A width of `word` is 32 or 64 bits, following the
+/// class of the file.
+macro_rules! declare_relocation_types {
+    ($typ:ty, {$($name:ident = $value:literal => ($width:tt, $kind:ident)),* $(,)?}) => {
+        declare_constants!($typ, {$($name = $value),*});
+
+        impl $typ {
+            /// The size in bits of the field the relocation writes, in a
+            /// 64-bit file if `is_64` is true.
+            pub fn width(self, is_64: bool) -> Option<u8> {
+                let word = if is_64 { 64 } else { 32 };
+                match self {
+                    $(
+                        Self::$name => Some(relocation_width!($width, word)),
+                    )*
+                    Self(_) => None,
+                }
+            }
+
+            pub fn kind(self) -> Option<ElfRelocationKind> {
+                match self {
+                    $(
+                        Self::$name => Some(ElfRelocationKind::$kind),
+                    )*
+                    Self(_) => None,
+                }
+            }
+        }
+    };
+}
+
+macro_rules! relocation_width {
+    (word, $word:ident) => {
+        $word
+    };
+    ($width:literal, $word:ident) => {
+        $width
+    };
+}
+
 pub(crate) use declare_constants;
+pub(crate) use declare_flags;
+pub(crate) use declare_relocation_types;
 pub(crate) use enum_getter;
+pub(crate) use enum_setter;
+pub(crate) use relocation_width;
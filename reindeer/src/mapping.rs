@@ -0,0 +1,194 @@
+//! Which sections each segment contains, following the rules readelf uses
+//! for its "Section to Segment mapping".
+
+use crate::{elf_aux_structures::*, ElfFile, ElfProgramHeader, ElfSectionHeader};
+
+/// Whether `start..start + size` lies within `base..base + limit`. An empty
+/// range must start before the end, so that it isn't counted as part of the
+/// next segment too.
+fn within(start: u64, size: u64, base: u64, limit: u64) -> bool {
+    start
+        .checked_sub(base)
+        .is_some_and(|delta| delta < limit && delta.saturating_add(size) <= limit)
+}
+
+/// Whether `value` lies strictly inside `base..base + limit`, not at its
+/// start.
+fn strictly_within(value: u64, base: u64, limit: u64) -> bool {
+    value
+        .checked_sub(base)
+        .is_some_and(|delta| delta > 0 && delta < limit)
+}
+
+fn is_tbss(section: ElfSectionHeader) -> bool {
+    ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_TLS)
+        && section.sh_type() == ElfSectionType::SHT_NOBITS
+}
+
+/// Whether the bytes of `section` in the file lie within those of
+/// `segment`. `SHT_NOBITS` sections have no bytes in the file, so are never
+/// contained.
+pub fn section_in_file_image(section: ElfSectionHeader, segment: ElfProgramHeader) -> bool {
+    let p_filesz = segment.p_filesz().map_or(0, |size| size.get());
+
+    section.sh_type() != ElfSectionType::SHT_NOBITS
+        && within(
+            section.sh_offset(),
+            section.sh_size(),
+            segment.p_offset(),
+            p_filesz,
+        )
+}
+
+/// Whether the memory of `section` lies within that of `segment`. Sections
+/// without `SHF_ALLOC` are not loaded, so are never contained, and neither is
+/// `.tbss` outside of `PT_TLS`, as each thread gets its own copy of it.
+pub fn section_in_memory_image(section: ElfSectionHeader, segment: ElfProgramHeader) -> bool {
+    let is_alloc = ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_ALLOC);
+    let p_memsz = segment.p_memsz().map_or(0, |size| size.get());
+    let sh_addr = section.sh_addr().map_or(0, |addr| addr.get());
+
+    is_alloc
+        && (!is_tbss(section) || segment.p_type() == ElfSegmentType::PT_TLS)
+        && within(sh_addr, section.sh_size(), segment.p_vaddr(), p_memsz)
+}
+
+/// Whether readelf would list `section` as part of `segment`, following
+/// `ELF_SECTION_IN_SEGMENT_STRICT` from binutils' `include/elf/internal.h`.
+pub fn section_in_segment(section: ElfSectionHeader, segment: ElfProgramHeader) -> bool {
+    let flags = ElfSectionFlags(section.sh_flags());
+    let is_tls = flags.contains(ElfSectionFlags::SHF_TLS);
+    let is_alloc = flags.contains(ElfSectionFlags::SHF_ALLOC);
+    let is_nobits = section.sh_type() == ElfSectionType::SHT_NOBITS;
+    let p_type = segment.p_type();
+
+    // .tbss occupies no space in segments other than PT_TLS.
+    if is_tbss(section) && p_type != ElfSegmentType::PT_TLS {
+        return false;
+    }
+
+    let section_size = section.sh_size();
+    let p_filesz = segment.p_filesz().map_or(0, |size| size.get());
+    let p_memsz = segment.p_memsz().map_or(0, |size| size.get());
+    let sh_addr = section.sh_addr().map_or(0, |addr| addr.get());
+
+    // Only PT_LOAD, PT_GNU_RELRO and PT_TLS segments can contain SHF_TLS
+    // sections, PT_TLS segments only contain SHF_TLS sections and PT_PHDR
+    // contains no sections at all.
+    let type_ok = if is_tls {
+        matches!(
+            p_type,
+            ElfSegmentType::PT_TLS | ElfSegmentType::PT_GNU_RELRO | ElfSegmentType::PT_LOAD
+        )
+    } else {
+        p_type != ElfSegmentType::PT_TLS && p_type != ElfSegmentType::PT_PHDR
+    };
+
+    // PT_LOAD and similar segments only have SHF_ALLOC sections.
+    let alloc_ok = is_alloc
+        || !matches!(
+            p_type,
+            ElfSegmentType::PT_LOAD
+                | ElfSegmentType::PT_DYNAMIC
+                | ElfSegmentType::PT_GNU_EH_FRAME
+                | ElfSegmentType::PT_GNU_STACK
+                | ElfSegmentType::PT_GNU_RELRO
+                | ElfSegmentType::PT_GNU_SFRAME
+        );
+
+    // Any section besides one of type SHT_NOBITS must have file offsets
+    // within the segment.
+    let offset_ok = is_nobits
+        || within(
+            section.sh_offset(),
+            section_size,
+            segment.p_offset(),
+            p_filesz,
+        );
+
+    // SHF_ALLOC sections must have addresses within the segment.
+    let address_ok = !is_alloc || within(sh_addr, section_size, segment.p_vaddr(), p_memsz);
+
+    // No zero size sections at the start or end of PT_DYNAMIC nor PT_NOTE.
+    let empty_ok = (p_type != ElfSegmentType::PT_DYNAMIC && p_type != ElfSegmentType::PT_NOTE)
+        || section_size != 0
+        || p_memsz == 0
+        || ((is_nobits || strictly_within(section.sh_offset(), segment.p_offset(), p_filesz))
+            && (!is_alloc || strictly_within(sh_addr, segment.p_vaddr(), p_memsz)));
+
+    type_ok && alloc_ok && offset_ok && address_ok && empty_ok
+}
+
+/// The sections readelf lists as part of `segment`, with their indices. The
+/// null section at index 0 is never included.
+pub fn segment_sections<'buf>(
+    file: &ElfFile<'buf>,
+    segment: ElfProgramHeader<'buf>,
+) -> impl Iterator<Item = (usize, ElfSectionHeader<'buf>)> {
+    file.sections()
+        .enumerate()
+        .skip(1)
+        .filter(move |&(_, section)| section_in_segment(section, segment))
+}
+
+/// The `SHF_ALLOC` sections, with their indices, that are not part of any
+/// segment. In a file without program headers, such as a relocatable
+/// object, this is every allocatable section.
+pub fn unmapped_sections<'buf>(
+    file: &ElfFile<'buf>,
+) -> impl Iterator<Item = (usize, ElfSectionHeader<'buf>)> {
+    let segments = file.program_headers();
+
+    file.sections()
+        .enumerate()
+        .skip(1)
+        .filter(move |&(_, section)| {
+            ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_ALLOC)
+                && !segments
+                    .into_iter()
+                    .flatten()
+                    .any(|segment| section_in_segment(section, segment))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU64;
+
+    use zerocopy::FromZeroes;
+
+    use super::*;
+    use crate::elf_structures::{Elf64ProgramHeader, Elf64SectionHeader};
+
+    fn segment(p_type: ElfSegmentType, p_memsz: u64) -> Elf64ProgramHeader {
+        let mut segment = Elf64ProgramHeader::new_zeroed();
+        segment.p_type = p_type;
+        segment.p_offset = 0x1000;
+        segment.p_vaddr = 0x1000;
+        segment.p_filesz = NonZeroU64::new(0x10);
+        segment.p_memsz = NonZeroU64::new(p_memsz);
+        segment
+    }
+
+    #[test]
+    fn tbss_is_only_in_pt_tls() {
+        let mut tbss = Elf64SectionHeader::new_zeroed();
+        tbss.sh_type = ElfSectionType::SHT_NOBITS;
+        tbss.sh_flags = ElfSectionFlags::SHF_ALLOC.0 | ElfSectionFlags::SHF_TLS.0;
+        tbss.sh_addr = NonZeroU64::new(0x1010);
+        tbss.sh_offset = 0x1010;
+        tbss.sh_size = 0x8;
+        let tbss = ElfSectionHeader::Elf64(&tbss);
+
+        let load = segment(ElfSegmentType::PT_LOAD, 0x100);
+        let load = ElfProgramHeader::Elf64(&load);
+        let tls = segment(ElfSegmentType::PT_TLS, 0x18);
+        let tls = ElfProgramHeader::Elf64(&tls);
+
+        assert!(!section_in_segment(tbss, load));
+        assert!(!section_in_memory_image(tbss, load));
+        assert!(section_in_segment(tbss, tls));
+        assert!(section_in_memory_image(tbss, tls));
+        assert!(!section_in_file_image(tbss, tls));
+    }
+}
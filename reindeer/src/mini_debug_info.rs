@@ -0,0 +1,91 @@
+//! MiniDebugInfo, the xz-compressed ELF file that Fedora and other
+//! distributions embed in the `.gnu_debugdata` section of stripped files, so
+//! that backtraces can name local functions without the debug package.
+//!
+//! The embedded file has a `.symtab` with the function symbols missing from
+//! `.dynsym`, and `SHT_NOBITS` copies of the other sections, at the same
+//! addresses as in the stripped file. Decompressing it needs the `xz`
+//! feature.
+
+use std::vec::Vec;
+
+use crate::{compression::decompress_xz, errors::ElfError, ElfFile};
+
+const GNU_DEBUGDATA: &str = ".gnu_debugdata";
+
+/// How large [`ElfMiniDebugInfo::from_file`] lets the embedded file be. Its
+/// symbol table may well be larger than the stripped file it describes, so
+/// this is generous, and only guards against xz bombs.
+pub const DEFAULT_SIZE_LIMIT: usize = 256 << 20;
+
+/// The decompressed contents of a `.gnu_debugdata` section.
+#[derive(Debug, Clone)]
+pub struct ElfMiniDebugInfo {
+    buffer: Vec<u8>,
+}
+
+impl ElfMiniDebugInfo {
+    /// Decompresses the `.gnu_debugdata` section, if the file has one, of
+    /// up to [`DEFAULT_SIZE_LIMIT`] bytes.
+    pub fn from_file(file: &ElfFile) -> Result<Option<Self>, ElfError> {
+        Self::from_file_with_limit(file, DEFAULT_SIZE_LIMIT)
+    }
+
+    /// Decompresses the `.gnu_debugdata` section, if the file has one,
+    /// failing if it is larger than `limit` bytes.
+    pub fn from_file_with_limit(file: &ElfFile, limit: usize) -> Result<Option<Self>, ElfError> {
+        let Some(section) = file.section_by_name(GNU_DEBUGDATA) else {
+            return Ok(None);
+        };
+
+        let buffer = decompress_xz(file.section_data(section)?, limit)?;
+        Ok(Some(Self { buffer }))
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Parses the embedded file.
+    pub fn file(&self) -> Result<ElfFile<'_>, ElfError> {
+        ElfFile::parse(&self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{symbolize::ElfSymbolizer, testdata::MINI_DEBUGINFO_X86_64};
+
+    #[test]
+    fn names_functions_of_stripped_files() {
+        let file = ElfFile::parse(&MINI_DEBUGINFO_X86_64.0).expect("valid file");
+        let symbolizer = ElfSymbolizer::new(&file).expect("readable symbols");
+        assert_eq!(symbolizer.symbolize(0x4000b2), None);
+
+        let mini_debug_info = ElfMiniDebugInfo::from_file(&file);
+        if !cfg!(feature = "xz") {
+            assert!(matches!(
+                mini_debug_info,
+                Err(ElfError::UnsupportedXzCompression)
+            ));
+            return;
+        }
+
+        let mini_debug_info = mini_debug_info
+            .expect("decompressible section")
+            .expect("embedded file");
+        let inner = mini_debug_info.file().expect("valid embedded file");
+        let symbolizer = symbolizer
+            .with_symbols_from(&inner)
+            .expect("readable symbols");
+        let found = symbolizer.symbolize(0x4000b2).expect("named function");
+        assert_eq!((found.symbol.name, found.offset), ("hidden", 0x2));
+
+        // The embedded file is no smaller than the stripped one.
+        assert!(matches!(
+            ElfMiniDebugInfo::from_file_with_limit(&file, file.buffer().len().saturating_sub(1)),
+            Err(ElfError::MalformedXzData)
+        ));
+    }
+}
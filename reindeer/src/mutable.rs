@@ -0,0 +1,249 @@
+//! Mutable views over the ELF, section and program headers, for patching
+//! fields in place without rewriting the file.
+//!
+//! The setters are class-agnostic and take the same types as the getters of
+//! [`ElfHeader`], [`ElfSectionHeader`] and [`ElfProgramHeader`]. Fields that
+//! are 32 bits wide on ELF32 fail with [`ElfError::ValueOutOfRange`] rather
+//! than truncating the value.
+
+use core::{
+    num::{NonZeroU16, NonZeroU32, NonZeroU64},
+    ops::Range,
+};
+
+use zerocopy::FromBytes;
+
+use crate::{
+    dynamic::ElfDynamic, elf_aux_structures::*, elf_structures::*, errors::ElfError, macros::*,
+    range::TryIntoRangeUsize, ElfHeader, ElfProgramHeader, ElfSectionHeader,
+};
+
+/// Returns the class of the file in `buffer`, and the location of the
+/// requested table entry, so that the immutable borrow of `buffer` ends
+/// before it is borrowed mutably.
+fn entry_location(
+    buffer: &[u8],
+    location: impl Fn(&ElfHeader) -> Option<Range<u64>>,
+    index: u16,
+) -> Result<(ElfIdentClass, Range<usize>), ElfError> {
+    let header = ElfHeader::parse(buffer)?;
+    let location = location(&header).ok_or(ElfError::HeaderIndexOutOfBounds(index))?;
+    Ok((header.e_ident().ei_class, location.try_into_usize()?))
+}
+
+/// A mutable ELF header, representing either 64 or 32 bit little-endian ELFs.
+#[derive(Debug)]
+pub enum ElfHeaderMut<'buf> {
+    Elf32(&'buf mut Elf32Header),
+    Elf64(&'buf mut Elf64Header),
+}
+
+impl<'buf> ElfHeaderMut<'buf> {
+    /// Checks the ident like [`ElfHeader::parse`], then borrows the header
+    /// at the start of `bytes` mutably.
+    pub fn parse(bytes: &'buf mut [u8]) -> Result<Self, ElfError> {
+        let class = ElfHeader::parse(bytes)?.e_ident().ei_class;
+
+        let header = match class {
+            ElfIdentClass::CLASS_32 => {
+                Self::Elf32(Elf32Header::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            _ => Self::Elf64(Elf64Header::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?),
+        };
+
+        Ok(header)
+    }
+
+    /// An immutable view of the header, for reading fields.
+    pub fn as_ref(&self) -> ElfHeader<'_> {
+        match self {
+            Self::Elf32(header) => ElfHeader::Elf32(header),
+            Self::Elf64(header) => ElfHeader::Elf64(header),
+        }
+    }
+
+    enum_setter!(set_e_type, e_type, ElfHeaderType);
+    enum_setter!(set_e_machine, e_machine, ElfHeaderMachine);
+    enum_setter!(set_e_version, e_version, ElfHeaderVersion);
+    enum_setter!(set_e_entry, e_entry, Option<NonZeroU64>);
+    enum_setter!(set_e_phoff, e_phoff, Option<NonZeroU64>);
+    enum_setter!(set_e_shoff, e_shoff, Option<NonZeroU64>);
+    enum_setter!(set_e_flags, e_flags, u32);
+    enum_setter!(set_e_ehsize, e_ehsize, u16);
+    enum_setter!(set_e_phentsize, e_phentsize, u16);
+    enum_setter!(set_e_phnum, e_phnum, Option<NonZeroU16>);
+    enum_setter!(set_e_shentsize, e_shentsize, u16);
+    enum_setter!(set_e_shnum, e_shnum, Option<NonZeroU16>);
+    enum_setter!(set_e_shstrndx, e_shstrndx, Option<NonZeroU16>);
+}
+
+/// A mutable section header, representing either 64 or 32 bit section headers.
+#[derive(Debug)]
+pub enum ElfSectionHeaderMut<'buf> {
+    Elf32(&'buf mut Elf32SectionHeader),
+    Elf64(&'buf mut Elf64SectionHeader),
+}
+
+impl<'buf> ElfSectionHeaderMut<'buf> {
+    pub fn parse(class: ElfIdentClass, bytes: &'buf mut [u8]) -> Result<Self, ElfError> {
+        let sh_header = match class {
+            ElfIdentClass::CLASS_32 => Self::Elf32(
+                Elf32SectionHeader::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?,
+            ),
+            ElfIdentClass::CLASS_64 => Self::Elf64(
+                Elf64SectionHeader::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?,
+            ),
+            class => return Err(ElfError::InvalidClass(class)),
+        };
+
+        Ok(sh_header)
+    }
+
+    /// Borrows the section header with the given index from a whole file.
+    pub fn from_file(buffer: &'buf mut [u8], index: u16) -> Result<Self, ElfError> {
+        let (class, location) = entry_location(
+            buffer,
+            |header| header.section_header_location(index),
+            index,
+        )?;
+        let bytes = buffer.get_mut(location).ok_or(ElfError::ZeroCopyError)?;
+
+        Self::parse(class, bytes)
+    }
+
+    /// An immutable view of the section header, for reading fields.
+    pub fn as_ref(&self) -> ElfSectionHeader<'_> {
+        match self {
+            Self::Elf32(header) => ElfSectionHeader::Elf32(header),
+            Self::Elf64(header) => ElfSectionHeader::Elf64(header),
+        }
+    }
+
+    enum_setter!(set_sh_name, sh_name, u32);
+    enum_setter!(set_sh_type, sh_type, ElfSectionType);
+    enum_setter!(set_sh_flags, sh_flags, u64);
+    enum_setter!(set_sh_addr, sh_addr, Option<NonZeroU64>);
+    enum_setter!(set_sh_offset, sh_offset, u64);
+    enum_setter!(set_sh_size, sh_size, u64);
+    enum_setter!(set_sh_link, sh_link, u32);
+    enum_setter!(set_sh_info, sh_info, u32);
+    enum_setter!(set_sh_addralign, sh_addralign, u64);
+    enum_setter!(set_sh_entsize, sh_entsize, Option<NonZeroU64>);
+}
+
+/// A mutable program header, representing either 64 or 32 bit program headers.
+#[derive(Debug)]
+pub enum ElfProgramHeaderMut<'buf> {
+    Elf32(&'buf mut Elf32ProgramHeader),
+    Elf64(&'buf mut Elf64ProgramHeader),
+}
+
+impl<'buf> ElfProgramHeaderMut<'buf> {
+    pub fn parse(class: ElfIdentClass, bytes: &'buf mut [u8]) -> Result<Self, ElfError> {
+        let p_header = match class {
+            ElfIdentClass::CLASS_32 => Self::Elf32(
+                Elf32ProgramHeader::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?,
+            ),
+            ElfIdentClass::CLASS_64 => Self::Elf64(
+                Elf64ProgramHeader::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?,
+            ),
+            class => return Err(ElfError::InvalidClass(class)),
+        };
+
+        Ok(p_header)
+    }
+
+    /// Borrows the program header with the given index from a whole file.
+    pub fn from_file(buffer: &'buf mut [u8], index: u16) -> Result<Self, ElfError> {
+        let (class, location) = entry_location(
+            buffer,
+            |header| header.program_header_location(index),
+            index,
+        )?;
+        let bytes = buffer.get_mut(location).ok_or(ElfError::ZeroCopyError)?;
+
+        Self::parse(class, bytes)
+    }
+
+    /// An immutable view of the program header, for reading fields.
+    pub fn as_ref(&self) -> ElfProgramHeader<'_> {
+        match self {
+            Self::Elf32(header) => ElfProgramHeader::Elf32(header),
+            Self::Elf64(header) => ElfProgramHeader::Elf64(header),
+        }
+    }
+
+    enum_setter!(set_p_type, p_type, ElfSegmentType);
+    enum_setter!(set_p_offset, p_offset, u64);
+    enum_setter!(set_p_vaddr, p_vaddr, u64);
+    enum_setter!(set_p_paddr, p_paddr, u64);
+    enum_setter!(set_p_filesz, p_filesz, Option<NonZeroU64>);
+    enum_setter!(set_p_memsz, p_memsz, Option<NonZeroU64>);
+    enum_setter!(set_p_flags, p_flags, u32);
+    enum_setter!(set_p_align, p_align, u64);
+}
+
+/// A mutable dynamic array entry, representing either 64 or 32 bit entries.
+#[derive(Debug)]
+pub enum ElfDynamicMut<'buf> {
+    Elf32(&'buf mut Elf32Dynamic),
+    Elf64(&'buf mut Elf64Dynamic),
+}
+
+impl<'buf> ElfDynamicMut<'buf> {
+    pub fn parse(class: ElfIdentClass, bytes: &'buf mut [u8]) -> Result<Self, ElfError> {
+        let dynamic = match class {
+            ElfIdentClass::CLASS_32 => {
+                Self::Elf32(Elf32Dynamic::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            ElfIdentClass::CLASS_64 => {
+                Self::Elf64(Elf64Dynamic::mut_from_prefix(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            class => return Err(ElfError::InvalidClass(class)),
+        };
+
+        Ok(dynamic)
+    }
+
+    /// An immutable view of the entry, for reading fields.
+    pub fn as_ref(&self) -> ElfDynamic<'_> {
+        match self {
+            Self::Elf32(dynamic) => ElfDynamic::Elf32(dynamic),
+            Self::Elf64(dynamic) => ElfDynamic::Elf64(dynamic),
+        }
+    }
+
+    pub fn set_d_tag(&mut self, tag: ElfDynamicTag) -> Result<(), ElfError> {
+        match self {
+            Self::Elf32(dynamic) => {
+                dynamic.d_tag = i32::try_from(tag.0)
+                    .map_err(|_| ElfError::ValueOutOfRange("d_tag", tag.0.unsigned_abs()))?
+            }
+            Self::Elf64(dynamic) => dynamic.d_tag = tag.0,
+        }
+        Ok(())
+    }
+
+    enum_setter!(set_d_val, d_val, u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_out_of_range_elf32_values() {
+        let mut buffer = [0u32; 13];
+        buffer[0] = u32::from_le_bytes(*b"\x7fELF");
+        buffer[1] = u32::from_le_bytes([1, 1, 1, 0]);
+        let bytes = zerocopy::AsBytes::as_bytes_mut(&mut buffer);
+
+        let mut header = ElfHeaderMut::parse(bytes).expect("valid header");
+        assert!(header.set_e_entry(NonZeroU64::new(0x1000)).is_ok());
+        assert!(matches!(
+            header.set_e_entry(NonZeroU64::new(0x1_0000_0000)),
+            Err(ElfError::ValueOutOfRange("e_entry", 0x1_0000_0000))
+        ));
+        assert_eq!(header.as_ref().e_entry(), NonZeroU64::new(0x1000));
+    }
+}
@@ -0,0 +1,316 @@
+//! Notes, as found in `SHT_NOTE` sections and `PT_NOTE` segments.
+
+use core::mem::size_of;
+
+use zerocopy::FromBytes;
+
+use crate::{elf_aux_structures::*, elf_structures::*, errors::ElfError, ElfFile, ElfHeader};
+
+/// A single note entry.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfNote<'buf> {
+    /// The type of the note, interpreted with respect to the owner name.
+    pub n_type: ElfNoteType,
+    /// The owner name, without the terminating null byte.
+    pub name: &'buf [u8],
+    /// The descriptor contents.
+    pub desc: &'buf [u8],
+}
+
+impl<'buf> ElfNote<'buf> {
+    pub const GNU_OWNER: &'static [u8] = b"GNU";
+
+    pub fn name_str(&self) -> Result<&'buf str, ElfError> {
+        Ok(core::str::from_utf8(self.name)?)
+    }
+
+    pub fn is_gnu(&self) -> bool {
+        self.name == Self::GNU_OWNER
+    }
+
+    /// The properties of a GNU `NT_GNU_PROPERTY_TYPE_0` note, or `None` for
+    /// any other note.
+    pub fn gnu_properties(&self, header: ElfHeader) -> Option<ElfGnuProperties<'buf>> {
+        (self.is_gnu() && self.n_type == ElfNoteType::NT_GNU_PROPERTY_TYPE_0)
+            .then(|| ElfGnuProperties::parse(header, self.desc))
+    }
+}
+
+/// Iterates over the notes in a note section or segment.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfNotes<'buf> {
+    buffer: &'buf [u8],
+    align: usize,
+}
+
+impl<'buf> ElfNotes<'buf> {
+    /// `align` is the section or segment alignment. Notes in 8-byte aligned
+    /// sections (such as `.note.gnu.property` on ELF64) pad their name and
+    /// descriptor to 8 bytes, everything else pads to 4 bytes.
+    pub fn parse(buffer: &'buf [u8], align: u64) -> Self {
+        Self {
+            buffer,
+            align: if align == 8 { 8 } else { 4 },
+        }
+    }
+
+    fn align_up(&self, value: usize) -> Option<usize> {
+        // align is always 4 or 8, so the mask is well formed.
+        #[allow(clippy::arithmetic_side_effects)]
+        let mask = self.align - 1;
+        Some(value.checked_add(mask)? & !mask)
+    }
+
+    fn parse_note(&self) -> Result<(ElfNote<'buf>, usize), ElfError> {
+        let header = ElfNoteHeader::ref_from_prefix(self.buffer).ok_or(ElfError::ZeroCopyError)?;
+        let namesz = usize::try_from(header.n_namesz)?;
+        let descsz = usize::try_from(header.n_descsz)?;
+
+        let name_end = size_of::<ElfNoteHeader>()
+            .checked_add(namesz)
+            .ok_or(ElfError::MalformedNote)?;
+        let desc_start = self.align_up(name_end).ok_or(ElfError::MalformedNote)?;
+        let desc_end = desc_start
+            .checked_add(descsz)
+            .ok_or(ElfError::MalformedNote)?;
+        let next = self.align_up(desc_end).ok_or(ElfError::MalformedNote)?;
+
+        let name = self
+            .buffer
+            .get(size_of::<ElfNoteHeader>()..name_end)
+            .ok_or(ElfError::MalformedNote)?;
+        let desc = self
+            .buffer
+            .get(desc_start..desc_end)
+            .ok_or(ElfError::MalformedNote)?;
+
+        // The name's terminating null byte is counted in n_namesz.
+        let name = match name.iter().position(|&byte| byte == 0) {
+            Some(end) => name.get(..end).unwrap_or(name),
+            None => name,
+        };
+
+        let note = ElfNote {
+            n_type: header.n_type,
+            name,
+            desc,
+        };
+
+        // The final note need not be padded to the alignment.
+        Ok((note, next.min(self.buffer.len())))
+    }
+}
+
+impl<'buf> Iterator for ElfNotes<'buf> {
+    type Item = Result<ElfNote<'buf>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        match self.parse_note() {
+            Ok((note, next)) => {
+                self.buffer = self.buffer.get(next..).unwrap_or_default();
+                Some(Ok(note))
+            }
+            Err(error) => {
+                // Stop after the first error, the rest of the buffer can't
+                // be found without a valid header.
+                self.buffer = &[];
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// The descriptor of the GNU `NT_GNU_BUILD_ID` note, read from the
+/// `SHT_NOTE` sections, or the `PT_NOTE` segments of a file without section
+/// headers.
+pub fn build_id<'buf>(file: &ElfFile<'buf>) -> Result<Option<&'buf [u8]>, ElfError> {
+    let find = |notes: ElfNotes<'buf>| -> Result<Option<&'buf [u8]>, ElfError> {
+        for note in notes {
+            let note = note?;
+            if note.is_gnu() && note.n_type == ElfNoteType::NT_GNU_BUILD_ID {
+                return Ok(Some(note.desc));
+            }
+        }
+        Ok(None)
+    };
+
+    if file.section_headers().is_some() {
+        for section in file
+            .sections()
+            .filter(|section| section.sh_type() == ElfSectionType::SHT_NOTE)
+        {
+            let notes = ElfNotes::parse(file.section_data(section)?, section.sh_addralign());
+            if let Some(build_id) = find(notes)? {
+                return Ok(Some(build_id));
+            }
+        }
+    } else {
+        for segment in file
+            .segments()
+            .filter(|segment| segment.p_type() == ElfSegmentType::PT_NOTE)
+        {
+            let notes = ElfNotes::parse(file.segment_data(segment)?, segment.p_align());
+            if let Some(build_id) = find(notes)? {
+                return Ok(Some(build_id));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// A single property of a `NT_GNU_PROPERTY_TYPE_0` note.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfGnuProperty<'buf> {
+    pub pr_type: ElfGnuPropertyType,
+    pub data: &'buf [u8],
+}
+
+impl ElfGnuProperty<'_> {
+    /// The data as a 32-bit value, which is how the bitmask properties (such
+    /// as `GNU_PROPERTY_X86_FEATURE_1_AND`) are stored.
+    pub fn value_u32(&self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.data.try_into().ok()?))
+    }
+}
+
+/// Iterates over the properties in the descriptor of a `NT_GNU_PROPERTY_TYPE_0`
+/// note.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfGnuProperties<'buf> {
+    buffer: &'buf [u8],
+    align: usize,
+}
+
+impl<'buf> ElfGnuProperties<'buf> {
+    /// Properties are padded to 8 bytes on ELF64 and to 4 bytes on ELF32.
+    pub fn parse(header: ElfHeader, desc: &'buf [u8]) -> Self {
+        Self {
+            buffer: desc,
+            align: match header {
+                ElfHeader::Elf32(_) => 4,
+                ElfHeader::Elf64(_) => 8,
+            },
+        }
+    }
+
+    fn parse_property(&self) -> Result<(ElfGnuProperty<'buf>, usize), ElfError> {
+        let header =
+            ElfGnuPropertyHeader::ref_from_prefix(self.buffer).ok_or(ElfError::MalformedNote)?;
+        let start = size_of::<ElfGnuPropertyHeader>();
+        let end = start
+            .checked_add(usize::try_from(header.pr_datasz)?)
+            .ok_or(ElfError::MalformedNote)?;
+        let data = self.buffer.get(start..end).ok_or(ElfError::MalformedNote)?;
+
+        // align is always 4 or 8, so the mask is well formed.
+        #[allow(clippy::arithmetic_side_effects)]
+        let mask = self.align - 1;
+        let next = end.checked_add(mask).ok_or(ElfError::MalformedNote)? & !mask;
+
+        let property = ElfGnuProperty {
+            pr_type: header.pr_type,
+            data,
+        };
+
+        Ok((property, next.min(self.buffer.len())))
+    }
+}
+
+impl<'buf> Iterator for ElfGnuProperties<'buf> {
+    type Item = Result<ElfGnuProperty<'buf>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        match self.parse_property() {
+            Ok((property, next)) => {
+                self.buffer = self.buffer.get(next..).unwrap_or_default();
+                Some(Ok(property))
+            }
+            Err(error) => {
+                self.buffer = &[];
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_build_id_note() {
+        let buffer: [u32; 6] = [
+            4,
+            8,
+            3,
+            u32::from_le_bytes(*b"GNU\0"),
+            0x04030201,
+            0x08070605,
+        ];
+        let bytes = zerocopy::AsBytes::as_bytes(&buffer);
+
+        let mut notes = ElfNotes::parse(bytes, 4);
+        let Some(Ok(note)) = notes.next() else {
+            panic!("expected a note");
+        };
+
+        assert!(note.is_gnu());
+        assert_eq!(note.n_type, ElfNoteType::NT_GNU_BUILD_ID);
+        assert_eq!(note.desc, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(notes.next().is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_note() {
+        let buffer: [u32; 4] = [4, 8, 3, u32::from_le_bytes(*b"GNU\0")];
+        let bytes = zerocopy::AsBytes::as_bytes(&buffer);
+
+        let mut notes = ElfNotes::parse(bytes, 4);
+        assert!(matches!(notes.next(), Some(Err(ElfError::MalformedNote))));
+        assert!(notes.next().is_none());
+    }
+
+    #[test]
+    fn parses_gnu_properties() {
+        // The header of an ELF64 file, followed by a property descriptor
+        // holding an x86 feature property and a padded ISA property.
+        let buffer: [u32; 24] = {
+            let mut buffer = [0; 24];
+            buffer[0] = u32::from_le_bytes(*b"\x7fELF");
+            buffer[1] = u32::from_le_bytes([2, 1, 1, 0]);
+            buffer[16..24].copy_from_slice(&[0xc000_0002, 4, 3, 0, 0xc000_8002, 4, 1, 0]);
+            buffer
+        };
+        let bytes = zerocopy::AsBytes::as_bytes(&buffer);
+        let header = ElfHeader::parse(bytes).expect("valid header");
+        let desc = bytes.get(64..).expect("descriptor");
+
+        let mut properties = ElfGnuProperties::parse(header, desc);
+        let Some(Ok(feature)) = properties.next() else {
+            panic!("expected a property");
+        };
+        assert_eq!(
+            feature.pr_type,
+            ElfGnuPropertyType::GNU_PROPERTY_X86_FEATURE_1_AND
+        );
+        assert_eq!(feature.value_u32(), Some(3));
+
+        let Some(Ok(isa)) = properties.next() else {
+            panic!("expected a property");
+        };
+        assert_eq!(
+            isa.pr_type,
+            ElfGnuPropertyType::GNU_PROPERTY_X86_ISA_1_NEEDED
+        );
+        assert!(properties.next().is_none());
+    }
+}
@@ -0,0 +1,1221 @@
+//! Loading relocatable objects (`ET_REL`) into memory, much as the Linux
+//! kernel loads modules.
+//!
+//! [`ElfObjectLoader`] lays out the `SHF_ALLOC` sections one after another,
+//! each at its alignment, followed by a GOT and branch veneers if the
+//! relocations need them. The caller provides [`ElfObjectLoader::size`]
+//! bytes aligned to [`ElfObjectLoader::align`], and
+//! [`ElfObjectLoader::load`] copies the sections in, resolves undefined
+//! symbols with a callback and applies the `SHT_RELA` and `SHT_REL`
+//! relocations of x86-64, AArch64 and RISC-V.
+//!
+//! Nothing is allocated. The offsets of the sections are recomputed from the
+//! section headers when needed, and the GOT and the veneers have a slot for
+//! each entry of the symbol table, so relocations against the same symbol
+//! share them without any bookkeeping.
+
+use crate::{
+    elf_aux_structures::*,
+    errors::ElfError,
+    relocation_types::{ElfAarch64RelocationType, ElfRiscvRelocationType, ElfX86_64RelocationType},
+    relocations::{ElfRelocation, ElfRelocationTable},
+    symbols::{ElfSymbol, ElfSymbolTable},
+    ElfFile, ElfHeader, ElfSectionHeader, ElfStringTable,
+};
+
+const GOT_ENTRY_SIZE: u64 = 8;
+
+/// The machines relocations can be applied for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arch {
+    X86_64,
+    Aarch64,
+    Riscv64,
+}
+
+/// The kind of slot a relocation may need for its symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotKind {
+    Got,
+    Veneer,
+}
+
+impl Arch {
+    fn slot_kind(self, r_type: u32) -> Option<SlotKind> {
+        use ElfAarch64RelocationType as Aarch64;
+        use ElfRiscvRelocationType as Riscv;
+        use ElfX86_64RelocationType as X86_64;
+
+        match self {
+            Self::X86_64 => match X86_64(r_type) {
+                X86_64::R_X86_64_GOTPCREL
+                | X86_64::R_X86_64_GOTPCRELX
+                | X86_64::R_X86_64_REX_GOTPCRELX
+                | X86_64::R_X86_64_CODE_4_GOTPCRELX
+                | X86_64::R_X86_64_GOTOFF64
+                | X86_64::R_X86_64_GOTPC32 => Some(SlotKind::Got),
+                X86_64::R_X86_64_PLT32 => Some(SlotKind::Veneer),
+                _ => None,
+            },
+            Self::Aarch64 => match Aarch64(r_type) {
+                Aarch64::R_AARCH64_ADR_GOT_PAGE
+                | Aarch64::R_AARCH64_LD64_GOT_LO12_NC
+                | Aarch64::R_AARCH64_GOTPCREL32 => Some(SlotKind::Got),
+                Aarch64::R_AARCH64_CALL26 | Aarch64::R_AARCH64_JUMP26 => Some(SlotKind::Veneer),
+                _ => None,
+            },
+            Self::Riscv64 => match Riscv(r_type) {
+                Riscv::R_RISCV_GOT_HI20 => Some(SlotKind::Got),
+                Riscv::R_RISCV_CALL | Riscv::R_RISCV_CALL_PLT => Some(SlotKind::Veneer),
+                _ => None,
+            },
+        }
+    }
+
+    /// The code of a veneer, which jumps to the address stored after it, and
+    /// the offset of that address.
+    fn veneer_code(self) -> (&'static [u8], usize) {
+        match self {
+            // jmp *0(%rip)
+            Self::X86_64 => (&[0xff, 0x25, 0x00, 0x00, 0x00, 0x00], 6),
+            // ldr x16, #8; br x16
+            Self::Aarch64 => (&[0x50, 0x00, 0x00, 0x58, 0x00, 0x02, 0x1f, 0xd6], 8),
+            // auipc t1, 0; ld t1, 16(t1); jr t1; nop
+            Self::Riscv64 => (
+                &[
+                    0x17, 0x03, 0x00, 0x00, 0x03, 0x33, 0x03, 0x01, 0x67, 0x00, 0x03, 0x00, 0x13,
+                    0x00, 0x00, 0x00,
+                ],
+                16,
+            ),
+        }
+    }
+
+    fn veneer_size(self) -> u64 {
+        match self {
+            Self::X86_64 | Self::Aarch64 => 16,
+            Self::Riscv64 => 24,
+        }
+    }
+}
+
+fn is_alloc(section: ElfSectionHeader) -> bool {
+    ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_ALLOC)
+}
+
+/// The `SHF_ALLOC` sections, with their indices and offsets in the image.
+/// This stops early if the offsets overflow, which [`ElfObjectLoader::new`]
+/// checks for.
+fn loaded_sections<'buf>(
+    file: &ElfFile<'buf>,
+) -> impl Iterator<Item = (usize, ElfSectionHeader<'buf>, u64)> {
+    file.sections()
+        .enumerate()
+        .filter(|&(_, section)| is_alloc(section))
+        .scan(0u64, |end, (index, section)| {
+            let offset = end.checked_next_multiple_of(section.sh_addralign().max(1))?;
+            *end = offset.checked_add(section.sh_size())?;
+            Some((index, section, offset))
+        })
+}
+
+/// A location in the image, by its offset and its address once loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Slot {
+    offset: usize,
+    address: u64,
+}
+
+impl Slot {
+    fn new(base: u64, offset: u64) -> Result<Self, ElfError> {
+        Ok(Self {
+            offset: usize::try_from(offset)?,
+            address: base.wrapping_add(offset),
+        })
+    }
+
+    fn add(self, delta: u64) -> Result<Self, ElfError> {
+        Ok(Self {
+            offset: self
+                .offset
+                .checked_add(usize::try_from(delta)?)
+                .ok_or(ElfError::ImageTooLarge)?,
+            address: self.address.wrapping_add(delta),
+        })
+    }
+}
+
+fn read(image: &[u8], offset: usize, size: usize) -> Result<u64, ElfError> {
+    let bytes = offset
+        .checked_add(size)
+        .and_then(|end| image.get(offset..end))
+        .ok_or(ElfError::ZeroCopyError)?;
+    let mut value = [0; 8];
+    value
+        .get_mut(..size)
+        .ok_or(ElfError::ZeroCopyError)?
+        .copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(value))
+}
+
+/// Writes the low `size` bytes of `value`, in little-endian order.
+fn write(image: &mut [u8], offset: usize, size: usize, value: u64) -> Result<(), ElfError> {
+    let bytes = value.to_le_bytes();
+    let value = bytes.get(..size).ok_or(ElfError::ZeroCopyError)?;
+    offset
+        .checked_add(size)
+        .and_then(|end| image.get_mut(offset..end))
+        .ok_or(ElfError::ZeroCopyError)?
+        .copy_from_slice(value);
+    Ok(())
+}
+
+fn fits_signed(value: u64, bits: u32) -> bool {
+    let high = value.cast_signed().wrapping_shr(bits.wrapping_sub(1));
+    high == 0 || high == -1
+}
+
+fn fits_unsigned(value: u64, bits: u32) -> bool {
+    value.checked_shr(bits).unwrap_or(0) == 0
+}
+
+/// How a value written to a field is checked for overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overflow {
+    Signed,
+    Unsigned,
+    /// Either signed or unsigned, as for `R_AARCH64_ABS32`.
+    Either,
+    None,
+}
+
+/// Everything a relocation is applied with.
+#[derive(Debug, Clone, Copy)]
+struct Site {
+    r_type: u32,
+    /// Where the relocation is applied.
+    place: Slot,
+    /// The address of the symbol.
+    symbol: u64,
+    /// The size of the symbol.
+    size: u64,
+    /// The explicit addend, only present for `SHT_RELA` entries.
+    addend: Option<i64>,
+    /// The GOT entry of the symbol, and the start of the GOT, if the image
+    /// has one.
+    got: Option<(Slot, u64)>,
+    /// The veneer of the symbol, if the image has them.
+    veneer: Option<Slot>,
+}
+
+impl Site {
+    fn overflow(&self) -> ElfError {
+        ElfError::RelocationOverflow(self.place.address)
+    }
+
+    fn unsupported(&self) -> ElfError {
+        ElfError::UnsupportedRelocation(self.r_type)
+    }
+
+    /// The explicit addend, or for `SHT_REL` the signed value of the `size`
+    /// bytes at the place.
+    fn addend(&self, image: &[u8], size: usize) -> Result<i64, ElfError> {
+        match self.addend {
+            Some(addend) => Ok(addend),
+            None => {
+                let bits = u32::try_from(size)?.saturating_mul(8);
+                let value = read(image, self.place.offset, size)?;
+                let unused = 64u32.saturating_sub(bits);
+                Ok(value
+                    .cast_signed()
+                    .wrapping_shl(unused)
+                    .wrapping_shr(unused))
+            }
+        }
+    }
+
+    /// The explicit addend, as the implicit addend of a field inside an
+    /// instruction isn't read.
+    fn explicit_addend(&self) -> Result<i64, ElfError> {
+        self.addend.ok_or_else(|| self.unsupported())
+    }
+
+    fn symbol_plus(&self, addend: i64) -> u64 {
+        self.symbol.wrapping_add_signed(addend)
+    }
+
+    fn pc_relative(&self, target: u64) -> u64 {
+        target.wrapping_sub(self.place.address)
+    }
+
+    /// Writes `value` to the GOT entry of the symbol, returning its address.
+    fn got_entry(&self, image: &mut [u8], value: u64) -> Result<u64, ElfError> {
+        let (entry, _) = self.got.ok_or_else(|| self.unsupported())?;
+        write(image, entry.offset, 8, value)?;
+        Ok(entry.address)
+    }
+
+    fn got_start(&self) -> Result<u64, ElfError> {
+        self.got
+            .map(|(_, start)| start)
+            .ok_or_else(|| self.unsupported())
+    }
+
+    /// Writes a veneer jumping to `target`, returning its address.
+    fn veneer(&self, arch: Arch, image: &mut [u8], target: u64) -> Result<u64, ElfError> {
+        let veneer = self.veneer.ok_or_else(|| self.overflow())?;
+        let (code, target_offset) = arch.veneer_code();
+        let end = veneer
+            .offset
+            .checked_add(code.len())
+            .ok_or(ElfError::ZeroCopyError)?;
+        image
+            .get_mut(veneer.offset..end)
+            .ok_or(ElfError::ZeroCopyError)?
+            .copy_from_slice(code);
+        let target_offset = veneer
+            .offset
+            .checked_add(target_offset)
+            .ok_or(ElfError::ZeroCopyError)?;
+        write(image, target_offset, 8, target)?;
+        Ok(veneer.address)
+    }
+
+    /// Writes `value` to the `bits` wide field at the place.
+    fn data(
+        &self,
+        image: &mut [u8],
+        bits: u32,
+        value: u64,
+        overflow: Overflow,
+    ) -> Result<(), ElfError> {
+        let fits = match overflow {
+            Overflow::Signed => fits_signed(value, bits),
+            Overflow::Unsigned => fits_unsigned(value, bits),
+            Overflow::Either => fits_signed(value, bits) || fits_unsigned(value, bits),
+            Overflow::None => true,
+        };
+        if !fits {
+            return Err(self.overflow());
+        }
+        write(image, self.place.offset, usize::try_from(bits / 8)?, value)
+    }
+
+    /// Replaces the bits of the instruction at `delta` from the place that
+    /// aren't in `keep` with `bits`.
+    fn patch(
+        &self,
+        image: &mut [u8],
+        delta: usize,
+        size: usize,
+        keep: u64,
+        bits: u64,
+    ) -> Result<(), ElfError> {
+        let offset = self
+            .place
+            .offset
+            .checked_add(delta)
+            .ok_or(ElfError::ZeroCopyError)?;
+        let instruction = read(image, offset, size)?;
+        write(image, offset, size, (instruction & keep) | (bits & !keep))
+    }
+
+    fn check(&self, fits: bool) -> Result<(), ElfError> {
+        if fits {
+            Ok(())
+        } else {
+            Err(self.overflow())
+        }
+    }
+}
+
+/// Encodes the immediate of an AArch64 `adr` or `adrp`.
+fn aarch64_adr(value: u64) -> u64 {
+    ((value & 0x3) << 29) | (((value >> 2) & 0x7_ffff) << 5)
+}
+
+fn aarch64_page(address: u64) -> u64 {
+    address & !0xfff
+}
+
+fn apply_x86_64(image: &mut [u8], site: &Site) -> Result<(), ElfError> {
+    use ElfX86_64RelocationType as R;
+
+    match R(site.r_type) {
+        R::R_X86_64_NONE => Ok(()),
+        R::R_X86_64_64 => {
+            let value = site.symbol_plus(site.addend(image, 8)?);
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_X86_64_PC32 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 4)?));
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_X86_64_PLT32 => {
+            let addend = site.addend(image, 4)?;
+            let mut value = site.pc_relative(site.symbol_plus(addend));
+            if !fits_signed(value, 32) {
+                let veneer = site.veneer(Arch::X86_64, image, site.symbol)?;
+                value = site.pc_relative(veneer.wrapping_add_signed(addend));
+            }
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_X86_64_GOTPCREL
+        | R::R_X86_64_GOTPCRELX
+        | R::R_X86_64_REX_GOTPCRELX
+        | R::R_X86_64_CODE_4_GOTPCRELX => {
+            let addend = site.addend(image, 4)?;
+            let entry = site.got_entry(image, site.symbol)?;
+            let value = site.pc_relative(entry.wrapping_add_signed(addend));
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_X86_64_32 => {
+            let value = site.symbol_plus(site.addend(image, 4)?);
+            site.data(image, 32, value, Overflow::Unsigned)
+        }
+        R::R_X86_64_32S => {
+            let value = site.symbol_plus(site.addend(image, 4)?);
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_X86_64_16 => {
+            let value = site.symbol_plus(site.addend(image, 2)?);
+            site.data(image, 16, value, Overflow::Either)
+        }
+        R::R_X86_64_PC16 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 2)?));
+            site.data(image, 16, value, Overflow::Signed)
+        }
+        R::R_X86_64_8 => {
+            let value = site.symbol_plus(site.addend(image, 1)?);
+            site.data(image, 8, value, Overflow::Either)
+        }
+        R::R_X86_64_PC8 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 1)?));
+            site.data(image, 8, value, Overflow::Signed)
+        }
+        R::R_X86_64_PC64 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 8)?));
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_X86_64_GOTOFF64 => {
+            let value = site
+                .symbol_plus(site.addend(image, 8)?)
+                .wrapping_sub(site.got_start()?);
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_X86_64_GOTPC32 => {
+            let value = site.pc_relative(
+                site.got_start()?
+                    .wrapping_add_signed(site.addend(image, 4)?),
+            );
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_X86_64_SIZE32 => {
+            let value = site.size.wrapping_add_signed(site.addend(image, 4)?);
+            site.data(image, 32, value, Overflow::Unsigned)
+        }
+        R::R_X86_64_SIZE64 => {
+            let value = site.size.wrapping_add_signed(site.addend(image, 8)?);
+            site.data(image, 64, value, Overflow::None)
+        }
+        _ => Err(site.unsupported()),
+    }
+}
+
+fn apply_aarch64(image: &mut [u8], site: &Site) -> Result<(), ElfError> {
+    use ElfAarch64RelocationType as R;
+
+    match R(site.r_type) {
+        R::R_AARCH64_NONE => Ok(()),
+        R::R_AARCH64_ABS64 => {
+            let value = site.symbol_plus(site.addend(image, 8)?);
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_AARCH64_ABS32 => {
+            let value = site.symbol_plus(site.addend(image, 4)?);
+            site.data(image, 32, value, Overflow::Either)
+        }
+        R::R_AARCH64_ABS16 => {
+            let value = site.symbol_plus(site.addend(image, 2)?);
+            site.data(image, 16, value, Overflow::Either)
+        }
+        R::R_AARCH64_PREL64 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 8)?));
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_AARCH64_PREL32 | R::R_AARCH64_PLT32 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 4)?));
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_AARCH64_PREL16 => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 2)?));
+            site.data(image, 16, value, Overflow::Signed)
+        }
+        R::R_AARCH64_GOTPCREL32 => {
+            let addend = site.addend(image, 4)?;
+            let entry = site.got_entry(image, site.symbol)?;
+            let value = site.pc_relative(entry.wrapping_add_signed(addend));
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        r_type @ (R::R_AARCH64_MOVW_UABS_G0
+        | R::R_AARCH64_MOVW_UABS_G0_NC
+        | R::R_AARCH64_MOVW_UABS_G1
+        | R::R_AARCH64_MOVW_UABS_G1_NC
+        | R::R_AARCH64_MOVW_UABS_G2
+        | R::R_AARCH64_MOVW_UABS_G2_NC
+        | R::R_AARCH64_MOVW_UABS_G3) => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            let (shift, checked): (u32, bool) = match r_type {
+                R::R_AARCH64_MOVW_UABS_G0 => (0, true),
+                R::R_AARCH64_MOVW_UABS_G0_NC => (0, false),
+                R::R_AARCH64_MOVW_UABS_G1 => (16, true),
+                R::R_AARCH64_MOVW_UABS_G1_NC => (16, false),
+                R::R_AARCH64_MOVW_UABS_G2 => (32, true),
+                R::R_AARCH64_MOVW_UABS_G2_NC => (32, false),
+                _ => (48, false),
+            };
+            site.check(!checked || fits_unsigned(value, shift.saturating_add(16)))?;
+            let imm16 = value.wrapping_shr(shift) & 0xffff;
+            site.patch(image, 0, 4, !(0xffff << 5), imm16 << 5)
+        }
+        R::R_AARCH64_LD_PREL_LO19 | R::R_AARCH64_CONDBR19 => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 21))?;
+            site.patch(
+                image,
+                0,
+                4,
+                !(0x7_ffff << 5),
+                ((value >> 2) & 0x7_ffff) << 5,
+            )
+        }
+        R::R_AARCH64_TSTBR14 => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 16))?;
+            site.patch(image, 0, 4, !(0x3fff << 5), ((value >> 2) & 0x3fff) << 5)
+        }
+        R::R_AARCH64_ADR_PREL_LO21 => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 21))?;
+            site.patch(image, 0, 4, !aarch64_adr(u64::MAX), aarch64_adr(value))
+        }
+        r_type @ (R::R_AARCH64_ADR_PREL_PG_HI21 | R::R_AARCH64_ADR_PREL_PG_HI21_NC) => {
+            let target = site.symbol_plus(site.explicit_addend()?);
+            let value = aarch64_page(target).wrapping_sub(aarch64_page(site.place.address));
+            site.check(r_type == R::R_AARCH64_ADR_PREL_PG_HI21_NC || fits_signed(value, 33))?;
+            site.patch(
+                image,
+                0,
+                4,
+                !aarch64_adr(u64::MAX),
+                aarch64_adr(value >> 12),
+            )
+        }
+        r_type @ (R::R_AARCH64_ADD_ABS_LO12_NC
+        | R::R_AARCH64_LDST8_ABS_LO12_NC
+        | R::R_AARCH64_LDST16_ABS_LO12_NC
+        | R::R_AARCH64_LDST32_ABS_LO12_NC
+        | R::R_AARCH64_LDST64_ABS_LO12_NC
+        | R::R_AARCH64_LDST128_ABS_LO12_NC) => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            let scale = match r_type {
+                R::R_AARCH64_LDST16_ABS_LO12_NC => 1,
+                R::R_AARCH64_LDST32_ABS_LO12_NC => 2,
+                R::R_AARCH64_LDST64_ABS_LO12_NC => 3,
+                R::R_AARCH64_LDST128_ABS_LO12_NC => 4,
+                _ => 0,
+            };
+            let imm12 = (value & 0xfff).wrapping_shr(scale);
+            site.patch(image, 0, 4, !(0xfff << 10), imm12 << 10)
+        }
+        R::R_AARCH64_CALL26 | R::R_AARCH64_JUMP26 => {
+            let addend = site.explicit_addend()?;
+            let mut value = site.pc_relative(site.symbol_plus(addend));
+            if !fits_signed(value, 28) && addend == 0 {
+                value = site.pc_relative(site.veneer(Arch::Aarch64, image, site.symbol)?);
+            }
+            site.check(fits_signed(value, 28))?;
+            site.patch(image, 0, 4, !0x3ff_ffff, (value >> 2) & 0x3ff_ffff)
+        }
+        R::R_AARCH64_ADR_GOT_PAGE => {
+            let entry = site.got_entry(image, site.symbol_plus(site.explicit_addend()?))?;
+            let value = aarch64_page(entry).wrapping_sub(aarch64_page(site.place.address));
+            site.check(fits_signed(value, 33))?;
+            site.patch(
+                image,
+                0,
+                4,
+                !aarch64_adr(u64::MAX),
+                aarch64_adr(value >> 12),
+            )
+        }
+        R::R_AARCH64_LD64_GOT_LO12_NC => {
+            let entry = site.got_entry(image, site.symbol_plus(site.explicit_addend()?))?;
+            site.patch(image, 0, 4, !(0xfff << 10), ((entry & 0xfff) >> 3) << 10)
+        }
+        _ => Err(site.unsupported()),
+    }
+}
+
+/// Encodes the upper 20 bits of `value` for a RISC-V `auipc` or `lui`,
+/// rounded so that the sign-extended lower 12 bits can be added to them.
+fn riscv_hi20(site: &Site, image: &mut [u8], delta: usize, value: u64) -> Result<(), ElfError> {
+    let value = value.wrapping_add(0x800);
+    site.check(fits_signed(value, 32))?;
+    site.patch(image, delta, 4, 0xfff, value & 0xffff_f000)
+}
+
+/// Encodes the lower 12 bits of `value` in a RISC-V I-type instruction.
+fn riscv_lo12_i(site: &Site, image: &mut [u8], delta: usize, value: u64) -> Result<(), ElfError> {
+    site.patch(image, delta, 4, 0x000f_ffff, (value & 0xfff) << 20)
+}
+
+/// Encodes the lower 12 bits of `value` in a RISC-V S-type instruction.
+fn riscv_lo12_s(site: &Site, image: &mut [u8], value: u64) -> Result<(), ElfError> {
+    let bits = ((value & 0xfe0) << 20) | ((value & 0x1f) << 7);
+    site.patch(image, 0, 4, 0x01ff_f07f, bits)
+}
+
+/// Applies a RISC-V relocation. `pcrel_hi` is the value of the `auipc`
+/// that an `R_RISCV_PCREL_LO12_*` relocation's symbol points to.
+fn apply_riscv(image: &mut [u8], site: &Site, pcrel_hi: Option<u64>) -> Result<(), ElfError> {
+    use ElfRiscvRelocationType as R;
+
+    match R(site.r_type) {
+        R::R_RISCV_NONE | R::R_RISCV_ALIGN | R::R_RISCV_RELAX => Ok(()),
+        R::R_RISCV_32 => {
+            let value = site.symbol_plus(site.addend(image, 4)?);
+            site.data(image, 32, value, Overflow::Either)
+        }
+        R::R_RISCV_64 => {
+            let value = site.symbol_plus(site.addend(image, 8)?);
+            site.data(image, 64, value, Overflow::None)
+        }
+        R::R_RISCV_32_PCREL => {
+            let value = site.pc_relative(site.symbol_plus(site.addend(image, 4)?));
+            site.data(image, 32, value, Overflow::Signed)
+        }
+        R::R_RISCV_BRANCH => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 13))?;
+            let bits = ((value & 0x1000) << 19)
+                | ((value & 0x7e0) << 20)
+                | ((value & 0x1e) << 7)
+                | ((value & 0x800) >> 4);
+            site.patch(image, 0, 4, 0x01ff_f07f, bits)
+        }
+        R::R_RISCV_JAL => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 21))?;
+            let bits = ((value & 0x10_0000) << 11)
+                | ((value & 0x7fe) << 20)
+                | ((value & 0x800) << 9)
+                | (value & 0xf_f000);
+            site.patch(image, 0, 4, 0xfff, bits)
+        }
+        R::R_RISCV_CALL | R::R_RISCV_CALL_PLT => {
+            let addend = site.explicit_addend()?;
+            let mut value = site.pc_relative(site.symbol_plus(addend));
+            if !fits_signed(value.wrapping_add(0x800), 32) && addend == 0 {
+                value = site.pc_relative(site.veneer(Arch::Riscv64, image, site.symbol)?);
+            }
+            riscv_hi20(site, image, 0, value)?;
+            riscv_lo12_i(site, image, 4, value)
+        }
+        R::R_RISCV_GOT_HI20 => {
+            let entry = site.got_entry(image, site.symbol)?;
+            let value = site.pc_relative(entry.wrapping_add_signed(site.explicit_addend()?));
+            riscv_hi20(site, image, 0, value)
+        }
+        R::R_RISCV_PCREL_HI20 => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            riscv_hi20(site, image, 0, value)
+        }
+        R::R_RISCV_PCREL_LO12_I => {
+            riscv_lo12_i(site, image, 0, pcrel_hi.ok_or_else(|| site.unsupported())?)
+        }
+        R::R_RISCV_PCREL_LO12_S => {
+            riscv_lo12_s(site, image, pcrel_hi.ok_or_else(|| site.unsupported())?)
+        }
+        R::R_RISCV_HI20 => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            riscv_hi20(site, image, 0, value)
+        }
+        R::R_RISCV_LO12_I => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            riscv_lo12_i(site, image, 0, value)
+        }
+        R::R_RISCV_LO12_S => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            riscv_lo12_s(site, image, value)
+        }
+        r_type @ (R::R_RISCV_ADD8
+        | R::R_RISCV_ADD16
+        | R::R_RISCV_ADD32
+        | R::R_RISCV_ADD64
+        | R::R_RISCV_SUB8
+        | R::R_RISCV_SUB16
+        | R::R_RISCV_SUB32
+        | R::R_RISCV_SUB64) => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            let size = match r_type {
+                R::R_RISCV_ADD8 | R::R_RISCV_SUB8 => 1,
+                R::R_RISCV_ADD16 | R::R_RISCV_SUB16 => 2,
+                R::R_RISCV_ADD32 | R::R_RISCV_SUB32 => 4,
+                _ => 8,
+            };
+            let current = read(image, site.place.offset, size)?;
+            let value = match r_type {
+                R::R_RISCV_ADD8 | R::R_RISCV_ADD16 | R::R_RISCV_ADD32 | R::R_RISCV_ADD64 => {
+                    current.wrapping_add(value)
+                }
+                _ => current.wrapping_sub(value),
+            };
+            write(image, site.place.offset, size, value)
+        }
+        r_type @ (R::R_RISCV_SUB6 | R::R_RISCV_SET6) => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            let current = read(image, site.place.offset, 1)?;
+            let value = match r_type {
+                R::R_RISCV_SUB6 => current.wrapping_sub(value),
+                _ => value,
+            };
+            write(
+                image,
+                site.place.offset,
+                1,
+                (current & 0xc0) | (value & 0x3f),
+            )
+        }
+        r_type @ (R::R_RISCV_SET8 | R::R_RISCV_SET16 | R::R_RISCV_SET32) => {
+            let value = site.symbol_plus(site.explicit_addend()?);
+            let size = match r_type {
+                R::R_RISCV_SET8 => 1,
+                R::R_RISCV_SET16 => 2,
+                _ => 4,
+            };
+            write(image, site.place.offset, size, value)
+        }
+        R::R_RISCV_RVC_BRANCH => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 9))?;
+            let bits = ((value & 0x100) << 4)
+                | ((value & 0x18) << 7)
+                | ((value & 0xc0) >> 1)
+                | ((value & 0x6) << 2)
+                | ((value & 0x20) >> 3);
+            site.patch(image, 0, 2, 0xe383, bits)
+        }
+        R::R_RISCV_RVC_JUMP => {
+            let value = site.pc_relative(site.symbol_plus(site.explicit_addend()?));
+            site.check(fits_signed(value, 12))?;
+            let bits = ((value & 0x800) << 1)
+                | ((value & 0x10) << 7)
+                | ((value & 0x300) << 1)
+                | ((value & 0x400) >> 2)
+                | ((value & 0x40) << 1)
+                | ((value & 0x80) >> 1)
+                | ((value & 0xe) << 2)
+                | ((value & 0x20) >> 3);
+            site.patch(image, 0, 2, 0xe003, bits)
+        }
+        _ => Err(site.unsupported()),
+    }
+}
+
+/// Lays out and loads a relocatable object.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfObjectLoader<'buf> {
+    file: ElfFile<'buf>,
+    arch: Arch,
+    symbols: ElfSymbolTable<'buf>,
+    strings: ElfStringTable<'buf>,
+    /// The offsets of the GOT and the veneers, if the image has them.
+    got: Option<u64>,
+    veneers: Option<u64>,
+    size: u64,
+    align: u64,
+}
+
+impl<'buf> ElfObjectLoader<'buf> {
+    pub fn new(file: ElfFile<'buf>) -> Result<Self, ElfError> {
+        let header = file.header();
+        let e_type = header.e_type();
+        if e_type != ElfHeaderType::ET_REL {
+            return Err(ElfError::UnsupportedFileType(e_type));
+        }
+
+        let machine = header.e_machine();
+        let arch = match (header, machine) {
+            (ElfHeader::Elf64(_), ElfHeaderMachine::EM_X86_64) => Arch::X86_64,
+            (ElfHeader::Elf64(_), ElfHeaderMachine::EM_AARCH64) => Arch::Aarch64,
+            (ElfHeader::Elf64(_), ElfHeaderMachine::EM_RISCV) => Arch::Riscv64,
+            _ => return Err(ElfError::UnsupportedMachine(machine)),
+        };
+
+        let (symbols, strings) = match file.section_by_type(ElfSectionType::SHT_SYMTAB) {
+            Some(section) => (
+                file.symbol_table(section)?,
+                file.linked_string_table(section)?,
+            ),
+            None => (
+                ElfSymbolTable::Elf64(&[]),
+                ElfStringTable::parse_lenient(&[]),
+            ),
+        };
+
+        let mut loader = Self {
+            file,
+            arch,
+            symbols,
+            strings,
+            got: None,
+            veneers: None,
+            size: 0,
+            align: 1,
+        };
+
+        let mut placed = 0usize;
+        for (_, section, offset) in loaded_sections(&file) {
+            placed = placed.saturating_add(1);
+            loader.size = offset.saturating_add(section.sh_size());
+            loader.align = loader.align.max(section.sh_addralign());
+        }
+        if placed != file.sections().filter(|&section| is_alloc(section)).count() {
+            return Err(ElfError::ImageTooLarge);
+        }
+
+        let (mut needs_got, mut needs_veneers) = (false, false);
+        for table in loader.relocation_tables() {
+            let (_, table) = table?;
+            for relocation in table {
+                match arch.slot_kind(relocation.r_type) {
+                    Some(SlotKind::Got) => needs_got = true,
+                    Some(SlotKind::Veneer) => {
+                        needs_veneers |= symbols
+                            .get(relocation.r_sym)
+                            .is_some_and(|symbol| symbol.is_undefined())
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let count = u64::try_from(symbols.len())?;
+        if needs_got {
+            loader.got = Some(loader.reserve(count, GOT_ENTRY_SIZE, GOT_ENTRY_SIZE)?);
+        }
+        if needs_veneers {
+            loader.veneers = Some(loader.reserve(count, arch.veneer_size(), 16)?);
+        }
+
+        Ok(loader)
+    }
+
+    /// Adds `count` slots of `size` bytes after the sections, returning the
+    /// offset of the first.
+    fn reserve(&mut self, count: u64, size: u64, align: u64) -> Result<u64, ElfError> {
+        let offset = self
+            .size
+            .checked_next_multiple_of(align)
+            .ok_or(ElfError::ImageTooLarge)?;
+        self.size = count
+            .checked_mul(size)
+            .and_then(|size| offset.checked_add(size))
+            .ok_or(ElfError::ImageTooLarge)?;
+        self.align = self.align.max(align);
+        Ok(offset)
+    }
+
+    /// The number of bytes the image needs.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The alignment the image needs, the largest of its sections.
+    pub fn align(&self) -> u64 {
+        self.align
+    }
+
+    /// The offset of section `index` in the image, if it is loaded.
+    pub fn section_offset(&self, index: u16) -> Option<u64> {
+        loaded_sections(&self.file)
+            .find(|&(section, _, _)| section == usize::from(index))
+            .map(|(_, _, offset)| offset)
+    }
+
+    /// The relocation tables that apply to loaded sections, with the index
+    /// of the section each applies to.
+    fn relocation_tables(
+        &self,
+    ) -> impl Iterator<Item = Result<(u16, ElfRelocationTable<'buf>), ElfError>> + '_ {
+        self.file
+            .sections()
+            .filter(|section| {
+                matches!(
+                    section.sh_type(),
+                    ElfSectionType::SHT_REL | ElfSectionType::SHT_RELA
+                )
+            })
+            .filter_map(|section| {
+                let target = u16::try_from(section.sh_info()).ok()?;
+                self.section_offset(target)?;
+                let table = self
+                    .file
+                    .relocation_table(section)
+                    .map(|table| (target, table));
+                Some(table)
+            })
+    }
+
+    /// The address of symbol `index` in an image loaded at `base`, resolving
+    /// undefined symbols with `resolve`. Undefined weak symbols that aren't
+    /// resolved are at zero.
+    fn symbol_address(
+        &self,
+        base: u64,
+        index: u32,
+        resolve: &mut impl FnMut(&str) -> Option<u64>,
+    ) -> Result<(ElfSymbol<'buf>, u64), ElfError> {
+        let symbol = self
+            .symbols
+            .get(index)
+            .ok_or(ElfError::UnresolvedSymbol(index))?;
+
+        let address = match symbol.st_shndx() {
+            _ if index == 0 => Some(0),
+            ElfSectionIndex::SHN_UNDEF => {
+                let name = self.strings.get(symbol.st_name())?;
+                resolve(name).or((symbol.binding() == ElfSymbolBinding::STB_WEAK).then_some(0))
+            }
+            ElfSectionIndex::SHN_ABS => Some(symbol.st_value()),
+            ElfSectionIndex(shndx) if shndx < ElfSectionIndex::SHN_LORESERVE.0 => self
+                .section_offset(shndx)
+                .map(|offset| base.wrapping_add(offset).wrapping_add(symbol.st_value())),
+            _ => None,
+        };
+
+        Ok((symbol, address.ok_or(ElfError::UnresolvedSymbol(index))?))
+    }
+
+    fn site(
+        &self,
+        base: u64,
+        place: Slot,
+        relocation: ElfRelocation,
+        resolve: &mut impl FnMut(&str) -> Option<u64>,
+    ) -> Result<Site, ElfError> {
+        let (symbol, address) = self.symbol_address(base, relocation.r_sym, resolve)?;
+        let slot = |start: Option<u64>, size: u64| {
+            start
+                .map(|start| {
+                    let delta = u64::from(relocation.r_sym).wrapping_mul(size);
+                    Slot::new(base, start)?.add(delta)
+                })
+                .transpose()
+        };
+
+        Ok(Site {
+            r_type: relocation.r_type,
+            place,
+            symbol: address,
+            size: symbol.st_size(),
+            addend: relocation.r_addend,
+            got: slot(self.got, GOT_ENTRY_SIZE)?.zip(self.got.map(|got| base.wrapping_add(got))),
+            veneer: slot(self.veneers, self.arch.veneer_size())?,
+        })
+    }
+
+    /// The value of the `R_RISCV_PCREL_HI20` or `R_RISCV_GOT_HI20`
+    /// relocation at `label` in `table`, which applies to the section at
+    /// `target`.
+    fn riscv_pcrel_hi(
+        &self,
+        base: u64,
+        target: Slot,
+        table: ElfRelocationTable<'buf>,
+        label: u64,
+        resolve: &mut impl FnMut(&str) -> Option<u64>,
+    ) -> Option<u64> {
+        use ElfRiscvRelocationType as R;
+
+        let relocation = table.into_iter().find(|relocation| {
+            target.address.wrapping_add(relocation.r_offset) == label
+                && matches!(
+                    R(relocation.r_type),
+                    R::R_RISCV_PCREL_HI20 | R::R_RISCV_GOT_HI20
+                )
+        })?;
+        let place = target.add(relocation.r_offset).ok()?;
+        let site = self.site(base, place, relocation, resolve).ok()?;
+        let addend = relocation.r_addend.unwrap_or(0);
+
+        let target = match R(relocation.r_type) {
+            R::R_RISCV_GOT_HI20 => site.got?.0.address,
+            _ => site.symbol,
+        };
+        Some(site.pc_relative(target.wrapping_add_signed(addend)))
+    }
+
+    /// Loads the object into `image`, which is mapped at `base` and must be
+    /// at least [`Self::size`] bytes. `resolve` gives the address of each
+    /// undefined symbol, and may be called more than once for a symbol.
+    ///
+    /// The GOT entry and veneer of a symbol are shared by all its
+    /// relocations, so a branch with an addend can't use a veneer.
+    pub fn load(
+        &self,
+        image: &mut [u8],
+        base: u64,
+        mut resolve: impl FnMut(&str) -> Option<u64>,
+    ) -> Result<ElfLoadedObject<'buf>, ElfError> {
+        let image = image
+            .get_mut(..usize::try_from(self.size)?)
+            .ok_or(ElfError::ZeroCopyError)?;
+        if base.checked_rem(self.align.max(1)) != Some(0) {
+            return Err(ElfError::ZeroCopyError);
+        }
+
+        image.fill(0);
+        for (_, section, offset) in loaded_sections(&self.file) {
+            if section.sh_type() == ElfSectionType::SHT_NOBITS {
+                continue;
+            }
+            let data = self.file.section_data(section)?;
+            let start = usize::try_from(offset)?;
+            start
+                .checked_add(data.len())
+                .and_then(|end| image.get_mut(start..end))
+                .ok_or(ElfError::ZeroCopyError)?
+                .copy_from_slice(data);
+        }
+
+        for table in self.relocation_tables() {
+            let (target, table) = table?;
+            let offset = self
+                .section_offset(target)
+                .ok_or(ElfError::HeaderIndexOutOfBounds(target))?;
+            let target = Slot::new(base, offset)?;
+
+            for relocation in table {
+                let place = target.add(relocation.r_offset)?;
+                let site = self.site(base, place, relocation, &mut resolve)?;
+                match self.arch {
+                    Arch::X86_64 => apply_x86_64(image, &site)?,
+                    Arch::Aarch64 => apply_aarch64(image, &site)?,
+                    Arch::Riscv64 => {
+                        let pcrel_hi = matches!(
+                            ElfRiscvRelocationType(relocation.r_type),
+                            ElfRiscvRelocationType::R_RISCV_PCREL_LO12_I
+                                | ElfRiscvRelocationType::R_RISCV_PCREL_LO12_S
+                        )
+                        .then(|| {
+                            self.riscv_pcrel_hi(base, target, table, site.symbol, &mut resolve)
+                        })
+                        .flatten();
+                        apply_riscv(image, &site, pcrel_hi)?
+                    }
+                }
+            }
+        }
+
+        Ok(ElfLoadedObject {
+            loader: *self,
+            base,
+        })
+    }
+}
+
+/// A symbol defined by a loaded object.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfLoadedSymbol<'buf> {
+    pub index: u32,
+    pub name: &'buf str,
+    pub address: u64,
+    pub symbol: ElfSymbol<'buf>,
+}
+
+/// An object loaded by [`ElfObjectLoader::load`].
+#[derive(Debug, Clone, Copy)]
+pub struct ElfLoadedObject<'buf> {
+    loader: ElfObjectLoader<'buf>,
+    base: u64,
+}
+
+impl<'buf> ElfLoadedObject<'buf> {
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn section_address(&self, index: u16) -> Option<u64> {
+        self.loader
+            .section_offset(index)
+            .map(|offset| self.base.wrapping_add(offset))
+    }
+
+    /// The symbols the object defines, with their final addresses. Section
+    /// and file symbols are left out, as are undefined symbols, whose
+    /// addresses came from the resolver.
+    pub fn symbols(&self) -> impl Iterator<Item = ElfLoadedSymbol<'buf>> + '_ {
+        self.loader
+            .symbols
+            .into_iter()
+            .zip(0u32..)
+            .filter(|(symbol, _)| {
+                !symbol.is_undefined()
+                    && !matches!(
+                        symbol.symbol_type(),
+                        ElfSymbolType::STT_SECTION | ElfSymbolType::STT_FILE
+                    )
+            })
+            .filter_map(|(_, index)| {
+                let (symbol, address) = self
+                    .loader
+                    .symbol_address(self.base, index, &mut |_| None)
+                    .ok()?;
+                Some(ElfLoadedSymbol {
+                    index,
+                    name: self.loader.strings.get(symbol.st_name()).ok()?,
+                    address,
+                    symbol,
+                })
+            })
+    }
+
+    /// The address of the global or weak symbol called `name`.
+    pub fn symbol_by_name(&self, name: &str) -> Option<u64> {
+        self.symbols()
+            .find(|symbol| {
+                symbol.name == name && symbol.symbol.binding() != ElfSymbolBinding::STB_LOCAL
+            })
+            .map(|symbol| symbol.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::OBJECT_RISCV64;
+
+    fn site(r_type: u32, place: u64, symbol: u64, addend: i64) -> Site {
+        Site {
+            r_type,
+            place: Slot {
+                offset: 0,
+                address: place,
+            },
+            symbol,
+            size: 0,
+            addend: Some(addend),
+            got: None,
+            veneer: Some(Slot {
+                offset: 8,
+                address: place.wrapping_add(8),
+            }),
+        }
+    }
+
+    #[test]
+    fn encodes_branches() {
+        // bl 0; a call 0x1000 forward
+        let mut image = [0u8; 32];
+        image[..4].copy_from_slice(&0x9400_0000u32.to_le_bytes());
+        let call26 = ElfAarch64RelocationType::R_AARCH64_CALL26.0;
+        apply_aarch64(&mut image, &site(call26, 0x1_0000, 0x1_1000, 0)).expect("in range");
+        assert_eq!(image[..4], 0x9400_0400u32.to_le_bytes());
+
+        // Out of range, so through a veneer holding the target.
+        apply_aarch64(&mut image, &site(call26, 0x1_0000, 0x10_0000_0000, 0)).expect("veneer");
+        assert_eq!(image[..4], 0x9400_0002u32.to_le_bytes());
+        assert_eq!(&image[8..16], Arch::Aarch64.veneer_code().0);
+        assert_eq!(image[16..24], 0x10_0000_0000u64.to_le_bytes());
+
+        // auipc ra, 0; jalr ra, 0(ra); a call 0x1234 back
+        let mut image = [0u8; 32];
+        image[..4].copy_from_slice(&0x0000_0097u32.to_le_bytes());
+        image[4..8].copy_from_slice(&0x0000_80e7u32.to_le_bytes());
+        let call = ElfRiscvRelocationType::R_RISCV_CALL_PLT.0;
+        apply_riscv(&mut image, &site(call, 0x2_0000, 0x1_edcc, 0), None).expect("in range");
+        assert_eq!(image[..4], 0xffff_f097u32.to_le_bytes());
+        assert_eq!(image[4..8], 0xdcc0_80e7u32.to_le_bytes());
+
+        // beq a0, a1, -8
+        let mut image = [0u8; 4];
+        image.copy_from_slice(&0x00b5_0063u32.to_le_bytes());
+        let branch = ElfRiscvRelocationType::R_RISCV_BRANCH.0;
+        apply_riscv(&mut image, &site(branch, 0x100, 0xf8, 0), None).expect("in range");
+        assert_eq!(image, 0xfeb5_0ce3u32.to_le_bytes());
+
+        // call with rel32 -4, to a symbol 0x10 past the place.
+        let mut image = [0u8; 32];
+        let plt32 = ElfX86_64RelocationType::R_X86_64_PLT32.0;
+        apply_x86_64(&mut image, &site(plt32, 0x1000, 0x1010, -4)).expect("in range");
+        assert_eq!(image[..4], 0x0cu32.to_le_bytes());
+        let error = apply_aarch64(&mut image, &site(0xffff, 0, 0, 0));
+        assert!(matches!(
+            error,
+            Err(ElfError::UnsupportedRelocation(0xffff))
+        ));
+    }
+
+    /// The offset from its place that the RISC-V `auipc` at `offset` and
+    /// the I-type instruction after it add up to.
+    fn riscv_pcrel(image: &[u8], offset: usize) -> u64 {
+        let hi = read(image, offset, 4).expect("in the image") & 0xffff_f000;
+        let lo = read(image, offset.saturating_add(4), 4).expect("in the image") >> 20;
+        let hi = (hi ^ 0x8000_0000).wrapping_sub(0x8000_0000);
+        let lo = (lo ^ 0x800).wrapping_sub(0x800);
+        hi.wrapping_add(lo)
+    }
+
+    #[test]
+    fn loads_riscv_object() {
+        let file = ElfFile::parse(&OBJECT_RISCV64.0).expect("valid file");
+        let loader = ElfObjectLoader::new(file).expect("loadable");
+
+        // .text, .data and .bss each at its alignment, then a GOT entry and
+        // a veneer for each of the 8 symbols.
+        assert_eq!(loader.section_offset(2), Some(0));
+        assert_eq!(loader.section_offset(4), Some(0x20));
+        assert_eq!(loader.section_offset(5), Some(0x30));
+        assert_eq!(loader.section_offset(6), None);
+        assert_eq!(loader.size(), 0x140);
+        assert_eq!(loader.align(), 16);
+
+        let external = 0x10_0000_0000;
+        let mut image = [0xff; 0x140];
+        let object = loader
+            .load(&mut image, 0x10_0000, |name| {
+                (name == "external").then_some(external)
+            })
+            .expect("relocatable");
+
+        // auipc a0, %pcrel_hi(counter); addi a0, a0, %pcrel_lo(.Lcounter)
+        assert_eq!(riscv_pcrel(&image, 0), 0x20);
+        // auipc a1, %got_pcrel_hi(external); ld a1, %pcrel_lo(.Lexternal)(a1)
+        // loads the GOT entry of symbol 7.
+        assert_eq!(riscv_pcrel(&image, 8), 0x78 - 8);
+        assert_eq!(read(&image, 0x78, 8).ok(), Some(external));
+        // call external, which is too far, so goes through its veneer.
+        assert_eq!(riscv_pcrel(&image, 0x10), 0x128 - 0x10);
+        assert_eq!(image.get(0x128..0x138), Some(Arch::Riscv64.veneer_code().0));
+        assert_eq!(read(&image, 0x138, 8).ok(), Some(external));
+        // .bss is cleared.
+        assert_eq!(image.get(0x30..0x40), Some(&[0; 16][..]));
+
+        assert!(object
+            .symbols()
+            .map(|symbol| (symbol.name, symbol.address))
+            .eq([
+                (".Lcounter", 0x10_0000),
+                (".Lexternal", 0x10_0008),
+                ("helper", 0x10_001c),
+                ("buffer", 0x10_0030),
+                ("entry", 0x10_0000),
+                ("counter", 0x10_0020),
+            ]));
+        assert_eq!(object.symbol_by_name("counter"), Some(0x10_0020));
+        assert_eq!(object.symbol_by_name("helper"), None);
+        assert_eq!(object.section_address(4), Some(0x10_0020));
+    }
+}
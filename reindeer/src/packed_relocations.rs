@@ -0,0 +1,515 @@
+//! Relocation tables in compressed formats: `SHT_RELR`, which lists the
+//! places of relative relocations as addresses and bitmaps, and Android's
+//! `SHT_ANDROID_REL` and `SHT_ANDROID_RELA`, whose "APS2" format groups
+//! similar entries and stores their fields as SLEB128 deltas.
+//!
+//! [`ElfRelocationTable`](crate::relocations::ElfRelocationTable) expands
+//! both into [`ElfRelocation`]s, like the entries of a `SHT_RELA` table.
+//! [`ElfRelrEncoder`] goes the other way, for writing a `SHT_RELR` table.
+
+use core::iter::Peekable;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::ElfHeader;
+use crate::{dwarf::DwarfReader, errors::ElfError, relocations::ElfRelocation};
+
+/// Decodes `SHT_RELR` entries into the offsets of the words they relocate.
+///
+/// Entries are decoded one at a time, so that they can be read from the
+/// file or from memory. An even entry is the offset of a word. An odd entry
+/// is a bitmap of the words following the last one decoded, bit 1 being the
+/// next word.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfRelrDecoder {
+    word: u64,
+    /// The offset of the word that bit 1 of the next bitmap stands for.
+    next: u64,
+}
+
+impl ElfRelrDecoder {
+    /// `word` is the size of the entries, and of the relocated words, in
+    /// bytes.
+    pub fn new(word: u8) -> Self {
+        Self {
+            word: word.into(),
+            next: 0,
+        }
+    }
+
+    pub fn decode(&mut self, entry: u64) -> ElfRelrOffsets {
+        if entry & 1 == 0 {
+            self.next = entry.wrapping_add(self.word);
+            return ElfRelrOffsets {
+                bitmap: 1,
+                offset: entry,
+                word: self.word,
+            };
+        }
+
+        let offsets = ElfRelrOffsets {
+            bitmap: entry >> 1,
+            offset: self.next,
+            word: self.word,
+        };
+        let bits = self.word.saturating_mul(8).saturating_sub(1);
+        self.next = self.next.wrapping_add(bits.wrapping_mul(self.word));
+        offsets
+    }
+}
+
+/// The offsets of the words relocated by one `SHT_RELR` entry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ElfRelrOffsets {
+    bitmap: u64,
+    offset: u64,
+    word: u64,
+}
+
+impl Iterator for ElfRelrOffsets {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bitmap == 0 {
+            return None;
+        }
+
+        let skip = self.bitmap.trailing_zeros();
+        let offset = self
+            .offset
+            .wrapping_add(u64::from(skip).wrapping_mul(self.word));
+        self.bitmap = self.bitmap.checked_shr(skip.saturating_add(1)).unwrap_or(0);
+        self.offset = offset.wrapping_add(self.word);
+        Some(offset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = usize::try_from(self.bitmap.count_ones()).unwrap_or(usize::MAX);
+        (count, Some(count))
+    }
+}
+
+/// The entries of a `SHT_RELR` or `SHT_ANDROID_RELR` section.
+#[derive(Debug, Clone, Copy)]
+pub enum ElfRelrTable<'buf> {
+    Elf32(&'buf [u32]),
+    Elf64(&'buf [u64]),
+}
+
+impl<'buf> ElfRelrTable<'buf> {
+    /// The size of an entry in bytes.
+    pub fn word(&self) -> u8 {
+        match self {
+            Self::Elf32(_) => 4,
+            Self::Elf64(_) => 8,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<u64> {
+        match self {
+            Self::Elf32(entries) => entries.get(index).copied().map(Into::into),
+            Self::Elf64(entries) => entries.get(index).copied(),
+        }
+    }
+
+    /// The number of entries, rather than of relocated words.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Elf32(entries) => entries.len(),
+            Self::Elf64(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The offsets of the relocated words, in the order of the entries.
+    pub fn offsets(self) -> ElfRelrTableOffsets<'buf> {
+        ElfRelrTableOffsets {
+            table: self,
+            index: 0,
+            decoder: ElfRelrDecoder::new(self.word()),
+            offsets: ElfRelrOffsets::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfRelrTableOffsets<'buf> {
+    table: ElfRelrTable<'buf>,
+    index: usize,
+    decoder: ElfRelrDecoder,
+    offsets: ElfRelrOffsets,
+}
+
+impl Iterator for ElfRelrTableOffsets<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(offset) = self.offsets.next() {
+                return Some(offset);
+            }
+            let entry = self.table.get(self.index)?;
+            self.index = self.index.saturating_add(1);
+            self.offsets = self.decoder.decode(entry);
+        }
+    }
+}
+
+/// Encodes the offsets of relative relocations as `SHT_RELR` entries, in the
+/// way `lld` and `ld -z pack-relative-relocs` do.
+///
+/// The offsets must be sorted, distinct and aligned to a word, which
+/// [`encode_relr`] ensures.
+#[derive(Debug, Clone)]
+pub struct ElfRelrEncoder<I: Iterator<Item = u64>> {
+    offsets: Peekable<I>,
+    word: u64,
+    /// The offset of the word that bit 1 of the next bitmap stands for, if
+    /// the last entry can be followed by one.
+    next: Option<u64>,
+}
+
+impl<I: Iterator<Item = u64>> ElfRelrEncoder<I> {
+    /// `word` is the size of the entries, and of the relocated words, in
+    /// bytes.
+    pub fn new(offsets: impl IntoIterator<IntoIter = I>, word: u8) -> Self {
+        Self {
+            offsets: offsets.into_iter().peekable(),
+            word: word.into(),
+            next: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = u64>> Iterator for ElfRelrEncoder<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bits = self.word.saturating_mul(8).saturating_sub(1);
+
+        if let Some(next) = self.next {
+            let mut bitmap = 0u64;
+            while let Some(&offset) = self.offsets.peek() {
+                let Some(delta) = offset.checked_sub(next) else {
+                    break;
+                };
+                match (delta.checked_rem(self.word), delta.checked_div(self.word)) {
+                    (Some(0), Some(index)) if index < bits => {
+                        bitmap |= 1u64.checked_shl(u32::try_from(index).ok()?)?;
+                        self.offsets.next();
+                    }
+                    _ => break,
+                }
+            }
+
+            if bitmap != 0 {
+                self.next = Some(next.wrapping_add(bits.wrapping_mul(self.word)));
+                return Some(bitmap << 1 | 1);
+            }
+        }
+
+        let offset = self.offsets.next()?;
+        self.next = Some(offset.wrapping_add(self.word));
+        Some(offset)
+    }
+}
+
+/// Encodes the offsets of relative relocations as the contents of a
+/// `SHT_RELR` section for `header`'s class. The offsets may be in any order.
+#[cfg(feature = "std")]
+pub fn encode_relr(
+    header: ElfHeader,
+    offsets: impl IntoIterator<Item = u64>,
+) -> Result<Vec<u8>, ElfError> {
+    let word: u8 = match header {
+        ElfHeader::Elf32(_) => 4,
+        ElfHeader::Elf64(_) => 8,
+    };
+    let mut offsets: Vec<u64> = offsets.into_iter().collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+
+    for &offset in &offsets {
+        if offset & u64::from(word).wrapping_sub(1) != 0 {
+            return Err(ElfError::UnalignedRelocation(offset));
+        }
+        if word == 4 && u32::try_from(offset).is_err() {
+            return Err(ElfError::ValueOutOfRange("r_offset", offset));
+        }
+    }
+
+    let mut bytes = Vec::new();
+    for entry in ElfRelrEncoder::new(offsets, word) {
+        let entry = entry.to_le_bytes();
+        bytes.extend_from_slice(entry.get(..usize::from(word)).unwrap_or_default());
+    }
+    Ok(bytes)
+}
+
+const APS2_MAGIC: &[u8] = b"APS2";
+
+/// Every relocation of the group has the same `r_info`.
+const GROUPED_BY_INFO: u64 = 1;
+/// The offsets of the group are evenly spaced.
+const GROUPED_BY_OFFSET_DELTA: u64 = 2;
+/// Every relocation of the group has the same addend.
+const GROUPED_BY_ADDEND: u64 = 4;
+/// The relocations of the group have addends, which are otherwise zero.
+const GROUP_HAS_ADDEND: u64 = 8;
+
+/// A `SHT_ANDROID_REL` or `SHT_ANDROID_RELA` section, in the "APS2" format of
+/// `lld --pack-dyn-relocs=android`.
+///
+/// After the magic come the number of relocations and the initial offset,
+/// then groups of relocations. Each group starts with its size and flags,
+/// and any fields shared by the group, followed by the remaining fields of
+/// each relocation. Offsets and addends are deltas from the previous
+/// relocation's. A group can stand for any number of relocations in a few
+/// bytes, so [`parse`](Self::parse) only reads the header, and iterating
+/// stops early at a malformed relocation.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfAndroidPackedTable<'buf> {
+    bytes: &'buf [u8],
+    is_64: bool,
+    is_rela: bool,
+    len: usize,
+}
+
+impl<'buf> ElfAndroidPackedTable<'buf> {
+    pub fn parse(is_64: bool, is_rela: bool, bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        let mut table = Self {
+            bytes,
+            is_64,
+            is_rela,
+            len: 0,
+        };
+
+        let count = table.decoder()?.remaining;
+        table.len = usize::try_from(count).unwrap_or(usize::MAX);
+        Ok(table)
+    }
+
+    /// The number of relocations the header gives, which there are fewer
+    /// of if the table is malformed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the relocations carry explicit addends.
+    pub fn is_rela(&self) -> bool {
+        self.is_rela
+    }
+
+    fn decoder(&self) -> Result<AndroidPackedDecoder<'buf>, ElfError> {
+        let mut decoder = AndroidPackedDecoder {
+            reader: DwarfReader::new("APS2", self.bytes, 0),
+            is_64: self.is_64,
+            is_rela: self.is_rela,
+            remaining: 0,
+            group_remaining: 0,
+            flags: 0,
+            offset_delta: 0,
+            r_offset: 0,
+            r_info: 0,
+            r_addend: 0,
+        };
+
+        let magic = decoder.reader.bytes(APS2_MAGIC.len());
+        if magic.ok() != Some(APS2_MAGIC) {
+            return Err(ElfError::MalformedPackedRelocations(0));
+        }
+        decoder.remaining = decoder.sleb128()?;
+        decoder.r_offset = decoder.sleb128()?;
+
+        Ok(decoder)
+    }
+}
+
+impl<'buf> IntoIterator for ElfAndroidPackedTable<'buf> {
+    type Item = ElfRelocation;
+    type IntoIter = ElfAndroidPackedIter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ElfAndroidPackedIter {
+            decoder: self.decoder().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfAndroidPackedIter<'buf> {
+    decoder: Option<AndroidPackedDecoder<'buf>>,
+}
+
+impl Iterator for ElfAndroidPackedIter<'_> {
+    type Item = ElfRelocation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decoder.as_mut()?.relocation().ok().flatten()
+    }
+}
+
+/// The state of the decoder, which is the fields of the last relocation
+/// and those shared by the current group. Values are kept as 64 bits, and
+/// truncated for ELF32.
+#[derive(Debug, Clone)]
+struct AndroidPackedDecoder<'buf> {
+    reader: DwarfReader<'buf>,
+    is_64: bool,
+    is_rela: bool,
+    remaining: u64,
+    group_remaining: u64,
+    flags: u64,
+    offset_delta: u64,
+    r_offset: u64,
+    r_info: u64,
+    r_addend: u64,
+}
+
+impl AndroidPackedDecoder<'_> {
+    fn sleb128(&mut self) -> Result<u64, ElfError> {
+        let offset = self.reader.offset();
+        self.reader
+            .sleb128()
+            .map(i64::cast_unsigned)
+            .map_err(|_| ElfError::MalformedPackedRelocations(offset))
+    }
+
+    fn has(&self, flag: u64) -> bool {
+        self.flags & flag != 0
+    }
+
+    fn group(&mut self) -> Result<(), ElfError> {
+        let offset = self.reader.offset();
+        self.group_remaining = self.sleb128()?;
+        self.flags = self.sleb128()?;
+        if self.group_remaining > self.remaining {
+            return Err(ElfError::MalformedPackedRelocations(offset));
+        }
+
+        if self.has(GROUPED_BY_OFFSET_DELTA) {
+            self.offset_delta = self.sleb128()?;
+        }
+        if self.has(GROUPED_BY_INFO) {
+            self.r_info = self.sleb128()?;
+        }
+        if !self.has(GROUP_HAS_ADDEND) {
+            self.r_addend = 0;
+        } else if !self.is_rela {
+            return Err(ElfError::MalformedPackedRelocations(offset));
+        } else if self.has(GROUPED_BY_ADDEND) {
+            self.r_addend = self.r_addend.wrapping_add(self.sleb128()?);
+        }
+
+        Ok(())
+    }
+
+    fn relocation(&mut self) -> Result<Option<ElfRelocation>, ElfError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        while self.group_remaining == 0 {
+            self.group()?;
+        }
+
+        let offset_delta = match self.has(GROUPED_BY_OFFSET_DELTA) {
+            true => self.offset_delta,
+            false => self.sleb128()?,
+        };
+        self.r_offset = self.r_offset.wrapping_add(offset_delta);
+        if !self.has(GROUPED_BY_INFO) {
+            self.r_info = self.sleb128()?;
+        }
+        if self.has(GROUP_HAS_ADDEND) && !self.has(GROUPED_BY_ADDEND) {
+            self.r_addend = self.r_addend.wrapping_add(self.sleb128()?);
+        }
+        self.group_remaining = self.group_remaining.saturating_sub(1);
+        self.remaining = self.remaining.saturating_sub(1);
+
+        let relocation = if self.is_64 {
+            let r_addend = self.is_rela.then_some(self.r_addend.cast_signed());
+            ElfRelocation::from_elf64_info(self.r_offset, self.r_info, r_addend)
+        } else {
+            let r_addend = self.is_rela.then_some(low_u32(self.r_addend).cast_signed());
+            ElfRelocation::from_elf32_info(low_u32(self.r_offset), low_u32(self.r_info), r_addend)
+        };
+        Ok(Some(relocation))
+    }
+}
+
+fn low_u32(value: u64) -> u32 {
+    let [b0, b1, b2, b3, ..] = value.to_le_bytes();
+    u32::from_le_bytes([b0, b1, b2, b3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relr_round_trip() {
+        let offsets = [0x1000, 0x1008, 0x1018, 0x1100, 0x1200, 0x13f0, 0x2000];
+        // 0x1100 is bit 32 of the first bitmap, and 0x13f0 the last bit of
+        // the second.
+        let entries = [0x1000, 0x1_0000_000b, 0x8000_0000_0000_0003, 0x2000];
+
+        assert!(ElfRelrEncoder::new(offsets, 8).eq(entries));
+        assert!(ElfRelrTable::Elf64(&entries).offsets().eq(offsets));
+    }
+
+    #[test]
+    fn decodes_aps2() {
+        // Two relative relocations 8 bytes apart sharing r_info and an
+        // addend, then one with its own fields.
+        let bytes = [
+            b'A', b'P', b'S', b'2', 3, 0x80, 0x20, // count, initial offset 0x1000
+            2, 0xf, 8, 8, 0x10, // group of 2, offsets +8, r_info 8, addend 0x10
+            1, 8, 0x10, 0x81, 0x02, 0x7c, // group of 1, offset +0x10, r_info 0x101, addend -4
+        ];
+        let table = ElfAndroidPackedTable::parse(true, true, &bytes).expect("parsed");
+        assert_eq!(table.len(), 3);
+
+        let relocations = table
+            .into_iter()
+            .map(|r| (r.r_offset, r.r_sym, r.r_type, r.r_addend));
+        assert!(relocations.eq([
+            (0x1008, 0, 8, Some(0x10)),
+            (0x1010, 0, 8, Some(0x10)),
+            (0x1020, 0, 0x101, Some(0xc)),
+        ]));
+
+        // The last relocation is cut short, so isn't decoded.
+        let truncated = bytes
+            .get(..bytes.len().saturating_sub(1))
+            .unwrap_or_default();
+        let table = ElfAndroidPackedTable::parse(true, true, truncated).expect("valid header");
+        assert_eq!(table.into_iter().count(), 2);
+        assert!(matches!(
+            ElfAndroidPackedTable::parse(true, true, b"APS"),
+            Err(ElfError::MalformedPackedRelocations(_))
+        ));
+    }
+
+    #[test]
+    fn doesnt_decode_aps2_up_front() {
+        // 2^40 relocations in one group sharing r_info and an offset delta,
+        // which take no bytes each.
+        let bytes = [
+            b'A', b'P', b'S', b'2', 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0, // count, offset 0
+            0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 3, 8, 8, // group, offsets +8, r_info 8
+        ];
+        let table = ElfAndroidPackedTable::parse(true, false, &bytes).expect("parsed");
+        assert_eq!(table.len(), 1 << 40);
+
+        let relocations = table.into_iter().map(|r| (r.r_offset, r.r_type)).take(2);
+        assert!(relocations.eq([(8, 8), (0x10, 8)]));
+    }
+}
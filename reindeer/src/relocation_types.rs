@@ -0,0 +1,1008 @@
+//! The relocation types of each architecture, with their names, the width of
+//! the field they write and whether the value is PC-relative, so that a
+//! relocation can be shown as `R_X86_64_GLOB_DAT` rather than `6`.
+//!
+//! Use [`ElfRelocationType::new`] to pick the catalogue from `e_machine`.
+
+use crate::{elf_aux_structures::ElfHeaderMachine, macros::*};
+
+/// How the value written by a relocation is computed.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ElfRelocationKind {
+    /// The value is based on a symbol or GOT address, not on the place.
+    Absolute,
+    /// The value is relative to the place being relocated.
+    PcRelative,
+    /// The relocation writes no value, copies data (`COPY`), is a hint or
+    /// marker for the linker, has a platform-defined meaning or operates on
+    /// the LoongArch relocation stack.
+    Other,
+}
+
+/// The relocation types of `EM_386`, from the i386 psABI.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfI386RelocationType(pub u32);
+
+declare_relocation_types!(ElfI386RelocationType, {
+    R_386_NONE = 0 => (0, Other),
+    R_386_32 = 1 => (32, Absolute),
+    R_386_PC32 = 2 => (32, PcRelative),
+    R_386_GOT32 = 3 => (32, Absolute),
+    R_386_PLT32 = 4 => (32, PcRelative),
+    R_386_COPY = 5 => (0, Other),
+    R_386_GLOB_DAT = 6 => (word, Absolute),
+    R_386_JUMP_SLOT = 7 => (word, Absolute),
+    R_386_RELATIVE = 8 => (word, Absolute),
+    R_386_GOTOFF = 9 => (32, Absolute),
+    R_386_GOTPC = 10 => (32, PcRelative),
+    R_386_32PLT = 11 => (32, Absolute),
+    R_386_TLS_TPOFF = 14 => (32, Absolute),
+    R_386_TLS_IE = 15 => (32, Absolute),
+    R_386_TLS_GOTIE = 16 => (32, Absolute),
+    R_386_TLS_LE = 17 => (32, Absolute),
+    R_386_TLS_GD = 18 => (32, Absolute),
+    R_386_TLS_LDM = 19 => (32, Absolute),
+    R_386_16 = 20 => (16, Absolute),
+    R_386_PC16 = 21 => (16, PcRelative),
+    R_386_8 = 22 => (8, Absolute),
+    R_386_PC8 = 23 => (8, PcRelative),
+    R_386_TLS_GD_32 = 24 => (32, Absolute),
+    R_386_TLS_GD_PUSH = 25 => (32, Absolute),
+    R_386_TLS_GD_CALL = 26 => (32, PcRelative),
+    R_386_TLS_GD_POP = 27 => (32, Absolute),
+    R_386_TLS_LDM_32 = 28 => (32, Absolute),
+    R_386_TLS_LDM_PUSH = 29 => (32, Absolute),
+    R_386_TLS_LDM_CALL = 30 => (32, PcRelative),
+    R_386_TLS_LDM_POP = 31 => (32, Absolute),
+    R_386_TLS_LDO_32 = 32 => (32, Absolute),
+    R_386_TLS_IE_32 = 33 => (32, Absolute),
+    R_386_TLS_LE_32 = 34 => (32, Absolute),
+    R_386_TLS_DTPMOD32 = 35 => (32, Absolute),
+    R_386_TLS_DTPOFF32 = 36 => (32, Absolute),
+    R_386_TLS_TPOFF32 = 37 => (32, Absolute),
+    R_386_SIZE32 = 38 => (32, Absolute),
+    R_386_TLS_GOTDESC = 39 => (32, Absolute),
+    R_386_TLS_DESC_CALL = 40 => (0, Other),
+    R_386_TLS_DESC = 41 => (word, Absolute),
+    R_386_IRELATIVE = 42 => (word, Absolute),
+    R_386_GOT32X = 43 => (32, Absolute),
+    R_386_GNU_VTINHERIT = 250 => (0, Other),
+    R_386_GNU_VTENTRY = 251 => (0, Other),
+});
+
+/// The relocation types of `EM_X86_64`, from the x86-64 psABI.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfX86_64RelocationType(pub u32);
+
+declare_relocation_types!(ElfX86_64RelocationType, {
+    R_X86_64_NONE = 0 => (0, Other),
+    R_X86_64_64 = 1 => (64, Absolute),
+    R_X86_64_PC32 = 2 => (32, PcRelative),
+    R_X86_64_GOT32 = 3 => (32, Absolute),
+    R_X86_64_PLT32 = 4 => (32, PcRelative),
+    R_X86_64_COPY = 5 => (0, Other),
+    R_X86_64_GLOB_DAT = 6 => (word, Absolute),
+    R_X86_64_JUMP_SLOT = 7 => (word, Absolute),
+    R_X86_64_RELATIVE = 8 => (word, Absolute),
+    R_X86_64_GOTPCREL = 9 => (32, PcRelative),
+    R_X86_64_32 = 10 => (32, Absolute),
+    R_X86_64_32S = 11 => (32, Absolute),
+    R_X86_64_16 = 12 => (16, Absolute),
+    R_X86_64_PC16 = 13 => (16, PcRelative),
+    R_X86_64_8 = 14 => (8, Absolute),
+    R_X86_64_PC8 = 15 => (8, PcRelative),
+    R_X86_64_DTPMOD64 = 16 => (64, Absolute),
+    R_X86_64_DTPOFF64 = 17 => (64, Absolute),
+    R_X86_64_TPOFF64 = 18 => (64, Absolute),
+    R_X86_64_TLSGD = 19 => (32, PcRelative),
+    R_X86_64_TLSLD = 20 => (32, PcRelative),
+    R_X86_64_DTPOFF32 = 21 => (32, Absolute),
+    R_X86_64_GOTTPOFF = 22 => (32, PcRelative),
+    R_X86_64_TPOFF32 = 23 => (32, Absolute),
+    R_X86_64_PC64 = 24 => (64, PcRelative),
+    R_X86_64_GOTOFF64 = 25 => (64, Absolute),
+    R_X86_64_GOTPC32 = 26 => (32, PcRelative),
+    R_X86_64_GOT64 = 27 => (64, Absolute),
+    R_X86_64_GOTPCREL64 = 28 => (64, PcRelative),
+    R_X86_64_GOTPC64 = 29 => (64, PcRelative),
+    R_X86_64_GOTPLT64 = 30 => (64, Absolute),
+    R_X86_64_PLTOFF64 = 31 => (64, Absolute),
+    R_X86_64_SIZE32 = 32 => (32, Absolute),
+    R_X86_64_SIZE64 = 33 => (64, Absolute),
+    R_X86_64_GOTPC32_TLSDESC = 34 => (32, PcRelative),
+    R_X86_64_TLSDESC_CALL = 35 => (0, Other),
+    R_X86_64_TLSDESC = 36 => (word, Absolute),
+    R_X86_64_IRELATIVE = 37 => (word, Absolute),
+    R_X86_64_RELATIVE64 = 38 => (64, Absolute),
+    R_X86_64_GOTPCRELX = 41 => (32, PcRelative),
+    R_X86_64_REX_GOTPCRELX = 42 => (32, PcRelative),
+    R_X86_64_CODE_4_GOTPCRELX = 43 => (32, PcRelative),
+    R_X86_64_CODE_4_GOTTPOFF = 44 => (32, PcRelative),
+    R_X86_64_CODE_4_GOTPC32_TLSDESC = 45 => (32, PcRelative),
+    R_X86_64_CODE_5_GOTPCRELX = 46 => (32, PcRelative),
+    R_X86_64_CODE_5_GOTTPOFF = 47 => (32, PcRelative),
+    R_X86_64_CODE_5_GOTPC32_TLSDESC = 48 => (32, PcRelative),
+    R_X86_64_CODE_6_GOTPCRELX = 49 => (32, PcRelative),
+    R_X86_64_CODE_6_GOTTPOFF = 50 => (32, PcRelative),
+    R_X86_64_CODE_6_GOTPC32_TLSDESC = 51 => (32, PcRelative),
+    R_X86_64_GNU_VTINHERIT = 250 => (0, Other),
+    R_X86_64_GNU_VTENTRY = 251 => (0, Other),
+});
+
+/// The relocation types of `EM_ARM`, from the Arm AAELF32.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfArmRelocationType(pub u32);
+
+declare_relocation_types!(ElfArmRelocationType, {
+    R_ARM_NONE = 0 => (0, Other),
+    R_ARM_PC24 = 1 => (32, PcRelative),
+    R_ARM_ABS32 = 2 => (32, Absolute),
+    R_ARM_REL32 = 3 => (32, PcRelative),
+    R_ARM_LDR_PC_G0 = 4 => (32, PcRelative),
+    R_ARM_ABS16 = 5 => (16, Absolute),
+    R_ARM_ABS12 = 6 => (32, Absolute),
+    R_ARM_THM_ABS5 = 7 => (16, Absolute),
+    R_ARM_ABS8 = 8 => (8, Absolute),
+    R_ARM_SBREL32 = 9 => (32, Absolute),
+    R_ARM_THM_CALL = 10 => (32, PcRelative),
+    R_ARM_THM_PC8 = 11 => (16, PcRelative),
+    R_ARM_BREL_ADJ = 12 => (32, Absolute),
+    R_ARM_TLS_DESC = 13 => (word, Absolute),
+    R_ARM_THM_SWI8 = 14 => (16, Other),
+    R_ARM_XPC25 = 15 => (32, PcRelative),
+    R_ARM_THM_XPC22 = 16 => (32, PcRelative),
+    R_ARM_TLS_DTPMOD32 = 17 => (32, Absolute),
+    R_ARM_TLS_DTPOFF32 = 18 => (32, Absolute),
+    R_ARM_TLS_TPOFF32 = 19 => (32, Absolute),
+    R_ARM_COPY = 20 => (0, Other),
+    R_ARM_GLOB_DAT = 21 => (word, Absolute),
+    R_ARM_JUMP_SLOT = 22 => (word, Absolute),
+    R_ARM_RELATIVE = 23 => (word, Absolute),
+    R_ARM_GOTOFF32 = 24 => (32, Absolute),
+    R_ARM_BASE_PREL = 25 => (32, PcRelative),
+    R_ARM_GOT_BREL = 26 => (32, Absolute),
+    R_ARM_PLT32 = 27 => (32, PcRelative),
+    R_ARM_CALL = 28 => (32, PcRelative),
+    R_ARM_JUMP24 = 29 => (32, PcRelative),
+    R_ARM_THM_JUMP24 = 30 => (32, PcRelative),
+    R_ARM_BASE_ABS = 31 => (32, Absolute),
+    R_ARM_ALU_PCREL7_0 = 32 => (32, PcRelative),
+    R_ARM_ALU_PCREL15_8 = 33 => (32, PcRelative),
+    R_ARM_ALU_PCREL23_15 = 34 => (32, PcRelative),
+    R_ARM_LDR_SBREL_11_0 = 35 => (32, Absolute),
+    R_ARM_ALU_SBREL_19_12 = 36 => (32, Absolute),
+    R_ARM_ALU_SBREL_27_20 = 37 => (32, Absolute),
+    R_ARM_TARGET1 = 38 => (32, Other),
+    R_ARM_SBREL31 = 39 => (32, Absolute),
+    R_ARM_V4BX = 40 => (32, Other),
+    R_ARM_TARGET2 = 41 => (32, Other),
+    R_ARM_PREL31 = 42 => (32, PcRelative),
+    R_ARM_MOVW_ABS_NC = 43 => (32, Absolute),
+    R_ARM_MOVT_ABS = 44 => (32, Absolute),
+    R_ARM_MOVW_PREL_NC = 45 => (32, PcRelative),
+    R_ARM_MOVT_PREL = 46 => (32, PcRelative),
+    R_ARM_THM_MOVW_ABS_NC = 47 => (32, Absolute),
+    R_ARM_THM_MOVT_ABS = 48 => (32, Absolute),
+    R_ARM_THM_MOVW_PREL_NC = 49 => (32, PcRelative),
+    R_ARM_THM_MOVT_PREL = 50 => (32, PcRelative),
+    R_ARM_THM_JUMP19 = 51 => (32, PcRelative),
+    R_ARM_THM_JUMP6 = 52 => (16, PcRelative),
+    R_ARM_THM_ALU_PREL_11_0 = 53 => (32, PcRelative),
+    R_ARM_THM_PC12 = 54 => (32, PcRelative),
+    R_ARM_ABS32_NOI = 55 => (32, Absolute),
+    R_ARM_REL32_NOI = 56 => (32, PcRelative),
+    R_ARM_ALU_PC_G0_NC = 57 => (32, PcRelative),
+    R_ARM_ALU_PC_G0 = 58 => (32, PcRelative),
+    R_ARM_ALU_PC_G1_NC = 59 => (32, PcRelative),
+    R_ARM_ALU_PC_G1 = 60 => (32, PcRelative),
+    R_ARM_ALU_PC_G2 = 61 => (32, PcRelative),
+    R_ARM_LDR_PC_G1 = 62 => (32, PcRelative),
+    R_ARM_LDR_PC_G2 = 63 => (32, PcRelative),
+    R_ARM_LDRS_PC_G0 = 64 => (32, PcRelative),
+    R_ARM_LDRS_PC_G1 = 65 => (32, PcRelative),
+    R_ARM_LDRS_PC_G2 = 66 => (32, PcRelative),
+    R_ARM_LDC_PC_G0 = 67 => (32, PcRelative),
+    R_ARM_LDC_PC_G1 = 68 => (32, PcRelative),
+    R_ARM_LDC_PC_G2 = 69 => (32, PcRelative),
+    R_ARM_ALU_SB_G0_NC = 70 => (32, Absolute),
+    R_ARM_ALU_SB_G0 = 71 => (32, Absolute),
+    R_ARM_ALU_SB_G1_NC = 72 => (32, Absolute),
+    R_ARM_ALU_SB_G1 = 73 => (32, Absolute),
+    R_ARM_ALU_SB_G2 = 74 => (32, Absolute),
+    R_ARM_LDR_SB_G0 = 75 => (32, Absolute),
+    R_ARM_LDR_SB_G1 = 76 => (32, Absolute),
+    R_ARM_LDR_SB_G2 = 77 => (32, Absolute),
+    R_ARM_LDRS_SB_G0 = 78 => (32, Absolute),
+    R_ARM_LDRS_SB_G1 = 79 => (32, Absolute),
+    R_ARM_LDRS_SB_G2 = 80 => (32, Absolute),
+    R_ARM_LDC_SB_G0 = 81 => (32, Absolute),
+    R_ARM_LDC_SB_G1 = 82 => (32, Absolute),
+    R_ARM_LDC_SB_G2 = 83 => (32, Absolute),
+    R_ARM_MOVW_BREL_NC = 84 => (32, Absolute),
+    R_ARM_MOVT_BREL = 85 => (32, Absolute),
+    R_ARM_MOVW_BREL = 86 => (32, Absolute),
+    R_ARM_THM_MOVW_BREL_NC = 87 => (32, Absolute),
+    R_ARM_THM_MOVT_BREL = 88 => (32, Absolute),
+    R_ARM_THM_MOVW_BREL = 89 => (32, Absolute),
+    R_ARM_TLS_GOTDESC = 90 => (32, Absolute),
+    R_ARM_TLS_CALL = 91 => (32, Other),
+    R_ARM_TLS_DESCSEQ = 92 => (32, Other),
+    R_ARM_THM_TLS_CALL = 93 => (32, Other),
+    R_ARM_PLT32_ABS = 94 => (32, Absolute),
+    R_ARM_GOT_ABS = 95 => (32, Absolute),
+    R_ARM_GOT_PREL = 96 => (32, PcRelative),
+    R_ARM_GOT_BREL12 = 97 => (32, Absolute),
+    R_ARM_GOTOFF12 = 98 => (32, Absolute),
+    R_ARM_GOTRELAX = 99 => (0, Other),
+    R_ARM_GNU_VTENTRY = 100 => (0, Other),
+    R_ARM_GNU_VTINHERIT = 101 => (0, Other),
+    R_ARM_THM_JUMP11 = 102 => (16, PcRelative),
+    R_ARM_THM_JUMP8 = 103 => (16, PcRelative),
+    R_ARM_TLS_GD32 = 104 => (32, PcRelative),
+    R_ARM_TLS_LDM32 = 105 => (32, PcRelative),
+    R_ARM_TLS_LDO32 = 106 => (32, Absolute),
+    R_ARM_TLS_IE32 = 107 => (32, PcRelative),
+    R_ARM_TLS_LE32 = 108 => (32, Absolute),
+    R_ARM_TLS_LDO12 = 109 => (32, Absolute),
+    R_ARM_TLS_LE12 = 110 => (32, Absolute),
+    R_ARM_TLS_IE12GP = 111 => (32, Absolute),
+    R_ARM_THM_TLS_DESCSEQ = 129 => (16, Other),
+    R_ARM_THM_ALU_ABS_G0_NC = 132 => (16, Absolute),
+    R_ARM_THM_ALU_ABS_G1_NC = 133 => (16, Absolute),
+    R_ARM_THM_ALU_ABS_G2_NC = 134 => (16, Absolute),
+    R_ARM_THM_ALU_ABS_G3_NC = 135 => (16, Absolute),
+    R_ARM_THM_BF16 = 136 => (32, PcRelative),
+    R_ARM_THM_BF12 = 137 => (32, PcRelative),
+    R_ARM_THM_BF18 = 138 => (32, PcRelative),
+    R_ARM_IRELATIVE = 160 => (word, Absolute),
+    R_ARM_GOTFUNCDESC = 161 => (32, Absolute),
+    R_ARM_GOTOFFFUNCDESC = 162 => (32, Absolute),
+    R_ARM_FUNCDESC = 163 => (32, Absolute),
+    R_ARM_FUNCDESC_VALUE = 164 => (64, Absolute),
+    R_ARM_TLS_GD32_FDPIC = 165 => (32, Absolute),
+    R_ARM_TLS_LDM32_FDPIC = 166 => (32, Absolute),
+    R_ARM_TLS_IE32_FDPIC = 167 => (32, Absolute),
+});
+
+/// The relocation types of `EM_AARCH64`, from the Arm AAELF64, for the
+/// LP64 data model only.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfAarch64RelocationType(pub u32);
+
+declare_relocation_types!(ElfAarch64RelocationType, {
+    R_AARCH64_NONE = 0 => (0, Other),
+    R_AARCH64_ABS64 = 257 => (64, Absolute),
+    R_AARCH64_ABS32 = 258 => (32, Absolute),
+    R_AARCH64_ABS16 = 259 => (16, Absolute),
+    R_AARCH64_PREL64 = 260 => (64, PcRelative),
+    R_AARCH64_PREL32 = 261 => (32, PcRelative),
+    R_AARCH64_PREL16 = 262 => (16, PcRelative),
+    R_AARCH64_MOVW_UABS_G0 = 263 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G0_NC = 264 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G1 = 265 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G1_NC = 266 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G2 = 267 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G2_NC = 268 => (32, Absolute),
+    R_AARCH64_MOVW_UABS_G3 = 269 => (32, Absolute),
+    R_AARCH64_MOVW_SABS_G0 = 270 => (32, Absolute),
+    R_AARCH64_MOVW_SABS_G1 = 271 => (32, Absolute),
+    R_AARCH64_MOVW_SABS_G2 = 272 => (32, Absolute),
+    R_AARCH64_LD_PREL_LO19 = 273 => (32, PcRelative),
+    R_AARCH64_ADR_PREL_LO21 = 274 => (32, PcRelative),
+    R_AARCH64_ADR_PREL_PG_HI21 = 275 => (32, PcRelative),
+    R_AARCH64_ADR_PREL_PG_HI21_NC = 276 => (32, PcRelative),
+    R_AARCH64_ADD_ABS_LO12_NC = 277 => (32, Absolute),
+    R_AARCH64_LDST8_ABS_LO12_NC = 278 => (32, Absolute),
+    R_AARCH64_TSTBR14 = 279 => (32, PcRelative),
+    R_AARCH64_CONDBR19 = 280 => (32, PcRelative),
+    R_AARCH64_JUMP26 = 282 => (32, PcRelative),
+    R_AARCH64_CALL26 = 283 => (32, PcRelative),
+    R_AARCH64_LDST16_ABS_LO12_NC = 284 => (32, Absolute),
+    R_AARCH64_LDST32_ABS_LO12_NC = 285 => (32, Absolute),
+    R_AARCH64_LDST64_ABS_LO12_NC = 286 => (32, Absolute),
+    R_AARCH64_MOVW_PREL_G0 = 287 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G0_NC = 288 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G1 = 289 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G1_NC = 290 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G2 = 291 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G2_NC = 292 => (32, PcRelative),
+    R_AARCH64_MOVW_PREL_G3 = 293 => (32, PcRelative),
+    R_AARCH64_LDST128_ABS_LO12_NC = 299 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G0 = 300 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G0_NC = 301 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G1 = 302 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G1_NC = 303 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G2 = 304 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G2_NC = 305 => (32, Absolute),
+    R_AARCH64_MOVW_GOTOFF_G3 = 306 => (32, Absolute),
+    R_AARCH64_GOTREL64 = 307 => (64, Absolute),
+    R_AARCH64_GOTREL32 = 308 => (32, Absolute),
+    R_AARCH64_GOT_LD_PREL19 = 309 => (32, PcRelative),
+    R_AARCH64_LD64_GOTOFF_LO15 = 310 => (32, Absolute),
+    R_AARCH64_ADR_GOT_PAGE = 311 => (32, PcRelative),
+    R_AARCH64_LD64_GOT_LO12_NC = 312 => (32, Absolute),
+    R_AARCH64_LD64_GOTPAGE_LO15 = 313 => (32, Absolute),
+    R_AARCH64_PLT32 = 314 => (32, PcRelative),
+    R_AARCH64_GOTPCREL32 = 315 => (32, PcRelative),
+    R_AARCH64_TLSGD_ADR_PREL21 = 512 => (32, PcRelative),
+    R_AARCH64_TLSGD_ADR_PAGE21 = 513 => (32, PcRelative),
+    R_AARCH64_TLSGD_ADD_LO12_NC = 514 => (32, Absolute),
+    R_AARCH64_TLSGD_MOVW_G1 = 515 => (32, Absolute),
+    R_AARCH64_TLSGD_MOVW_G0_NC = 516 => (32, Absolute),
+    R_AARCH64_TLSLD_ADR_PREL21 = 517 => (32, PcRelative),
+    R_AARCH64_TLSLD_ADR_PAGE21 = 518 => (32, PcRelative),
+    R_AARCH64_TLSLD_ADD_LO12_NC = 519 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_G1 = 520 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_G0_NC = 521 => (32, Absolute),
+    R_AARCH64_TLSLD_LD_PREL19 = 522 => (32, PcRelative),
+    R_AARCH64_TLSLD_MOVW_DTPREL_G2 = 523 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_DTPREL_G1 = 524 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_DTPREL_G1_NC = 525 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_DTPREL_G0 = 526 => (32, Absolute),
+    R_AARCH64_TLSLD_MOVW_DTPREL_G0_NC = 527 => (32, Absolute),
+    R_AARCH64_TLSLD_ADD_DTPREL_HI12 = 528 => (32, Absolute),
+    R_AARCH64_TLSLD_ADD_DTPREL_LO12 = 529 => (32, Absolute),
+    R_AARCH64_TLSLD_ADD_DTPREL_LO12_NC = 530 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST8_DTPREL_LO12 = 531 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST8_DTPREL_LO12_NC = 532 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST16_DTPREL_LO12 = 533 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST16_DTPREL_LO12_NC = 534 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST32_DTPREL_LO12 = 535 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST32_DTPREL_LO12_NC = 536 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST64_DTPREL_LO12 = 537 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST64_DTPREL_LO12_NC = 538 => (32, Absolute),
+    R_AARCH64_TLSIE_MOVW_GOTTPREL_G1 = 539 => (32, Absolute),
+    R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC = 540 => (32, Absolute),
+    R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21 = 541 => (32, PcRelative),
+    R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC = 542 => (32, Absolute),
+    R_AARCH64_TLSIE_LD_GOTTPREL_PREL19 = 543 => (32, PcRelative),
+    R_AARCH64_TLSLE_MOVW_TPREL_G2 = 544 => (32, Absolute),
+    R_AARCH64_TLSLE_MOVW_TPREL_G1 = 545 => (32, Absolute),
+    R_AARCH64_TLSLE_MOVW_TPREL_G1_NC = 546 => (32, Absolute),
+    R_AARCH64_TLSLE_MOVW_TPREL_G0 = 547 => (32, Absolute),
+    R_AARCH64_TLSLE_MOVW_TPREL_G0_NC = 548 => (32, Absolute),
+    R_AARCH64_TLSLE_ADD_TPREL_HI12 = 549 => (32, Absolute),
+    R_AARCH64_TLSLE_ADD_TPREL_LO12 = 550 => (32, Absolute),
+    R_AARCH64_TLSLE_ADD_TPREL_LO12_NC = 551 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST8_TPREL_LO12 = 552 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST8_TPREL_LO12_NC = 553 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST16_TPREL_LO12 = 554 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST16_TPREL_LO12_NC = 555 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST32_TPREL_LO12 = 556 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST32_TPREL_LO12_NC = 557 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST64_TPREL_LO12 = 558 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST64_TPREL_LO12_NC = 559 => (32, Absolute),
+    R_AARCH64_TLSDESC_LD_PREL19 = 560 => (32, PcRelative),
+    R_AARCH64_TLSDESC_ADR_PREL21 = 561 => (32, PcRelative),
+    R_AARCH64_TLSDESC_ADR_PAGE21 = 562 => (32, PcRelative),
+    R_AARCH64_TLSDESC_LD64_LO12 = 563 => (32, Absolute),
+    R_AARCH64_TLSDESC_ADD_LO12 = 564 => (32, Absolute),
+    R_AARCH64_TLSDESC_OFF_G1 = 565 => (32, Absolute),
+    R_AARCH64_TLSDESC_OFF_G0_NC = 566 => (32, Absolute),
+    R_AARCH64_TLSDESC_LDR = 567 => (0, Other),
+    R_AARCH64_TLSDESC_ADD = 568 => (0, Other),
+    R_AARCH64_TLSDESC_CALL = 569 => (0, Other),
+    R_AARCH64_TLSLE_LDST128_TPREL_LO12 = 570 => (32, Absolute),
+    R_AARCH64_TLSLE_LDST128_TPREL_LO12_NC = 571 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST128_DTPREL_LO12 = 572 => (32, Absolute),
+    R_AARCH64_TLSLD_LDST128_DTPREL_LO12_NC = 573 => (32, Absolute),
+    R_AARCH64_COPY = 1024 => (0, Other),
+    R_AARCH64_GLOB_DAT = 1025 => (word, Absolute),
+    R_AARCH64_JUMP_SLOT = 1026 => (word, Absolute),
+    R_AARCH64_RELATIVE = 1027 => (word, Absolute),
+    R_AARCH64_TLS_DTPMOD64 = 1028 => (word, Absolute),
+    R_AARCH64_TLS_DTPREL64 = 1029 => (word, Absolute),
+    R_AARCH64_TLS_TPREL64 = 1030 => (word, Absolute),
+    R_AARCH64_TLSDESC = 1031 => (word, Absolute),
+    R_AARCH64_IRELATIVE = 1032 => (word, Absolute),
+});
+
+/// The relocation types of `EM_RISCV`, from the RISC-V psABI.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfRiscvRelocationType(pub u32);
+
+declare_relocation_types!(ElfRiscvRelocationType, {
+    R_RISCV_NONE = 0 => (0, Other),
+    R_RISCV_32 = 1 => (32, Absolute),
+    R_RISCV_64 = 2 => (64, Absolute),
+    R_RISCV_RELATIVE = 3 => (word, Absolute),
+    R_RISCV_COPY = 4 => (0, Other),
+    R_RISCV_JUMP_SLOT = 5 => (word, Absolute),
+    R_RISCV_TLS_DTPMOD32 = 6 => (32, Absolute),
+    R_RISCV_TLS_DTPMOD64 = 7 => (64, Absolute),
+    R_RISCV_TLS_DTPREL32 = 8 => (32, Absolute),
+    R_RISCV_TLS_DTPREL64 = 9 => (64, Absolute),
+    R_RISCV_TLS_TPREL32 = 10 => (32, Absolute),
+    R_RISCV_TLS_TPREL64 = 11 => (64, Absolute),
+    R_RISCV_TLSDESC = 12 => (word, Absolute),
+    R_RISCV_BRANCH = 16 => (32, PcRelative),
+    R_RISCV_JAL = 17 => (32, PcRelative),
+    R_RISCV_CALL = 18 => (64, PcRelative),
+    R_RISCV_CALL_PLT = 19 => (64, PcRelative),
+    R_RISCV_GOT_HI20 = 20 => (32, PcRelative),
+    R_RISCV_TLS_GOT_HI20 = 21 => (32, PcRelative),
+    R_RISCV_TLS_GD_HI20 = 22 => (32, PcRelative),
+    R_RISCV_PCREL_HI20 = 23 => (32, PcRelative),
+    R_RISCV_PCREL_LO12_I = 24 => (32, PcRelative),
+    R_RISCV_PCREL_LO12_S = 25 => (32, PcRelative),
+    R_RISCV_HI20 = 26 => (32, Absolute),
+    R_RISCV_LO12_I = 27 => (32, Absolute),
+    R_RISCV_LO12_S = 28 => (32, Absolute),
+    R_RISCV_TPREL_HI20 = 29 => (32, Absolute),
+    R_RISCV_TPREL_LO12_I = 30 => (32, Absolute),
+    R_RISCV_TPREL_LO12_S = 31 => (32, Absolute),
+    R_RISCV_TPREL_ADD = 32 => (0, Other),
+    R_RISCV_ADD8 = 33 => (8, Absolute),
+    R_RISCV_ADD16 = 34 => (16, Absolute),
+    R_RISCV_ADD32 = 35 => (32, Absolute),
+    R_RISCV_ADD64 = 36 => (64, Absolute),
+    R_RISCV_SUB8 = 37 => (8, Absolute),
+    R_RISCV_SUB16 = 38 => (16, Absolute),
+    R_RISCV_SUB32 = 39 => (32, Absolute),
+    R_RISCV_SUB64 = 40 => (64, Absolute),
+    R_RISCV_GOT32_PCREL = 41 => (32, PcRelative),
+    R_RISCV_ALIGN = 43 => (0, Other),
+    R_RISCV_RVC_BRANCH = 44 => (16, PcRelative),
+    R_RISCV_RVC_JUMP = 45 => (16, PcRelative),
+    R_RISCV_RVC_LUI = 46 => (16, Absolute),
+    R_RISCV_GPREL_I = 47 => (32, Absolute),
+    R_RISCV_GPREL_S = 48 => (32, Absolute),
+    R_RISCV_TPREL_I = 49 => (32, Absolute),
+    R_RISCV_TPREL_S = 50 => (32, Absolute),
+    R_RISCV_RELAX = 51 => (0, Other),
+    R_RISCV_SUB6 = 52 => (8, Absolute),
+    R_RISCV_SET6 = 53 => (8, Absolute),
+    R_RISCV_SET8 = 54 => (8, Absolute),
+    R_RISCV_SET16 = 55 => (16, Absolute),
+    R_RISCV_SET32 = 56 => (32, Absolute),
+    R_RISCV_32_PCREL = 57 => (32, PcRelative),
+    R_RISCV_IRELATIVE = 58 => (word, Absolute),
+    R_RISCV_PLT32 = 59 => (32, PcRelative),
+    R_RISCV_SET_ULEB128 = 60 => (0, Absolute),
+    R_RISCV_SUB_ULEB128 = 61 => (0, Absolute),
+    R_RISCV_TLSDESC_HI20 = 62 => (32, PcRelative),
+    R_RISCV_TLSDESC_LOAD_LO12 = 63 => (32, PcRelative),
+    R_RISCV_TLSDESC_ADD_LO12 = 64 => (32, PcRelative),
+    R_RISCV_TLSDESC_CALL = 65 => (0, Other),
+});
+
+/// The relocation types of `EM_PPC64`, from the 64-bit ELF V2 ABI.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfPpc64RelocationType(pub u32);
+
+declare_relocation_types!(ElfPpc64RelocationType, {
+    R_PPC64_NONE = 0 => (0, Other),
+    R_PPC64_ADDR32 = 1 => (32, Absolute),
+    R_PPC64_ADDR24 = 2 => (32, Absolute),
+    R_PPC64_ADDR16 = 3 => (16, Absolute),
+    R_PPC64_ADDR16_LO = 4 => (16, Absolute),
+    R_PPC64_ADDR16_HI = 5 => (16, Absolute),
+    R_PPC64_ADDR16_HA = 6 => (16, Absolute),
+    R_PPC64_ADDR14 = 7 => (32, Absolute),
+    R_PPC64_ADDR14_BRTAKEN = 8 => (32, Absolute),
+    R_PPC64_ADDR14_BRNTAKEN = 9 => (32, Absolute),
+    R_PPC64_REL24 = 10 => (32, PcRelative),
+    R_PPC64_REL14 = 11 => (32, PcRelative),
+    R_PPC64_REL14_BRTAKEN = 12 => (32, PcRelative),
+    R_PPC64_REL14_BRNTAKEN = 13 => (32, PcRelative),
+    R_PPC64_GOT16 = 14 => (16, Absolute),
+    R_PPC64_GOT16_LO = 15 => (16, Absolute),
+    R_PPC64_GOT16_HI = 16 => (16, Absolute),
+    R_PPC64_GOT16_HA = 17 => (16, Absolute),
+    R_PPC64_COPY = 19 => (0, Other),
+    R_PPC64_GLOB_DAT = 20 => (word, Absolute),
+    R_PPC64_JMP_SLOT = 21 => (word, Absolute),
+    R_PPC64_RELATIVE = 22 => (word, Absolute),
+    R_PPC64_UADDR32 = 24 => (32, Absolute),
+    R_PPC64_UADDR16 = 25 => (16, Absolute),
+    R_PPC64_REL32 = 26 => (32, PcRelative),
+    R_PPC64_PLT32 = 27 => (32, Absolute),
+    R_PPC64_PLTREL32 = 28 => (32, PcRelative),
+    R_PPC64_PLT16_LO = 29 => (16, Absolute),
+    R_PPC64_PLT16_HI = 30 => (16, Absolute),
+    R_PPC64_PLT16_HA = 31 => (16, Absolute),
+    R_PPC64_SECTOFF = 33 => (16, Absolute),
+    R_PPC64_SECTOFF_LO = 34 => (16, Absolute),
+    R_PPC64_SECTOFF_HI = 35 => (16, Absolute),
+    R_PPC64_SECTOFF_HA = 36 => (16, Absolute),
+    R_PPC64_REL30 = 37 => (32, PcRelative),
+    R_PPC64_ADDR64 = 38 => (64, Absolute),
+    R_PPC64_ADDR16_HIGHER = 39 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHERA = 40 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHEST = 41 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHESTA = 42 => (16, Absolute),
+    R_PPC64_UADDR64 = 43 => (64, Absolute),
+    R_PPC64_REL64 = 44 => (64, PcRelative),
+    R_PPC64_PLT64 = 45 => (64, Absolute),
+    R_PPC64_PLTREL64 = 46 => (64, PcRelative),
+    R_PPC64_TOC16 = 47 => (16, Absolute),
+    R_PPC64_TOC16_LO = 48 => (16, Absolute),
+    R_PPC64_TOC16_HI = 49 => (16, Absolute),
+    R_PPC64_TOC16_HA = 50 => (16, Absolute),
+    R_PPC64_TOC = 51 => (64, Absolute),
+    R_PPC64_PLTGOT16 = 52 => (16, Absolute),
+    R_PPC64_PLTGOT16_LO = 53 => (16, Absolute),
+    R_PPC64_PLTGOT16_HI = 54 => (16, Absolute),
+    R_PPC64_PLTGOT16_HA = 55 => (16, Absolute),
+    R_PPC64_ADDR16_DS = 56 => (16, Absolute),
+    R_PPC64_ADDR16_LO_DS = 57 => (16, Absolute),
+    R_PPC64_GOT16_DS = 58 => (16, Absolute),
+    R_PPC64_GOT16_LO_DS = 59 => (16, Absolute),
+    R_PPC64_PLT16_LO_DS = 60 => (16, Absolute),
+    R_PPC64_SECTOFF_DS = 61 => (16, Absolute),
+    R_PPC64_SECTOFF_LO_DS = 62 => (16, Absolute),
+    R_PPC64_TOC16_DS = 63 => (16, Absolute),
+    R_PPC64_TOC16_LO_DS = 64 => (16, Absolute),
+    R_PPC64_PLTGOT16_DS = 65 => (16, Absolute),
+    R_PPC64_PLTGOT16_LO_DS = 66 => (16, Absolute),
+    R_PPC64_TLS = 67 => (0, Other),
+    R_PPC64_DTPMOD64 = 68 => (64, Absolute),
+    R_PPC64_TPREL16 = 69 => (16, Absolute),
+    R_PPC64_TPREL16_LO = 70 => (16, Absolute),
+    R_PPC64_TPREL16_HI = 71 => (16, Absolute),
+    R_PPC64_TPREL16_HA = 72 => (16, Absolute),
+    R_PPC64_TPREL64 = 73 => (64, Absolute),
+    R_PPC64_DTPREL16 = 74 => (16, Absolute),
+    R_PPC64_DTPREL16_LO = 75 => (16, Absolute),
+    R_PPC64_DTPREL16_HI = 76 => (16, Absolute),
+    R_PPC64_DTPREL16_HA = 77 => (16, Absolute),
+    R_PPC64_DTPREL64 = 78 => (64, Absolute),
+    R_PPC64_GOT_TLSGD16 = 79 => (16, Absolute),
+    R_PPC64_GOT_TLSGD16_LO = 80 => (16, Absolute),
+    R_PPC64_GOT_TLSGD16_HI = 81 => (16, Absolute),
+    R_PPC64_GOT_TLSGD16_HA = 82 => (16, Absolute),
+    R_PPC64_GOT_TLSLD16 = 83 => (16, Absolute),
+    R_PPC64_GOT_TLSLD16_LO = 84 => (16, Absolute),
+    R_PPC64_GOT_TLSLD16_HI = 85 => (16, Absolute),
+    R_PPC64_GOT_TLSLD16_HA = 86 => (16, Absolute),
+    R_PPC64_GOT_TPREL16_DS = 87 => (16, Absolute),
+    R_PPC64_GOT_TPREL16_LO_DS = 88 => (16, Absolute),
+    R_PPC64_GOT_TPREL16_HI = 89 => (16, Absolute),
+    R_PPC64_GOT_TPREL16_HA = 90 => (16, Absolute),
+    R_PPC64_GOT_DTPREL16_DS = 91 => (16, Absolute),
+    R_PPC64_GOT_DTPREL16_LO_DS = 92 => (16, Absolute),
+    R_PPC64_GOT_DTPREL16_HI = 93 => (16, Absolute),
+    R_PPC64_GOT_DTPREL16_HA = 94 => (16, Absolute),
+    R_PPC64_TPREL16_DS = 95 => (16, Absolute),
+    R_PPC64_TPREL16_LO_DS = 96 => (16, Absolute),
+    R_PPC64_TPREL16_HIGHER = 97 => (16, Absolute),
+    R_PPC64_TPREL16_HIGHERA = 98 => (16, Absolute),
+    R_PPC64_TPREL16_HIGHEST = 99 => (16, Absolute),
+    R_PPC64_TPREL16_HIGHESTA = 100 => (16, Absolute),
+    R_PPC64_DTPREL16_DS = 101 => (16, Absolute),
+    R_PPC64_DTPREL16_LO_DS = 102 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGHER = 103 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGHERA = 104 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGHEST = 105 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGHESTA = 106 => (16, Absolute),
+    R_PPC64_TLSGD = 107 => (0, Other),
+    R_PPC64_TLSLD = 108 => (0, Other),
+    R_PPC64_TOCSAVE = 109 => (0, Other),
+    R_PPC64_ADDR16_HIGH = 110 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHA = 111 => (16, Absolute),
+    R_PPC64_TPREL16_HIGH = 112 => (16, Absolute),
+    R_PPC64_TPREL16_HIGHA = 113 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGH = 114 => (16, Absolute),
+    R_PPC64_DTPREL16_HIGHA = 115 => (16, Absolute),
+    R_PPC64_REL24_NOTOC = 116 => (32, PcRelative),
+    R_PPC64_ADDR64_LOCAL = 117 => (64, Absolute),
+    R_PPC64_ENTRY = 118 => (0, Other),
+    R_PPC64_PLTSEQ = 119 => (0, Other),
+    R_PPC64_PLTCALL = 120 => (0, Other),
+    R_PPC64_PLTSEQ_NOTOC = 121 => (0, Other),
+    R_PPC64_PLTCALL_NOTOC = 122 => (0, Other),
+    R_PPC64_PCREL_OPT = 123 => (0, Other),
+    R_PPC64_REL24_P9NOTOC = 124 => (32, PcRelative),
+    R_PPC64_D34 = 128 => (64, Absolute),
+    R_PPC64_D34_LO = 129 => (64, Absolute),
+    R_PPC64_D34_HI30 = 130 => (64, Absolute),
+    R_PPC64_D34_HA30 = 131 => (64, Absolute),
+    R_PPC64_PCREL34 = 132 => (64, PcRelative),
+    R_PPC64_GOT_PCREL34 = 133 => (64, PcRelative),
+    R_PPC64_PLT_PCREL34 = 134 => (64, PcRelative),
+    R_PPC64_PLT_PCREL34_NOTOC = 135 => (64, PcRelative),
+    R_PPC64_ADDR16_HIGHER34 = 136 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHERA34 = 137 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHEST34 = 138 => (16, Absolute),
+    R_PPC64_ADDR16_HIGHESTA34 = 139 => (16, Absolute),
+    R_PPC64_REL16_HIGHER34 = 140 => (16, PcRelative),
+    R_PPC64_REL16_HIGHERA34 = 141 => (16, PcRelative),
+    R_PPC64_REL16_HIGHEST34 = 142 => (16, PcRelative),
+    R_PPC64_REL16_HIGHESTA34 = 143 => (16, PcRelative),
+    R_PPC64_D28 = 144 => (64, Absolute),
+    R_PPC64_PCREL28 = 145 => (64, PcRelative),
+    R_PPC64_TPREL34 = 146 => (64, Absolute),
+    R_PPC64_DTPREL34 = 147 => (64, Absolute),
+    R_PPC64_GOT_TLSGD_PCREL34 = 148 => (64, PcRelative),
+    R_PPC64_GOT_TLSLD_PCREL34 = 149 => (64, PcRelative),
+    R_PPC64_GOT_TPREL_PCREL34 = 150 => (64, PcRelative),
+    R_PPC64_GOT_DTPREL_PCREL34 = 151 => (64, PcRelative),
+    R_PPC64_REL16_HIGH = 240 => (16, PcRelative),
+    R_PPC64_REL16_HIGHA = 241 => (16, PcRelative),
+    R_PPC64_REL16_HIGHER = 242 => (16, PcRelative),
+    R_PPC64_REL16_HIGHERA = 243 => (16, PcRelative),
+    R_PPC64_REL16_HIGHEST = 244 => (16, PcRelative),
+    R_PPC64_REL16_HIGHESTA = 245 => (16, PcRelative),
+    R_PPC64_REL16DX_HA = 246 => (32, PcRelative),
+    R_PPC64_JMP_IREL = 247 => (word, Absolute),
+    R_PPC64_IRELATIVE = 248 => (word, Absolute),
+    R_PPC64_REL16 = 249 => (16, PcRelative),
+    R_PPC64_REL16_LO = 250 => (16, PcRelative),
+    R_PPC64_REL16_HI = 251 => (16, PcRelative),
+    R_PPC64_REL16_HA = 252 => (16, PcRelative),
+    R_PPC64_GNU_VTINHERIT = 253 => (0, Other),
+    R_PPC64_GNU_VTENTRY = 254 => (0, Other),
+});
+
+/// The relocation types of `EM_MIPS`, including those of MIPS16 and
+/// microMIPS code.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfMipsRelocationType(pub u32);
+
+declare_relocation_types!(ElfMipsRelocationType, {
+    R_MIPS_NONE = 0 => (0, Other),
+    R_MIPS_16 = 1 => (16, Absolute),
+    R_MIPS_32 = 2 => (32, Absolute),
+    R_MIPS_REL32 = 3 => (word, Absolute),
+    R_MIPS_26 = 4 => (32, Absolute),
+    R_MIPS_HI16 = 5 => (32, Absolute),
+    R_MIPS_LO16 = 6 => (32, Absolute),
+    R_MIPS_GPREL16 = 7 => (32, Absolute),
+    R_MIPS_LITERAL = 8 => (32, Absolute),
+    R_MIPS_GOT16 = 9 => (32, Absolute),
+    R_MIPS_PC16 = 10 => (32, PcRelative),
+    R_MIPS_CALL16 = 11 => (32, Absolute),
+    R_MIPS_GPREL32 = 12 => (32, Absolute),
+    R_MIPS_SHIFT5 = 16 => (32, Absolute),
+    R_MIPS_SHIFT6 = 17 => (32, Absolute),
+    R_MIPS_64 = 18 => (64, Absolute),
+    R_MIPS_GOT_DISP = 19 => (32, Absolute),
+    R_MIPS_GOT_PAGE = 20 => (32, Absolute),
+    R_MIPS_GOT_OFST = 21 => (32, Absolute),
+    R_MIPS_GOT_HI16 = 22 => (32, Absolute),
+    R_MIPS_GOT_LO16 = 23 => (32, Absolute),
+    R_MIPS_SUB = 24 => (64, Absolute),
+    R_MIPS_INSERT_A = 25 => (0, Other),
+    R_MIPS_INSERT_B = 26 => (0, Other),
+    R_MIPS_DELETE = 27 => (0, Other),
+    R_MIPS_HIGHER = 28 => (32, Absolute),
+    R_MIPS_HIGHEST = 29 => (32, Absolute),
+    R_MIPS_CALL_HI16 = 30 => (32, Absolute),
+    R_MIPS_CALL_LO16 = 31 => (32, Absolute),
+    R_MIPS_SCN_DISP = 32 => (32, Absolute),
+    R_MIPS_REL16 = 33 => (16, Absolute),
+    R_MIPS_ADD_IMMEDIATE = 34 => (0, Other),
+    R_MIPS_PJUMP = 35 => (0, Other),
+    R_MIPS_RELGOT = 36 => (0, Other),
+    R_MIPS_JALR = 37 => (32, Other),
+    R_MIPS_TLS_DTPMOD32 = 38 => (32, Absolute),
+    R_MIPS_TLS_DTPREL32 = 39 => (32, Absolute),
+    R_MIPS_TLS_DTPMOD64 = 40 => (64, Absolute),
+    R_MIPS_TLS_DTPREL64 = 41 => (64, Absolute),
+    R_MIPS_TLS_GD = 42 => (32, Absolute),
+    R_MIPS_TLS_LDM = 43 => (32, Absolute),
+    R_MIPS_TLS_DTPREL_HI16 = 44 => (32, Absolute),
+    R_MIPS_TLS_DTPREL_LO16 = 45 => (32, Absolute),
+    R_MIPS_TLS_GOTTPREL = 46 => (32, Absolute),
+    R_MIPS_TLS_TPREL32 = 47 => (32, Absolute),
+    R_MIPS_TLS_TPREL64 = 48 => (64, Absolute),
+    R_MIPS_TLS_TPREL_HI16 = 49 => (32, Absolute),
+    R_MIPS_TLS_TPREL_LO16 = 50 => (32, Absolute),
+    R_MIPS_GLOB_DAT = 51 => (word, Absolute),
+    R_MIPS_PC21_S2 = 60 => (32, PcRelative),
+    R_MIPS_PC26_S2 = 61 => (32, PcRelative),
+    R_MIPS_PC18_S3 = 62 => (32, PcRelative),
+    R_MIPS_PC19_S2 = 63 => (32, PcRelative),
+    R_MIPS_PCHI16 = 64 => (32, PcRelative),
+    R_MIPS_PCLO16 = 65 => (32, PcRelative),
+    R_MIPS16_26 = 100 => (32, Absolute),
+    R_MIPS16_GPREL = 101 => (32, Absolute),
+    R_MIPS16_GOT16 = 102 => (32, Absolute),
+    R_MIPS16_CALL16 = 103 => (32, Absolute),
+    R_MIPS16_HI16 = 104 => (32, Absolute),
+    R_MIPS16_LO16 = 105 => (32, Absolute),
+    R_MIPS16_TLS_GD = 106 => (32, Absolute),
+    R_MIPS16_TLS_LDM = 107 => (32, Absolute),
+    R_MIPS16_TLS_DTPREL_HI16 = 108 => (32, Absolute),
+    R_MIPS16_TLS_DTPREL_LO16 = 109 => (32, Absolute),
+    R_MIPS16_TLS_GOTTPREL = 110 => (32, Absolute),
+    R_MIPS16_TLS_TPREL_HI16 = 111 => (32, Absolute),
+    R_MIPS16_TLS_TPREL_LO16 = 112 => (32, Absolute),
+    R_MIPS16_PC16_S1 = 113 => (32, PcRelative),
+    R_MIPS_COPY = 126 => (0, Other),
+    R_MIPS_JUMP_SLOT = 127 => (word, Absolute),
+    R_MICROMIPS_26_S1 = 133 => (32, Absolute),
+    R_MICROMIPS_HI16 = 134 => (32, Absolute),
+    R_MICROMIPS_LO16 = 135 => (32, Absolute),
+    R_MICROMIPS_GPREL16 = 136 => (32, Absolute),
+    R_MICROMIPS_LITERAL = 137 => (32, Absolute),
+    R_MICROMIPS_GOT16 = 138 => (32, Absolute),
+    R_MICROMIPS_PC7_S1 = 139 => (16, PcRelative),
+    R_MICROMIPS_PC10_S1 = 140 => (16, PcRelative),
+    R_MICROMIPS_PC16_S1 = 141 => (32, PcRelative),
+    R_MICROMIPS_CALL16 = 142 => (32, Absolute),
+    R_MICROMIPS_GOT_DISP = 145 => (32, Absolute),
+    R_MICROMIPS_GOT_PAGE = 146 => (32, Absolute),
+    R_MICROMIPS_GOT_OFST = 147 => (32, Absolute),
+    R_MICROMIPS_GOT_HI16 = 148 => (32, Absolute),
+    R_MICROMIPS_GOT_LO16 = 149 => (32, Absolute),
+    R_MICROMIPS_SUB = 150 => (64, Absolute),
+    R_MICROMIPS_HIGHER = 151 => (32, Absolute),
+    R_MICROMIPS_HIGHEST = 152 => (32, Absolute),
+    R_MICROMIPS_CALL_HI16 = 153 => (32, Absolute),
+    R_MICROMIPS_CALL_LO16 = 154 => (32, Absolute),
+    R_MICROMIPS_SCN_DISP = 155 => (32, Absolute),
+    R_MICROMIPS_JALR = 156 => (32, Other),
+    R_MICROMIPS_HI0_LO16 = 157 => (32, Absolute),
+    R_MICROMIPS_TLS_GD = 162 => (32, Absolute),
+    R_MICROMIPS_TLS_LDM = 163 => (32, Absolute),
+    R_MICROMIPS_TLS_DTPREL_HI16 = 164 => (32, Absolute),
+    R_MICROMIPS_TLS_DTPREL_LO16 = 165 => (32, Absolute),
+    R_MICROMIPS_TLS_GOTTPREL = 166 => (32, Absolute),
+    R_MICROMIPS_TLS_TPREL_HI16 = 169 => (32, Absolute),
+    R_MICROMIPS_TLS_TPREL_LO16 = 170 => (32, Absolute),
+    R_MICROMIPS_GPREL7_S2 = 172 => (16, Absolute),
+    R_MICROMIPS_PC23_S2 = 173 => (32, PcRelative),
+    R_MIPS_PC32 = 248 => (32, PcRelative),
+    R_MIPS_EH = 249 => (32, Absolute),
+    R_MIPS_GNU_REL16_S2 = 250 => (32, PcRelative),
+    R_MIPS_GNU_VTINHERIT = 253 => (0, Other),
+    R_MIPS_GNU_VTENTRY = 254 => (0, Other),
+});
+
+/// The relocation types of `EM_LOONGARCH`, from the LoongArch psABI.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ElfLoongArchRelocationType(pub u32);
+
+declare_relocation_types!(ElfLoongArchRelocationType, {
+    R_LARCH_NONE = 0 => (0, Other),
+    R_LARCH_32 = 1 => (32, Absolute),
+    R_LARCH_64 = 2 => (64, Absolute),
+    R_LARCH_RELATIVE = 3 => (word, Absolute),
+    R_LARCH_COPY = 4 => (0, Other),
+    R_LARCH_JUMP_SLOT = 5 => (word, Absolute),
+    R_LARCH_TLS_DTPMOD32 = 6 => (32, Absolute),
+    R_LARCH_TLS_DTPMOD64 = 7 => (64, Absolute),
+    R_LARCH_TLS_DTPREL32 = 8 => (32, Absolute),
+    R_LARCH_TLS_DTPREL64 = 9 => (64, Absolute),
+    R_LARCH_TLS_TPREL32 = 10 => (32, Absolute),
+    R_LARCH_TLS_TPREL64 = 11 => (64, Absolute),
+    R_LARCH_IRELATIVE = 12 => (word, Absolute),
+    R_LARCH_TLS_DESC32 = 13 => (32, Absolute),
+    R_LARCH_TLS_DESC64 = 14 => (64, Absolute),
+    R_LARCH_MARK_LA = 20 => (0, Other),
+    R_LARCH_MARK_PCREL = 21 => (0, Other),
+    R_LARCH_SOP_PUSH_PCREL = 22 => (0, Other),
+    R_LARCH_SOP_PUSH_ABSOLUTE = 23 => (0, Other),
+    R_LARCH_SOP_PUSH_DUP = 24 => (0, Other),
+    R_LARCH_SOP_PUSH_GPREL = 25 => (0, Other),
+    R_LARCH_SOP_PUSH_TLS_TPREL = 26 => (0, Other),
+    R_LARCH_SOP_PUSH_TLS_GOT = 27 => (0, Other),
+    R_LARCH_SOP_PUSH_TLS_GD = 28 => (0, Other),
+    R_LARCH_SOP_PUSH_PLT_PCREL = 29 => (0, Other),
+    R_LARCH_SOP_ASSERT = 30 => (0, Other),
+    R_LARCH_SOP_NOT = 31 => (0, Other),
+    R_LARCH_SOP_SUB = 32 => (0, Other),
+    R_LARCH_SOP_SL = 33 => (0, Other),
+    R_LARCH_SOP_SR = 34 => (0, Other),
+    R_LARCH_SOP_ADD = 35 => (0, Other),
+    R_LARCH_SOP_AND = 36 => (0, Other),
+    R_LARCH_SOP_IF_ELSE = 37 => (0, Other),
+    R_LARCH_SOP_POP_32_S_10_5 = 38 => (32, Other),
+    R_LARCH_SOP_POP_32_U_10_12 = 39 => (32, Other),
+    R_LARCH_SOP_POP_32_S_10_12 = 40 => (32, Other),
+    R_LARCH_SOP_POP_32_S_10_16 = 41 => (32, Other),
+    R_LARCH_SOP_POP_32_S_10_16_S2 = 42 => (32, Other),
+    R_LARCH_SOP_POP_32_S_5_20 = 43 => (32, Other),
+    R_LARCH_SOP_POP_32_S_0_5_10_16_S2 = 44 => (32, Other),
+    R_LARCH_SOP_POP_32_S_0_10_10_16_S2 = 45 => (32, Other),
+    R_LARCH_SOP_POP_32_U = 46 => (32, Other),
+    R_LARCH_ADD8 = 47 => (8, Absolute),
+    R_LARCH_ADD16 = 48 => (16, Absolute),
+    R_LARCH_ADD24 = 49 => (24, Absolute),
+    R_LARCH_ADD32 = 50 => (32, Absolute),
+    R_LARCH_ADD64 = 51 => (64, Absolute),
+    R_LARCH_SUB8 = 52 => (8, Absolute),
+    R_LARCH_SUB16 = 53 => (16, Absolute),
+    R_LARCH_SUB24 = 54 => (24, Absolute),
+    R_LARCH_SUB32 = 55 => (32, Absolute),
+    R_LARCH_SUB64 = 56 => (64, Absolute),
+    R_LARCH_GNU_VTINHERIT = 57 => (0, Other),
+    R_LARCH_GNU_VTENTRY = 58 => (0, Other),
+    R_LARCH_B16 = 64 => (32, PcRelative),
+    R_LARCH_B21 = 65 => (32, PcRelative),
+    R_LARCH_B26 = 66 => (32, PcRelative),
+    R_LARCH_ABS_HI20 = 67 => (32, Absolute),
+    R_LARCH_ABS_LO12 = 68 => (32, Absolute),
+    R_LARCH_ABS64_LO20 = 69 => (32, Absolute),
+    R_LARCH_ABS64_HI12 = 70 => (32, Absolute),
+    R_LARCH_PCALA_HI20 = 71 => (32, PcRelative),
+    R_LARCH_PCALA_LO12 = 72 => (32, Absolute),
+    R_LARCH_PCALA64_LO20 = 73 => (32, PcRelative),
+    R_LARCH_PCALA64_HI12 = 74 => (32, PcRelative),
+    R_LARCH_GOT_PC_HI20 = 75 => (32, PcRelative),
+    R_LARCH_GOT_PC_LO12 = 76 => (32, Absolute),
+    R_LARCH_GOT64_PC_LO20 = 77 => (32, PcRelative),
+    R_LARCH_GOT64_PC_HI12 = 78 => (32, PcRelative),
+    R_LARCH_GOT_HI20 = 79 => (32, Absolute),
+    R_LARCH_GOT_LO12 = 80 => (32, Absolute),
+    R_LARCH_GOT64_LO20 = 81 => (32, Absolute),
+    R_LARCH_GOT64_HI12 = 82 => (32, Absolute),
+    R_LARCH_TLS_LE_HI20 = 83 => (32, Absolute),
+    R_LARCH_TLS_LE_LO12 = 84 => (32, Absolute),
+    R_LARCH_TLS_LE64_LO20 = 85 => (32, Absolute),
+    R_LARCH_TLS_LE64_HI12 = 86 => (32, Absolute),
+    R_LARCH_TLS_IE_PC_HI20 = 87 => (32, PcRelative),
+    R_LARCH_TLS_IE_PC_LO12 = 88 => (32, Absolute),
+    R_LARCH_TLS_IE64_PC_LO20 = 89 => (32, PcRelative),
+    R_LARCH_TLS_IE64_PC_HI12 = 90 => (32, PcRelative),
+    R_LARCH_TLS_IE_HI20 = 91 => (32, Absolute),
+    R_LARCH_TLS_IE_LO12 = 92 => (32, Absolute),
+    R_LARCH_TLS_IE64_LO20 = 93 => (32, Absolute),
+    R_LARCH_TLS_IE64_HI12 = 94 => (32, Absolute),
+    R_LARCH_TLS_LD_PC_HI20 = 95 => (32, PcRelative),
+    R_LARCH_TLS_LD_HI20 = 96 => (32, Absolute),
+    R_LARCH_TLS_GD_PC_HI20 = 97 => (32, PcRelative),
+    R_LARCH_TLS_GD_HI20 = 98 => (32, Absolute),
+    R_LARCH_32_PCREL = 99 => (32, PcRelative),
+    R_LARCH_RELAX = 100 => (0, Other),
+    R_LARCH_ALIGN = 102 => (0, Other),
+    R_LARCH_PCREL20_S2 = 103 => (32, PcRelative),
+    R_LARCH_ADD6 = 105 => (8, Absolute),
+    R_LARCH_SUB6 = 106 => (8, Absolute),
+    R_LARCH_ADD_ULEB128 = 107 => (0, Absolute),
+    R_LARCH_SUB_ULEB128 = 108 => (0, Absolute),
+    R_LARCH_64_PCREL = 109 => (64, PcRelative),
+    R_LARCH_CALL36 = 110 => (64, PcRelative),
+    R_LARCH_TLS_DESC_PC_HI20 = 111 => (32, PcRelative),
+    R_LARCH_TLS_DESC_PC_LO12 = 112 => (32, Absolute),
+    R_LARCH_TLS_DESC64_PC_LO20 = 113 => (32, PcRelative),
+    R_LARCH_TLS_DESC64_PC_HI12 = 114 => (32, PcRelative),
+    R_LARCH_TLS_DESC_HI20 = 115 => (32, Absolute),
+    R_LARCH_TLS_DESC_LO12 = 116 => (32, Absolute),
+    R_LARCH_TLS_DESC64_LO20 = 117 => (32, Absolute),
+    R_LARCH_TLS_DESC64_HI12 = 118 => (32, Absolute),
+    R_LARCH_TLS_DESC_LD = 119 => (0, Other),
+    R_LARCH_TLS_DESC_CALL = 120 => (0, Other),
+    R_LARCH_TLS_LE_HI20_R = 121 => (32, Absolute),
+    R_LARCH_TLS_LE_ADD_R = 122 => (0, Other),
+    R_LARCH_TLS_LE_LO12_R = 123 => (32, Absolute),
+    R_LARCH_TLS_LD_PCREL20_S2 = 124 => (32, PcRelative),
+    R_LARCH_TLS_GD_PCREL20_S2 = 125 => (32, PcRelative),
+    R_LARCH_TLS_DESC_PCREL20_S2 = 126 => (32, PcRelative),
+});
+
+/// A relocation type in the catalogue of its architecture.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ElfRelocationType {
+    I386(ElfI386RelocationType),
+    X86_64(ElfX86_64RelocationType),
+    Arm(ElfArmRelocationType),
+    Aarch64(ElfAarch64RelocationType),
+    Riscv(ElfRiscvRelocationType),
+    Ppc64(ElfPpc64RelocationType),
+    Mips(ElfMipsRelocationType),
+    LoongArch(ElfLoongArchRelocationType),
+    /// A relocation type of an architecture without a catalogue.
+    Unknown(u32),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $r_type:ident => $known:expr, $unknown:expr) => {
+        match $self {
+            ElfRelocationType::I386($r_type) => $known,
+            ElfRelocationType::X86_64($r_type) => $known,
+            ElfRelocationType::Arm($r_type) => $known,
+            ElfRelocationType::Aarch64($r_type) => $known,
+            ElfRelocationType::Riscv($r_type) => $known,
+            ElfRelocationType::Ppc64($r_type) => $known,
+            ElfRelocationType::Mips($r_type) => $known,
+            ElfRelocationType::LoongArch($r_type) => $known,
+            ElfRelocationType::Unknown(_) => $unknown,
+        }
+    };
+}
+
+impl ElfRelocationType {
+    pub fn new(machine: ElfHeaderMachine, r_type: u32) -> Self {
+        match machine {
+            ElfHeaderMachine::EM_386 => Self::I386(ElfI386RelocationType(r_type)),
+            ElfHeaderMachine::EM_X86_64 => Self::X86_64(ElfX86_64RelocationType(r_type)),
+            ElfHeaderMachine::EM_ARM => Self::Arm(ElfArmRelocationType(r_type)),
+            ElfHeaderMachine::EM_AARCH64 => Self::Aarch64(ElfAarch64RelocationType(r_type)),
+            ElfHeaderMachine::EM_RISCV => Self::Riscv(ElfRiscvRelocationType(r_type)),
+            ElfHeaderMachine::EM_PPC64 => Self::Ppc64(ElfPpc64RelocationType(r_type)),
+            ElfHeaderMachine::EM_MIPS => Self::Mips(ElfMipsRelocationType(r_type)),
+            ElfHeaderMachine::EM_LOONGARCH => Self::LoongArch(ElfLoongArchRelocationType(r_type)),
+            _ => Self::Unknown(r_type),
+        }
+    }
+
+    /// The raw type, as found in `r_info`.
+    pub fn r_type(self) -> u32 {
+        match self {
+            Self::Unknown(r_type) => r_type,
+            _ => dispatch!(self, r_type => r_type.0, 0),
+        }
+    }
+
+    pub fn name(self) -> Option<&'static str> {
+        dispatch!(self, r_type => r_type.name(), None)
+    }
+
+    /// The size in bits of the field the relocation writes, in a 64-bit file
+    /// if `is_64` is true. It is zero for relocations that write nothing, or
+    /// write a variable-length field such as a ULEB128. For TLS descriptors,
+    /// it is the width of the first word.
+    pub fn width(self, is_64: bool) -> Option<u8> {
+        dispatch!(self, r_type => r_type.width(is_64), None)
+    }
+
+    pub fn kind(self) -> Option<ElfRelocationKind> {
+        dispatch!(self, r_type => r_type.kind(), None)
+    }
+
+    /// Whether this is the `R_*_RELATIVE` type of its machine, which adds the
+    /// load bias to the addend without a symbol. MIPS has no such type.
+    pub fn is_relative(self) -> bool {
+        match self {
+            Self::I386(r_type) => r_type == ElfI386RelocationType::R_386_RELATIVE,
+            Self::X86_64(r_type) => r_type == ElfX86_64RelocationType::R_X86_64_RELATIVE,
+            Self::Arm(r_type) => r_type == ElfArmRelocationType::R_ARM_RELATIVE,
+            Self::Aarch64(r_type) => r_type == ElfAarch64RelocationType::R_AARCH64_RELATIVE,
+            Self::Riscv(r_type) => r_type == ElfRiscvRelocationType::R_RISCV_RELATIVE,
+            Self::Ppc64(r_type) => r_type == ElfPpc64RelocationType::R_PPC64_RELATIVE,
+            Self::LoongArch(r_type) => r_type == ElfLoongArchRelocationType::R_LARCH_RELATIVE,
+            Self::Mips(_) | Self::Unknown(_) => false,
+        }
+    }
+
+    /// The `R_*_RELATIVE` type of `machine`, which `SHT_RELR` entries stand
+    /// for.
+    pub fn relative(machine: ElfHeaderMachine) -> Option<Self> {
+        let r_type = match machine {
+            ElfHeaderMachine::EM_386 => ElfI386RelocationType::R_386_RELATIVE.0,
+            ElfHeaderMachine::EM_X86_64 => ElfX86_64RelocationType::R_X86_64_RELATIVE.0,
+            ElfHeaderMachine::EM_ARM => ElfArmRelocationType::R_ARM_RELATIVE.0,
+            ElfHeaderMachine::EM_AARCH64 => ElfAarch64RelocationType::R_AARCH64_RELATIVE.0,
+            ElfHeaderMachine::EM_RISCV => ElfRiscvRelocationType::R_RISCV_RELATIVE.0,
+            ElfHeaderMachine::EM_PPC64 => ElfPpc64RelocationType::R_PPC64_RELATIVE.0,
+            ElfHeaderMachine::EM_LOONGARCH => ElfLoongArchRelocationType::R_LARCH_RELATIVE.0,
+            _ => return None,
+        };
+        Some(Self::new(machine, r_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_widths_and_kinds() {
+        let glob_dat = ElfRelocationType::new(ElfHeaderMachine::EM_X86_64, 6);
+        assert_eq!(glob_dat.name(), Some("R_X86_64_GLOB_DAT"));
+        assert_eq!(glob_dat.width(true), Some(64));
+        assert_eq!(glob_dat.width(false), Some(32));
+        assert_eq!(glob_dat.kind(), Some(ElfRelocationKind::Absolute));
+        assert_eq!(glob_dat.r_type(), 6);
+
+        let call26 = ElfRelocationType::new(ElfHeaderMachine::EM_AARCH64, 283);
+        assert_eq!(call26.name(), Some("R_AARCH64_CALL26"));
+        assert_eq!(call26.kind(), Some(ElfRelocationKind::PcRelative));
+
+        let pc16 = ElfRelocationType::new(ElfHeaderMachine::EM_386, 21);
+        assert_eq!(pc16.name(), Some("R_386_PC16"));
+        assert_eq!(pc16.width(false), Some(16));
+
+        let unnamed = ElfRelocationType::new(ElfHeaderMachine::EM_X86_64, 1000);
+        assert_eq!(unnamed.name(), None);
+        assert_eq!(unnamed.width(true), None);
+
+        let unknown = ElfRelocationType::new(ElfHeaderMachine(0xffff), 6);
+        assert_eq!(unknown, ElfRelocationType::Unknown(6));
+        assert_eq!(unknown.name(), None);
+        assert_eq!(unknown.kind(), None);
+        assert_eq!(unknown.r_type(), 6);
+    }
+}
@@ -0,0 +1,255 @@
+//! Relocation entries, as found in `SHT_REL` and `SHT_RELA` sections, and
+//! expanded from the compressed tables of
+//! [`packed_relocations`](crate::packed_relocations).
+
+use zerocopy::FromBytes;
+
+use crate::{
+    elf_aux_structures::*,
+    elf_structures::*,
+    errors::ElfError,
+    packed_relocations::{
+        ElfAndroidPackedIter, ElfAndroidPackedTable, ElfRelrTable, ElfRelrTableOffsets,
+    },
+    relocation_types::ElfRelocationType,
+    ElfHeader,
+};
+
+/// A decoded relocation entry, independent of the class and of whether the
+/// entry came from a `SHT_REL` or `SHT_RELA` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfRelocation {
+    /// The location at which to apply the relocation action.
+    pub r_offset: u64,
+    /// The processor-specific relocation type.
+    pub r_type: u32,
+    /// The symbol table index with respect to which the relocation is made.
+    pub r_sym: u32,
+    /// The explicit addend, only present for `SHT_RELA` entries.
+    pub r_addend: Option<i64>,
+}
+
+impl ElfRelocation {
+    pub(crate) fn from_elf32_info(r_offset: u32, r_info: u32, r_addend: Option<i32>) -> Self {
+        // ELF32_R_SYM(i) is ((i)>>8), ELF32_R_TYPE(i) is ((unsigned char)(i))
+        let [r_type, sym0, sym1, sym2] = r_info.to_le_bytes();
+
+        Self {
+            r_offset: r_offset.into(),
+            r_type: r_type.into(),
+            r_sym: u32::from_le_bytes([sym0, sym1, sym2, 0]),
+            r_addend: r_addend.map(Into::into),
+        }
+    }
+
+    pub(crate) fn from_elf64_info(r_offset: u64, r_info: u64, r_addend: Option<i64>) -> Self {
+        // ELF64_R_SYM(i) is ((i)>>32), ELF64_R_TYPE(i) is ((i)&0xffffffffL)
+        let [t0, t1, t2, t3, s0, s1, s2, s3] = r_info.to_le_bytes();
+
+        Self {
+            r_offset,
+            r_type: u32::from_le_bytes([t0, t1, t2, t3]),
+            r_sym: u32::from_le_bytes([s0, s1, s2, s3]),
+            r_addend,
+        }
+    }
+
+    /// Re-encodes the symbol and type into the `r_info` field for `header`'s class.
+    pub fn r_info(&self, header: ElfHeader) -> u64 {
+        match header {
+            ElfHeader::Elf32(_) => {
+                let [sym0, sym1, sym2, _] = self.r_sym.to_le_bytes();
+                let [r_type, ..] = self.r_type.to_le_bytes();
+                u32::from_le_bytes([r_type, sym0, sym1, sym2]).into()
+            }
+            ElfHeader::Elf64(_) => {
+                let [t0, t1, t2, t3] = self.r_type.to_le_bytes();
+                let [s0, s1, s2, s3] = self.r_sym.to_le_bytes();
+                u64::from_le_bytes([t0, t1, t2, t3, s0, s1, s2, s3])
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ElfRelocationTable<'buf> {
+    Elf32Rel(&'buf [Elf32Rel]),
+    Elf32Rela(&'buf [Elf32Rela]),
+    Elf64Rel(&'buf [Elf64Rel]),
+    Elf64Rela(&'buf [Elf64Rela]),
+    /// A `SHT_RELR` or `SHT_ANDROID_RELR` table, whose relocations are all
+    /// of the given relative type.
+    Relr(ElfRelrTable<'buf>, u32),
+    /// A `SHT_ANDROID_REL` or `SHT_ANDROID_RELA` table.
+    AndroidPacked(ElfAndroidPackedTable<'buf>),
+}
+
+impl<'buf> ElfRelocationTable<'buf> {
+    pub fn parse(
+        header: ElfHeader,
+        section_type: ElfSectionType,
+        bytes: &'buf [u8],
+    ) -> Result<Self, ElfError> {
+        let table = match (header, section_type) {
+            (ElfHeader::Elf32(_), ElfSectionType::SHT_REL) => {
+                Self::Elf32Rel(Elf32Rel::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            (ElfHeader::Elf32(_), ElfSectionType::SHT_RELA) => {
+                Self::Elf32Rela(Elf32Rela::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            (ElfHeader::Elf64(_), ElfSectionType::SHT_REL) => {
+                Self::Elf64Rel(Elf64Rel::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            (ElfHeader::Elf64(_), ElfSectionType::SHT_RELA) => {
+                Self::Elf64Rela(Elf64Rela::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            (_, ElfSectionType::SHT_RELR | ElfSectionType::SHT_ANDROID_RELR) => {
+                let machine = header.e_machine();
+                let r_type = ElfRelocationType::relative(machine)
+                    .ok_or(ElfError::UnsupportedMachine(machine))?
+                    .r_type();
+                let entries = match header {
+                    ElfHeader::Elf32(_) => {
+                        ElfRelrTable::Elf32(u32::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+                    }
+                    ElfHeader::Elf64(_) => {
+                        ElfRelrTable::Elf64(u64::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+                    }
+                };
+                Self::Relr(entries, r_type)
+            }
+            (_, ElfSectionType::SHT_ANDROID_REL | ElfSectionType::SHT_ANDROID_RELA) => {
+                let is_64 = matches!(header, ElfHeader::Elf64(_));
+                let is_rela = section_type == ElfSectionType::SHT_ANDROID_RELA;
+                Self::AndroidPacked(ElfAndroidPackedTable::parse(is_64, is_rela, bytes)?)
+            }
+            (_, section_type) => return Err(ElfError::NotARelocationSection(section_type)),
+        };
+
+        Ok(table)
+    }
+
+    /// The relocation at `index`. Compressed tables are decoded from the
+    /// start, so this takes time proportional to `index` for them.
+    pub fn get(&self, index: usize) -> Option<ElfRelocation> {
+        let relocation = match self {
+            Self::Elf32Rel(entries) => {
+                let entry = entries.get(index)?;
+                ElfRelocation::from_elf32_info(entry.r_offset, entry.r_info, None)
+            }
+            Self::Elf32Rela(entries) => {
+                let entry = entries.get(index)?;
+                ElfRelocation::from_elf32_info(entry.r_offset, entry.r_info, Some(entry.r_addend))
+            }
+            Self::Elf64Rel(entries) => {
+                let entry = entries.get(index)?;
+                ElfRelocation::from_elf64_info(entry.r_offset, entry.r_info, None)
+            }
+            Self::Elf64Rela(entries) => {
+                let entry = entries.get(index)?;
+                ElfRelocation::from_elf64_info(entry.r_offset, entry.r_info, Some(entry.r_addend))
+            }
+            Self::Relr(..) | Self::AndroidPacked(_) => return self.into_iter().nth(index),
+        };
+
+        Some(relocation)
+    }
+
+    /// The number of relocations, which for a `SHT_RELR` table is counted by
+    /// decoding it, and for an Android packed table is the count its header
+    /// gives.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Elf32Rel(entries) => entries.len(),
+            Self::Elf32Rela(entries) => entries.len(),
+            Self::Elf64Rel(entries) => entries.len(),
+            Self::Elf64Rela(entries) => entries.len(),
+            Self::Relr(entries, _) => entries.offsets().count(),
+            Self::AndroidPacked(table) => table.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the entries of this table carry explicit addends.
+    pub fn is_rela(&self) -> bool {
+        match self {
+            Self::Elf32Rela(_) | Self::Elf64Rela(_) => true,
+            Self::AndroidPacked(table) => table.is_rela(),
+            _ => false,
+        }
+    }
+}
+
+impl<'buf> IntoIterator for ElfRelocationTable<'buf> {
+    type Item = ElfRelocation;
+    type IntoIter = ElfRelocationTableIter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let entries = match self {
+            ElfRelocationTable::Relr(entries, r_type) => Entries::Relr(entries.offsets(), r_type),
+            ElfRelocationTable::AndroidPacked(table) => Entries::AndroidPacked(table.into_iter()),
+            table => Entries::Table(table, 0),
+        };
+
+        ElfRelocationTableIter { entries }
+    }
+}
+
+pub struct ElfRelocationTableIter<'buf> {
+    entries: Entries<'buf>,
+}
+
+/// How the entries of each kind of table are iterated over.
+enum Entries<'buf> {
+    /// A table of fixed-size entries, and the index of the next one.
+    Table(ElfRelocationTable<'buf>, usize),
+    Relr(ElfRelrTableOffsets<'buf>, u32),
+    AndroidPacked(ElfAndroidPackedIter<'buf>),
+}
+
+impl Iterator for ElfRelocationTableIter<'_> {
+    type Item = ElfRelocation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.entries {
+            Entries::Table(table, index) => {
+                let relocation = table.get(*index)?;
+                *index = index.saturating_add(1);
+                Some(relocation)
+            }
+            Entries::Relr(offsets, r_type) => Some(ElfRelocation {
+                r_offset: offsets.next()?,
+                r_type: *r_type,
+                r_sym: 0,
+                r_addend: None,
+            }),
+            Entries::AndroidPacked(relocations) => relocations.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_elf64_info() {
+        let relocation = ElfRelocation::from_elf64_info(0x1000, 0x0000_0003_0000_0007, Some(-4));
+
+        assert_eq!(relocation.r_sym, 3);
+        assert_eq!(relocation.r_type, 7);
+        assert_eq!(relocation.r_addend, Some(-4));
+    }
+
+    #[test]
+    fn decodes_elf32_info() {
+        let relocation = ElfRelocation::from_elf32_info(0x1000, 0x0000_0305, None);
+
+        assert_eq!(relocation.r_sym, 3);
+        assert_eq!(relocation.r_type, 5);
+        assert_eq!(relocation.r_addend, None);
+    }
+}
@@ -0,0 +1,843 @@
+//! Rewriting the program interpreter and the strings of the dynamic array,
+//! in the style of `patchelf`.
+//!
+//! New strings are added to a copy of the dynamic string table, so that the
+//! symbol names and version entries referring to the old strings stay valid.
+//! When the string table or the interpreter path no longer fit in place, they
+//! are moved into a new read-only `PT_LOAD` segment at the end of the file,
+//! together with a copy of the program header table that has room for the
+//! new segment. The section headers, the `DT_STRTAB` and `DT_STRSZ` entries
+//! and the `PT_PHDR` and `PT_INTERP` segments are updated to match.
+//!
+//! The new segment is placed so that its address and offset differ by the
+//! same amount as the first `PT_LOAD`'s, which older kernels assume when
+//! computing `AT_PHDR`.
+
+use core::{
+    ffi::CStr,
+    mem::offset_of,
+    num::{NonZeroU16, NonZeroU64},
+};
+use std::{string::String, vec::Vec};
+
+use crate::{
+    dynamic::ElfDynamicTable,
+    elf_aux_structures::*,
+    elf_structures::ElfVerneed,
+    errors::ElfError,
+    mutable::{ElfDynamicMut, ElfHeaderMut, ElfProgramHeaderMut, ElfSectionHeaderMut},
+    versions::ElfVersionNeeds,
+    ElfFile,
+};
+
+/// The page size assumed when no `PT_LOAD` segment gives an alignment.
+const DEFAULT_PAGE_SIZE: u64 = 0x1000;
+
+/// Collects changes to a linked file, which are applied by
+/// [`ElfRewriter::finish`].
+#[derive(Debug, Clone)]
+pub struct ElfRewriter {
+    buffer: Vec<u8>,
+    interpreter: Option<String>,
+    soname: Option<String>,
+    search_path: Option<(ElfDynamicTag, String)>,
+    needed: Vec<(String, String)>,
+}
+
+/// The dynamic array and its string table, copied out of the file so that
+/// they can be edited before it is modified.
+struct DynamicStrings {
+    offset: usize,
+    entries: Vec<(ElfDynamicTag, u64)>,
+    /// The file offsets and values of the `vn_file` fields of the version
+    /// needs, which name the same files as `DT_NEEDED`.
+    needs: Vec<(usize, u32)>,
+    strings: Vec<u8>,
+    original_size: usize,
+    address: u64,
+}
+
+impl DynamicStrings {
+    fn read(file: &ElfFile) -> Result<Self, ElfError> {
+        let segment = file
+            .segments()
+            .find(|segment| segment.p_type() == ElfSegmentType::PT_DYNAMIC)
+            .ok_or(ElfError::MissingSegment(ElfSegmentType::PT_DYNAMIC))?;
+        let table = ElfDynamicTable::parse(file.header(), file.segment_data(segment)?)?;
+
+        // Every slot is kept, including those after the terminator, which
+        // can be reused for new entries.
+        let entries: Vec<_> = (0..)
+            .map_while(|index| table.get(index))
+            .map(|entry| (entry.d_tag(), entry.d_val()))
+            .collect();
+
+        let value = |tag| table.value(tag).ok_or(ElfError::MissingDynamicEntry(tag));
+        let address = value(ElfDynamicTag::DT_STRTAB)?;
+        let size = value(ElfDynamicTag::DT_STRSZ)?;
+        let start = file
+            .virtual_address_to_offset(address)
+            .ok_or(ElfError::DataOutOfBounds(
+                address..address.saturating_add(size),
+            ))?;
+        let location = start..start.saturating_add(size);
+        let strings = file
+            .buffer()
+            .get(usize::try_from(location.start)?..usize::try_from(location.end)?)
+            .ok_or(ElfError::DataOutOfBounds(location))?;
+
+        let mut needs = Vec::new();
+        if let (Some(address), Some(count)) = (
+            table.value(ElfDynamicTag::DT_VERNEED),
+            table.value(ElfDynamicTag::DT_VERNEEDNUM),
+        ) {
+            let start = file
+                .virtual_address_to_offset(address)
+                .ok_or(ElfError::DataOutOfBounds(address..address))?;
+            let start = usize::try_from(start)?;
+            let section = file
+                .buffer()
+                .get(start..)
+                .ok_or(ElfError::StringTableOutOfBounds(start))?;
+
+            for need in ElfVersionNeeds::parse(section, u32::try_from(count)?).iter() {
+                let need = need?;
+                let field = start
+                    .checked_add(need.offset)
+                    .and_then(|offset| offset.checked_add(offset_of!(ElfVerneed, vn_file)))
+                    .ok_or(ElfError::MalformedVersionEntry(need.offset))?;
+                needs.push((field, need.verneed.vn_file));
+            }
+        }
+
+        Ok(Self {
+            offset: usize::try_from(segment.p_offset())?,
+            entries,
+            needs,
+            strings: strings.into(),
+            original_size: strings.len(),
+            address,
+        })
+    }
+
+    fn string(&self, offset: u64) -> Result<&[u8], ElfError> {
+        let offset = usize::try_from(offset)?;
+        let rest = self
+            .strings
+            .get(offset..)
+            .ok_or(ElfError::StringTableOutOfBounds(offset))?;
+        Ok(CStr::from_bytes_until_nul(rest)?.to_bytes())
+    }
+
+    /// Returns the offset of `string`, reusing an existing string or the
+    /// tail of one if possible, and appending it otherwise.
+    fn add_string(&mut self, string: &str) -> Result<u64, ElfError> {
+        let mut needle = Vec::from(string.as_bytes());
+        needle.push(0);
+
+        let offset = match self
+            .strings
+            .windows(needle.len())
+            .position(|window| window == needle)
+        {
+            Some(offset) => offset,
+            None => {
+                let offset = self.strings.len();
+                self.strings.extend_from_slice(&needle);
+                offset
+            }
+        };
+
+        Ok(u64::try_from(offset)?)
+    }
+
+    /// Returns the index of a `DT_NULL` entry that can be turned into an
+    /// entry with `tag`, as it is followed by another `DT_NULL`.
+    fn spare_entry(&self, tag: ElfDynamicTag) -> Result<usize, ElfError> {
+        self.entries
+            .windows(2)
+            .position(|pair| {
+                matches!(pair, [(first, _), (second, _)]
+                    if *first == ElfDynamicTag::DT_NULL && *second == ElfDynamicTag::DT_NULL)
+            })
+            .ok_or(ElfError::NoSpareDynamicEntry(tag))
+    }
+
+    /// Points the entries with one of `tags` at `string`, changing their tag
+    /// to `tag`. If there are none, a spare entry is used.
+    fn set_string(
+        &mut self,
+        tags: &[ElfDynamicTag],
+        tag: ElfDynamicTag,
+        string: &str,
+    ) -> Result<(), ElfError> {
+        let offset = self.add_string(string)?;
+        let mut found = false;
+
+        for entry in &mut self.entries {
+            if entry.0 == ElfDynamicTag::DT_NULL {
+                break;
+            }
+            if tags.contains(&entry.0) {
+                *entry = (tag, offset);
+                found = true;
+            }
+        }
+
+        if !found {
+            let index = self.spare_entry(tag)?;
+            if let Some(entry) = self.entries.get_mut(index) {
+                *entry = (tag, offset);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn replace_needed(&mut self, old: &str, new: &str) -> Result<(), ElfError> {
+        let mut matches = Vec::new();
+        for (index, (tag, value)) in self.entries.iter().enumerate() {
+            if *tag == ElfDynamicTag::DT_NULL {
+                break;
+            }
+            if *tag == ElfDynamicTag::DT_NEEDED && self.string(*value)? == old.as_bytes() {
+                matches.push(index);
+            }
+        }
+
+        if matches.is_empty() {
+            return Err(ElfError::MissingDynamicEntry(ElfDynamicTag::DT_NEEDED));
+        }
+
+        let offset = self.add_string(new)?;
+        for index in matches {
+            if let Some(entry) = self.entries.get_mut(index) {
+                entry.1 = offset;
+            }
+        }
+
+        let file_offset = u32::try_from(offset)?;
+        let mut needs = core::mem::take(&mut self.needs);
+        for (_, file) in &mut needs {
+            if self.string(u64::from(*file))? == old.as_bytes() {
+                *file = file_offset;
+            }
+        }
+        self.needs = needs;
+
+        Ok(())
+    }
+
+    fn set_value(&mut self, tag: ElfDynamicTag, value: u64) {
+        for entry in &mut self.entries {
+            if entry.0 == tag {
+                entry.1 = value;
+            }
+        }
+    }
+
+    fn grew(&self) -> bool {
+        self.strings.len() > self.original_size
+    }
+}
+
+/// Where the new `PT_LOAD` segment goes, following the existing ones.
+struct Placement {
+    offset: u64,
+    address: u64,
+    align: u64,
+    last_load: u16,
+}
+
+impl Placement {
+    fn new(file: &ElfFile) -> Result<Self, ElfError> {
+        let mut last_load = None;
+        let mut first_delta = None;
+        let mut memory_end: u64 = 0;
+        let mut align = DEFAULT_PAGE_SIZE;
+
+        for (index, segment) in file.segments().enumerate() {
+            if segment.p_type() != ElfSegmentType::PT_LOAD {
+                continue;
+            }
+
+            last_load = Some(u16::try_from(index)?);
+            first_delta.get_or_insert(segment.p_vaddr().checked_sub(segment.p_offset()));
+            align = align.max(segment.p_align());
+            if let Some(location) = file.segment_memory_location(segment)? {
+                memory_end = memory_end.max(location.end);
+            }
+        }
+
+        let last_load = last_load.ok_or(ElfError::MissingSegment(ElfSegmentType::PT_LOAD))?;
+        let delta = first_delta.flatten().unwrap_or_default();
+        let file_end = u64::try_from(file.buffer().len())?;
+        let offset = file_end
+            .max(memory_end.saturating_sub(delta))
+            .checked_next_multiple_of(align)
+            .ok_or(ElfError::IncongurentSegmentAlignment)?;
+        let address = offset
+            .checked_add(delta)
+            .ok_or(ElfError::ValueOutOfRange("p_vaddr", offset))?;
+
+        Ok(Self {
+            offset,
+            address,
+            align,
+            last_load,
+        })
+    }
+}
+
+fn section_index(file: &ElfFile, predicate: impl Fn(&str, u64) -> bool) -> Option<u16> {
+    file.sections().enumerate().find_map(|(index, section)| {
+        let name = file.section_name(section).unwrap_or_default();
+        let address = section.sh_addr().map_or(0, NonZeroU64::get);
+        predicate(name, address)
+            .then(|| u16::try_from(index).ok())
+            .flatten()
+    })
+}
+
+fn checked_offset(offset: u64, length: usize) -> Result<u64, ElfError> {
+    offset
+        .checked_add(u64::try_from(length)?)
+        .ok_or(ElfError::ValueOutOfRange("offset", offset))
+}
+
+impl ElfRewriter {
+    /// Takes ownership of the file's contents, which are checked to parse.
+    pub fn new(buffer: Vec<u8>) -> Result<Self, ElfError> {
+        ElfFile::parse(&buffer)?;
+
+        Ok(Self {
+            buffer,
+            interpreter: None,
+            soname: None,
+            search_path: None,
+            needed: Vec::new(),
+        })
+    }
+
+    /// Sets the path of the program interpreter, in the `PT_INTERP` segment.
+    pub fn set_interpreter(&mut self, path: &str) -> &mut Self {
+        self.interpreter = Some(path.into());
+        self
+    }
+
+    /// Sets `DT_SONAME`, adding the entry if there is a spare slot. If there
+    /// isn't, [`Self::finish`] fails with [`ElfError::NoSpareDynamicEntry`].
+    pub fn set_soname(&mut self, soname: &str) -> &mut Self {
+        self.soname = Some(soname.into());
+        self
+    }
+
+    /// Sets `DT_RUNPATH`, turning an existing `DT_RPATH` into `DT_RUNPATH`.
+    pub fn set_runpath(&mut self, path: &str) -> &mut Self {
+        self.search_path = Some((ElfDynamicTag::DT_RUNPATH, path.into()));
+        self
+    }
+
+    /// Sets `DT_RPATH`, turning an existing `DT_RUNPATH` into `DT_RPATH`.
+    pub fn set_rpath(&mut self, path: &str) -> &mut Self {
+        self.search_path = Some((ElfDynamicTag::DT_RPATH, path.into()));
+        self
+    }
+
+    /// Replaces every `DT_NEEDED` entry naming `old` with `new`.
+    pub fn replace_needed(&mut self, old: &str, new: &str) -> &mut Self {
+        self.needed.push((old.into(), new.into()));
+        self
+    }
+
+    fn edits_dynamic(&self) -> bool {
+        self.soname.is_some() || self.search_path.is_some() || !self.needed.is_empty()
+    }
+
+    /// Applies the changes, returning the rewritten file.
+    pub fn finish(self) -> Result<Vec<u8>, ElfError> {
+        let mut strings = self
+            .interpreter
+            .iter()
+            .chain(&self.soname)
+            .chain(self.search_path.iter().map(|(_, path)| path))
+            .chain(self.needed.iter().map(|(_, new)| new));
+        if strings.any(|string| string.contains('\0')) {
+            return Err(ElfError::EmbeddedNul);
+        }
+
+        let file = ElfFile::parse(&self.buffer)?;
+        let class = file.header().e_ident().ei_class;
+
+        let mut dynamic = None;
+        if self.edits_dynamic() {
+            let mut strings = DynamicStrings::read(&file)?;
+            if let Some(soname) = &self.soname {
+                strings.set_string(
+                    &[ElfDynamicTag::DT_SONAME],
+                    ElfDynamicTag::DT_SONAME,
+                    soname,
+                )?;
+            }
+            if let Some((tag, path)) = &self.search_path {
+                strings.set_string(
+                    &[ElfDynamicTag::DT_RPATH, ElfDynamicTag::DT_RUNPATH],
+                    *tag,
+                    path,
+                )?;
+            }
+            for (old, new) in &self.needed {
+                strings.replace_needed(old, new)?;
+            }
+            dynamic = Some(strings);
+        }
+
+        let mut interpreter = None;
+        if let Some(path) = &self.interpreter {
+            let (index, segment) = file
+                .segments()
+                .enumerate()
+                .find(|(_, segment)| segment.p_type() == ElfSegmentType::PT_INTERP)
+                .ok_or(ElfError::MissingSegment(ElfSegmentType::PT_INTERP))?;
+            let mut bytes = Vec::from(path.as_bytes());
+            bytes.push(0);
+            let fits = u64::try_from(bytes.len())? <= segment.p_filesz().map_or(0, NonZeroU64::get);
+            interpreter = Some((u16::try_from(index)?, segment.p_offset(), bytes, fits));
+        }
+
+        let relocate_strings = dynamic.as_ref().is_some_and(DynamicStrings::grew);
+        let relocate_interpreter = interpreter.as_ref().is_some_and(|(.., fits)| !fits);
+
+        let interp_section = interpreter.as_ref().and_then(|(index, ..)| {
+            let segment = file.program_headers()?.get(*index)?;
+            section_index(&file, |name, address| {
+                name == ".interp" || (address != 0 && address == segment.p_vaddr())
+            })
+        });
+        let strings_section = dynamic
+            .as_ref()
+            .and_then(|dynamic| section_index(&file, |_, address| address == dynamic.address));
+        let phdr = file
+            .segments()
+            .position(|segment| segment.p_type() == ElfSegmentType::PT_PHDR)
+            .map(u16::try_from)
+            .transpose()?;
+
+        let header = file.header();
+        let phnum = header.e_phnum().map_or(0, NonZeroU16::get);
+        let entry_size = usize::from(header.e_phentsize());
+        let placement = if relocate_strings || relocate_interpreter {
+            let location = header
+                .program_headers_location()
+                .ok_or(ElfError::MissingSegment(ElfSegmentType::PT_LOAD))?;
+            let table = file
+                .buffer()
+                .get(usize::try_from(location.start)?..usize::try_from(location.end)?)
+                .ok_or(ElfError::DataOutOfBounds(location))?;
+            Some((Placement::new(&file)?, Vec::from(table)))
+        } else {
+            None
+        };
+
+        let mut buffer = self.buffer;
+
+        if let Some((placement, table)) = &placement {
+            // The new segment follows the last PT_LOAD, keeping them sorted.
+            let split = usize::from(placement.last_load)
+                .checked_add(1)
+                .and_then(|count| count.checked_mul(entry_size))
+                .ok_or(ElfError::HeaderIndexOutOfBounds(placement.last_load))?;
+            let (before, after) = table
+                .split_at_checked(split)
+                .ok_or(ElfError::HeaderIndexOutOfBounds(placement.last_load))?;
+            let template = before
+                .get(split.saturating_sub(entry_size)..)
+                .ok_or(ElfError::HeaderIndexOutOfBounds(placement.last_load))?;
+
+            buffer.resize(usize::try_from(placement.offset)?, 0);
+            buffer.extend_from_slice(before);
+            buffer.extend_from_slice(template);
+            buffer.extend_from_slice(after);
+            let table_size = checked_offset(0, table.len().saturating_add(entry_size))?;
+
+            let new_phnum = phnum
+                .checked_add(1)
+                .ok_or(ElfError::ValueOutOfRange("e_phnum", u64::from(phnum)))?;
+            let mut header = ElfHeaderMut::parse(&mut buffer)?;
+            header.set_e_phoff(NonZeroU64::new(placement.offset))?;
+            header.set_e_phnum(NonZeroU16::new(new_phnum));
+
+            // Indices after the new segment have moved up by one.
+            let moved = |index: u16| {
+                if index > placement.last_load {
+                    index.saturating_add(1)
+                } else {
+                    index
+                }
+            };
+
+            if let Some(phdr) = phdr {
+                let mut segment = ElfProgramHeaderMut::from_file(&mut buffer, moved(phdr))?;
+                segment.set_p_offset(placement.offset)?;
+                segment.set_p_vaddr(placement.address)?;
+                segment.set_p_paddr(placement.address)?;
+                segment.set_p_filesz(NonZeroU64::new(table_size))?;
+                segment.set_p_memsz(NonZeroU64::new(table_size))?;
+            }
+
+            if let Some((index, offset, bytes, fits)) = &mut interpreter {
+                *index = moved(*index);
+                if !*fits {
+                    *offset = u64::try_from(buffer.len())?;
+                    buffer.extend_from_slice(bytes);
+                }
+            }
+
+            if let Some(dynamic) = &mut dynamic {
+                if dynamic.grew() {
+                    let offset = u64::try_from(buffer.len())?;
+                    buffer.extend_from_slice(&dynamic.strings);
+                    dynamic.address = placement
+                        .address
+                        .checked_add(offset.saturating_sub(placement.offset))
+                        .ok_or(ElfError::ValueOutOfRange("p_vaddr", offset))?;
+                }
+            }
+
+            let size = u64::try_from(buffer.len())?.saturating_sub(placement.offset);
+            let index = placement.last_load.saturating_add(1);
+            let mut segment = ElfProgramHeaderMut::from_file(&mut buffer, index)?;
+            segment.set_p_type(ElfSegmentType::PT_LOAD);
+            segment.set_p_offset(placement.offset)?;
+            segment.set_p_vaddr(placement.address)?;
+            segment.set_p_paddr(placement.address)?;
+            segment.set_p_filesz(NonZeroU64::new(size))?;
+            segment.set_p_memsz(NonZeroU64::new(size))?;
+            segment.set_p_flags(ElfSegmentFlags::PF_R.0);
+            segment.set_p_align(placement.align)?;
+        }
+
+        let placement = placement.map(|(placement, _)| placement);
+        if let Some((index, offset, bytes, fits)) = &interpreter {
+            let size = NonZeroU64::new(u64::try_from(bytes.len())?);
+            let address = placement
+                .as_ref()
+                .filter(|_| !fits)
+                .map(|placement| {
+                    placement
+                        .address
+                        .checked_add(offset.saturating_sub(placement.offset))
+                        .ok_or(ElfError::ValueOutOfRange("p_vaddr", *offset))
+                })
+                .transpose()?;
+
+            if *fits {
+                let start = usize::try_from(*offset)?;
+                let end = start.saturating_add(bytes.len());
+                buffer
+                    .get_mut(start..end)
+                    .ok_or(ElfError::DataOutOfBounds(
+                        *offset..checked_offset(*offset, bytes.len())?,
+                    ))?
+                    .copy_from_slice(bytes);
+            }
+
+            let mut segment = ElfProgramHeaderMut::from_file(&mut buffer, *index)?;
+            segment.set_p_offset(*offset)?;
+            segment.set_p_filesz(size)?;
+            segment.set_p_memsz(size)?;
+            if let Some(address) = address {
+                segment.set_p_vaddr(address)?;
+                segment.set_p_paddr(address)?;
+            }
+
+            if let Some(section) = interp_section {
+                let mut section = ElfSectionHeaderMut::from_file(&mut buffer, section)?;
+                section.set_sh_offset(*offset)?;
+                section.set_sh_size(size.map_or(0, NonZeroU64::get))?;
+                if let Some(address) = address {
+                    section.set_sh_addr(NonZeroU64::new(address))?;
+                }
+            }
+        }
+
+        if let Some(mut dynamic) = dynamic {
+            if let Some(placement) = placement.as_ref().filter(|_| relocate_strings) {
+                let size = u64::try_from(dynamic.strings.len())?;
+                dynamic.set_value(ElfDynamicTag::DT_STRTAB, dynamic.address);
+                dynamic.set_value(ElfDynamicTag::DT_STRSZ, size);
+
+                if let Some(section) = strings_section {
+                    let offset = dynamic
+                        .address
+                        .checked_sub(placement.address)
+                        .and_then(|delta| placement.offset.checked_add(delta))
+                        .ok_or(ElfError::ValueOutOfRange("sh_offset", dynamic.address))?;
+                    let mut section = ElfSectionHeaderMut::from_file(&mut buffer, section)?;
+                    section.set_sh_offset(offset)?;
+                    section.set_sh_addr(NonZeroU64::new(dynamic.address))?;
+                    section.set_sh_size(size)?;
+                }
+            }
+
+            for (field, file) in dynamic.needs {
+                buffer
+                    .get_mut(field..field.saturating_add(4))
+                    .ok_or(ElfError::MalformedVersionEntry(field))?
+                    .copy_from_slice(&file.to_le_bytes());
+            }
+
+            let entry_size = match class {
+                ElfIdentClass::CLASS_32 => 8,
+                _ => 16,
+            };
+            let mut offset = dynamic.offset;
+            for (tag, value) in dynamic.entries {
+                let bytes = buffer.get_mut(offset..).ok_or(ElfError::ZeroCopyError)?;
+                let mut entry = ElfDynamicMut::parse(class, bytes)?;
+                entry.set_d_tag(tag)?;
+                entry.set_d_val(value)?;
+                offset = offset.saturating_add(entry_size);
+            }
+        }
+
+        // The result must still be readable by this crate.
+        ElfFile::parse(&buffer)?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testdata::HARDENING_PIE, versions::ElfVersionNeeds};
+
+    /// The string-valued entries of the dynamic array, read through
+    /// `DT_STRTAB`.
+    fn dynamic_strings<'buf>(file: &ElfFile<'buf>) -> Vec<(ElfDynamicTag, &'buf str)> {
+        let dynamic = file.dynamic_table().expect("readable").expect("dynamic");
+        let strings = file
+            .dynamic_string_table()
+            .expect("readable")
+            .expect("strings");
+        dynamic
+            .into_iter()
+            .filter(|entry| {
+                matches!(
+                    entry.d_tag(),
+                    ElfDynamicTag::DT_NEEDED
+                        | ElfDynamicTag::DT_SONAME
+                        | ElfDynamicTag::DT_RPATH
+                        | ElfDynamicTag::DT_RUNPATH
+                )
+            })
+            .map(|entry| {
+                let offset = u32::try_from(entry.d_val()).expect("string offset");
+                (entry.d_tag(), strings.get(offset).expect("string"))
+            })
+            .collect()
+    }
+
+    /// The file named by the first version requirement, read through the
+    /// section headers.
+    fn version_need_file<'buf>(file: &ElfFile<'buf>) -> &'buf str {
+        let section = file
+            .section_by_type(ElfSectionType::SHT_VERNEED)
+            .expect("version requirements");
+        let need = ElfVersionNeeds::parse(file.section_data(section).expect("readable"), 1)
+            .iter()
+            .next()
+            .expect("requirement")
+            .expect("readable");
+        file.linked_string_table(section)
+            .expect("strings")
+            .get(need.verneed.vn_file)
+            .expect("file name")
+    }
+
+    #[test]
+    fn reuses_existing_strings() {
+        let mut dynamic = DynamicStrings {
+            offset: 0,
+            entries: Vec::from([
+                (ElfDynamicTag::DT_NEEDED, 1),
+                (ElfDynamicTag::DT_NULL, 0),
+                (ElfDynamicTag::DT_NULL, 0),
+            ]),
+            needs: Vec::new(),
+            strings: Vec::from(*b"\0libfoo.so.1\0"),
+            original_size: 13,
+            address: 0,
+        };
+
+        // A tail of an existing string is reused.
+        assert!(matches!(dynamic.add_string("foo.so.1"), Ok(4)));
+        assert!(!dynamic.grew());
+
+        assert!(dynamic
+            .set_string(
+                &[ElfDynamicTag::DT_RUNPATH],
+                ElfDynamicTag::DT_RUNPATH,
+                "$ORIGIN"
+            )
+            .is_ok());
+        assert!(dynamic.grew());
+        assert_eq!(
+            dynamic.entries.get(1),
+            Some(&(ElfDynamicTag::DT_RUNPATH, 13))
+        );
+
+        assert!(matches!(
+            dynamic.set_string(&[ElfDynamicTag::DT_SONAME], ElfDynamicTag::DT_SONAME, "x"),
+            Err(ElfError::NoSpareDynamicEntry(ElfDynamicTag::DT_SONAME))
+        ));
+    }
+
+    #[test]
+    fn needs_spare_entries_only_for_new_tags() {
+        let mut dynamic = DynamicStrings {
+            offset: 0,
+            entries: Vec::from([(ElfDynamicTag::DT_RPATH, 1), (ElfDynamicTag::DT_NULL, 0)]),
+            needs: Vec::new(),
+            strings: Vec::from(*b"\0/opt/lib\0"),
+            original_size: 10,
+            address: 0,
+        };
+
+        // An existing entry is changed in place, even without a spare entry.
+        assert!(dynamic
+            .set_string(
+                &[ElfDynamicTag::DT_RPATH, ElfDynamicTag::DT_RUNPATH],
+                ElfDynamicTag::DT_RUNPATH,
+                "$ORIGIN"
+            )
+            .is_ok());
+        assert_eq!(
+            dynamic.entries,
+            [(ElfDynamicTag::DT_RUNPATH, 10), (ElfDynamicTag::DT_NULL, 0)]
+        );
+
+        // The last DT_NULL terminates the array, so can't be used.
+        assert!(matches!(
+            dynamic.set_string(&[ElfDynamicTag::DT_SONAME], ElfDynamicTag::DT_SONAME, "x"),
+            Err(ElfError::NoSpareDynamicEntry(ElfDynamicTag::DT_SONAME))
+        ));
+        assert_eq!(dynamic.entries.len(), 2);
+    }
+
+    #[test]
+    fn rewrites_in_place() {
+        let mut rewriter = ElfRewriter::new(Vec::from(HARDENING_PIE.0)).expect("valid file");
+        rewriter
+            .set_interpreter("/lib/ld.so")
+            .set_runpath("/opt/lib");
+        let buffer = rewriter.finish().expect("rewritable file");
+        assert_eq!(buffer.len(), HARDENING_PIE.0.len());
+
+        let file = ElfFile::parse(&buffer).expect("valid file");
+        assert_eq!(file.segments().count(), 11);
+        assert!(matches!(file.interpreter(), Ok(Some("/lib/ld.so"))));
+        let interp = file.section_by_name(".interp").expect("interp section");
+        assert_eq!(interp.sh_size(), 11);
+        assert_eq!(
+            dynamic_strings(&file),
+            [
+                (ElfDynamicTag::DT_NEEDED, "libc.so.6"),
+                (ElfDynamicTag::DT_RUNPATH, "/opt/lib"),
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_what_no_longer_fits_into_a_new_segment() {
+        let original = ElfFile::parse(&HARDENING_PIE.0).expect("valid file");
+        let interpreter = "/opt/toolchain/lib64/ld-linux-x86-64.so.2";
+        let mut rewriter = ElfRewriter::new(Vec::from(HARDENING_PIE.0)).expect("valid file");
+        rewriter
+            .set_interpreter(interpreter)
+            .set_soname("libhardening.so.1")
+            .set_runpath("$ORIGIN/../lib")
+            .replace_needed("libc.so.6", "libc-2.so.6");
+        let buffer = rewriter.finish().expect("rewritable file");
+        let file = ElfFile::parse(&buffer).expect("valid file");
+
+        // The new segment follows the last PT_LOAD, and holds the program
+        // header table, which PT_PHDR describes.
+        let segments: Vec<_> = file.segments().collect();
+        assert_eq!(
+            segments.len(),
+            original.segments().count().saturating_add(1)
+        );
+        let types: Vec<_> = segments.iter().map(|segment| segment.p_type()).collect();
+        assert_eq!(
+            types.get(..5),
+            Some(
+                &[
+                    ElfSegmentType::PT_PHDR,
+                    ElfSegmentType::PT_INTERP,
+                    ElfSegmentType::PT_LOAD,
+                    ElfSegmentType::PT_LOAD,
+                    ElfSegmentType::PT_LOAD,
+                ][..]
+            )
+        );
+        let new_load = segments.get(4).expect("new segment");
+        assert_eq!(new_load.p_flags(), ElfSegmentFlags::PF_R.0);
+        assert_eq!(
+            new_load.p_vaddr() % new_load.p_align(),
+            new_load.p_offset() % new_load.p_align()
+        );
+        let phdr = segments.first().expect("PT_PHDR");
+        let phoff = file.header().e_phoff().map_or(0, NonZeroU64::get);
+        assert_eq!(phdr.p_offset(), phoff);
+        assert_eq!(phdr.p_offset(), new_load.p_offset());
+        assert_eq!(phdr.p_vaddr(), new_load.p_vaddr());
+
+        // The interpreter and the string table moved into it, with their
+        // sections.
+        let interp = segments.get(1).expect("PT_INTERP");
+        assert!(interp.p_offset() > new_load.p_offset());
+        assert!(matches!(file.interpreter(), Ok(Some(path)) if path == interpreter));
+        let section = file.section_by_name(".interp").expect("interp section");
+        assert_eq!(section.sh_offset(), interp.p_offset());
+        assert_eq!(
+            section.sh_addr().map_or(0, NonZeroU64::get),
+            interp.p_vaddr()
+        );
+
+        let dynstr = file.section_by_name(".dynstr").expect("dynstr section");
+        let dynamic = file.dynamic_table().expect("readable").expect("dynamic");
+        assert_eq!(
+            dynamic.value(ElfDynamicTag::DT_STRTAB),
+            dynstr.sh_addr().map(NonZeroU64::get)
+        );
+        assert_eq!(
+            dynamic.value(ElfDynamicTag::DT_STRSZ),
+            Some(dynstr.sh_size())
+        );
+        assert!(dynstr.sh_offset() > new_load.p_offset());
+
+        assert_eq!(
+            dynamic_strings(&file),
+            [
+                (ElfDynamicTag::DT_NEEDED, "libc-2.so.6"),
+                (ElfDynamicTag::DT_RUNPATH, "$ORIGIN/../lib"),
+                (ElfDynamicTag::DT_SONAME, "libhardening.so.1"),
+            ]
+        );
+        assert_eq!(version_need_file(&file), "libc-2.so.6");
+
+        // The symbols keep their names, from the moved string table.
+        let dynsym = file
+            .section_by_type(ElfSectionType::SHT_DYNSYM)
+            .expect("dynamic symbols");
+        let strings = file.linked_string_table(dynsym).expect("strings");
+        let names: Vec<_> = file
+            .symbol_table(dynsym)
+            .expect("symbols")
+            .into_iter()
+            .filter_map(|symbol| strings.get(symbol.st_name()).ok())
+            .collect();
+        assert!(names.contains(&"__stack_chk_fail"));
+    }
+}
@@ -0,0 +1,389 @@
+//! Relocating a static PIE in place, before anything else runs.
+//!
+//! A static PIE only has relative relocations, which add the load bias to a
+//! word. They are found through `PT_DYNAMIC`, in the `DT_RELA`, `DT_REL` and
+//! `DT_RELR` tables. [`relocate_with`] reads the tables and applies them
+//! through [`ElfWritableMemory`], and [`self_relocate`] does so for the
+//! running image.
+//!
+//! Until the relocations are applied, any pointer stored in the image is
+//! wrong, so this code avoids trait objects, function pointers and panics,
+//! and allocates nothing.
+
+use core::num::NonZeroU64;
+
+use crate::{
+    elf_aux_structures::*, errors::ElfError, packed_relocations::ElfRelrDecoder,
+    relocation_types::ElfRelocationType, unwind::ElfMemory, ElfProgramHeader, ElfProgramHeaders,
+};
+
+/// Somewhere to read relocation tables from and apply them to.
+pub trait ElfWritableMemory: ElfMemory {
+    /// Writes a little-endian value of 4 or 8 bytes.
+    fn write(&mut self, address: u64, size: u8, value: u64) -> Option<()>;
+}
+
+fn read(memory: &mut impl ElfMemory, address: u64, size: u8) -> Result<u64, ElfError> {
+    memory
+        .read(address, size)
+        .ok_or(ElfError::UnreadableMemory(address))
+}
+
+/// Adds `bias` to the word at `place`.
+fn add_bias(
+    memory: &mut impl ElfWritableMemory,
+    place: u64,
+    word: u8,
+    bias: u64,
+) -> Result<(), ElfError> {
+    let value = read(memory, place, word)?.wrapping_add(bias);
+    memory
+        .write(place, word, value)
+        .ok_or(ElfError::UnwritableMemory(place))
+}
+
+/// A relocation table, by its address once loaded, its size and the entry
+/// size given for it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Table {
+    address: u64,
+    size: u64,
+    entry_size: Option<u64>,
+}
+
+impl Table {
+    /// The addresses of the entries, checking that they are `entry_size`
+    /// bytes.
+    fn entries(self, entry_size: u64) -> Result<impl Iterator<Item = u64>, ElfError> {
+        if self.entry_size.is_some_and(|size| size != entry_size) {
+            let size = self.entry_size.unwrap_or_default();
+            return Err(ElfError::UnexpectedEntrySize(
+                u16::try_from(size).unwrap_or(u16::MAX),
+            ));
+        }
+
+        let count = self.size.checked_div(entry_size).unwrap_or_default();
+        Ok((0..count).map(move |index| self.address.wrapping_add(index.wrapping_mul(entry_size))))
+    }
+}
+
+/// The relocation tables named by a dynamic array.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tables {
+    rela: Table,
+    rel: Table,
+    relr: Table,
+}
+
+impl Tables {
+    /// Reads the dynamic array of `dynamic`, which has entries of two words.
+    fn read(
+        memory: &mut impl ElfMemory,
+        bias: u64,
+        dynamic: ElfProgramHeader,
+        word: u8,
+    ) -> Result<Self, ElfError> {
+        let mut tables = Self::default();
+        let start = bias.wrapping_add(dynamic.p_vaddr());
+        let size = dynamic.p_memsz().map_or(0, NonZeroU64::get);
+        let word_size = u64::from(word);
+
+        let mut offset = 0;
+        while offset < size {
+            let address = start.wrapping_add(offset);
+            let tag = read(memory, address, word)?;
+            let value = read(memory, address.wrapping_add(word_size), word)?;
+            // ELF32 tags are signed 32-bit values.
+            let tag = match u32::try_from(tag) {
+                Ok(tag) if word == 4 => i64::from(tag.cast_signed()),
+                _ => tag.cast_signed(),
+            };
+
+            match ElfDynamicTag(tag) {
+                ElfDynamicTag::DT_NULL => break,
+                ElfDynamicTag::DT_RELA => tables.rela.address = bias.wrapping_add(value),
+                ElfDynamicTag::DT_RELASZ => tables.rela.size = value,
+                ElfDynamicTag::DT_RELAENT => tables.rela.entry_size = Some(value),
+                ElfDynamicTag::DT_REL => tables.rel.address = bias.wrapping_add(value),
+                ElfDynamicTag::DT_RELSZ => tables.rel.size = value,
+                ElfDynamicTag::DT_RELENT => tables.rel.entry_size = Some(value),
+                ElfDynamicTag::DT_RELR => tables.relr.address = bias.wrapping_add(value),
+                ElfDynamicTag::DT_RELRSZ => tables.relr.size = value,
+                ElfDynamicTag::DT_RELRENT => tables.relr.entry_size = Some(value),
+                _ => {}
+            }
+            offset = offset.saturating_add(word_size.saturating_mul(2));
+        }
+
+        Ok(tables)
+    }
+}
+
+/// Whether the relocation with `r_info` is relative, or `R_*_NONE`, which is
+/// type zero on every machine and is skipped. Anything else can't be applied
+/// without symbols.
+fn is_relative(machine: ElfHeaderMachine, r_info: u64, word: u8) -> Result<bool, ElfError> {
+    let r_type = if word == 8 {
+        r_info & 0xffff_ffff
+    } else {
+        r_info & 0xff
+    };
+    let r_type = u32::try_from(r_type)?;
+
+    if r_type == 0 {
+        Ok(false)
+    } else if ElfRelocationType::new(machine, r_type).is_relative() {
+        Ok(true)
+    } else {
+        Err(ElfError::UnsupportedRelocation(r_type))
+    }
+}
+
+/// Applies the relative relocations of an image of `machine` loaded with
+/// `bias`, whose program headers are `program_headers`. Returns the number
+/// of words relocated, which is zero if there is no `PT_DYNAMIC` segment.
+pub fn relocate_with(
+    machine: ElfHeaderMachine,
+    bias: u64,
+    program_headers: ElfProgramHeaders,
+    memory: &mut impl ElfWritableMemory,
+) -> Result<usize, ElfError> {
+    let word: u8 = match program_headers {
+        ElfProgramHeaders::Elf32(_) | ElfProgramHeaders::Elf32Strided(..) => 4,
+        ElfProgramHeaders::Elf64(_) | ElfProgramHeaders::Elf64Strided(..) => 8,
+    };
+    let word_size = u64::from(word);
+
+    let Some(dynamic) = program_headers.find_by_type(ElfSegmentType::PT_DYNAMIC) else {
+        return Ok(0);
+    };
+    let tables = Tables::read(memory, bias, dynamic, word)?;
+    let mut count = 0usize;
+
+    for entry in tables.rela.entries(word_size.saturating_mul(3))? {
+        let r_offset = read(memory, entry, word)?;
+        let r_info = read(memory, entry.wrapping_add(word_size), word)?;
+        let r_addend = read(
+            memory,
+            entry.wrapping_add(word_size.saturating_mul(2)),
+            word,
+        )?;
+
+        if is_relative(machine, r_info, word)? {
+            let place = bias.wrapping_add(r_offset);
+            memory
+                .write(place, word, bias.wrapping_add(r_addend))
+                .ok_or(ElfError::UnwritableMemory(place))?;
+            count = count.saturating_add(1);
+        }
+    }
+
+    for entry in tables.rel.entries(word_size.saturating_mul(2))? {
+        let r_offset = read(memory, entry, word)?;
+        let r_info = read(memory, entry.wrapping_add(word_size), word)?;
+
+        if is_relative(machine, r_info, word)? {
+            add_bias(memory, bias.wrapping_add(r_offset), word, bias)?;
+            count = count.saturating_add(1);
+        }
+    }
+
+    let mut decoder = ElfRelrDecoder::new(word);
+    for entry in tables.relr.entries(word_size)? {
+        for offset in decoder.decode(read(memory, entry, word)?) {
+            add_bias(memory, bias.wrapping_add(offset), word, bias)?;
+            count = count.saturating_add(1);
+        }
+    }
+
+    Ok(count)
+}
+
+/// The machine this code was compiled for.
+const HOST_MACHINE: ElfHeaderMachine = if cfg!(target_arch = "x86_64") {
+    ElfHeaderMachine::EM_X86_64
+} else if cfg!(target_arch = "x86") {
+    ElfHeaderMachine::EM_386
+} else if cfg!(target_arch = "aarch64") {
+    ElfHeaderMachine::EM_AARCH64
+} else if cfg!(target_arch = "arm") {
+    ElfHeaderMachine::EM_ARM
+} else if cfg!(any(target_arch = "riscv64", target_arch = "riscv32")) {
+    ElfHeaderMachine::EM_RISCV
+} else if cfg!(target_arch = "powerpc64") {
+    ElfHeaderMachine::EM_PPC64
+} else if cfg!(target_arch = "loongarch64") {
+    ElfHeaderMachine::EM_LOONGARCH
+} else {
+    ElfHeaderMachine::EM_NONE
+};
+
+/// The memory of the running process, only used by [`self_relocate`].
+struct ProcessMemory;
+
+impl ElfMemory for ProcessMemory {
+    fn read(&mut self, address: u64, size: u8) -> Option<u64> {
+        let pointer = core::ptr::with_exposed_provenance::<u8>(usize::try_from(address).ok()?);
+        // SAFETY: the caller of `self_relocate` guarantees that the dynamic
+        // array, the relocation tables and the relocated words are mapped.
+        unsafe {
+            match size {
+                4 => Some(u64::from(pointer.cast::<u32>().read_unaligned())),
+                8 => Some(pointer.cast::<u64>().read_unaligned()),
+                _ => None,
+            }
+        }
+    }
+}
+
+impl ElfWritableMemory for ProcessMemory {
+    fn write(&mut self, address: u64, size: u8, value: u64) -> Option<()> {
+        let pointer = core::ptr::with_exposed_provenance_mut::<u8>(usize::try_from(address).ok()?);
+        // SAFETY: the caller of `self_relocate` guarantees that the relocated
+        // words are writable, and nothing else is using them.
+        unsafe {
+            match size {
+                4 => pointer
+                    .cast::<u32>()
+                    .write_unaligned(u32::try_from(value & 0xffff_ffff).ok()?),
+                8 => pointer.cast::<u64>().write_unaligned(value),
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+}
+
+/// Applies the relative relocations of the running static PIE, which is
+/// loaded with `bias` and has `program_headers`, as found with `AT_PHDR` or
+/// `__ehdr_start`. Returns the number of words relocated.
+///
+/// # Safety
+///
+/// The image must not have been relocated yet. Its dynamic array and
+/// relocation tables must be mapped, the relocated words must be mapped
+/// writable, and nothing else may use them until this returns.
+pub unsafe fn self_relocate(
+    bias: u64,
+    program_headers: ElfProgramHeaders,
+) -> Result<usize, ElfError> {
+    if HOST_MACHINE == ElfHeaderMachine::EM_NONE {
+        return Err(ElfError::UnsupportedMachine(HOST_MACHINE));
+    }
+    relocate_with(HOST_MACHINE, bias, program_headers, &mut ProcessMemory)
+}
+
+#[cfg(test)]
+mod tests {
+    use zerocopy::FromZeroes;
+
+    use super::*;
+    use crate::elf_structures::Elf64ProgramHeader;
+
+    const BIAS: u64 = 0x10_0000;
+
+    /// An image loaded at `BIAS`.
+    struct Image([u8; 0x100]);
+
+    impl Image {
+        fn range(&self, address: u64, size: u8) -> Option<core::ops::Range<usize>> {
+            let start = usize::try_from(address.checked_sub(BIAS)?).ok()?;
+            Some(start..start.checked_add(usize::from(size))?)
+        }
+
+        fn set(&mut self, offset: usize, value: u64) {
+            self.0
+                .get_mut(offset..offset.saturating_add(8))
+                .expect("in the image")
+                .copy_from_slice(&value.to_le_bytes());
+        }
+
+        fn get(&self, offset: usize) -> u64 {
+            let bytes = self.0.get(offset..offset.saturating_add(8));
+            u64::from_le_bytes(
+                bytes
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .expect("in the image"),
+            )
+        }
+    }
+
+    impl ElfMemory for Image {
+        fn read(&mut self, address: u64, size: u8) -> Option<u64> {
+            let bytes = self.0.get(self.range(address, size)?)?;
+            let mut value = [0; 8];
+            value.get_mut(..bytes.len())?.copy_from_slice(bytes);
+            Some(u64::from_le_bytes(value))
+        }
+    }
+
+    impl ElfWritableMemory for Image {
+        fn write(&mut self, address: u64, size: u8, value: u64) -> Option<()> {
+            let range = self.range(address, size)?;
+            let bytes = value.to_le_bytes();
+            self.0
+                .get_mut(range)?
+                .copy_from_slice(bytes.get(..usize::from(size))?);
+            Some(())
+        }
+    }
+
+    #[test]
+    fn applies_rela_and_relr() {
+        let mut image = Image([0; 0x100]);
+        let entries = [
+            (ElfDynamicTag::DT_RELA, 0x60),
+            (ElfDynamicTag::DT_RELASZ, 0x30),
+            (ElfDynamicTag::DT_RELAENT, 0x18),
+            (ElfDynamicTag::DT_RELR, 0x90),
+            (ElfDynamicTag::DT_RELRSZ, 0x10),
+        ];
+        for (index, (tag, value)) in entries.into_iter().enumerate() {
+            let offset = index.saturating_mul(16);
+            image.set(offset, tag.0.cast_unsigned());
+            image.set(offset.saturating_add(8), value);
+        }
+
+        // A relative relocation and an R_X86_64_NONE.
+        image.set(0x60, 0xc0);
+        image.set(0x68, 8);
+        image.set(0x70, 0x1234);
+
+        // Relocate 0xc8, then 0xd8 and 0xe0 from the bitmap.
+        image.set(0x90, 0xc8);
+        image.set(0x98, 0b1101);
+        for offset in [0xc8, 0xd0, 0xd8, 0xe0] {
+            image.set(offset, 0x10);
+        }
+
+        let mut dynamic = Elf64ProgramHeader::new_zeroed();
+        dynamic.p_type = ElfSegmentType::PT_DYNAMIC;
+        dynamic.p_memsz = NonZeroU64::new(0x60);
+        let program_headers = [dynamic];
+        let program_headers = ElfProgramHeaders::Elf64(&program_headers);
+
+        let count = relocate_with(
+            ElfHeaderMachine::EM_X86_64,
+            BIAS,
+            program_headers,
+            &mut image,
+        )
+        .expect("relocated");
+        assert_eq!(count, 4);
+        assert_eq!(image.get(0xc0), BIAS + 0x1234);
+        assert_eq!(image.get(0xc8), BIAS + 0x10);
+        assert_eq!(image.get(0xd0), 0x10);
+        assert_eq!(image.get(0xd8), BIAS + 0x10);
+        assert_eq!(image.get(0xe0), BIAS + 0x10);
+
+        // R_X86_64_64 needs a symbol.
+        image.set(0x68, 1);
+        let error = relocate_with(
+            ElfHeaderMachine::EM_X86_64,
+            BIAS,
+            program_headers,
+            &mut image,
+        );
+        assert!(matches!(error, Err(ElfError::UnsupportedRelocation(1))));
+    }
+}
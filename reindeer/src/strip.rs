@@ -0,0 +1,544 @@
+//! Removing debugging and symbol sections from linked files, in the style of
+//! `strip`, and splitting them into a separate debug file referred to by a
+//! `.gnu_debuglink` section.
+//!
+//! Everything up to the end of the last segment is copied unchanged, so every
+//! `PT_LOAD` stays byte-identical, apart from the `e_shoff`, `e_shnum` and
+//! `e_shstrndx` fields of the ELF header. Only the non-allocated sections
+//! after the segments, the section name string table and the section header
+//! table are rewritten.
+
+use core::{
+    mem::{offset_of, size_of},
+    num::{NonZeroU16, NonZeroU64},
+    ops::Range,
+};
+use std::vec::Vec;
+
+use crate::{
+    elf_aux_structures::*,
+    elf_structures::*,
+    errors::ElfError,
+    mutable::{ElfHeaderMut, ElfProgramHeaderMut, ElfSectionHeaderMut},
+    range::TryIntoRangeUsize,
+    ElfFile, ElfHeader, ElfProgramHeader, ElfSectionHeader,
+};
+
+const DEBUGLINK: &str = ".gnu_debuglink";
+
+/// Which sections [`strip`] removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfStripMode {
+    /// The debugging sections, like `strip --strip-debug`.
+    Debug,
+    /// Every non-allocated section, including the symbol table, like
+    /// `strip --strip-all`.
+    All,
+}
+
+/// The result of [`split_debug`].
+#[derive(Debug, Clone)]
+pub struct ElfSplitDebug {
+    /// The stripped file, with a `.gnu_debuglink` to the debug file.
+    pub stripped: Vec<u8>,
+    /// The removed sections. Every other section is kept as `SHT_NOBITS`,
+    /// so that section indices match the original file.
+    pub debug: Vec<u8>,
+}
+
+/// Removes the sections selected by `mode`, returning the stripped file.
+pub fn strip(buffer: &[u8], mode: ElfStripMode) -> Result<Vec<u8>, ElfError> {
+    let file = ElfFile::parse(buffer)?;
+    let removed = removed_sections(&file, mode)?;
+    write_stripped(&file, &removed, None)
+}
+
+/// Removes the sections selected by `mode` like [`strip`], and also returns
+/// them as a debug file. The stripped file gets a `.gnu_debuglink` naming
+/// `debug_file_name`, replacing any existing one.
+pub fn split_debug(
+    buffer: &[u8],
+    mode: ElfStripMode,
+    debug_file_name: &str,
+) -> Result<ElfSplitDebug, ElfError> {
+    if debug_file_name.contains('\0') {
+        return Err(ElfError::EmbeddedNul);
+    }
+
+    let file = ElfFile::parse(buffer)?;
+    let removed = removed_sections(&file, mode)?;
+    let debug = write_debug(&file, &removed)?;
+
+    // The file name is NUL-terminated and padded to 4 bytes, followed by the
+    // CRC of the debug file.
+    let mut link = Vec::from(debug_file_name.as_bytes());
+    link.push(0);
+    pad_to(&mut link, 4)?;
+    link.extend_from_slice(&crc32(&debug).to_le_bytes());
+
+    let stripped = write_stripped(&file, &removed, Some(&link))?;
+    Ok(ElfSplitDebug { stripped, debug })
+}
+
+/// The CRC-32 stored in `.gnu_debuglink`, as used by zlib and gzip.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn is_debug_section(name: &str) -> bool {
+    name.starts_with(".debug")
+        || name.starts_with(".zdebug")
+        || name.starts_with(".stab")
+        || name == ".gdb_index"
+}
+
+fn is_relocation(section: ElfSectionHeader) -> bool {
+    let section_type = section.sh_type();
+    section_type == ElfSectionType::SHT_REL || section_type == ElfSectionType::SHT_RELA
+}
+
+/// Whether `sh_info` holds a section index, rather than a symbol index.
+fn info_is_section(section: ElfSectionHeader) -> bool {
+    is_relocation(section)
+        || ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_INFO_LINK)
+}
+
+fn is_allocated(section: ElfSectionHeader) -> bool {
+    ElfSectionFlags(section.sh_flags()).contains(ElfSectionFlags::SHF_ALLOC)
+}
+
+fn shstrndx(file: &ElfFile) -> usize {
+    usize::from(file.header().e_shstrndx().map_or(0, NonZeroU16::get))
+}
+
+/// Marks the sections that `mode` removes, by index. The null section, the
+/// section name string table and `.gnu_debuglink` are never removed, and
+/// relocations go with the section they apply to.
+fn removed_sections(file: &ElfFile, mode: ElfStripMode) -> Result<Vec<bool>, ElfError> {
+    let e_type = file.header().e_type();
+    if e_type == ElfHeaderType::ET_REL {
+        return Err(ElfError::UnsupportedFileType(e_type));
+    }
+
+    let sections: Vec<_> = file.sections().collect();
+    if sections.is_empty() {
+        return Err(ElfError::NoSectionHeaders);
+    }
+
+    let mut removed = Vec::with_capacity(sections.len());
+    for (index, section) in sections.iter().enumerate() {
+        let name = file.section_name(*section)?;
+        let remove = index != 0
+            && index != shstrndx(file)
+            && name != DEBUGLINK
+            && match mode {
+                ElfStripMode::Debug => is_debug_section(name),
+                ElfStripMode::All => !is_allocated(*section),
+            };
+        removed.push(remove);
+    }
+
+    for (index, section) in sections.iter().enumerate() {
+        let target = usize::try_from(section.sh_info()).unwrap_or_default();
+        if is_relocation(*section) && target != 0 && removed.get(target) == Some(&true) {
+            if let Some(remove) = removed.get_mut(index) {
+                *remove = true;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+fn bytes(buffer: &[u8], location: Range<u64>) -> Result<&[u8], ElfError> {
+    buffer
+        .get(location.clone().try_into_usize()?)
+        .ok_or(ElfError::DataOutOfBounds(location))
+}
+
+/// Pads `out` with zeroes to a multiple of `align`, returning its length.
+fn pad_to(out: &mut Vec<u8>, align: u64) -> Result<u64, ElfError> {
+    let length = u64::try_from(out.len())?;
+    let aligned = length
+        .checked_next_multiple_of(align.max(1))
+        .ok_or(ElfError::ValueOutOfRange("sh_offset", length))?;
+    out.resize(usize::try_from(aligned)?, 0);
+    Ok(aligned)
+}
+
+fn section_header_bytes<'buf>(file: &ElfFile<'buf>, index: u16) -> Result<&'buf [u8], ElfError> {
+    let location = file
+        .header()
+        .section_header_location(index)
+        .ok_or(ElfError::HeaderIndexOutOfBounds(index))?;
+    bytes(file.buffer(), location)
+}
+
+fn write_stripped(
+    file: &ElfFile,
+    removed: &[bool],
+    debuglink: Option<&[u8]>,
+) -> Result<Vec<u8>, ElfError> {
+    let header = file.header();
+    let shstrndx = shstrndx(file);
+
+    let mut prefix_end = u64::from(header.e_ehsize());
+    if let Some(location) = header.program_headers_location() {
+        prefix_end = prefix_end.max(location.end);
+    }
+    for segment in file.segments() {
+        if let Some(location) = segment.file_location() {
+            prefix_end = prefix_end.max(location.end);
+        }
+    }
+    let mut out = Vec::from(bytes(file.buffer(), 0..prefix_end)?);
+
+    // Maps old section indices to new ones.
+    let mut mapping = Vec::with_capacity(removed.len());
+    let mut kept = Vec::new();
+    let mut count: u16 = 0;
+    for (index, section) in file.sections().enumerate() {
+        let replaced = debuglink.is_some() && file.section_name(section)? == DEBUGLINK;
+        if removed.get(index) == Some(&true) || replaced {
+            mapping.push(None);
+            continue;
+        }
+
+        mapping.push(Some(count));
+        kept.push((index, section));
+        count = count
+            .checked_add(1)
+            .ok_or(ElfError::ValueOutOfRange("e_shnum", u64::from(count)))?;
+    }
+    let map = |index: u32| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| mapping.get(index).copied().flatten())
+            .map_or(0, u32::from)
+    };
+
+    let mut names = Vec::from([0]);
+    let mut name = |name: &str| -> Result<u32, ElfError> {
+        let offset = u32::try_from(names.len())?;
+        names.extend_from_slice(name.as_bytes());
+        names.push(0);
+        Ok(offset)
+    };
+
+    let mut placed = Vec::with_capacity(kept.len());
+    for (index, section) in &kept {
+        let location = section.location();
+        let offset = if *index == shstrndx {
+            // Rebuilt below, once every name is known.
+            0
+        } else if section.sh_type() == ElfSectionType::SHT_NOBITS || location.end <= prefix_end {
+            location.start
+        } else {
+            let offset = pad_to(&mut out, section.sh_addralign())?;
+            out.extend_from_slice(file.section_data(*section)?);
+            offset
+        };
+        placed.push((name(file.section_name(*section)?)?, offset));
+    }
+
+    let debuglink = match debuglink {
+        Some(data) => {
+            let offset = pad_to(&mut out, 4)?;
+            out.extend_from_slice(data);
+            Some((name(DEBUGLINK)?, offset, u64::try_from(data.len())?))
+        }
+        None => None,
+    };
+
+    let names_offset = u64::try_from(out.len())?;
+    let names_size = u64::try_from(names.len())?;
+    out.extend_from_slice(&names);
+
+    let section_headers = pad_to(&mut out, 8)?;
+    for (index, _) in &kept {
+        out.extend_from_slice(section_header_bytes(file, u16::try_from(*index)?)?);
+    }
+    if debuglink.is_some() {
+        out.extend_from_slice(section_header_bytes(file, 0)?);
+        count = count
+            .checked_add(1)
+            .ok_or(ElfError::ValueOutOfRange("e_shnum", u64::from(count)))?;
+    }
+
+    let new_shstrndx = mapping.get(shstrndx).copied().flatten();
+    let mut elf_header = ElfHeaderMut::parse(&mut out)?;
+    elf_header.set_e_shoff(NonZeroU64::new(section_headers))?;
+    elf_header.set_e_shnum(NonZeroU16::new(count));
+    elf_header.set_e_shstrndx(new_shstrndx.and_then(NonZeroU16::new));
+
+    for (new_index, ((_, section), (name, offset))) in kept.iter().zip(&placed).enumerate() {
+        let new_index = u16::try_from(new_index)?;
+        let mut new_section = ElfSectionHeaderMut::from_file(&mut out, new_index)?;
+        new_section.set_sh_name(*name);
+        new_section.set_sh_link(map(section.sh_link()));
+        if info_is_section(*section) {
+            new_section.set_sh_info(map(section.sh_info()));
+        }
+
+        if Some(new_index) == new_shstrndx {
+            new_section.set_sh_offset(names_offset)?;
+            new_section.set_sh_size(names_size)?;
+        } else {
+            new_section.set_sh_offset(*offset)?;
+        }
+    }
+
+    if let Some((name, offset, size)) = debuglink {
+        let index = count.saturating_sub(1);
+        let mut section = ElfSectionHeaderMut::from_file(&mut out, index)?;
+        section.set_sh_name(name);
+        section.set_sh_type(ElfSectionType::SHT_PROGBITS);
+        section.set_sh_offset(offset)?;
+        section.set_sh_size(size)?;
+        section.set_sh_addralign(4)?;
+    }
+
+    for ((_, section), (_, offset)) in kept.iter().zip(&placed) {
+        let section_type = section.sh_type();
+        if section_type == ElfSectionType::SHT_SYMTAB || section_type == ElfSectionType::SHT_DYNSYM
+        {
+            renumber_symbols(file, *section, *offset, &mapping, &mut out)?;
+        }
+    }
+
+    // The result must still be readable by this crate.
+    ElfFile::parse(&out)?;
+    Ok(out)
+}
+
+/// Updates the section indices of the symbols in a kept symbol table, now at
+/// `offset` in `out`. Symbols in removed sections become undefined. The
+/// contents of allocated tables can't change, so renumbering them fails.
+fn renumber_symbols(
+    file: &ElfFile,
+    section: ElfSectionHeader,
+    offset: u64,
+    mapping: &[Option<u16>],
+    out: &mut [u8],
+) -> Result<(), ElfError> {
+    let (entry_size, field) = match file.header() {
+        ElfHeader::Elf32(_) => (size_of::<Elf32Symbol>(), offset_of!(Elf32Symbol, st_shndx)),
+        ElfHeader::Elf64(_) => (size_of::<Elf64Symbol>(), offset_of!(Elf64Symbol, st_shndx)),
+    };
+
+    let mut position = usize::try_from(offset)?;
+    for symbol in file.symbol_table(section)? {
+        let old = symbol.st_shndx();
+        let index = usize::from(old.0);
+        let field_position = position.saturating_add(field);
+        position = position.saturating_add(entry_size);
+
+        if old == ElfSectionIndex::SHN_UNDEF || old.0 >= ElfSectionIndex::SHN_LORESERVE.0 {
+            continue;
+        }
+
+        let new = mapping.get(index).copied().flatten().unwrap_or_default();
+        if new == old.0 {
+            continue;
+        }
+        if is_allocated(section) {
+            return Err(ElfError::StripWouldRenumber(old.0));
+        }
+
+        out.get_mut(field_position..field_position.saturating_add(2))
+            .ok_or(ElfError::ZeroCopyError)?
+            .copy_from_slice(&new.to_le_bytes());
+    }
+
+    Ok(())
+}
+
+/// Writes the removed sections and the section name string table into a
+/// file with the original program and section headers, like
+/// `objcopy --only-keep-debug`.
+///
+/// The start of the file, up to the end of the program headers and the
+/// allocated notes, is copied so that the build ID stays where the segments
+/// say it is. Every other section becomes `SHT_NOBITS`, and the segments are
+/// cut short to the copied part of the file.
+fn write_debug(file: &ElfFile, removed: &[bool]) -> Result<Vec<u8>, ElfError> {
+    let header = file.header();
+    let shstrndx = shstrndx(file);
+    let is_note = |section: ElfSectionHeader| {
+        section.sh_type() == ElfSectionType::SHT_NOTE && is_allocated(section)
+    };
+
+    let mut head_end = u64::from(header.e_ehsize());
+    if let Some(location) = header.program_headers_location() {
+        head_end = head_end.max(location.end);
+    }
+    for section in file.sections().filter(|section| is_note(*section)) {
+        head_end = head_end.max(section.location().end);
+    }
+    let mut out = Vec::from(bytes(file.buffer(), 0..head_end)?);
+
+    let mut placed = Vec::new();
+    for (index, section) in file.sections().enumerate() {
+        let nobits = section.sh_type() == ElfSectionType::SHT_NOBITS;
+        let location = section.location();
+        let keep = index != 0 && (removed.get(index) == Some(&true) || index == shstrndx);
+
+        let (offset, emptied) = if index == 0 || nobits {
+            (location.start, false)
+        } else if keep {
+            let offset = pad_to(&mut out, section.sh_addralign())?;
+            out.extend_from_slice(file.section_data(section)?);
+            (offset, false)
+        } else if is_note(section) {
+            (location.start, false)
+        } else if location.start < head_end {
+            (location.start, true)
+        } else {
+            (u64::try_from(out.len())?, true)
+        };
+        placed.push((offset, emptied));
+    }
+
+    let section_headers = pad_to(&mut out, 8)?;
+    for index in 0..placed.len() {
+        out.extend_from_slice(section_header_bytes(file, u16::try_from(index)?)?);
+    }
+
+    let mut elf_header = ElfHeaderMut::parse(&mut out)?;
+    elf_header.set_e_shoff(NonZeroU64::new(section_headers))?;
+
+    for (index, (offset, emptied)) in placed.into_iter().enumerate() {
+        let mut section = ElfSectionHeaderMut::from_file(&mut out, u16::try_from(index)?)?;
+        section.set_sh_offset(offset)?;
+        if emptied {
+            section.set_sh_type(ElfSectionType::SHT_NOBITS);
+        }
+    }
+
+    for (index, segment) in file.segments().enumerate() {
+        let size = segment.p_filesz().map_or(0, NonZeroU64::get);
+        let copied = head_end.saturating_sub(segment.p_offset()).min(size);
+        if copied == size {
+            continue;
+        }
+
+        let mut program_header = ElfProgramHeaderMut::from_file(&mut out, u16::try_from(index)?)?;
+        program_header.set_p_filesz(NonZeroU64::new(copied))?;
+        if segment.p_offset() > head_end {
+            program_header.set_p_offset(emptied_segment_offset(segment, head_end))?;
+        }
+    }
+
+    ElfFile::parse(&out)?;
+    Ok(out)
+}
+
+/// Where a segment starting after the copied part of the file is moved to,
+/// like `objcopy --only-keep-debug` does: the last offset within the copied
+/// part that is congruent with the old one, or the end of the copied part if
+/// there is none.
+fn emptied_segment_offset(segment: ElfProgramHeader, head_end: u64) -> u64 {
+    let remainder = segment
+        .p_offset()
+        .checked_rem(segment.p_align())
+        .unwrap_or_default();
+    head_end
+        .checked_sub(remainder)
+        .and_then(|distance| distance.checked_rem(segment.p_align().max(1)))
+        .and_then(|excess| head_end.checked_sub(excess))
+        .unwrap_or(head_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::STRIP_X86_64;
+
+    #[test]
+    fn computes_debuglink_crc() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    fn symbol_section(file: &ElfFile, name: &str) -> Option<u16> {
+        let symtab = file.section_by_type(ElfSectionType::SHT_SYMTAB)?;
+        let strings = file.linked_string_table(symtab).ok()?;
+        file.symbol_table(symtab)
+            .ok()?
+            .into_iter()
+            .find(|symbol| strings.get(symbol.st_name()).ok() == Some(name))
+            .map(|symbol| symbol.st_shndx().0)
+    }
+
+    /// The contents of the `PT_LOAD` segments, leaving out the ELF header,
+    /// whose section header table fields change.
+    fn load_segments<'buf>(file: &ElfFile<'buf>) -> Vec<&'buf [u8]> {
+        let header_size = usize::from(file.header().e_ehsize());
+        file.segments()
+            .filter(|segment| segment.p_type() == ElfSegmentType::PT_LOAD)
+            .map(|segment| {
+                let data = file.segment_data(segment).expect("segment in file");
+                match segment.p_offset() {
+                    0 => data.get(header_size..).unwrap_or_default(),
+                    _ => data,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strips_debug_sections() {
+        let original = ElfFile::parse(&STRIP_X86_64.0).expect("valid file");
+        let stripped = strip(&STRIP_X86_64.0, ElfStripMode::Debug).expect("strippable");
+        let file = ElfFile::parse(&stripped).expect("valid file");
+
+        assert!(file.section_by_name(".debug_str").is_none());
+        assert_eq!(load_segments(&file), load_segments(&original));
+
+        // `.kept` moves down a place, taking its symbol with it.
+        assert_eq!(symbol_section(&original, "marker"), Some(4));
+        assert_eq!(symbol_section(&file, "marker"), Some(3));
+        assert_eq!(symbol_section(&file, "counter"), Some(2));
+        let symtab = file
+            .section_by_type(ElfSectionType::SHT_SYMTAB)
+            .expect("symbol table");
+        let strtab = file.linked_section(symtab).expect("string table");
+        assert_eq!(file.section_name(strtab).ok(), Some(".strtab"));
+
+        let stripped = strip(&STRIP_X86_64.0, ElfStripMode::All).expect("strippable");
+        let file = ElfFile::parse(&stripped).expect("valid file");
+        assert!(file.section_by_type(ElfSectionType::SHT_SYMTAB).is_none());
+        assert_eq!(load_segments(&file), load_segments(&original));
+    }
+
+    #[test]
+    fn splits_debug_file() {
+        let split = split_debug(&STRIP_X86_64.0, ElfStripMode::Debug, "strip-x86_64.debug")
+            .expect("strippable");
+
+        let stripped = ElfFile::parse(&split.stripped).expect("valid file");
+        let debuglink = stripped
+            .section_by_name(DEBUGLINK)
+            .and_then(|section| stripped.section_data(section).ok());
+        let mut expected = Vec::from(*b"strip-x86_64.debug\0\0");
+        expected.extend_from_slice(&crc32(&split.debug).to_le_bytes());
+        assert_eq!(debuglink, Some(expected.as_slice()));
+
+        let debug = ElfFile::parse(&split.debug).expect("valid file");
+        let debug_str = debug
+            .section_by_name(".debug_str")
+            .and_then(|section| debug.section_data(section).ok());
+        assert_eq!(debug_str, Some(&b"strip-x86_64.s\0"[..]));
+        let data = debug.section_by_name(".data").expect("section header");
+        assert_eq!(data.sh_type(), ElfSectionType::SHT_NOBITS);
+        assert!(crate::validate::validate(&debug).is_empty());
+    }
+}
@@ -0,0 +1,417 @@
+//! Turning instruction addresses into `function+0x1c`, using an index of the
+//! `.symtab` and `.dynsym` symbols sorted by address.
+//!
+//! Only functions are indexed, unless [`ElfSymbolizer::new_with_all_types`]
+//! also indexes `STT_OBJECT` and `STT_NOTYPE` symbols, as labels in
+//! hand-written assembly often are.
+//!
+//! Symbols with an `st_size` cover exactly that many bytes, so an address in
+//! the padding between two functions has no symbol. An address past the end
+//! of a sized symbol nested in another falls back to the enclosing one.
+//! Zero-sized symbols cover up to the next symbol or the end of their
+//! section, unless they fall inside a sized symbol. Of several symbols at
+//! the same address, functions are preferred over other types, then global
+//! symbols over weak and local ones.
+//!
+//! A stripped file still has the `.eh_frame` FDEs of its functions, which
+//! [`ElfSymbolizer::with_eh_frame_functions`] adds as unnamed symbols where
+//! no named one covers them. They are shown as `sub_401130+0x1c`. Their
+//! names may instead be in the [MiniDebugInfo](crate::mini_debug_info) or
+//! the [separate debug file](crate::debug_file) of the stripped file, which
+//! [`ElfSymbolizer::with_symbols_from`] adds.
+//!
+//! Addresses are those of a linked file. The `st_value` of symbols in a
+//! relocatable object is relative to its section, and isn't useful here.
+
+use core::{cmp::Reverse, fmt};
+use std::vec::Vec;
+
+use crate::{
+    eh_frame, elf_aux_structures::*, errors::ElfError, symbols::ElfSymbol, ElfFile,
+    ElfSectionHeader,
+};
+
+/// A symbol in an [`ElfSymbolizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfIndexedSymbol<'buf> {
+    /// The name, which is empty for a function only known from its FDE.
+    pub name: &'buf str,
+    /// The address of the symbol, with the Thumb bit cleared on ARM.
+    pub address: u64,
+    /// The end of the addresses the symbol covers, which for a zero-sized
+    /// symbol is the start of the next symbol or the end of its section.
+    pub end: u64,
+    pub size: u64,
+    pub symbol_type: ElfSymbolType,
+    pub binding: ElfSymbolBinding,
+}
+
+impl ElfIndexedSymbol<'_> {
+    /// Whether the symbol was made from an FDE, rather than read from a
+    /// symbol table.
+    pub fn is_synthetic(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    /// Orders symbols at the same address, with the preferred one first.
+    fn rank(&self) -> (bool, Reverse<bool>, u8, Reverse<u64>, &str) {
+        let is_function = matches!(
+            self.symbol_type,
+            ElfSymbolType::STT_FUNC | ElfSymbolType::STT_GNU_IFUNC
+        );
+        let binding = match self.binding {
+            ElfSymbolBinding::STB_GLOBAL => 0,
+            ElfSymbolBinding::STB_WEAK => 1,
+            _ => 2,
+        };
+
+        (
+            self.is_synthetic(),
+            Reverse(is_function),
+            binding,
+            Reverse(self.size),
+            self.name,
+        )
+    }
+}
+
+/// An address, as an offset into the symbol containing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfSymbolization<'buf> {
+    pub symbol: ElfIndexedSymbol<'buf>,
+    pub offset: u64,
+}
+
+impl fmt::Display for ElfSymbolization<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.symbol.is_synthetic() {
+            write!(f, "sub_{:x}+{:#x}", self.symbol.address, self.offset)
+        } else {
+            write!(f, "{}+{:#x}", self.symbol.name, self.offset)
+        }
+    }
+}
+
+/// An index of the symbols of a file by address.
+#[derive(Debug, Clone, Default)]
+pub struct ElfSymbolizer<'buf> {
+    symbols: Vec<ElfIndexedSymbol<'buf>>,
+    /// The index of the symbol enclosing each symbol, if any.
+    parents: Vec<Option<usize>>,
+    bias: u64,
+    all_types: bool,
+}
+
+impl<'buf> ElfSymbolizer<'buf> {
+    /// Indexes the function symbols in the `SHT_SYMTAB` and `SHT_DYNSYM`
+    /// sections.
+    pub fn new(file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let mut symbols = Vec::new();
+        read_symbols(file, false, &mut symbols)?;
+        Ok(Self::from_symbols(symbols))
+    }
+
+    /// Indexes the `STT_OBJECT` and `STT_NOTYPE` symbols as well as the
+    /// functions.
+    pub fn new_with_all_types(file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let mut symbols = Vec::new();
+        read_symbols(file, true, &mut symbols)?;
+        Ok(Self {
+            all_types: true,
+            ..Self::from_symbols(symbols)
+        })
+    }
+
+    /// Adds the symbols of another file with the same addresses, such as
+    /// the separate debug file or the MiniDebugInfo of a stripped file.
+    pub fn with_symbols_from(mut self, file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let mut symbols = core::mem::take(&mut self.symbols);
+        read_symbols(file, self.all_types, &mut symbols)?;
+        Ok(self.rebuild(symbols))
+    }
+
+    /// Re-indexes the symbols, keeping the settings of `self`.
+    fn rebuild(self, symbols: Vec<ElfIndexedSymbol<'buf>>) -> Self {
+        Self {
+            bias: self.bias,
+            all_types: self.all_types,
+            ..Self::from_symbols(symbols)
+        }
+    }
+
+    fn from_symbols(mut symbols: Vec<ElfIndexedSymbol<'buf>>) -> Self {
+        symbols.sort_by(|a, b| a.address.cmp(&b.address).then(a.rank().cmp(&b.rank())));
+        symbols.dedup_by_key(|symbol| symbol.address);
+
+        // Zero-sized and synthetic symbols inside a sized one would hide it.
+        let mut sized_end = 0;
+        symbols.retain(|symbol| {
+            if symbol.size != 0 && !symbol.is_synthetic() {
+                sized_end = sized_end.max(symbol.end);
+                true
+            } else {
+                symbol.address >= sized_end
+            }
+        });
+
+        let starts: Vec<u64> = symbols.iter().map(|symbol| symbol.address).collect();
+        for (symbol, next) in symbols.iter_mut().zip(starts.iter().skip(1)) {
+            if symbol.size == 0 {
+                symbol.end = symbol.end.min(*next);
+            }
+        }
+
+        // Sized symbols may be nested, as for a local function inside a
+        // global one, so each keeps the innermost symbol still open at its
+        // start, which covers the addresses after its end.
+        let mut open: Vec<usize> = Vec::new();
+        let mut parents = Vec::with_capacity(symbols.len());
+        for (index, symbol) in symbols.iter().enumerate() {
+            while open
+                .last()
+                .and_then(|&parent| symbols.get(parent))
+                .is_some_and(|parent| parent.end <= symbol.address)
+            {
+                open.pop();
+            }
+            parents.push(open.last().copied());
+            open.push(index);
+        }
+
+        Self {
+            symbols,
+            parents,
+            bias: 0,
+            all_types: false,
+        }
+    }
+
+    /// Adds the functions described by `.eh_frame` that no symbol covers,
+    /// which for a stripped file is all but the exported ones.
+    pub fn with_eh_frame_functions(mut self, file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let mut symbols = core::mem::take(&mut self.symbols);
+        eh_frame::function_ranges_with(file, |range| {
+            symbols.push(ElfIndexedSymbol {
+                name: "",
+                address: range.start,
+                end: range.end,
+                size: range.end.saturating_sub(range.start),
+                symbol_type: ElfSymbolType::STT_FUNC,
+                binding: ElfSymbolBinding::STB_LOCAL,
+            });
+        })?;
+
+        Ok(self.rebuild(symbols))
+    }
+
+    /// Sets the load bias, the difference between the addresses the file
+    /// was loaded at and those in the file, as for a position-independent
+    /// executable or shared library.
+    pub fn with_load_bias(mut self, bias: u64) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// The indexed symbols, sorted by address.
+    pub fn symbols(&self) -> &[ElfIndexedSymbol<'buf>] {
+        &self.symbols
+    }
+
+    /// Finds the symbol containing a loaded address.
+    pub fn symbolize(&self, address: u64) -> Option<ElfSymbolization<'buf>> {
+        let address = address.checked_sub(self.bias)?;
+        let mut index = self
+            .symbols
+            .partition_point(|symbol| symbol.address <= address)
+            .checked_sub(1)?;
+
+        loop {
+            let symbol = *self.symbols.get(index)?;
+            let offset = address.checked_sub(symbol.address)?;
+            if address < symbol.end || offset == 0 {
+                return Some(ElfSymbolization { symbol, offset });
+            }
+            index = (*self.parents.get(index)?)?;
+        }
+    }
+}
+
+fn read_symbols<'buf>(
+    file: &ElfFile<'buf>,
+    all_types: bool,
+    symbols: &mut Vec<ElfIndexedSymbol<'buf>>,
+) -> Result<(), ElfError> {
+    let is_arm = file.header().e_machine() == ElfHeaderMachine::EM_ARM;
+    for section in file.sections().filter(|section| {
+        matches!(
+            section.sh_type(),
+            ElfSectionType::SHT_SYMTAB | ElfSectionType::SHT_DYNSYM
+        )
+    }) {
+        let strings = file.linked_string_table(section)?;
+        for symbol in file.symbol_table(section)? {
+            let name = strings.get(symbol.st_name());
+            if let Some(indexed) = index_symbol(file, symbol, name, all_types, is_arm) {
+                symbols.push(indexed);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn index_symbol<'buf>(
+    file: &ElfFile<'buf>,
+    symbol: ElfSymbol,
+    name: Result<&'buf str, ElfError>,
+    all_types: bool,
+    is_arm: bool,
+) -> Option<ElfIndexedSymbol<'buf>> {
+    let symbol_type = symbol.symbol_type();
+    let is_function = matches!(
+        symbol_type,
+        ElfSymbolType::STT_FUNC | ElfSymbolType::STT_GNU_IFUNC
+    );
+    let is_other = matches!(
+        symbol_type,
+        ElfSymbolType::STT_OBJECT | ElfSymbolType::STT_NOTYPE
+    );
+    if !(is_function || all_types && is_other) {
+        return None;
+    }
+
+    let shndx = symbol.st_shndx();
+    let section = (shndx.0 < ElfSectionIndex::SHN_LORESERVE.0)
+        .then(|| file.section_headers()?.get(shndx.0))
+        .flatten();
+    if symbol.is_undefined() || (section.is_none() && shndx != ElfSectionIndex::SHN_ABS) {
+        return None;
+    }
+
+    // ARM mapping symbols mark code and data, and aren't names.
+    let name = name.ok().filter(|name| !name.is_empty())?;
+    if is_arm && name.starts_with('$') {
+        return None;
+    }
+
+    // The lowest bit of a Thumb function's address selects the instruction
+    // set, and isn't part of the address.
+    let mut address = symbol.st_value();
+    if is_arm && is_function {
+        address &= !1;
+    }
+
+    let size = symbol.st_size();
+    let end = match size {
+        0 => section.map_or(address, section_end),
+        size => address.saturating_add(size),
+    };
+
+    Some(ElfIndexedSymbol {
+        name,
+        address,
+        end,
+        size,
+        symbol_type,
+        binding: symbol.binding(),
+    })
+}
+
+fn section_end(section: ElfSectionHeader) -> u64 {
+    section
+        .sh_addr()
+        .map_or(0, |address| address.get())
+        .saturating_add(section.sh_size())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::UNWIND_X86_64;
+
+    fn symbol(
+        name: &'static str,
+        address: u64,
+        size: u64,
+        binding: ElfSymbolBinding,
+    ) -> ElfIndexedSymbol<'static> {
+        ElfIndexedSymbol {
+            name,
+            address,
+            end: if size == 0 {
+                0x1100
+            } else {
+                address.saturating_add(size)
+            },
+            size,
+            symbol_type: ElfSymbolType::STT_FUNC,
+            binding,
+        }
+    }
+
+    #[test]
+    fn honours_sizes_and_aliases() {
+        let symbolizer = ElfSymbolizer::from_symbols(std::vec![
+            symbol("weak_alias", 0x1000, 0x20, ElfSymbolBinding::STB_WEAK),
+            symbol("label", 0x1010, 0, ElfSymbolBinding::STB_LOCAL),
+            symbol("main", 0x1000, 0x20, ElfSymbolBinding::STB_GLOBAL),
+            symbol("asm", 0x1040, 0, ElfSymbolBinding::STB_GLOBAL),
+            symbol("after", 0x1080, 0x10, ElfSymbolBinding::STB_GLOBAL),
+            symbol("", 0x1000, 0x20, ElfSymbolBinding::STB_LOCAL),
+            symbol("", 0x1028, 0x8, ElfSymbolBinding::STB_LOCAL),
+        ])
+        .with_load_bias(0x5000);
+
+        let names = |address| {
+            symbolizer
+                .symbolize(address)
+                .map(|found| (found.symbol.name, found.offset))
+        };
+        assert_eq!(names(0x6010), Some(("main", 0x10)));
+        assert_eq!(names(0x6030), None);
+        assert_eq!(names(0x602c), Some(("", 0x4)));
+        assert_eq!(names(0x6070), Some(("asm", 0x30)));
+        assert_eq!(names(0x6090), None);
+        assert_eq!(names(0x1000), None);
+    }
+
+    #[test]
+    fn falls_back_to_enclosing_symbols() {
+        let symbolizer = ElfSymbolizer::from_symbols(std::vec![
+            symbol("outer", 0x1000, 0x100, ElfSymbolBinding::STB_GLOBAL),
+            symbol("middle", 0x1010, 0x40, ElfSymbolBinding::STB_LOCAL),
+            symbol("inner", 0x1020, 0x10, ElfSymbolBinding::STB_LOCAL),
+            symbol("after", 0x1200, 0x10, ElfSymbolBinding::STB_GLOBAL),
+        ]);
+
+        let names = |address| {
+            symbolizer
+                .symbolize(address)
+                .map(|found| (found.symbol.name, found.offset))
+        };
+        assert_eq!(names(0x1024), Some(("inner", 0x4)));
+        assert_eq!(names(0x1030), Some(("middle", 0x20)));
+        assert_eq!(names(0x1050), Some(("outer", 0x50)));
+        assert_eq!(names(0x1100), None);
+        assert_eq!(names(0x1204), Some(("after", 0x4)));
+    }
+
+    #[test]
+    fn indexes_functions_unless_asked() {
+        let file = ElfFile::parse(&UNWIND_X86_64.0).expect("valid file");
+        let name = |symbolizer: &ElfSymbolizer<'static>, address| {
+            symbolizer
+                .symbolize(address)
+                .map(|found| (found.symbol.name, found.offset))
+        };
+
+        let functions = ElfSymbolizer::new(&file).expect("readable symbols");
+        assert_eq!(functions.symbols().len(), 3);
+        assert_eq!(name(&functions, 0x400110), Some(("early_return", 0x10)));
+        assert_eq!(name(&functions, 0x400154), None);
+
+        let all = ElfSymbolizer::new_with_all_types(&file).expect("readable symbols");
+        assert_eq!(name(&all, 0x400110), Some(("early_return", 0x10)));
+        assert_eq!(name(&all, 0x400154), Some(("__GNU_EH_FRAME_HDR", 0x4)));
+    }
+}
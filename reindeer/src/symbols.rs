@@ -0,0 +1,98 @@
+//! Symbol tables, as found in `SHT_SYMTAB` and `SHT_DYNSYM` sections.
+
+use zerocopy::FromBytes;
+
+use crate::{elf_aux_structures::*, elf_structures::*, errors::ElfError, ElfHeader};
+
+/// A symbol table entry, representing either 64 or 32 bit symbols.
+#[derive(Debug, Clone, Copy)]
+pub enum ElfSymbol<'buf> {
+    Elf32(&'buf Elf32Symbol),
+    Elf64(&'buf Elf64Symbol),
+}
+
+impl ElfSymbol<'_> {
+    pub fn binding(&self) -> ElfSymbolBinding {
+        ElfSymbolBinding(self.st_info() >> 4)
+    }
+
+    pub fn symbol_type(&self) -> ElfSymbolType {
+        ElfSymbolType(self.st_info() & 0xf)
+    }
+
+    pub fn visibility(&self) -> ElfSymbolVisibility {
+        ElfSymbolVisibility(self.st_other() & 0x3)
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        self.st_shndx() == ElfSectionIndex::SHN_UNDEF
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ElfSymbolTable<'buf> {
+    Elf32(&'buf [Elf32Symbol]),
+    Elf64(&'buf [Elf64Symbol]),
+}
+
+impl<'buf> ElfSymbolTable<'buf> {
+    pub fn parse(header: ElfHeader, bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        let symbols = match header {
+            ElfHeader::Elf32(_) => {
+                Self::Elf32(Elf32Symbol::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+            ElfHeader::Elf64(_) => {
+                Self::Elf64(Elf64Symbol::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?)
+            }
+        };
+
+        Ok(symbols)
+    }
+
+    pub fn get(&self, index: u32) -> Option<ElfSymbol<'buf>> {
+        let index = usize::try_from(index).ok()?;
+        match self {
+            Self::Elf32(symbols) => symbols.get(index).map(ElfSymbol::Elf32),
+            Self::Elf64(symbols) => symbols.get(index).map(ElfSymbol::Elf64),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Elf32(symbols) => symbols.len(),
+            Self::Elf64(symbols) => symbols.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'buf> IntoIterator for ElfSymbolTable<'buf> {
+    type Item = ElfSymbol<'buf>;
+    type IntoIter = ElfSymbolTableIter<'buf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            ElfSymbolTable::Elf32(symbols) => Self::IntoIter::Elf32(symbols.iter()),
+            ElfSymbolTable::Elf64(symbols) => Self::IntoIter::Elf64(symbols.iter()),
+        }
+    }
+}
+
+pub enum ElfSymbolTableIter<'buf> {
+    Elf32(core::slice::Iter<'buf, Elf32Symbol>),
+    Elf64(core::slice::Iter<'buf, Elf64Symbol>),
+}
+
+impl<'buf> Iterator for ElfSymbolTableIter<'buf> {
+    type Item = ElfSymbol<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Elf32(iter) => iter.next().map(Self::Item::Elf32),
+            Self::Elf64(iter) => iter.next().map(Self::Item::Elf64),
+        }
+    }
+}
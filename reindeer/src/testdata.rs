@@ -0,0 +1,34 @@
+//! Files used by the tests, built from the sources next to them in
+//! `testdata/`.
+
+/// Aligns an included file, as ELF structures are read in place.
+#[derive(Clone, Copy)]
+#[repr(C, align(8))]
+pub struct Aligned<T>(pub T);
+
+macro_rules! testdata {
+    ($name:ident, $path:literal) => {
+        pub static $name: Aligned<[u8; include_bytes!($path).len()]> =
+            Aligned(*include_bytes!($path));
+    };
+}
+
+testdata!(HARDENING_X86_64, "../testdata/hardening-x86_64.o");
+testdata!(HARDENING_AARCH64, "../testdata/hardening-aarch64.o");
+testdata!(HARDENING_PIE, "../testdata/hardening-pie");
+testdata!(HARDENING_TEXTREL, "../testdata/hardening-textrel");
+testdata!(OBJECT_RISCV64, "../testdata/object-riscv64.o");
+testdata!(UNWIND_X86_64, "../testdata/unwind-x86_64");
+testdata!(CORE_X86_64, "../testdata/core-x86_64");
+#[cfg(feature = "std")]
+testdata!(STRIP_X86_64, "../testdata/strip-x86_64");
+#[cfg(feature = "std")]
+testdata!(DEPENDENCIES_APP, "../testdata/dependencies-app");
+#[cfg(feature = "std")]
+testdata!(DEPENDENCIES_FIRST, "../testdata/dependencies-first");
+#[cfg(feature = "std")]
+testdata!(DEPENDENCIES_PLAIN, "../testdata/dependencies-plain");
+#[cfg(feature = "std")]
+testdata!(DEPENDENCIES_PLAIN32, "../testdata/dependencies-plain32");
+#[cfg(feature = "std")]
+testdata!(MINI_DEBUGINFO_X86_64, "../testdata/mini-debuginfo-x86_64");
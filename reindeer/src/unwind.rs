@@ -0,0 +1,1300 @@
+//! Unwinding the stack with DWARF call frame information.
+//!
+//! [`ElfUnwinder`] finds the FDE for a frame's program counter, runs the CIE
+//! and FDE instructions up to it to get the rules for the CFA and each
+//! register, and applies them to recover the caller's registers. Memory is
+//! read through [`ElfMemory`], which may be the live stack of a loaded image,
+//! or the `PT_LOAD` segments of a core file with [`ElfCoreMemory`].
+//!
+//! Nothing is allocated, so unwinding can run in a crash handler.
+
+use crate::{
+    dwarf::{DwarfCallFrameInstruction, DwarfOperation, DwarfPointerBases, DwarfReader},
+    eh_frame::{ElfCallFrameInfo, ElfCie, ElfEhFrameHeader, ElfFde},
+    elf_aux_structures::*,
+    errors::ElfError,
+    notes::ElfNotes,
+    ElfFile,
+};
+
+/// The number of DWARF registers an [`ElfRegisters`] holds, enough for the
+/// general purpose and floating point registers of the supported machines.
+pub const MAX_DWARF_REGISTERS: usize = 96;
+
+/// The number of registers a row can have rules for.
+const MAX_RULES: usize = 32;
+
+/// How deeply `DW_CFA_remember_state` can nest.
+const MAX_REMEMBERED_STATES: usize = 4;
+
+/// How many values a DWARF expression can push.
+const MAX_STACK: usize = 64;
+
+/// The machines unwinding knows the registers of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfUnwindArch {
+    X86_64,
+    Aarch64,
+    Riscv64,
+}
+
+impl ElfUnwindArch {
+    pub fn from_machine(machine: ElfHeaderMachine) -> Option<Self> {
+        match machine {
+            ElfHeaderMachine::EM_X86_64 => Some(Self::X86_64),
+            ElfHeaderMachine::EM_AARCH64 => Some(Self::Aarch64),
+            ElfHeaderMachine::EM_RISCV => Some(Self::Riscv64),
+            _ => None,
+        }
+    }
+
+    /// The DWARF number of the stack pointer, which is the CFA in the
+    /// caller.
+    pub fn stack_pointer(self) -> u16 {
+        match self {
+            Self::X86_64 => 7,
+            Self::Aarch64 => 31,
+            Self::Riscv64 => 2,
+        }
+    }
+}
+
+/// Register values by DWARF register number, and the program counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfRegisters {
+    pub pc: u64,
+    values: [Option<u64>; MAX_DWARF_REGISTERS],
+}
+
+impl ElfRegisters {
+    /// Registers with only the program counter known.
+    pub fn new(pc: u64) -> Self {
+        Self {
+            pc,
+            values: [None; MAX_DWARF_REGISTERS],
+        }
+    }
+
+    pub fn get(&self, register: u16) -> Option<u64> {
+        *self.values.get(usize::from(register))?
+    }
+
+    /// Sets a register, ignoring those past [`MAX_DWARF_REGISTERS`].
+    pub fn set(&mut self, register: u16, value: Option<u64>) {
+        if let Some(slot) = self.values.get_mut(usize::from(register)) {
+            *slot = value;
+        }
+    }
+
+    /// The registers in the descriptor of a 64-bit Linux `NT_PRSTATUS`
+    /// note, as found in core files.
+    pub fn from_prstatus(arch: ElfUnwindArch, desc: &[u8]) -> Result<Self, ElfError> {
+        // pr_reg follows the signal information, pids and times.
+        const PR_REG_OFFSET: usize = 112;
+
+        let register = |index: usize| -> Result<u64, ElfError> {
+            let start = index
+                .checked_mul(8)
+                .and_then(|offset| offset.checked_add(PR_REG_OFFSET))
+                .ok_or(ElfError::MalformedNote)?;
+            let end = start.checked_add(8).ok_or(ElfError::MalformedNote)?;
+            let bytes = desc.get(start..end).ok_or(ElfError::MalformedNote)?;
+            Ok(u64::from_le_bytes(
+                bytes.try_into().map_err(|_| ElfError::MalformedNote)?,
+            ))
+        };
+
+        // x86-64's registers are in a different order to their DWARF
+        // numbers. AArch64 has x0 to x30 and sp, then pc. RISC-V has pc in
+        // place of x0, then x1 to x31.
+        const X86_64: [(u16, usize); 16] = [
+            (0, 10),
+            (1, 12),
+            (2, 11),
+            (3, 5),
+            (4, 13),
+            (5, 14),
+            (6, 4),
+            (7, 19),
+            (8, 9),
+            (9, 8),
+            (10, 7),
+            (11, 6),
+            (12, 3),
+            (13, 2),
+            (14, 1),
+            (15, 0),
+        ];
+
+        let mut registers;
+        match arch {
+            ElfUnwindArch::X86_64 => {
+                registers = Self::new(register(16)?);
+                for (dwarf, index) in X86_64 {
+                    registers.set(dwarf, Some(register(index)?));
+                }
+            }
+            ElfUnwindArch::Aarch64 => {
+                registers = Self::new(register(32)?);
+                for dwarf in 0..32 {
+                    registers.set(dwarf, Some(register(usize::from(dwarf))?));
+                }
+            }
+            ElfUnwindArch::Riscv64 => {
+                registers = Self::new(register(0)?);
+                registers.set(0, Some(0));
+                for dwarf in 1..32 {
+                    registers.set(dwarf, Some(register(usize::from(dwarf))?));
+                }
+            }
+        }
+        Ok(registers)
+    }
+}
+
+/// Somewhere to read the stack and other memory from.
+pub trait ElfMemory {
+    /// Reads a little-endian value of 1, 2, 4 or 8 bytes.
+    fn read(&mut self, address: u64, size: u8) -> Option<u64>;
+}
+
+impl<F: FnMut(u64, u8) -> Option<u64>> ElfMemory for F {
+    fn read(&mut self, address: u64, size: u8) -> Option<u64> {
+        self(address, size)
+    }
+}
+
+// Note types for the owner "CORE".
+const NT_PRSTATUS: ElfNoteType = ElfNoteType(1);
+const NT_FILE: ElfNoteType = ElfNoteType(0x4649_4c45);
+
+/// A file mapped into the address space of a crashed process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfCoreMapping<'buf> {
+    pub start: u64,
+    pub end: u64,
+    pub file_offset: u64,
+    pub path: &'buf [u8],
+}
+
+/// The memory of a core file, from its `PT_LOAD` segments. Memory that
+/// wasn't dumped can't be read.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfCoreMemory<'buf> {
+    file: ElfFile<'buf>,
+}
+
+impl<'buf> ElfCoreMemory<'buf> {
+    pub fn new(file: ElfFile<'buf>) -> Self {
+        Self { file }
+    }
+
+    /// The descriptors of the notes with the owner `CORE` and type `n_type`.
+    fn core_notes(
+        &self,
+        n_type: ElfNoteType,
+    ) -> impl Iterator<Item = Result<&'buf [u8], ElfError>> + 'buf {
+        let file = self.file;
+
+        file.segments()
+            .filter(|segment| segment.p_type() == ElfSegmentType::PT_NOTE)
+            .flat_map(move |segment| {
+                let (notes, error) = match file.segment_data(segment) {
+                    Ok(data) => (Some(ElfNotes::parse(data, segment.p_align())), None),
+                    Err(error) => (None, Some(Err(error))),
+                };
+                notes.into_iter().flatten().chain(error)
+            })
+            .filter_map(move |note| match note {
+                Ok(note) if note.name == b"CORE" && note.n_type == n_type => Some(Ok(note.desc)),
+                Ok(_) => None,
+                Err(error) => Some(Err(error)),
+            })
+    }
+
+    /// The registers of each thread, from the `NT_PRSTATUS` notes.
+    pub fn threads(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<ElfRegisters, ElfError>> + 'buf, ElfError> {
+        let machine = self.file.header().e_machine();
+        let arch =
+            ElfUnwindArch::from_machine(machine).ok_or(ElfError::UnsupportedMachine(machine))?;
+
+        Ok(self
+            .core_notes(NT_PRSTATUS)
+            .map(move |desc| ElfRegisters::from_prstatus(arch, desc?)))
+    }
+
+    /// Calls `mapping` with each file mapped into the process, from the
+    /// 64-bit `NT_FILE` note. The load bias of a mapped ELF file is the
+    /// start of its mapping at offset 0, less the address of its first
+    /// `PT_LOAD` segment.
+    pub fn mapped_files_with(
+        &self,
+        mut mapping: impl FnMut(ElfCoreMapping<'buf>),
+    ) -> Result<(), ElfError> {
+        for desc in self.core_notes(NT_FILE) {
+            let mut reader = DwarfReader::new("NT_FILE note", desc?, 0);
+            let count = reader.u64()?;
+            let page_size = reader.u64()?;
+
+            let mut names = reader;
+            let table_size = count
+                .checked_mul(24)
+                .and_then(|size| usize::try_from(size).ok())
+                .ok_or(ElfError::MalformedNote)?;
+            names.bytes(table_size)?;
+
+            for _ in 0..count {
+                let start = reader.u64()?;
+                let end = reader.u64()?;
+                let page = reader.u64()?;
+                mapping(ElfCoreMapping {
+                    start,
+                    end,
+                    file_offset: page.saturating_mul(page_size),
+                    path: names.cstr()?,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ElfMemory for ElfCoreMemory<'_> {
+    fn read(&mut self, address: u64, size: u8) -> Option<u64> {
+        let end = address.checked_add(u64::from(size))?;
+        let segment = self
+            .file
+            .segments()
+            .filter(|segment| segment.p_type() == ElfSegmentType::PT_LOAD)
+            .find(|segment| {
+                let filesz = segment.p_filesz().map_or(0, |size| size.get());
+                address >= segment.p_vaddr() && end <= segment.p_vaddr().saturating_add(filesz)
+            })?;
+
+        let start = usize::try_from(address.checked_sub(segment.p_vaddr())?).ok()?;
+        let end = start.checked_add(usize::from(size))?;
+        let bytes = self.file.segment_data(segment).ok()?.get(start..end)?;
+        let mut value = [0; 8];
+        value.get_mut(..bytes.len())?.copy_from_slice(bytes);
+        Some(u64::from_le_bytes(value))
+    }
+}
+
+/// How to find the CFA, the value of the stack pointer in the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfCfaRule<'buf> {
+    RegisterOffset(u16, i64),
+    Expression(&'buf [u8]),
+}
+
+/// How to recover a register's value in the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfRegisterRule<'buf> {
+    Undefined,
+    SameValue,
+    /// Saved at this offset from the CFA.
+    Offset(i64),
+    /// The CFA plus this offset.
+    ValOffset(i64),
+    /// In another register.
+    Register(u16),
+    /// Saved at the address computed by the expression, which starts with
+    /// the CFA on its stack.
+    Expression(&'buf [u8]),
+    /// The value computed by the expression.
+    ValExpression(&'buf [u8]),
+}
+
+/// The rules in effect at an address, a row of the table the CFI encodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfUnwindRow<'buf> {
+    pub cfa: ElfCfaRule<'buf>,
+    rules: [(u16, ElfRegisterRule<'buf>); MAX_RULES],
+    count: usize,
+    /// Whether the return address is signed with AArch64 pointer
+    /// authentication.
+    pub is_return_address_signed: bool,
+}
+
+impl<'buf> ElfUnwindRow<'buf> {
+    fn new() -> Self {
+        Self {
+            cfa: ElfCfaRule::RegisterOffset(0, 0),
+            rules: [(0, ElfRegisterRule::SameValue); MAX_RULES],
+            count: 0,
+            is_return_address_signed: false,
+        }
+    }
+
+    /// The registers with rules, in the order they were first given one.
+    pub fn rules(&self) -> impl Iterator<Item = (u16, ElfRegisterRule<'buf>)> + '_ {
+        self.rules.iter().take(self.count).copied()
+    }
+
+    /// The rule for a register, which is `SameValue` if it has none.
+    pub fn rule(&self, register: u16) -> ElfRegisterRule<'buf> {
+        self.rules()
+            .find(|&(other, _)| other == register)
+            .map_or(ElfRegisterRule::SameValue, |(_, rule)| rule)
+    }
+
+    fn set_rule(&mut self, register: u16, rule: ElfRegisterRule<'buf>) -> Result<(), ElfError> {
+        let count = self.count;
+        if let Some(slot) = self
+            .rules
+            .iter_mut()
+            .take(count)
+            .find(|(other, _)| *other == register)
+        {
+            slot.1 = rule;
+            return Ok(());
+        }
+
+        let slot = self
+            .rules
+            .get_mut(count)
+            .ok_or(ElfError::MalformedDwarf(INSTRUCTIONS, 0))?;
+        *slot = (register, rule);
+        self.count = count.saturating_add(1);
+        Ok(())
+    }
+}
+
+/// The name of call frame instructions in errors.
+const INSTRUCTIONS: &str = "call frame instructions";
+
+/// The name of DWARF expressions in errors.
+const EXPRESSION: &str = "DWARF expression";
+
+/// A frame on the stack: its registers, and whether its program counter is
+/// a return address. A return address is past the call instruction, so may
+/// be past the end of the calling function, and is looked up one byte
+/// earlier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfFrame {
+    pub registers: ElfRegisters,
+    pub is_return_address: bool,
+}
+
+impl ElfFrame {
+    /// The innermost frame, whose program counter is the instruction that
+    /// was executing.
+    pub fn new(registers: ElfRegisters) -> Self {
+        Self {
+            registers,
+            is_return_address: false,
+        }
+    }
+}
+
+/// Unwinds through the functions of one ELF file.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfUnwinder<'buf> {
+    arch: ElfUnwindArch,
+    bias: u64,
+    header: Option<ElfEhFrameHeader<'buf>>,
+    eh_frame: Option<ElfCallFrameInfo<'buf>>,
+    debug_frame: Option<ElfCallFrameInfo<'buf>>,
+}
+
+impl<'buf> ElfUnwinder<'buf> {
+    /// An unwinder without any call frame information, for building up from
+    /// the sections of a loaded image.
+    pub fn new(arch: ElfUnwindArch) -> Self {
+        Self {
+            arch,
+            bias: 0,
+            header: None,
+            eh_frame: None,
+            debug_frame: None,
+        }
+    }
+
+    /// Uses the `.eh_frame`, `.eh_frame_hdr` and `.debug_frame` of a file.
+    pub fn from_file(file: &ElfFile<'buf>) -> Result<Self, ElfError> {
+        let machine = file.header().e_machine();
+        let arch =
+            ElfUnwindArch::from_machine(machine).ok_or(ElfError::UnsupportedMachine(machine))?;
+
+        Ok(Self {
+            arch,
+            bias: 0,
+            header: ElfEhFrameHeader::from_file(file)?,
+            eh_frame: ElfCallFrameInfo::from_eh_frame(file)?,
+            debug_frame: ElfCallFrameInfo::from_debug_frame(file)?,
+        })
+    }
+
+    /// Sets `.eh_frame`, and the `.eh_frame_hdr` table to search it with.
+    pub fn with_eh_frame(
+        mut self,
+        eh_frame: ElfCallFrameInfo<'buf>,
+        header: Option<ElfEhFrameHeader<'buf>>,
+    ) -> Self {
+        self.eh_frame = Some(eh_frame);
+        self.header = header;
+        self
+    }
+
+    /// Sets `.debug_frame`, which may come from a separate debug file.
+    pub fn with_debug_frame(mut self, debug_frame: ElfCallFrameInfo<'buf>) -> Self {
+        self.debug_frame = Some(debug_frame);
+        self
+    }
+
+    /// Sets the load bias, the difference between the addresses the file
+    /// was loaded at and those in the file.
+    pub fn with_load_bias(mut self, bias: u64) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    pub fn arch(&self) -> ElfUnwindArch {
+        self.arch
+    }
+
+    /// The FDE covering a file address, from the `.eh_frame_hdr` table if
+    /// there is one, and otherwise by searching `.eh_frame` and then
+    /// `.debug_frame`.
+    pub fn find_fde(&self, address: u64) -> Result<Option<ElfFde<'buf>>, ElfError> {
+        if let (Some(header), Some(eh_frame)) = (self.header, self.eh_frame) {
+            if let Some(fde) = header.find(address)? {
+                let fde = eh_frame.fde_at_address(fde)?;
+                if fde.contains(address) {
+                    return Ok(Some(fde));
+                }
+            }
+        } else if let Some(eh_frame) = self.eh_frame {
+            if let Some(fde) = eh_frame.find_fde(address)? {
+                return Ok(Some(fde));
+            }
+        }
+
+        match self.debug_frame {
+            Some(debug_frame) => debug_frame.find_fde(address),
+            None => Ok(None),
+        }
+    }
+
+    /// The rules in effect at a file address within the function `fde`
+    /// describes.
+    pub fn row(&self, fde: &ElfFde<'buf>, address: u64) -> Result<ElfUnwindRow<'buf>, ElfError> {
+        let mut row = ElfUnwindRow::new();
+        execute(
+            fde.cie.initial_instructions,
+            &fde.cie,
+            &mut row,
+            None,
+            fde.initial_location,
+            u64::MAX,
+        )?;
+        let initial = row;
+        execute(
+            fde.instructions,
+            &fde.cie,
+            &mut row,
+            Some(&initial),
+            fde.initial_location,
+            address,
+        )?;
+        Ok(row)
+    }
+
+    /// Recovers the caller of `frame`, or returns `None` if this file has no
+    /// FDE for it, or it is the outermost frame. With several files loaded,
+    /// each is tried in turn.
+    pub fn step(
+        &self,
+        frame: &ElfFrame,
+        memory: &mut impl ElfMemory,
+    ) -> Result<Option<ElfFrame>, ElfError> {
+        let registers = &frame.registers;
+        let pc = if frame.is_return_address {
+            registers.pc.saturating_sub(1)
+        } else {
+            registers.pc
+        };
+        let Some(address) = pc.checked_sub(self.bias) else {
+            return Ok(None);
+        };
+        let Some(fde) = self.find_fde(address)? else {
+            return Ok(None);
+        };
+        let row = self.row(&fde, address)?;
+
+        let cfa = match row.cfa {
+            ElfCfaRule::RegisterOffset(register, offset) => registers
+                .get(register)
+                .ok_or(ElfError::UnknownRegister(register))?
+                .wrapping_add_signed(offset),
+            ElfCfaRule::Expression(expression) => evaluate(expression, registers, None, memory)?,
+        };
+
+        let mut caller = *registers;
+        for (register, rule) in row.rules() {
+            let value = match rule {
+                ElfRegisterRule::Undefined => None,
+                ElfRegisterRule::SameValue => registers.get(register),
+                ElfRegisterRule::Offset(offset) => {
+                    Some(read(memory, cfa.wrapping_add_signed(offset), 8)?)
+                }
+                ElfRegisterRule::ValOffset(offset) => Some(cfa.wrapping_add_signed(offset)),
+                ElfRegisterRule::Register(other) => registers.get(other),
+                ElfRegisterRule::Expression(expression) => {
+                    let address = evaluate(expression, registers, Some(cfa), memory)?;
+                    Some(read(memory, address, 8)?)
+                }
+                ElfRegisterRule::ValExpression(expression) => {
+                    Some(evaluate(expression, registers, Some(cfa), memory)?)
+                }
+            };
+            caller.set(register, value);
+        }
+
+        let stack_pointer = self.arch.stack_pointer();
+        if !row.rules().any(|(register, _)| register == stack_pointer) {
+            caller.set(stack_pointer, Some(cfa));
+        }
+
+        let return_address = u16::try_from(fde.cie.return_address_register)
+            .ok()
+            .and_then(|register| caller.get(register));
+        let Some(mut return_address) = return_address.filter(|&address| address != 0) else {
+            return Ok(None);
+        };
+        if row.is_return_address_signed {
+            return_address = strip_pointer_authentication(return_address);
+        }
+        caller.pc = return_address;
+
+        Ok(Some(ElfFrame {
+            registers: caller,
+            is_return_address: !fde.cie.is_signal_frame,
+        }))
+    }
+
+    /// Calls `visit` with each frame from `registers` outwards, until it
+    /// returns `false`, the outermost frame is reached, or a frame fails to
+    /// make progress up the stack.
+    pub fn backtrace_with(
+        &self,
+        registers: ElfRegisters,
+        memory: &mut impl ElfMemory,
+        mut visit: impl FnMut(&ElfFrame) -> bool,
+    ) -> Result<(), ElfError> {
+        let stack_pointer = self.arch.stack_pointer();
+        let mut frame = ElfFrame::new(registers);
+
+        while visit(&frame) {
+            let Some(caller) = self.step(&frame, memory)? else {
+                return Ok(());
+            };
+            let sp = frame.registers.get(stack_pointer);
+            let caller_sp = caller.registers.get(stack_pointer);
+            if caller.registers.pc == frame.registers.pc && caller_sp <= sp {
+                return Ok(());
+            }
+            frame = caller;
+        }
+        Ok(())
+    }
+}
+
+/// Clears the pointer authentication code from the top bits of an AArch64
+/// return address, assuming 48-bit virtual addresses. Bit 55 selects
+/// between the user and kernel halves of the address space.
+fn strip_pointer_authentication(address: u64) -> u64 {
+    const MASK: u64 = 0xffff_0000_0000_0000;
+    const BIT_55: u64 = 0x0080_0000_0000_0000;
+    if address & BIT_55 != 0 {
+        address | MASK
+    } else {
+        address & !MASK
+    }
+}
+
+fn read(memory: &mut impl ElfMemory, address: u64, size: u8) -> Result<u64, ElfError> {
+    memory
+        .read(address, size)
+        .ok_or(ElfError::UnreadableMemory(address))
+}
+
+fn register_operand(reader: &mut DwarfReader) -> Result<u16, ElfError> {
+    let error = reader.error();
+    u16::try_from(reader.uleb128()?).map_err(|_| error)
+}
+
+fn factored(value: i64, factor: i64) -> Result<i64, ElfError> {
+    value
+        .checked_mul(factor)
+        .ok_or(ElfError::MalformedDwarf(INSTRUCTIONS, 0))
+}
+
+fn unsigned_offset(reader: &mut DwarfReader, cie: &ElfCie) -> Result<i64, ElfError> {
+    let error = reader.error();
+    let value = i64::try_from(reader.uleb128()?).map_err(|_| error)?;
+    factored(value, cie.data_alignment_factor)
+}
+
+fn signed_offset(reader: &mut DwarfReader, cie: &ElfCie) -> Result<i64, ElfError> {
+    let value = reader.sleb128()?;
+    factored(value, cie.data_alignment_factor)
+}
+
+fn block<'buf>(reader: &mut DwarfReader<'buf>) -> Result<&'buf [u8], ElfError> {
+    let length = usize::try_from(reader.uleb128()?)?;
+    reader.bytes(length)
+}
+
+/// Runs call frame instructions, from a function's start at `location`, up
+/// to the row for `target`. `initial` is the row after the CIE's
+/// instructions, which `DW_CFA_restore` returns registers to.
+fn execute<'buf>(
+    instructions: &'buf [u8],
+    cie: &ElfCie<'buf>,
+    row: &mut ElfUnwindRow<'buf>,
+    initial: Option<&ElfUnwindRow<'buf>>,
+    mut location: u64,
+    target: u64,
+) -> Result<(), ElfError> {
+    let mut reader = DwarfReader::new(INSTRUCTIONS, instructions, 0);
+    let mut remembered = [None; MAX_REMEMBERED_STATES];
+    let mut depth = 0usize;
+
+    let advance = |location: &mut u64, delta: u64| -> bool {
+        *location = location.wrapping_add(delta.wrapping_mul(cie.code_alignment_factor));
+        *location <= target
+    };
+
+    while reader.offset() < instructions.len() {
+        let start = reader.offset();
+        let error = || ElfError::MalformedDwarf(INSTRUCTIONS, start);
+        let opcode = reader.u8()?;
+        let operand = opcode & 0x3f;
+
+        match DwarfCallFrameInstruction(opcode & 0xc0) {
+            DwarfCallFrameInstruction::DW_CFA_ADVANCE_LOC => {
+                if !advance(&mut location, u64::from(operand)) {
+                    return Ok(());
+                }
+                continue;
+            }
+            DwarfCallFrameInstruction::DW_CFA_OFFSET => {
+                let offset = unsigned_offset(&mut reader, cie)?;
+                row.set_rule(u16::from(operand), ElfRegisterRule::Offset(offset))?;
+                continue;
+            }
+            DwarfCallFrameInstruction::DW_CFA_RESTORE => {
+                let register = u16::from(operand);
+                let rule = initial.map_or(ElfRegisterRule::SameValue, |row| row.rule(register));
+                row.set_rule(register, rule)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        match DwarfCallFrameInstruction(opcode) {
+            DwarfCallFrameInstruction::DW_CFA_NOP => {}
+            DwarfCallFrameInstruction::DW_CFA_SET_LOC => {
+                location = reader.pointer(
+                    cie.fde_encoding,
+                    cie.address_size,
+                    &DwarfPointerBases::default(),
+                )?;
+                if location > target {
+                    return Ok(());
+                }
+            }
+            DwarfCallFrameInstruction::DW_CFA_ADVANCE_LOC1 => {
+                if !advance(&mut location, u64::from(reader.u8()?)) {
+                    return Ok(());
+                }
+            }
+            DwarfCallFrameInstruction::DW_CFA_ADVANCE_LOC2 => {
+                if !advance(&mut location, u64::from(reader.u16()?)) {
+                    return Ok(());
+                }
+            }
+            DwarfCallFrameInstruction::DW_CFA_ADVANCE_LOC4 => {
+                if !advance(&mut location, u64::from(reader.u32()?)) {
+                    return Ok(());
+                }
+            }
+            DwarfCallFrameInstruction::DW_CFA_OFFSET_EXTENDED => {
+                let register = register_operand(&mut reader)?;
+                let offset = unsigned_offset(&mut reader, cie)?;
+                row.set_rule(register, ElfRegisterRule::Offset(offset))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_RESTORE_EXTENDED => {
+                let register = register_operand(&mut reader)?;
+                let rule = initial.map_or(ElfRegisterRule::SameValue, |row| row.rule(register));
+                row.set_rule(register, rule)?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_UNDEFINED => {
+                let register = register_operand(&mut reader)?;
+                row.set_rule(register, ElfRegisterRule::Undefined)?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_SAME_VALUE => {
+                let register = register_operand(&mut reader)?;
+                row.set_rule(register, ElfRegisterRule::SameValue)?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_REGISTER => {
+                let register = register_operand(&mut reader)?;
+                let other = register_operand(&mut reader)?;
+                row.set_rule(register, ElfRegisterRule::Register(other))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_REMEMBER_STATE => {
+                let slot = remembered.get_mut(depth).ok_or_else(error)?;
+                *slot = Some(*row);
+                depth = depth.saturating_add(1);
+            }
+            DwarfCallFrameInstruction::DW_CFA_RESTORE_STATE => {
+                depth = depth.checked_sub(1).ok_or_else(error)?;
+                let state = remembered
+                    .get_mut(depth)
+                    .and_then(Option::take)
+                    .ok_or(ElfError::MalformedDwarf(INSTRUCTIONS, 0))?;
+                // Like GCC's unwinder, the CFA rule is restored along with
+                // the register rules, as epilogues change it.
+                *row = state;
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA => {
+                let register = register_operand(&mut reader)?;
+                let offset = i64::try_from(reader.uleb128()?).map_err(|_| error())?;
+                row.cfa = ElfCfaRule::RegisterOffset(register, offset);
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA_SF => {
+                let register = register_operand(&mut reader)?;
+                let offset = signed_offset(&mut reader, cie)?;
+                row.cfa = ElfCfaRule::RegisterOffset(register, offset);
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA_REGISTER => {
+                let register = register_operand(&mut reader)?;
+                let ElfCfaRule::RegisterOffset(_, offset) = row.cfa else {
+                    return Err(error());
+                };
+                row.cfa = ElfCfaRule::RegisterOffset(register, offset);
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA_OFFSET => {
+                let offset = i64::try_from(reader.uleb128()?).map_err(|_| error())?;
+                let ElfCfaRule::RegisterOffset(register, _) = row.cfa else {
+                    return Err(error());
+                };
+                row.cfa = ElfCfaRule::RegisterOffset(register, offset);
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA_OFFSET_SF => {
+                let offset = signed_offset(&mut reader, cie)?;
+                let ElfCfaRule::RegisterOffset(register, _) = row.cfa else {
+                    return Err(error());
+                };
+                row.cfa = ElfCfaRule::RegisterOffset(register, offset);
+            }
+            DwarfCallFrameInstruction::DW_CFA_DEF_CFA_EXPRESSION => {
+                row.cfa = ElfCfaRule::Expression(block(&mut reader)?);
+            }
+            DwarfCallFrameInstruction::DW_CFA_EXPRESSION => {
+                let register = register_operand(&mut reader)?;
+                row.set_rule(register, ElfRegisterRule::Expression(block(&mut reader)?))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_VAL_EXPRESSION => {
+                let register = register_operand(&mut reader)?;
+                row.set_rule(
+                    register,
+                    ElfRegisterRule::ValExpression(block(&mut reader)?),
+                )?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_OFFSET_EXTENDED_SF => {
+                let register = register_operand(&mut reader)?;
+                let offset = signed_offset(&mut reader, cie)?;
+                row.set_rule(register, ElfRegisterRule::Offset(offset))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_VAL_OFFSET => {
+                let register = register_operand(&mut reader)?;
+                let offset = unsigned_offset(&mut reader, cie)?;
+                row.set_rule(register, ElfRegisterRule::ValOffset(offset))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_VAL_OFFSET_SF => {
+                let register = register_operand(&mut reader)?;
+                let offset = signed_offset(&mut reader, cie)?;
+                row.set_rule(register, ElfRegisterRule::ValOffset(offset))?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_AARCH64_NEGATE_RA_STATE => {
+                row.is_return_address_signed = !row.is_return_address_signed;
+            }
+            DwarfCallFrameInstruction::DW_CFA_GNU_ARGS_SIZE => {
+                reader.uleb128()?;
+            }
+            DwarfCallFrameInstruction::DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => {
+                let register = register_operand(&mut reader)?;
+                let offset = unsigned_offset(&mut reader, cie)?;
+                row.set_rule(register, ElfRegisterRule::Offset(offset.wrapping_neg()))?;
+            }
+            _ => return Err(error()),
+        }
+    }
+
+    Ok(())
+}
+
+/// A fixed-size stack for evaluating DWARF expressions.
+struct Stack {
+    values: [u64; MAX_STACK],
+    len: usize,
+}
+
+impl Stack {
+    fn push(&mut self, value: u64) -> Result<(), ElfError> {
+        let slot = self
+            .values
+            .get_mut(self.len)
+            .ok_or(ElfError::MalformedDwarf(EXPRESSION, 0))?;
+        *slot = value;
+        self.len = self.len.saturating_add(1);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<u64, ElfError> {
+        self.len = self
+            .len
+            .checked_sub(1)
+            .ok_or(ElfError::MalformedDwarf(EXPRESSION, 0))?;
+        self.values
+            .get(self.len)
+            .copied()
+            .ok_or(ElfError::MalformedDwarf(EXPRESSION, 0))
+    }
+
+    /// The value `depth` entries below the top.
+    fn peek(&self, depth: usize) -> Result<u64, ElfError> {
+        self.len
+            .checked_sub(1)
+            .and_then(|top| top.checked_sub(depth))
+            .and_then(|index| self.values.get(index).copied())
+            .ok_or(ElfError::MalformedDwarf(EXPRESSION, 0))
+    }
+}
+
+fn signed(value: u64) -> i64 {
+    i64::from_le_bytes(value.to_le_bytes())
+}
+
+fn unsigned(value: i64) -> u64 {
+    u64::from_le_bytes(value.to_le_bytes())
+}
+
+/// Evaluates a DWARF expression from a CFA rule, starting with `initial` on
+/// the stack, and returns the value on top of it.
+fn evaluate(
+    expression: &[u8],
+    registers: &ElfRegisters,
+    initial: Option<u64>,
+    memory: &mut impl ElfMemory,
+) -> Result<u64, ElfError> {
+    let mut reader = DwarfReader::new(EXPRESSION, expression, 0);
+    let mut stack = Stack {
+        values: [0; MAX_STACK],
+        len: 0,
+    };
+    if let Some(initial) = initial {
+        stack.push(initial)?;
+    }
+
+    let register = |register: u16| {
+        registers
+            .get(register)
+            .ok_or(ElfError::UnknownRegister(register))
+    };
+
+    while reader.offset() < expression.len() {
+        let start = reader.offset();
+        let error = || ElfError::MalformedDwarf(EXPRESSION, start);
+        let opcode = reader.u8()?;
+
+        match opcode {
+            0x30..=0x4f => {
+                stack.push(u64::from(
+                    opcode.saturating_sub(DwarfOperation::DW_OP_LIT0.0),
+                ))?;
+                continue;
+            }
+            0x70..=0x8f => {
+                let base = register(u16::from(
+                    opcode.saturating_sub(DwarfOperation::DW_OP_BREG0.0),
+                ))?;
+                stack.push(base.wrapping_add_signed(reader.sleb128()?))?;
+                continue;
+            }
+            _ => {}
+        }
+
+        match DwarfOperation(opcode) {
+            DwarfOperation::DW_OP_ADDR => stack.push(reader.u64()?)?,
+            DwarfOperation::DW_OP_DEREF => {
+                let address = stack.pop()?;
+                stack.push(read(memory, address, 8)?)?;
+            }
+            DwarfOperation::DW_OP_DEREF_SIZE => {
+                let size = reader.u8()?;
+                let address = stack.pop()?;
+                stack.push(read(memory, address, size)?)?;
+            }
+            DwarfOperation::DW_OP_CONST1U => stack.push(u64::from(reader.u8()?))?,
+            DwarfOperation::DW_OP_CONST2U => stack.push(u64::from(reader.u16()?))?,
+            DwarfOperation::DW_OP_CONST4U => stack.push(u64::from(reader.u32()?))?,
+            DwarfOperation::DW_OP_CONST8U | DwarfOperation::DW_OP_CONST8S => {
+                stack.push(reader.u64()?)?
+            }
+            DwarfOperation::DW_OP_CONST1S => {
+                let value = i8::from_le_bytes([reader.u8()?]);
+                stack.push(unsigned(i64::from(value)))?;
+            }
+            DwarfOperation::DW_OP_CONST2S => {
+                let value = i16::from_le_bytes(reader.u16()?.to_le_bytes());
+                stack.push(unsigned(i64::from(value)))?;
+            }
+            DwarfOperation::DW_OP_CONST4S => {
+                let value = i32::from_le_bytes(reader.u32()?.to_le_bytes());
+                stack.push(unsigned(i64::from(value)))?;
+            }
+            DwarfOperation::DW_OP_CONSTU => stack.push(reader.uleb128()?)?,
+            DwarfOperation::DW_OP_CONSTS => stack.push(unsigned(reader.sleb128()?))?,
+            DwarfOperation::DW_OP_DUP => stack.push(stack.peek(0)?)?,
+            DwarfOperation::DW_OP_DROP => {
+                stack.pop()?;
+            }
+            DwarfOperation::DW_OP_OVER => stack.push(stack.peek(1)?)?,
+            DwarfOperation::DW_OP_PICK => {
+                let index = usize::from(reader.u8()?);
+                stack.push(stack.peek(index)?)?;
+            }
+            DwarfOperation::DW_OP_SWAP => {
+                let (a, b) = (stack.pop()?, stack.pop()?);
+                stack.push(a)?;
+                stack.push(b)?;
+            }
+            DwarfOperation::DW_OP_ROT => {
+                let (a, b, c) = (stack.pop()?, stack.pop()?, stack.pop()?);
+                stack.push(a)?;
+                stack.push(c)?;
+                stack.push(b)?;
+            }
+            DwarfOperation::DW_OP_ABS => {
+                let value = signed(stack.pop()?);
+                stack.push(unsigned(value.wrapping_abs()))?;
+            }
+            DwarfOperation::DW_OP_NEG => {
+                let value = signed(stack.pop()?);
+                stack.push(unsigned(value.wrapping_neg()))?;
+            }
+            DwarfOperation::DW_OP_NOT => {
+                let value = stack.pop()?;
+                stack.push(!value)?;
+            }
+            DwarfOperation::DW_OP_PLUS_UCONST => {
+                let value = stack.pop()?;
+                stack.push(value.wrapping_add(reader.uleb128()?))?;
+            }
+            DwarfOperation::DW_OP_AND
+            | DwarfOperation::DW_OP_DIV
+            | DwarfOperation::DW_OP_MINUS
+            | DwarfOperation::DW_OP_MOD
+            | DwarfOperation::DW_OP_MUL
+            | DwarfOperation::DW_OP_OR
+            | DwarfOperation::DW_OP_PLUS
+            | DwarfOperation::DW_OP_SHL
+            | DwarfOperation::DW_OP_SHR
+            | DwarfOperation::DW_OP_SHRA
+            | DwarfOperation::DW_OP_XOR
+            | DwarfOperation::DW_OP_EQ
+            | DwarfOperation::DW_OP_GE
+            | DwarfOperation::DW_OP_GT
+            | DwarfOperation::DW_OP_LE
+            | DwarfOperation::DW_OP_LT
+            | DwarfOperation::DW_OP_NE => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                stack.push(binary(DwarfOperation(opcode), a, b).ok_or_else(error)?)?;
+            }
+            DwarfOperation::DW_OP_SKIP => {
+                let delta = i16::from_le_bytes(reader.u16()?.to_le_bytes());
+                skip(&mut reader, delta)?;
+            }
+            DwarfOperation::DW_OP_BRA => {
+                let delta = i16::from_le_bytes(reader.u16()?.to_le_bytes());
+                if stack.pop()? != 0 {
+                    skip(&mut reader, delta)?;
+                }
+            }
+            DwarfOperation::DW_OP_BREGX => {
+                let base = register(register_operand(&mut reader)?)?;
+                stack.push(base.wrapping_add_signed(reader.sleb128()?))?;
+            }
+            DwarfOperation::DW_OP_NOP => {}
+            // DW_OP_call_frame_cfa, DW_OP_fbreg and DW_OP_reg* describe
+            // variables, and aren't allowed in call frame information.
+            _ => return Err(error()),
+        }
+    }
+
+    stack.pop()
+}
+
+/// Applies a binary operation to the second and top entries of the stack.
+fn binary(operation: DwarfOperation, a: u64, b: u64) -> Option<u64> {
+    let (sa, sb) = (signed(a), signed(b));
+    let shift = u32::try_from(b).unwrap_or(u32::MAX);
+    Some(match operation {
+        DwarfOperation::DW_OP_AND => a & b,
+        DwarfOperation::DW_OP_DIV => unsigned(sa.checked_div(sb)?),
+        DwarfOperation::DW_OP_MINUS => a.wrapping_sub(b),
+        DwarfOperation::DW_OP_MOD => a.checked_rem(b)?,
+        DwarfOperation::DW_OP_MUL => a.wrapping_mul(b),
+        DwarfOperation::DW_OP_OR => a | b,
+        DwarfOperation::DW_OP_PLUS => a.wrapping_add(b),
+        DwarfOperation::DW_OP_SHL => a.checked_shl(shift).unwrap_or(0),
+        DwarfOperation::DW_OP_SHR => a.checked_shr(shift).unwrap_or(0),
+        DwarfOperation::DW_OP_SHRA => {
+            unsigned(sa.checked_shr(shift).unwrap_or(if sa < 0 { -1 } else { 0 }))
+        }
+        DwarfOperation::DW_OP_XOR => a ^ b,
+        DwarfOperation::DW_OP_EQ => u64::from(sa == sb),
+        DwarfOperation::DW_OP_GE => u64::from(sa >= sb),
+        DwarfOperation::DW_OP_GT => u64::from(sa > sb),
+        DwarfOperation::DW_OP_LE => u64::from(sa <= sb),
+        DwarfOperation::DW_OP_LT => u64::from(sa < sb),
+        DwarfOperation::DW_OP_NE => u64::from(sa != sb),
+        _ => return None,
+    })
+}
+
+fn skip(reader: &mut DwarfReader, delta: i16) -> Result<(), ElfError> {
+    let target = i64::try_from(reader.offset())
+        .ok()
+        .and_then(|offset| offset.checked_add(i64::from(delta)))
+        .and_then(|offset| usize::try_from(offset).ok())
+        .ok_or_else(|| reader.error())?;
+    reader.seek(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eh_frame::ElfCallFrameFormat,
+        testdata::{CORE_X86_64, UNWIND_X86_64},
+    };
+
+    #[test]
+    fn steps_through_a_frame() {
+        // The CIE puts the CFA at rsp+8 and the return address at CFA-8. The
+        // FDE for 0x1000..0x1020 follows push rbp, so the CFA moves to rsp+16
+        // with rbp at CFA-16, and mov rbp, rsp, so the CFA moves to rbp+16.
+        let data = b"\
+            \x14\0\0\0\0\0\0\0\x01zR\0\x01\x78\x10\x01\x1b\x0c\x07\x08\x90\x01\0\0\
+            \x18\0\0\0\x1c\0\0\0\xe0\xef\xff\xff\x20\0\0\0\0\
+            \x41\x0e\x10\x86\x02\x43\x0d\x06\0\0\0\
+            \0\0\0\0";
+        let info = ElfCallFrameInfo::new(ElfCallFrameFormat::EhFrame, data, 0x2000, 8);
+        let unwinder = ElfUnwinder::new(ElfUnwindArch::X86_64).with_eh_frame(info, None);
+
+        let fde = unwinder
+            .find_fde(0x1010)
+            .expect("parses")
+            .expect("address is covered");
+        let row = unwinder.row(&fde, 0x1001).expect("valid instructions");
+        assert_eq!(row.cfa, ElfCfaRule::RegisterOffset(7, 16));
+        let row = unwinder.row(&fde, 0x1010).expect("valid instructions");
+        assert_eq!(row.cfa, ElfCfaRule::RegisterOffset(6, 16));
+        assert_eq!(row.rule(6), ElfRegisterRule::Offset(-16));
+        assert_eq!(row.rule(16), ElfRegisterRule::Offset(-8));
+        assert_eq!(row.rule(3), ElfRegisterRule::SameValue);
+
+        // The stack holds the saved rbp and the return address.
+        let mut memory = |address: u64, _size: u8| match address {
+            0x7ff0 => Some(0x9000),
+            0x7ff8 => Some(0x2345),
+            _ => None,
+        };
+        let mut registers = ElfRegisters::new(0x1010);
+        registers.set(6, Some(0x7ff0));
+        registers.set(7, Some(0x7fe0));
+
+        let caller = unwinder
+            .step(&ElfFrame::new(registers), &mut memory)
+            .expect("unwinds")
+            .expect("has a caller");
+        assert_eq!(caller.registers.pc, 0x2345);
+        assert_eq!(caller.registers.get(6), Some(0x9000));
+        assert_eq!(caller.registers.get(7), Some(0x8000));
+        assert!(caller.is_return_address);
+
+        // breg6 8; deref; plus_uconst 1
+        let expression = [0x76, 8, 0x06, 0x23, 1];
+        assert_eq!(
+            evaluate(&expression, &registers, None, &mut memory).ok(),
+            Some(0x2346)
+        );
+    }
+
+    #[test]
+    fn restores_the_cfa_with_remembered_state() {
+        let file = ElfFile::parse(&UNWIND_X86_64.0).expect("valid file");
+        let unwinder = ElfUnwinder::from_file(&file).expect("valid call frame information");
+
+        // early_return at 0x400100 pushes rbp and rbx and reserves 8 bytes,
+        // then has two epilogues. The second is after the first's ret, and
+        // restores the state remembered before it.
+        let cfa = |address| {
+            let fde = unwinder
+                .find_fde(address)
+                .expect("parses")
+                .expect("address is covered");
+            unwinder.row(&fde, address).map(|row| row.cfa).ok()
+        };
+        let rsp = |offset| Some(ElfCfaRule::RegisterOffset(7, offset));
+        assert_eq!(cfa(0x400106), rsp(32));
+        assert_eq!(cfa(0x400127), rsp(24));
+        assert_eq!(cfa(0x40012b), rsp(8));
+        assert_eq!(cfa(0x400130), rsp(32));
+        assert_eq!(cfa(0x400134), rsp(24));
+        assert_eq!(cfa(0x40013b), rsp(8));
+
+        let fde = unwinder.find_fde(0x400130).ok().flatten();
+        let row = fde.and_then(|fde| unwinder.row(&fde, 0x400130).ok());
+        assert_eq!(
+            row.map(|row| (row.rule(3), row.rule(6))),
+            Some((ElfRegisterRule::Offset(-24), ElfRegisterRule::Offset(-16)))
+        );
+    }
+
+    #[test]
+    fn unwinds_a_core_file() {
+        let file = ElfFile::parse(&UNWIND_X86_64.0).expect("valid file");
+        let unwinder = ElfUnwinder::from_file(&file).expect("valid call frame information");
+        let core = ElfCoreMemory::new(ElfFile::parse(&CORE_X86_64.0).expect("valid core file"));
+
+        let mut threads = core.threads().expect("an x86-64 core file");
+        let registers = threads.next().expect("a thread").expect("valid registers");
+        assert!(threads.next().is_none());
+        assert_eq!(registers.pc, 0x400134);
+        assert_eq!(registers.get(7), Some(0x7fff_ff08));
+        assert_eq!(registers.get(5), Some(7));
+
+        let mut mappings = 0;
+        core.mapped_files_with(|mapping| {
+            assert_eq!(
+                mapping,
+                ElfCoreMapping {
+                    start: 0x40_0000,
+                    end: 0x40_1000,
+                    file_offset: 0,
+                    path: b"/unwind-x86_64",
+                }
+            );
+            mappings += 1;
+        })
+        .expect("valid NT_FILE note");
+        assert_eq!(mappings, 1);
+
+        // The thread stopped in the second epilogue of early_return, which
+        // was called by _start, whose return address is 0.
+        let mut memory = core;
+        let mut frames = [None; 3];
+        let mut count = 0usize;
+        unwinder
+            .backtrace_with(registers, &mut memory, |frame| {
+                if let Some(slot) = frames.get_mut(count) {
+                    *slot = Some(*frame);
+                }
+                count = count.saturating_add(1);
+                true
+            })
+            .expect("unwinds");
+        assert_eq!(count, 2);
+
+        let caller = frames.get(1).copied().flatten().expect("a caller");
+        assert_eq!(caller.registers.pc, 0x40014e);
+        assert_eq!(caller.registers.get(3), Some(0x3333));
+        assert_eq!(caller.registers.get(6), Some(0x4444));
+        assert_eq!(caller.registers.get(7), Some(0x7fff_ff20));
+        assert_eq!(memory.read(0x7fff_ff30, 8), None);
+    }
+
+    #[test]
+    fn reads_aarch64_and_riscv_registers() {
+        // pr_reg, after 112 bytes, holding 34 registers numbered from 1.
+        let mut desc = [0u8; 112 + 34 * 8];
+        for (index, register) in
+            (1u64..).zip(desc.get_mut(112..).expect("in bounds").chunks_exact_mut(8))
+        {
+            register.copy_from_slice(&index.to_le_bytes());
+        }
+
+        // x0 to x30 and sp, then pc.
+        let registers =
+            ElfRegisters::from_prstatus(ElfUnwindArch::Aarch64, &desc).expect("long enough");
+        assert_eq!(registers.pc, 33);
+        assert_eq!(registers.get(0), Some(1));
+        assert_eq!(registers.get(31), Some(32));
+
+        // pc in place of x0, then x1 to x31.
+        let registers =
+            ElfRegisters::from_prstatus(ElfUnwindArch::Riscv64, &desc).expect("long enough");
+        assert_eq!(registers.pc, 1);
+        assert_eq!(registers.get(0), Some(0));
+        assert_eq!(registers.get(2), Some(3));
+
+        assert!(matches!(
+            ElfRegisters::from_prstatus(
+                ElfUnwindArch::Aarch64,
+                desc.get(..200).unwrap_or_default()
+            ),
+            Err(ElfError::MalformedNote)
+        ));
+    }
+
+    #[test]
+    fn strips_aarch64_pointer_authentication() {
+        // The CIE puts the CFA at sp and the return address in x30. The FDE
+        // for 0x1000..0x1020 signs the return address, then after one
+        // instruction moves the CFA to sp+16, with x29 at CFA-16 and x30 at
+        // CFA-8.
+        let data = b"\
+            \x14\0\0\0\0\0\0\0\x01zR\0\x04\x78\x1e\x01\x1b\x0c\x1f\0\0\0\0\0\
+            \x18\0\0\0\x1c\0\0\0\xe0\xef\xff\xff\x20\0\0\0\0\
+            \x2d\x41\x0e\x10\x9d\x02\x9e\x01\0\0\0\
+            \0\0\0\0";
+        let info = ElfCallFrameInfo::new(ElfCallFrameFormat::EhFrame, data, 0x2000, 8);
+        let unwinder = ElfUnwinder::new(ElfUnwindArch::Aarch64).with_eh_frame(info, None);
+
+        let fde = unwinder
+            .find_fde(0x1008)
+            .expect("parses")
+            .expect("address is covered");
+        let row = unwinder.row(&fde, 0x1008).expect("valid instructions");
+        assert!(row.is_return_address_signed);
+        assert_eq!(row.cfa, ElfCfaRule::RegisterOffset(31, 16));
+
+        let mut memory = |address: u64, _size: u8| match address {
+            0x7ff0 => Some(0x9000),
+            0x7ff8 => Some(0x002a_0000_0000_2345),
+            _ => None,
+        };
+        let mut registers = ElfRegisters::new(0x1008);
+        registers.set(31, Some(0x7ff0));
+
+        let caller = unwinder
+            .step(&ElfFrame::new(registers), &mut memory)
+            .expect("unwinds")
+            .expect("has a caller");
+        assert_eq!(caller.registers.pc, 0x2345);
+        assert_eq!(caller.registers.get(29), Some(0x9000));
+        assert_eq!(caller.registers.get(31), Some(0x8000));
+        assert_eq!(
+            strip_pointer_authentication(0xff80_0000_0000_1000),
+            0xffff_0000_0000_1000
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! A structural validation pass, reporting every problem found rather than
+//! stopping at the first, for rejecting malformed files.
+//!
+//! [`ElfFile::parse`] only checks what it needs to give access to the
+//! headers, so a file that parses can still have sections outside the file,
+//! overlapping segments and so on. Each [`ElfDiagnostic`] points at the
+//! header field responsible, by its byte offset in the file.
+
+use core::{
+    fmt,
+    mem::{offset_of, size_of},
+    ops::Range,
+};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::{elf_aux_structures::*, elf_structures::*, ElfFile, ElfHeader, ElfProgramHeader};
+
+/// The byte offset and name of a field, in whichever of the two structures
+/// matches the class of the file.
+macro_rules! field {
+    ($header:expr, $elf32:ty, $elf64:ty, $field:ident) => {
+        (
+            match $header {
+                ElfHeader::Elf32(_) => offset_of!($elf32, $field),
+                ElfHeader::Elf64(_) => offset_of!($elf64, $field),
+            },
+            stringify!($field),
+        )
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ElfSeverity {
+    /// Allowed by loaders in practice, but not by the specification.
+    Warning,
+    /// The file is malformed.
+    Error,
+}
+
+/// A structural problem with a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfProblem {
+    /// `e_ehsize` is not the size of the ELF header, which is given second.
+    UnexpectedHeaderSize(u16, u16),
+    /// A header table overlaps the named header or table.
+    OverlappingHeaders(&'static str),
+    /// The data extends to the given offset, past the end of the file.
+    PastEndOfFile(u64),
+    AlignmentNotPowerOfTwo(u64),
+    /// `sh_link` is not the index of a section.
+    LinkOutOfRange(u32),
+    /// A string table is empty, or doesn't start and end with a NUL byte.
+    UnterminatedStringTable,
+    FileSizeLargerThanMemorySize,
+    /// The segment's memory overlaps that of the `PT_LOAD` with the given
+    /// index.
+    OverlappingLoadSegments(u16),
+    /// The segment is at a lower address than the `PT_LOAD` with the given
+    /// index, which comes before it.
+    UnorderedLoadSegments(u16),
+}
+
+impl fmt::Display for ElfProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedHeaderSize(size, expected) => {
+                write!(f, "header size is {size}, expected {expected}")
+            }
+            Self::OverlappingHeaders(other) => write!(f, "overlaps the {other}"),
+            Self::PastEndOfFile(end) => write!(f, "extends to {end:#x}, past the end of the file"),
+            Self::AlignmentNotPowerOfTwo(align) => {
+                write!(f, "alignment {align:#x} is not a power of two")
+            }
+            Self::LinkOutOfRange(link) => write!(f, "link {link} is not a section index"),
+            Self::UnterminatedStringTable => write!(f, "string table is not NUL terminated"),
+            Self::FileSizeLargerThanMemorySize => {
+                write!(f, "file size is larger than memory size")
+            }
+            Self::OverlappingLoadSegments(other) => {
+                write!(f, "overlaps the memory of PT_LOAD segment {other}")
+            }
+            Self::UnorderedLoadSegments(other) => {
+                write!(
+                    f,
+                    "is below the address of the earlier PT_LOAD segment {other}"
+                )
+            }
+        }
+    }
+}
+
+/// A problem found by [`validate`], and the header field it was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfDiagnostic {
+    pub severity: ElfSeverity,
+    /// The byte offset of the field in the file.
+    pub offset: u64,
+    pub field: &'static str,
+    pub problem: ElfProblem,
+}
+
+impl fmt::Display for ElfDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            ElfSeverity::Warning => "warning",
+            ElfSeverity::Error => "error",
+        };
+        write!(
+            f,
+            "{severity}: {} at {:#x}: {}",
+            self.field, self.offset, self.problem
+        )
+    }
+}
+
+struct Validator<F> {
+    length: u64,
+    report: F,
+}
+
+impl<F: FnMut(ElfDiagnostic)> Validator<F> {
+    fn report(
+        &mut self,
+        severity: ElfSeverity,
+        base: u64,
+        (offset, field): (usize, &'static str),
+        problem: ElfProblem,
+    ) {
+        let offset = u64::try_from(offset).map_or(u64::MAX, |offset| base.saturating_add(offset));
+        (self.report)(ElfDiagnostic {
+            severity,
+            offset,
+            field,
+            problem,
+        });
+    }
+
+    /// Reports data at `start..start + size` that isn't within the file. An
+    /// empty range, like that of a segment only holding `.bss`, can be
+    /// anywhere.
+    fn check_in_file(&mut self, base: u64, field: (usize, &'static str), start: u64, size: u64) {
+        if size == 0 {
+            return;
+        }
+
+        match start.checked_add(size) {
+            Some(end) if end <= self.length => {}
+            end => self.report(
+                ElfSeverity::Error,
+                base,
+                field,
+                ElfProblem::PastEndOfFile(end.unwrap_or(u64::MAX)),
+            ),
+        }
+    }
+
+    fn check_alignment(&mut self, base: u64, field: (usize, &'static str), align: u64) {
+        // Both 0 and 1 mean there are no alignment constraints.
+        if align > 1 && !align.is_power_of_two() {
+            self.report(
+                ElfSeverity::Error,
+                base,
+                field,
+                ElfProblem::AlignmentNotPowerOfTwo(align),
+            );
+        }
+    }
+
+    fn check_header(&mut self, header: ElfHeader) {
+        let expected = match header {
+            ElfHeader::Elf32(_) => size_of::<Elf32Header>(),
+            ElfHeader::Elf64(_) => size_of::<Elf64Header>(),
+        };
+        let expected = u16::try_from(expected).unwrap_or(u16::MAX);
+        let size = header.e_ehsize();
+
+        if size != expected {
+            // Extra bytes are harmless, but fields would be missing.
+            let severity = if size > expected {
+                ElfSeverity::Warning
+            } else {
+                ElfSeverity::Error
+            };
+            self.report(
+                severity,
+                0,
+                field!(header, Elf32Header, Elf64Header, e_ehsize),
+                ElfProblem::UnexpectedHeaderSize(size, expected),
+            );
+        }
+
+        let ehdr = 0..u64::from(expected);
+        let phdrs = header.program_headers_location();
+        let shdrs = header.section_headers_location();
+
+        if phdrs
+            .as_ref()
+            .is_some_and(|phdrs| ranges_overlap(phdrs, &ehdr))
+        {
+            self.report(
+                ElfSeverity::Error,
+                0,
+                field!(header, Elf32Header, Elf64Header, e_phoff),
+                ElfProblem::OverlappingHeaders("ELF header"),
+            );
+        }
+        if shdrs
+            .as_ref()
+            .is_some_and(|shdrs| ranges_overlap(shdrs, &ehdr))
+        {
+            self.report(
+                ElfSeverity::Error,
+                0,
+                field!(header, Elf32Header, Elf64Header, e_shoff),
+                ElfProblem::OverlappingHeaders("ELF header"),
+            );
+        }
+        if let (Some(phdrs), Some(shdrs)) = (&phdrs, &shdrs) {
+            if ranges_overlap(phdrs, shdrs) {
+                self.report(
+                    ElfSeverity::Error,
+                    0,
+                    field!(header, Elf32Header, Elf64Header, e_shoff),
+                    ElfProblem::OverlappingHeaders("program header table"),
+                );
+            }
+        }
+    }
+
+    fn check_sections(&mut self, file: &ElfFile) {
+        let header = file.header();
+        let count = header.e_shnum().map_or(0, |count| count.get());
+
+        for (index, section) in (0..count).zip(file.sections()).skip(1) {
+            let base = header
+                .section_header_location(index)
+                .map_or(0, |location| location.start);
+            let size = section.sh_size();
+
+            if section.sh_type() != ElfSectionType::SHT_NOBITS {
+                self.check_in_file(
+                    base,
+                    field!(header, Elf32SectionHeader, Elf64SectionHeader, sh_offset),
+                    section.sh_offset(),
+                    size,
+                );
+            }
+
+            self.check_alignment(
+                base,
+                field!(header, Elf32SectionHeader, Elf64SectionHeader, sh_addralign),
+                section.sh_addralign(),
+            );
+
+            if section.sh_link() >= u32::from(count) {
+                self.report(
+                    ElfSeverity::Error,
+                    base,
+                    field!(header, Elf32SectionHeader, Elf64SectionHeader, sh_link),
+                    ElfProblem::LinkOutOfRange(section.sh_link()),
+                );
+            }
+
+            // Data outside the file has already been reported.
+            if section.sh_type() == ElfSectionType::SHT_STRTAB {
+                let Ok(data) = file.section_data(section) else {
+                    continue;
+                };
+                if !matches!((data.first(), data.last()), (Some(0), Some(0))) {
+                    self.report(
+                        ElfSeverity::Error,
+                        base,
+                        field!(header, Elf32SectionHeader, Elf64SectionHeader, sh_size),
+                        ElfProblem::UnterminatedStringTable,
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_segments(&mut self, file: &ElfFile) {
+        let header = file.header();
+        let count = header.e_phnum().map_or(0, |count| count.get());
+        let mut previous_load: Option<(u16, u64)> = None;
+
+        for (index, segment) in (0..count).zip(file.segments()) {
+            let base = header
+                .program_header_location(index)
+                .map_or(0, |location| location.start);
+            let file_size = segment.p_filesz().map_or(0, |size| size.get());
+
+            self.check_in_file(
+                base,
+                field!(header, Elf32ProgramHeader, Elf64ProgramHeader, p_offset),
+                segment.p_offset(),
+                file_size,
+            );
+            self.check_alignment(
+                base,
+                field!(header, Elf32ProgramHeader, Elf64ProgramHeader, p_align),
+                segment.p_align(),
+            );
+
+            if segment.p_filesz() > segment.p_memsz() {
+                self.report(
+                    ElfSeverity::Error,
+                    base,
+                    field!(header, Elf32ProgramHeader, Elf64ProgramHeader, p_filesz),
+                    ElfProblem::FileSizeLargerThanMemorySize,
+                );
+            }
+
+            if segment.p_type() != ElfSegmentType::PT_LOAD {
+                continue;
+            }
+
+            let vaddr = field!(header, Elf32ProgramHeader, Elf64ProgramHeader, p_vaddr);
+            if let Some((other, _)) =
+                previous_load.filter(|&(_, address)| segment.p_vaddr() < address)
+            {
+                self.report(
+                    ElfSeverity::Warning,
+                    base,
+                    vaddr,
+                    ElfProblem::UnorderedLoadSegments(other),
+                );
+            }
+            previous_load = Some((index, segment.p_vaddr()));
+
+            let overlapping = (0..index)
+                .zip(file.segments())
+                .find(|&(_, other)| load_segments_overlap(segment, other));
+            if let Some((other, _)) = overlapping {
+                self.report(
+                    ElfSeverity::Error,
+                    base,
+                    vaddr,
+                    ElfProblem::OverlappingLoadSegments(other),
+                );
+            }
+        }
+    }
+}
+
+/// Whether two non-empty ranges share any bytes.
+fn ranges_overlap(a: &Range<u64>, b: &Range<u64>) -> bool {
+    !a.is_empty() && !b.is_empty() && a.start < b.end && b.start < a.end
+}
+
+fn load_segments_overlap(segment: ElfProgramHeader, other: ElfProgramHeader) -> bool {
+    let memory = |segment: ElfProgramHeader| {
+        let start = segment.p_vaddr();
+        let size = segment.p_memsz().map_or(0, |size| size.get());
+        start..start.saturating_add(size)
+    };
+
+    other.p_type() == ElfSegmentType::PT_LOAD && ranges_overlap(&memory(segment), &memory(other))
+}
+
+/// Checks the structure of `file`, calling `report` with each problem found.
+///
+/// This covers the ELF header size, overlapping header tables, section and
+/// segment data outside the file, alignments that aren't powers of two,
+/// out-of-range `sh_link` values, unterminated string tables, and
+/// overlapping or unordered `PT_LOAD` segments.
+pub fn validate_with(file: &ElfFile, report: impl FnMut(ElfDiagnostic)) {
+    let mut validator = Validator {
+        length: u64::try_from(file.buffer().len()).unwrap_or(u64::MAX),
+        report,
+    };
+
+    validator.check_header(file.header());
+    validator.check_sections(file);
+    validator.check_segments(file);
+}
+
+/// Checks the structure of `file`, returning every problem found. See
+/// [`validate_with`] for what is checked.
+#[cfg(feature = "std")]
+pub fn validate(file: &ElfFile) -> Vec<ElfDiagnostic> {
+    let mut diagnostics = Vec::new();
+    validate_with(file, |diagnostic| diagnostics.push(diagnostic));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::{NonZeroU16, NonZeroU64};
+
+    use zerocopy::AsBytes;
+
+    use super::*;
+    use crate::mutable::{ElfHeaderMut, ElfProgramHeaderMut};
+
+    #[test]
+    fn reports_every_problem() {
+        // An ELF64 header followed by two program headers.
+        let mut buffer = [0u64; 22];
+        buffer[0] = u64::from_le_bytes(*b"\x7fELF\x02\x01\x01\x00");
+        let bytes = buffer.as_bytes_mut();
+
+        let mut header = ElfHeaderMut::parse(bytes).expect("valid header");
+        header.set_e_ehsize(60);
+        assert!(header.set_e_phoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_phentsize(56);
+        header.set_e_phnum(NonZeroU16::new(2));
+
+        for (index, (vaddr, align)) in [(0x1000, 3), (0x1800, 0x1000)].into_iter().enumerate() {
+            let index = u16::try_from(index).expect("small index");
+            let mut segment = ElfProgramHeaderMut::from_file(bytes, index).expect("in bounds");
+            segment.set_p_type(ElfSegmentType::PT_LOAD);
+            assert!(segment.set_p_vaddr(vaddr).is_ok());
+            assert!(segment.set_p_memsz(NonZeroU64::new(0x2000)).is_ok());
+            assert!(segment.set_p_align(align).is_ok());
+        }
+
+        let file = ElfFile::parse(bytes).expect("parses");
+        let mut diagnostics = [None; 4];
+        let mut count = 0;
+        validate_with(&file, |diagnostic| {
+            if let Some(slot) = diagnostics.get_mut(count) {
+                *slot = Some((diagnostic.offset, diagnostic.field, diagnostic.problem));
+            }
+            count += 1;
+        });
+
+        assert_eq!(
+            diagnostics,
+            [
+                Some((52, "e_ehsize", ElfProblem::UnexpectedHeaderSize(60, 64))),
+                Some((112, "p_align", ElfProblem::AlignmentNotPowerOfTwo(3))),
+                Some((136, "p_vaddr", ElfProblem::OverlappingLoadSegments(0))),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn allows_empty_segments_past_end_of_file() {
+        // An ELF64 header followed by a program header for a segment with
+        // no file contents, starting past the end of the file.
+        let mut buffer = [0u64; 15];
+        buffer[0] = u64::from_le_bytes(*b"\x7fELF\x02\x01\x01\x00");
+        let bytes = buffer.as_bytes_mut();
+
+        let mut header = ElfHeaderMut::parse(bytes).expect("valid header");
+        header.set_e_ehsize(64);
+        assert!(header.set_e_phoff(NonZeroU64::new(64)).is_ok());
+        header.set_e_phentsize(56);
+        header.set_e_phnum(NonZeroU16::new(1));
+
+        let mut segment = ElfProgramHeaderMut::from_file(bytes, 0).expect("in bounds");
+        segment.set_p_type(ElfSegmentType::PT_LOAD);
+        assert!(segment.set_p_offset(0x1000).is_ok());
+        assert!(segment.set_p_memsz(NonZeroU64::new(0x2000)).is_ok());
+
+        let file = ElfFile::parse(bytes).expect("parses");
+        let mut count = 0;
+        validate_with(&file, |_| count += 1);
+        assert_eq!(count, 0);
+    }
+}
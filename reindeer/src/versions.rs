@@ -0,0 +1,281 @@
+//! GNU symbol versioning, as found in the `SHT_VERSYM` (`.gnu.version`),
+//! `SHT_VERNEED` (`.gnu.version_r`) and `SHT_VERDEF` (`.gnu.version_d`)
+//! sections.
+//!
+//! See the [LSB symbol versioning][lsb] documentation for details.
+//!
+//! [lsb]: https://refspecs.linuxfoundation.org/LSB_5.0.0/LSB-Core-generic/LSB-Core-generic/symversion.html
+
+use core::marker::PhantomData;
+
+use zerocopy::FromBytes;
+
+use crate::{elf_structures::*, errors::ElfError};
+
+/// The `SHT_VERSYM` table, holding one version index per dynamic symbol.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionSymbolTable<'buf> {
+    indices: &'buf [u16],
+}
+
+impl<'buf> ElfVersionSymbolTable<'buf> {
+    /// The symbol is local, and not available outside the object.
+    pub const VER_NDX_LOCAL: u16 = 0;
+    /// The symbol is defined in this object and is globally available.
+    pub const VER_NDX_GLOBAL: u16 = 1;
+    /// Set if the symbol is hidden, that is it is not the default version.
+    pub const VERSYM_HIDDEN: u16 = 0x8000;
+    /// Masks out the hidden bit, leaving the version index.
+    pub const VERSYM_VERSION: u16 = 0x7fff;
+
+    pub fn parse(bytes: &'buf [u8]) -> Result<Self, ElfError> {
+        Ok(Self {
+            indices: u16::slice_from(bytes).ok_or(ElfError::ZeroCopyError)?,
+        })
+    }
+
+    /// The raw version index, including the hidden bit, for the symbol with
+    /// the same index in the dynamic symbol table.
+    pub fn get(&self, symbol_index: usize) -> Option<u16> {
+        self.indices.get(symbol_index).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// Entries of the version sections are linked together by byte offsets
+/// relative to the entry, which this trait exposes.
+trait Chained: FromBytes + Sized {
+    fn next_offset(&self) -> u32;
+}
+
+impl Chained for ElfVerneed {
+    fn next_offset(&self) -> u32 {
+        self.vn_next
+    }
+}
+
+impl Chained for ElfVernaux {
+    fn next_offset(&self) -> u32 {
+        self.vna_next
+    }
+}
+
+impl Chained for ElfVerdef {
+    fn next_offset(&self) -> u32 {
+        self.vd_next
+    }
+}
+
+impl Chained for ElfVerdaux {
+    fn next_offset(&self) -> u32 {
+        self.vda_next
+    }
+}
+
+/// Follows a chain of version entries, yielding each entry and its offset
+/// from the start of the section. At most `remaining` entries are yielded, so
+/// that cyclic chains terminate.
+struct ChainIter<'buf, T> {
+    section: &'buf [u8],
+    offset: Option<usize>,
+    remaining: u32,
+    _entry: PhantomData<T>,
+}
+
+impl<'buf, T: Chained + 'buf> ChainIter<'buf, T> {
+    fn new(section: &'buf [u8], offset: usize, count: u32) -> Self {
+        Self {
+            section,
+            offset: Some(offset),
+            remaining: count,
+            _entry: PhantomData,
+        }
+    }
+}
+
+impl<'buf, T: Chained + 'buf> Iterator for ChainIter<'buf, T> {
+    type Item = Result<(usize, &'buf T), ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let offset = self.offset.take()?;
+        self.remaining = self.remaining.saturating_sub(1);
+
+        let Some(entry) = self.section.get(offset..).and_then(T::ref_from_prefix) else {
+            return Some(Err(ElfError::MalformedVersionEntry(offset)));
+        };
+
+        let next = usize::try_from(entry.next_offset()).ok();
+        self.offset = match next {
+            Some(0) | None => None,
+            Some(next) => offset.checked_add(next),
+        };
+
+        Some(Ok((offset, entry)))
+    }
+}
+
+/// Version requirements from a `SHT_VERNEED` section.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionNeeds<'buf> {
+    section: &'buf [u8],
+    count: u32,
+}
+
+impl<'buf> ElfVersionNeeds<'buf> {
+    /// `count` is the number of entries, from the section's `sh_info` or
+    /// the `DT_VERNEEDNUM` dynamic entry.
+    pub fn parse(section: &'buf [u8], count: u32) -> Self {
+        Self { section, count }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<ElfVersionNeed<'buf>, ElfError>> + 'buf {
+        let section = self.section;
+        ChainIter::<ElfVerneed>::new(section, 0, self.count).map(move |entry| {
+            let (offset, verneed) = entry?;
+            Ok(ElfVersionNeed {
+                section,
+                offset,
+                verneed,
+            })
+        })
+    }
+
+    /// Finds the needed version with the given version index.
+    pub fn find(
+        &self,
+        version_index: u16,
+    ) -> Option<(ElfVersionNeed<'buf>, ElfVersionNeedAux<'buf>)> {
+        self.iter().filter_map(Result::ok).find_map(|need| {
+            need.auxiliaries()
+                .filter_map(Result::ok)
+                .find(|aux| aux.vernaux.vna_other == version_index)
+                .map(|aux| (need, aux))
+        })
+    }
+}
+
+/// A file from which versions are needed.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionNeed<'buf> {
+    section: &'buf [u8],
+    /// The offset of this entry from the start of the section.
+    pub offset: usize,
+    pub verneed: &'buf ElfVerneed,
+}
+
+impl<'buf> ElfVersionNeed<'buf> {
+    pub fn auxiliaries(
+        &self,
+    ) -> impl Iterator<Item = Result<ElfVersionNeedAux<'buf>, ElfError>> + 'buf {
+        let start = usize::try_from(self.verneed.vn_aux)
+            .ok()
+            .and_then(|aux| self.offset.checked_add(aux))
+            .unwrap_or(usize::MAX);
+
+        ChainIter::<ElfVernaux>::new(self.section, start, self.verneed.vn_cnt.into()).map(|entry| {
+            let (offset, vernaux) = entry?;
+            Ok(ElfVersionNeedAux { offset, vernaux })
+        })
+    }
+}
+
+/// A single version needed from a file.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionNeedAux<'buf> {
+    /// The offset of this entry from the start of the section.
+    pub offset: usize,
+    pub vernaux: &'buf ElfVernaux,
+}
+
+/// Version definitions from a `SHT_VERDEF` section.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionDefinitions<'buf> {
+    section: &'buf [u8],
+    count: u32,
+}
+
+impl<'buf> ElfVersionDefinitions<'buf> {
+    /// The version definition is of the file itself.
+    pub const VER_FLG_BASE: u16 = 0x1;
+    /// The version definition is weak.
+    pub const VER_FLG_WEAK: u16 = 0x2;
+
+    /// `count` is the number of entries, from the section's `sh_info` or
+    /// the `DT_VERDEFNUM` dynamic entry.
+    pub fn parse(section: &'buf [u8], count: u32) -> Self {
+        Self { section, count }
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = Result<ElfVersionDefinition<'buf>, ElfError>> + 'buf {
+        let section = self.section;
+        ChainIter::<ElfVerdef>::new(section, 0, self.count).map(move |entry| {
+            let (offset, verdef) = entry?;
+            Ok(ElfVersionDefinition {
+                section,
+                offset,
+                verdef,
+            })
+        })
+    }
+
+    /// Finds the definition with the given version index.
+    pub fn find(&self, version_index: u16) -> Option<ElfVersionDefinition<'buf>> {
+        self.iter()
+            .filter_map(Result::ok)
+            .find(|definition| definition.verdef.vd_ndx == version_index)
+    }
+}
+
+/// A version defined by this file.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionDefinition<'buf> {
+    section: &'buf [u8],
+    /// The offset of this entry from the start of the section.
+    pub offset: usize,
+    pub verdef: &'buf ElfVerdef,
+}
+
+impl<'buf> ElfVersionDefinition<'buf> {
+    /// The first auxiliary entry names this version, the rest name its parents.
+    pub fn auxiliaries(
+        &self,
+    ) -> impl Iterator<Item = Result<ElfVersionDefinitionAux<'buf>, ElfError>> + 'buf {
+        let start = usize::try_from(self.verdef.vd_aux)
+            .ok()
+            .and_then(|aux| self.offset.checked_add(aux))
+            .unwrap_or(usize::MAX);
+
+        ChainIter::<ElfVerdaux>::new(self.section, start, self.verdef.vd_cnt.into()).map(|entry| {
+            let (offset, verdaux) = entry?;
+            Ok(ElfVersionDefinitionAux { offset, verdaux })
+        })
+    }
+
+    /// The name of this version, as an index into the linked string table.
+    pub fn name(&self) -> Option<u32> {
+        self.auxiliaries()
+            .next()
+            .and_then(Result::ok)
+            .map(|aux| aux.verdaux.vda_name)
+    }
+}
+
+/// A name attached to a version definition.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVersionDefinitionAux<'buf> {
+    /// The offset of this entry from the start of the section.
+    pub offset: usize,
+    pub verdaux: &'buf ElfVerdaux,
+}
@@ -0,0 +1,61 @@
+# A core file of unwind-x86_64, stopped in the early return path of
+# early_return, with just the stack dumped.
+#
+#   as core-x86_64.s -o core-x86_64.tmp.o
+#   objcopy -O binary -j .data core-x86_64.tmp.o core-x86_64
+
+    .data
+start:
+    # ELF header: ELFCLASS64, ELFDATA2LSB, ET_CORE, EM_X86_64
+    .byte 0x7f, 'E', 'L', 'F', 2, 1, 1, 0
+    .zero 8
+    .short 4, 62
+    .long 1
+    .quad 0, phdrs - start, 0
+    .long 0
+    .short 64, 56, 2, 64, 0, 0
+
+phdrs:
+    # PT_NOTE
+    .long 4, 0
+    .quad notes - start, 0, 0, notes_end - notes, 0, 4
+    # PT_LOAD, RW
+    .long 1, 6
+    .quad stack - start, 0x7fffff00, 0, stack_end - stack, 0x100, 8
+
+notes:
+    # NT_PRSTATUS
+    .long 5, prstatus_end - prstatus, 1
+    .asciz "CORE"
+    .balign 4
+prstatus:
+    .zero 112
+    # r15, r14, r13, r12, rbp, rbx, r11, r10, r9, r8, rax, rcx, rdx, rsi,
+    # rdi, orig_rax
+    .quad 0, 0, 0, 0, 0x2222, 0x1111, 0, 0, 0, 0, 0, 0, 0, 0, 7, 0
+    # rip (early_return+0x34), cs, eflags, rsp, ss, fs_base, gs_base, ds,
+    # es, fs, gs
+    .quad 0x400134, 0x33, 0x246, 0x7fffff08, 0x2b, 0, 0, 0, 0, 0, 0
+    # pr_fpvalid, and padding
+    .long 0, 0
+prstatus_end:
+
+    # NT_FILE
+    .long 5, file_end - file, 0x46494c45
+    .asciz "CORE"
+    .balign 4
+file:
+    .quad 1, 0x1000
+    .quad 0x400000, 0x401000, 0
+    .asciz "/unwind-x86_64"
+    .balign 4
+file_end:
+notes_end:
+
+    .balign 8
+stack:
+    # Padding, saved rbx and rbp, then the return address into _start.
+    .quad 0, 0x3333, 0x4444, 0x40014e
+    # _start's padding, and no return address.
+    .quad 0, 0
+stack_end:
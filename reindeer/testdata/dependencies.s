@@ -0,0 +1,25 @@
+# Shared objects for resolving dependencies, which only differ in their
+# dynamic arrays. The libraries linked against are copies of the plain one,
+# which has no DT_SONAME, so are needed by their file names.
+#
+#   as dependencies.s -o dependencies-64.tmp.o
+#   as --32 dependencies.s -o dependencies-32.tmp.o
+#   FLAGS="-shared -s -z max-page-size=0x400 -z noseparate-code -z norelro \
+#       --hash-style=gnu --build-id=none"
+#   ld $FLAGS dependencies-64.tmp.o -o dependencies-plain
+#   ld -m elf_i386 $FLAGS dependencies-32.tmp.o -o dependencies-plain32
+#   mkdir link
+#   for name in libfirst.so libsecond.so libthird.so; do
+#       cp dependencies-plain link/$name
+#   done
+#   ld $FLAGS -soname libfirst.so.1 -rpath /runpath --enable-new-dtags \
+#       dependencies-64.tmp.o -L link -l:libthird.so -o dependencies-first
+#   cp dependencies-first link/libfirst.so.1
+#   ld $FLAGS -rpath /rpath --disable-new-dtags dependencies-64.tmp.o \
+#       -L link -l:libfirst.so -l:libfirst.so.1 -l:libsecond.so \
+#       -o dependencies-app
+
+    .text
+    .globl f
+f:
+    ret
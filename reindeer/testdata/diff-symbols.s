@@ -0,0 +1,22 @@
+# A shared object with a local and an exported function, so that it has both
+# a static and a dynamic symbol table, and a copy stripped of the former.
+#
+#   as diff-symbols.s -o diff-symbols.tmp.o
+#   ld -shared --build-id=none --hash-style=gnu -z max-page-size=0x400 \
+#       -z noseparate-code diff-symbols.tmp.o -o diff-symbols
+#   objcopy --strip-all diff-symbols diff-symbols-stripped
+
+    .text
+    .type helper, @function
+helper:
+    mov $1, %eax
+    ret
+    .size helper, . - helper
+
+    .globl exported
+    .type exported, @function
+exported:
+    call helper
+    add $1, %eax
+    ret
+    .size exported, . - exported
@@ -0,0 +1,22 @@
+// A function marked as supporting BTI and PAC.
+//
+//   llvm-mc -triple=aarch64 -mattr=+bti,+pauth -filetype=obj hardening-aarch64.s -o hardening-aarch64.o
+
+    .text
+    .globl f
+f:
+    bti c
+    paciasp
+    autiasp
+    ret
+
+    .section .note.gnu.property, "a"
+    .p2align 3
+    .long 4
+    .long 16
+    .long 5 // NT_GNU_PROPERTY_TYPE_0
+    .asciz "GNU"
+    .long 0xc0000000 // GNU_PROPERTY_AARCH64_FEATURE_1_AND
+    .long 4
+    .long 3 // BTI | PAC
+    .p2align 3
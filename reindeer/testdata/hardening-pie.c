@@ -0,0 +1,21 @@
+/*
+ * A hardened, dynamically linked PIE, which references the stack protector
+ * and two fortified functions.
+ *
+ *   gcc -O2 -D_FORTIFY_SOURCE=2 -fstack-protector-strong -fcf-protection=none \
+ *       -pie -fPIE -s -Wl,-z,relro,-z,now,-z,noexecstack -Wl,-rpath,/opt/lib \
+ *       -Wl,--disable-new-dtags -Wl,--build-id=none -Wl,-z,max-page-size=0x400 \
+ *       -Wl,-z,noseparate-code -Wl,--hash-style=gnu hardening-pie.c \
+ *       -o hardening-pie
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+int main(int argc, char **argv)
+{
+    char buffer[16];
+    memcpy(buffer, argv[0], argc);
+    printf("%d %s\n", argc, buffer);
+    return buffer[1];
+}
@@ -0,0 +1,17 @@
+# A shared object with text relocations, an executable stack and no RELRO,
+# which defines the stack protector and a fortified function itself, like
+# libc does, rather than using them.
+#
+#   as hardening-textrel.s -o hardening-textrel.tmp.o
+#   ld -shared -s -z notext -z execstack -z norelro -z lazy -rpath /opt/lib \
+#       --enable-new-dtags --build-id=none --hash-style=gnu \
+#       -z max-page-size=0x400 -z noseparate-code hardening-textrel.tmp.o \
+#       -o hardening-textrel
+
+    .text
+    .globl __stack_chk_fail, __memcpy_chk
+__stack_chk_fail:
+__memcpy_chk:
+    ret
+address:
+    .quad __stack_chk_fail
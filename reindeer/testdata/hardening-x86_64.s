@@ -0,0 +1,23 @@
+# A function built with -fstack-protector and -D_FORTIFY_SOURCE, marked as
+# supporting IBT and SHSTK.
+#
+#   llvm-mc -triple=x86_64 -filetype=obj hardening-x86_64.s -o hardening-x86_64.o
+
+    .text
+    .globl f
+f:
+    endbr64
+    call __memcpy_chk
+    call __printf_chk
+    call __stack_chk_fail
+
+    .section .note.gnu.property, "a"
+    .p2align 3
+    .long 4
+    .long 16
+    .long 5                     # NT_GNU_PROPERTY_TYPE_0
+    .asciz "GNU"
+    .long 0xc0000002            # GNU_PROPERTY_X86_FEATURE_1_AND
+    .long 4
+    .long 3                     # IBT | SHSTK
+    .p2align 3
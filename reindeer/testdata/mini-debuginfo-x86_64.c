@@ -0,0 +1,29 @@
+/*
+ * A stripped static executable whose local function is only named in its
+ * MiniDebugInfo, built as Fedora's find-debuginfo does.
+ *
+ *   gcc -O2 -nostdlib -static -fno-stack-protector -fcf-protection=none \
+ *       -fno-asynchronous-unwind-tables -Wl,--build-id=none \
+ *       -Wl,-z,max-page-size=0x400 -Wl,-z,noseparate-code \
+ *       mini-debuginfo-x86_64.c -o full
+ *   nm full --format=posix --defined-only \
+ *       | awk '$2 ~ /[Tt]/ { print $1 }' > keep
+ *   objcopy --only-keep-debug full debug
+ *   objcopy -S --keep-symbols=keep debug mini
+ *   xz --check=crc32 mini
+ *   objcopy --strip-all --remove-section .comment \
+ *       --add-section .gnu_debugdata=mini.xz full mini-debuginfo-x86_64
+ */
+
+__attribute__((noipa)) static int hidden(int x)
+{
+    __asm__ volatile("" ::: "memory");
+    return x * 3;
+}
+
+void _start(void)
+{
+    int status = hidden(2);
+    __asm__ volatile("syscall" :: "a"(60), "D"(status));
+    __builtin_unreachable();
+}
@@ -0,0 +1,33 @@
+# A relocatable object whose relocations need a GOT entry and a veneer for
+# an undefined symbol, and pair R_RISCV_PCREL_LO12_I with their
+# R_RISCV_PCREL_HI20 or R_RISCV_GOT_HI20.
+#
+#   llvm-mc -triple=riscv64 -filetype=obj object-riscv64.s -o object-riscv64.o
+
+    .option norelax
+
+    .text
+    .globl entry
+entry:
+.Lcounter:
+    auipc a0, %pcrel_hi(counter)
+    addi a0, a0, %pcrel_lo(.Lcounter)
+.Lexternal:
+    auipc a1, %got_pcrel_hi(external)
+    ld a1, %pcrel_lo(.Lexternal)(a1)
+    call external
+    j helper
+
+helper:
+    ret
+
+    .data
+    .p2align 3
+    .globl counter
+counter:
+    .quad 1
+
+    .bss
+    .p2align 4
+buffer:
+    .zero 16
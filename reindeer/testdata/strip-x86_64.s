@@ -0,0 +1,24 @@
+# A static executable with a debugging section, followed by a non-allocated
+# section that a symbol points into.
+#
+#   as strip-x86_64.s -o strip-x86_64.tmp.o
+#   ld -z max-page-size=0x400 -z noseparate-code -T strip-x86_64.ld \
+#       strip-x86_64.tmp.o -o strip-x86_64
+
+    .text
+    .globl _start
+_start:
+    mov $60, %eax
+    xor %edi, %edi
+    syscall
+
+    .data
+counter:
+    .long 1
+
+    .section .debug_str, "MS", @progbits, 1
+    .asciz "strip-x86_64.s"
+
+    .section .kept, ""
+marker:
+    .long 0x12345678
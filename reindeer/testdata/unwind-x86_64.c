@@ -0,0 +1,32 @@
+/*
+ * Functions whose call frame information an unwinder has to follow,
+ * including the .cfi_remember_state and .cfi_restore_state GCC emits for
+ * the early return of early_return.
+ *
+ *   gcc -O2 -nostdlib -static -fno-stack-protector -fcf-protection=none \
+ *       -Wl,--eh-frame-hdr -Wl,--build-id=none -Wl,-z,max-page-size=0x400 \
+ *       -Wl,-z,noseparate-code unwind-x86_64.c -o unwind-x86_64
+ */
+
+__attribute__((noipa)) int leaf(int x)
+{
+    __asm__ volatile("" ::: "memory");
+    return x * 3;
+}
+
+__attribute__((noinline)) int early_return(int x)
+{
+    int a = leaf(x);
+    if (a == 0)
+        return leaf(7);
+    int b = leaf(a);
+    int c = leaf(b);
+    return a + b + c;
+}
+
+void _start(void)
+{
+    early_return(1);
+    for (;;)
+        ;
+}